@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use doppler_consumer::ConsumerError;
+
+/// [`ConsumerError`] as an Anchor error, with the same codes.
+#[error_code(offset = 0x444f_0000)]
+#[derive(PartialEq, Eq)]
+pub enum DopplerError {
+    #[msg("oracle account is not owned by the Doppler program")]
+    InvalidOwner,
+    #[msg("oracle account data does not match the payload layout")]
+    InvalidLayout,
+    #[msg("oracle account data is misaligned for the payload")]
+    Misaligned,
+    #[msg("oracle sequence is stale")]
+    Stale,
+    #[msg("oracle account data is already borrowed")]
+    AccountBorrowFailed,
+    #[msg("account is not the expected oracle")]
+    UnexpectedAccount,
+    #[msg("oracle has not been updated yet")]
+    Uninitialized,
+    #[msg("clock cannot be expressed as a sequence")]
+    InvalidClock,
+}
+
+impl From<ConsumerError> for DopplerError {
+    fn from(error: ConsumerError) -> Self {
+        match error {
+            ConsumerError::InvalidOwner => Self::InvalidOwner,
+            ConsumerError::InvalidLayout => Self::InvalidLayout,
+            ConsumerError::Misaligned => Self::Misaligned,
+            ConsumerError::Stale => Self::Stale,
+            ConsumerError::AccountBorrowFailed => Self::AccountBorrowFailed,
+            ConsumerError::UnexpectedAccount => Self::UnexpectedAccount,
+            ConsumerError::Uninitialized => Self::Uninitialized,
+            ConsumerError::InvalidClock => Self::InvalidClock,
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! Reads Doppler oracles from Anchor programs.
+//!
+//! Oracles of the default deployment are passed as plain accounts and read
+//! with [`load`], [`load_fresh`] or, for `PriceFeed` feeds, [`read_price`]:
+//!
+//! ```ignore
+//! impl<'info> PriceAccounts<'info> for Settle<'info> {
+//!     fn price_feed(&self) -> &AccountInfo<'info> { &self.oracle }
+//!     fn price_feed_pubkey(&self) -> Pubkey { self.market.oracle }
+//! }
+//!
+//! let price = read_price(&ctx, SequenceUnit::Millis, 30)?;
+//! ```
+//!
+//! Oracles of a program built with the `anchor` feature start with Anchor's
+//! `Oracle` discriminator; take them as `Account<'info, AnchorOracle<T>>` to
+//! get the owner and discriminator checked during account validation.
+//!
+//! Failures surface as [`DopplerError`], which keeps the codes of
+//! `doppler-consumer`'s errors.
+
+use core::ops::Deref;
+
+use anchor_lang::{error::ErrorCode, prelude::*, Bumps, Discriminator};
+pub use doppler_consumer::SequenceUnit;
+pub use doppler_sdk::{Oracle, ANCHOR_DISCRIMINATOR};
+
+mod error;
+pub use error::DopplerError;
+
+/// The program's default payload.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PriceFeed {
+    pub price: u64,
+}
+
+/// Accounts of an instruction that reads a `PriceFeed` oracle with
+/// [`read_price`].
+pub trait PriceAccounts<'info> {
+    /// The oracle to read, in the default layout.
+    fn price_feed(&self) -> &AccountInfo<'info>;
+
+    /// The oracle the instruction expects, e.g. the one stored in a market
+    /// account. Every Doppler oracle passes the owner and layout checks, so
+    /// without it a caller could pass any feed.
+    fn price_feed_pubkey(&self) -> Pubkey;
+}
+
+/// # Load
+/// Copies the oracle out of `account` after checking it is `oracle_pubkey`,
+/// is owned by the Doppler program, matches the layout of `Oracle<T>` and
+/// has received an update.
+pub fn load<T: Sized + Copy>(account: &AccountInfo, oracle_pubkey: &Pubkey) -> Result<Oracle<T>> {
+    let oracle = doppler_consumer::load::<T>(account, oracle_pubkey).map_err(DopplerError::from)?;
+    Ok(*oracle)
+}
+
+/// # Load Fresh
+/// Same as [`load`], additionally rejecting oracles whose sequence is more
+/// than `max_age` behind `now`, in the unit the publisher uses for the
+/// sequence.
+pub fn load_fresh<T: Sized + Copy>(
+    account: &AccountInfo,
+    oracle_pubkey: &Pubkey,
+    now: u64,
+    max_age: u64,
+) -> Result<Oracle<T>> {
+    let oracle = doppler_consumer::load_fresh::<T>(account, oracle_pubkey, now, max_age)
+        .map_err(DopplerError::from)?;
+    Ok(*oracle)
+}
+
+/// # Read Price
+/// Reads the price from the instruction's [`PriceAccounts::price_feed`]
+/// after checking it is [`PriceAccounts::price_feed_pubkey`], rejecting it
+/// if its sequence, read in `unit`, is more than `max_age_secs` seconds
+/// behind the `Clock` sysvar. Oracles updated by the keeper use
+/// [`SequenceUnit::Millis`].
+pub fn read_price<'info, A>(
+    ctx: &Context<'_, '_, '_, 'info, A>,
+    unit: SequenceUnit,
+    max_age_secs: u64,
+) -> Result<u64>
+where
+    A: Bumps + PriceAccounts<'info>,
+{
+    let now = unit
+        .now(Clock::get()?.unix_timestamp)
+        .map_err(DopplerError::from)?;
+    let max_age = unit.secs(max_age_secs).ok_or(DopplerError::InvalidClock)?;
+    let oracle = load_fresh::<PriceFeed>(
+        ctx.accounts.price_feed(),
+        &ctx.accounts.price_feed_pubkey(),
+        now,
+        max_age,
+    )?;
+    Ok(oracle.payload.price)
+}
+
+/// An oracle account prefixed with [`ANCHOR_DISCRIMINATOR`].
+#[derive(Clone, Copy, Debug)]
+pub struct AnchorOracle<T: Sized + Copy>(pub Oracle<T>);
+
+impl<T: Sized + Copy> AnchorOracle<T> {
+    /// Size of the oracle account data, discriminator included.
+    pub const LEN: usize = ANCHOR_DISCRIMINATOR.len() + Oracle::<T>::LEN;
+}
+
+impl<T: Sized + Copy> Deref for AnchorOracle<T> {
+    type Target = Oracle<T>;
+
+    fn deref(&self) -> &Oracle<T> {
+        &self.0
+    }
+}
+
+impl<T: Sized + Copy> Discriminator for AnchorOracle<T> {
+    const DISCRIMINATOR: &'static [u8] = &ANCHOR_DISCRIMINATOR;
+}
+
+impl<T: Sized + Copy> Owner for AnchorOracle<T> {
+    fn owner() -> Pubkey {
+        doppler_sdk::ID
+    }
+}
+
+impl<T: Sized + Copy> AccountDeserialize for AnchorOracle<T> {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        if buf.len() < ANCHOR_DISCRIMINATOR.len() {
+            return Err(ErrorCode::AccountDiscriminatorNotFound.into());
+        }
+        if buf[..ANCHOR_DISCRIMINATOR.len()] != ANCHOR_DISCRIMINATOR {
+            return Err(ErrorCode::AccountDiscriminatorMismatch.into());
+        }
+
+        Self::try_deserialize_unchecked(buf)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let data = buf
+            .get(ANCHOR_DISCRIMINATOR.len()..)
+            .ok_or(ErrorCode::AccountDidNotDeserialize)?;
+        let (oracle, _) =
+            Oracle::try_from_bytes(data).ok_or(ErrorCode::AccountDidNotDeserialize)?;
+
+        *buf = &buf[Self::LEN..];
+        Ok(Self(oracle))
+    }
+}
+
+/// Only the Doppler program writes oracles, so Anchor has nothing to
+/// serialize back.
+impl<T: Sized + Copy> AccountSerialize for AnchorOracle<T> {}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::solana_program::hash::hash;
+
+    use super::*;
+
+    fn oracle_data(discriminator: [u8; 8], sequence: u64, price: u64) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&sequence.to_le_bytes());
+        data.extend_from_slice(&price.to_le_bytes());
+        data
+    }
+
+    #[repr(C, align(8))]
+    struct AccountData([u8; 16]);
+
+    fn default_oracle_data(sequence: u64, price: u64) -> AccountData {
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&sequence.to_le_bytes());
+        data[8..].copy_from_slice(&price.to_le_bytes());
+        AccountData(data)
+    }
+
+    fn error_code(err: Error) -> u32 {
+        match err {
+            Error::AnchorError(err) => err.error_code_number,
+            Error::ProgramError(err) => panic!("unexpected program error {err}"),
+        }
+    }
+
+    #[test]
+    fn test_load_fresh_oracle() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = default_oracle_data(100, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &doppler_sdk::ID,
+            false,
+            0,
+        );
+
+        let oracle = load_fresh::<PriceFeed>(&account, &key, 110, 10).unwrap();
+        assert_eq!(oracle.sequence, 100);
+        assert_eq!(oracle.payload.price, 1_100_000);
+        assert_eq!(
+            error_code(load_fresh::<PriceFeed>(&account, &key, 111, 10).unwrap_err()),
+            u32::from(DopplerError::Stale)
+        );
+    }
+
+    #[test]
+    fn test_load_keeps_consumer_error_codes() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = default_oracle_data(100, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &owner,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            error_code(load::<PriceFeed>(&account, &key).unwrap_err()),
+            doppler_consumer::ConsumerError::InvalidOwner as u32
+        );
+        assert_eq!(
+            error_code(load::<PriceFeed>(&account, &Pubkey::new_unique()).unwrap_err()),
+            doppler_consumer::ConsumerError::UnexpectedAccount as u32
+        );
+    }
+
+    #[test]
+    fn test_discriminator_matches_anchor() {
+        assert_eq!(
+            ANCHOR_DISCRIMINATOR,
+            hash(b"account:Oracle").to_bytes()[..8]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_oracle() {
+        let data = oracle_data(ANCHOR_DISCRIMINATOR, 42, 1_100_000);
+        let oracle = AnchorOracle::<PriceFeed>::try_deserialize(&mut &data[..]).unwrap();
+
+        assert_eq!(AnchorOracle::<PriceFeed>::LEN, data.len());
+        assert_eq!(oracle.sequence, 42);
+        assert_eq!(oracle.payload.price, 1_100_000);
+    }
+
+    /// A created oracle is zeroed until its first update writes the
+    /// discriminator.
+    #[test]
+    fn test_deserialize_rejects_missing_discriminator() {
+        let data = oracle_data([0; 8], 0, 0);
+
+        assert_eq!(
+            error_code(AnchorOracle::<PriceFeed>::try_deserialize(&mut &data[..]).unwrap_err()),
+            ErrorCode::AccountDiscriminatorMismatch as u32
+        );
+    }
+
+    #[test]
+    fn test_deserialize_rejects_short_data() {
+        let data = oracle_data(ANCHOR_DISCRIMINATOR, 42, 1_100_000);
+
+        assert_eq!(
+            error_code(AnchorOracle::<PriceFeed>::try_deserialize(&mut &data[..12]).unwrap_err()),
+            ErrorCode::AccountDidNotDeserialize as u32
+        );
+        assert_eq!(
+            error_code(AnchorOracle::<PriceFeed>::try_deserialize(&mut &data[..4]).unwrap_err()),
+            ErrorCode::AccountDiscriminatorNotFound as u32
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::{fs, path::Path};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{registry::FeedRegistry, transaction::Builder, Oracle};
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::{create, Payload, Result};
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// Sets up every feed of a registry manifest on a devnet or local cluster:
+/// funds the admin with `airdrop_sol`, creates the oracle accounts that
+/// don't exist yet and publishes the `price` a feed lists, if any:
+///
+/// ```toml
+/// admin = "<admin pubkey>"
+///
+/// [feeds."SOL/USDC"]
+/// payload = "PriceFeed"
+/// price = 150000000
+/// ```
+///
+/// Prints each feed's oracle address.
+pub fn bootstrap(
+    client: &RpcClient,
+    admin: &Keypair,
+    config: &Path,
+    airdrop_sol: f64,
+) -> Result<()> {
+    let manifest = fs::read_to_string(config)
+        .map_err(|err| format!("failed to read {}: {err}", config.display()))?;
+    let registry = FeedRegistry::from_toml(&manifest)?;
+    if registry.admin() != admin.pubkey() {
+        return Err(format!(
+            "{} lists admin {}, but the keypair is {}",
+            config.display(),
+            registry.admin(),
+            admin.pubkey()
+        )
+        .into());
+    }
+    let prices: toml::Table = toml::from_str(&manifest)?;
+    let cluster = registry.cluster();
+
+    if airdrop_sol > 0.0 {
+        airdrop(
+            client,
+            admin,
+            (airdrop_sol * LAMPORTS_PER_SOL as f64) as u64,
+        )?;
+    }
+
+    for (name, feed) in registry.iter() {
+        let payload = match feed.payload.as_str() {
+            "PriceFeed" => Payload::Price,
+            other => return Err(format!("{name}: unsupported payload {other}").into()),
+        };
+
+        if client.get_account(&feed.pubkey).is_err() {
+            if Pubkey::create_with_seed(&admin.pubkey(), name, &cluster.program_id())?
+                != feed.pubkey
+            {
+                return Err(format!(
+                    "{name}: oracle {} does not exist and is not derived from the feed name",
+                    feed.pubkey
+                )
+                .into());
+            }
+            create(client, admin, cluster, name, payload)?;
+
+            let price = prices
+                .get("feeds")
+                .and_then(|feeds| feeds.get(name))
+                .and_then(|feed| feed.get("price"));
+            if let Some(price) = price {
+                let price = price
+                    .as_integer()
+                    .and_then(|price| u64::try_from(price).ok())
+                    .ok_or_else(|| format!("{name}: price must be a u64"))?;
+
+                Builder::new(admin)
+                    .with_cluster(cluster)
+                    .add_oracle_update(
+                        feed.pubkey,
+                        Oracle {
+                            sequence: 1,
+                            payload: PriceFeed { price },
+                        },
+                    )
+                    .send_and_confirm(client)?;
+            }
+        }
+
+        println!("{name} {}", feed.pubkey);
+    }
+    Ok(())
+}
+
+/// Requests `lamports` for the admin and waits until they arrive.
+fn airdrop(client: &RpcClient, admin: &Keypair, lamports: u64) -> Result<()> {
+    let signature = client
+        .request_airdrop(&admin.pubkey(), lamports)
+        .map_err(|err| {
+            format!(
+                "airdrop failed, fund {} by hand or pass --airdrop 0: {err}",
+                admin.pubkey()
+            )
+        })?;
+    client.poll_for_signature(&signature)?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::{
+    fs,
+    mem::{offset_of, size_of},
+    path::Path,
+};
+
+use doppler_program::{PythPriceUpdate, SwitchboardResult};
+use doppler_sdk::{
+    codegen,
+    schema::{Field, FieldType, Schema},
+};
+
+use crate::{Result, PRICE_FEED};
+
+const PYTH_PRICE_UPDATE: Schema = Schema {
+    name: "PythPriceUpdate",
+    size: size_of::<PythPriceUpdate>(),
+    fields: &[
+        Field {
+            name: "write_authority",
+            offset: offset_of!(PythPriceUpdate, write_authority),
+            ty: FieldType::Pubkey,
+        },
+        Field {
+            name: "verification_level",
+            offset: offset_of!(PythPriceUpdate, verification_level),
+            ty: FieldType::U8,
+        },
+        Field {
+            name: "feed_id",
+            offset: offset_of!(PythPriceUpdate, feed_id),
+            ty: FieldType::Bytes(32),
+        },
+        Field {
+            name: "price",
+            offset: offset_of!(PythPriceUpdate, price),
+            ty: FieldType::I64,
+        },
+        Field {
+            name: "conf",
+            offset: offset_of!(PythPriceUpdate, conf),
+            ty: FieldType::U64,
+        },
+        Field {
+            name: "exponent",
+            offset: offset_of!(PythPriceUpdate, exponent),
+            ty: FieldType::I32,
+        },
+        Field {
+            name: "publish_time",
+            offset: offset_of!(PythPriceUpdate, publish_time),
+            ty: FieldType::I64,
+        },
+        Field {
+            name: "prev_publish_time",
+            offset: offset_of!(PythPriceUpdate, prev_publish_time),
+            ty: FieldType::I64,
+        },
+        Field {
+            name: "ema_price",
+            offset: offset_of!(PythPriceUpdate, ema_price),
+            ty: FieldType::I64,
+        },
+        Field {
+            name: "ema_conf",
+            offset: offset_of!(PythPriceUpdate, ema_conf),
+            ty: FieldType::U64,
+        },
+        Field {
+            name: "posted_slot",
+            offset: offset_of!(PythPriceUpdate, posted_slot),
+            ty: FieldType::U64,
+        },
+    ],
+};
+
+const SWITCHBOARD_RESULT: Schema = Schema {
+    name: "SwitchboardResult",
+    size: size_of::<SwitchboardResult>(),
+    fields: &[
+        Field {
+            name: "value",
+            offset: offset_of!(SwitchboardResult, value),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "std_dev",
+            offset: offset_of!(SwitchboardResult, std_dev),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "mean",
+            offset: offset_of!(SwitchboardResult, mean),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "range",
+            offset: offset_of!(SwitchboardResult, range),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "min_value",
+            offset: offset_of!(SwitchboardResult, min_value),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "max_value",
+            offset: offset_of!(SwitchboardResult, max_value),
+            ty: FieldType::I128,
+        },
+        Field {
+            name: "num_samples",
+            offset: offset_of!(SwitchboardResult, num_samples),
+            ty: FieldType::U8,
+        },
+        Field {
+            name: "submission_idx",
+            offset: offset_of!(SwitchboardResult, submission_idx),
+            ty: FieldType::U8,
+        },
+        Field {
+            name: "slot",
+            offset: offset_of!(SwitchboardResult, slot),
+            ty: FieldType::U64,
+        },
+        Field {
+            name: "min_slot",
+            offset: offset_of!(SwitchboardResult, min_slot),
+            ty: FieldType::U64,
+        },
+        Field {
+            name: "max_slot",
+            offset: offset_of!(SwitchboardResult, max_slot),
+            ty: FieldType::U64,
+        },
+    ],
+};
+
+/// The payloads the program can be built with.
+pub const STANDARD_PAYLOADS: [Schema; 3] = [PRICE_FEED, PYTH_PRICE_UPDATE, SWITCHBOARD_RESULT];
+
+/// Writes the TypeScript codecs of the standard payloads to `out`, and
+/// their `@solana/kit` codecs to `kit_out`.
+pub fn typescript(out: &Path, kit_out: &Path) -> Result<()> {
+    for (path, module) in [
+        (out, codegen::typescript(&STANDARD_PAYLOADS)),
+        (kit_out, codegen::kit(&STANDARD_PAYLOADS)),
+    ] {
+        fs::write(path, module)?;
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The committed codecs of the TypeScript SDK must match the payloads.
+    #[test]
+    fn test_typescript_payloads_are_current() {
+        assert!(
+            include_str!("../../ts/src/payloads.ts") == codegen::typescript(&STANDARD_PAYLOADS),
+            "ts/src/payloads.ts is stale, regenerate it with `doppler codegen`"
+        );
+        assert!(
+            include_str!("../../ts/src/kit/payloads.ts") == codegen::kit(&STANDARD_PAYLOADS),
+            "ts/src/kit/payloads.ts is stale, regenerate it with `doppler codegen`"
+        );
+    }
+}
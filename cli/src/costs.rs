@@ -0,0 +1,221 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{decode_doppler_ix, registry::FeedRegistry};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+
+use crate::{
+    export::{signatures, transaction},
+    Result,
+};
+
+/// Fee of each signature on a transaction.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+const LAMPORTS_PER_SOL: f64 = 1e9;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A transaction that updated one or more feeds, as stored in the cost
+/// database, one JSON object per line.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    signature: String,
+    slot: u64,
+    block_time: Option<i64>,
+    /// Feeds the transaction updated, which share its cost evenly.
+    feeds: Vec<String>,
+    failed: bool,
+    fee: u64,
+    /// The part of `fee` paid above the base fee per signature.
+    priority_fee: u64,
+    compute_units: Option<u64>,
+}
+
+/// Appends every transaction that updated a feed of the registry manifest
+/// at `config` since the last collection to the database at `db`, reading
+/// up to `limit` transactions per feed. Failed transactions are kept, as
+/// their fees are spent all the same.
+pub fn collect(client: &RpcClient, config: &Path, db: &Path, limit: usize) -> Result<()> {
+    let manifest = fs::read_to_string(config)
+        .map_err(|err| format!("failed to read {}: {err}", config.display()))?;
+    let registry = FeedRegistry::from_toml(&manifest)?;
+    let names: HashMap<Pubkey, &str> = registry
+        .iter()
+        .map(|(name, feed)| (feed.pubkey, name))
+        .collect();
+
+    let entries = read(db)?;
+    let mut known: HashSet<String> = entries
+        .iter()
+        .map(|entry| entry.signature.clone())
+        .collect();
+    let mut file = OpenOptions::new().create(true).append(true).open(db)?;
+
+    for (name, feed) in registry.iter() {
+        // Resume after the newest transaction already collected for the feed.
+        let until = entries
+            .iter()
+            .filter(|entry| entry.feeds.iter().any(|feed| feed == name))
+            .max_by_key(|entry| entry.slot)
+            .map(|entry| Signature::from_str(&entry.signature))
+            .transpose()?;
+
+        let mut collected = 0;
+        for status in signatures(client, &feed.pubkey, limit, until)?.iter().rev() {
+            if known.contains(&status.signature) {
+                continue;
+            }
+            let signature = Signature::from_str(&status.signature)?;
+            let tx = transaction(client, &signature)?;
+            let (Some(decoded), Some(meta)) =
+                (tx.transaction.transaction.decode(), tx.transaction.meta)
+            else {
+                eprintln!("{signature}: cannot decode transaction");
+                continue;
+            };
+
+            let account_keys = decoded.message.static_account_keys();
+            let mut feeds: Vec<String> = decoded
+                .message
+                .instructions()
+                .iter()
+                .filter_map(|ix| {
+                    decode_doppler_ix::<PriceFeed>(ix, account_keys, registry.cluster()).ok()
+                })
+                .filter_map(|update| names.get(&update.oracle_pubkey))
+                .map(|name| name.to_string())
+                .collect();
+            feeds.dedup();
+            // Not a publishing cost, e.g. the oracle's creation.
+            if feeds.is_empty() {
+                continue;
+            }
+
+            let base_fee = LAMPORTS_PER_SIGNATURE
+                * u64::from(decoded.message.header().num_required_signatures);
+            let entry = Entry {
+                signature: status.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                feeds,
+                failed: meta.err.is_some(),
+                fee: meta.fee,
+                priority_fee: meta.fee.saturating_sub(base_fee),
+                compute_units: meta.compute_units_consumed.into(),
+            };
+            writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+            known.insert(entry.signature);
+            collected += 1;
+        }
+
+        println!("{name}: {collected} new transactions");
+    }
+    Ok(())
+}
+
+/// What a feed cost over a period. Transactions updating several feeds are
+/// split evenly between them.
+#[derive(Default)]
+struct Totals {
+    updates: u64,
+    failed: u64,
+    fees: f64,
+    priority_fees: f64,
+    /// Fees of failed transactions.
+    wasted: f64,
+    compute_units: f64,
+}
+
+/// Prints what each feed in the database at `db` cost: landed and failed
+/// updates, fees with the priority part and what failed transactions
+/// wasted, and compute units per landed update. Only transactions of the
+/// last `since_days` are counted if given, and `daily` breaks the totals
+/// down by UTC day.
+pub fn report(db: &Path, since_days: Option<u64>, daily: bool) -> Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let since = since_days.map(|days| now - days as i64 * SECONDS_PER_DAY);
+
+    let mut totals = BTreeMap::<(Option<i64>, String), Totals>::new();
+    for entry in read(db)? {
+        if since.is_some_and(|since| entry.block_time.is_none_or(|time| time < since)) {
+            continue;
+        }
+        let day = entry
+            .block_time
+            .filter(|_| daily)
+            .map(|time| time.div_euclid(SECONDS_PER_DAY));
+        let share = entry.feeds.len() as f64;
+
+        for feed in &entry.feeds {
+            let totals = totals.entry((day, feed.clone())).or_default();
+            if entry.failed {
+                totals.failed += 1;
+                totals.wasted += entry.fee as f64 / share;
+            } else {
+                totals.updates += 1;
+                totals.compute_units += entry.compute_units.unwrap_or_default() as f64 / share;
+            }
+            totals.fees += entry.fee as f64 / share;
+            totals.priority_fees += entry.priority_fee as f64 / share;
+        }
+    }
+
+    println!(
+        "{:<10}  {:<16}  {:>8}  {:>6}  {:>12}  {:>12}  {:>12}  {:>9}",
+        "day", "feed", "updates", "failed", "fees SOL", "priority SOL", "wasted SOL", "CU/update"
+    );
+    for ((day, feed), totals) in &totals {
+        println!(
+            "{:<10}  {feed:<16}  {:>8}  {:>6}  {:>12.6}  {:>12.6}  {:>12.6}  {:>9.0}",
+            day.map_or_else(|| "all".to_string(), date),
+            totals.updates,
+            totals.failed,
+            totals.fees / LAMPORTS_PER_SOL,
+            totals.priority_fees / LAMPORTS_PER_SOL,
+            totals.wasted / LAMPORTS_PER_SOL,
+            totals.compute_units / totals.updates.max(1) as f64,
+        );
+    }
+    Ok(())
+}
+
+fn read(db: &Path) -> Result<Vec<Entry>> {
+    if !db.exists() {
+        return Ok(vec![]);
+    }
+
+    fs::read_to_string(db)?
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|err| format!("{}:{}: {err}", db.display(), i + 1).into())
+        })
+        .collect()
+}
+
+/// Formats days since the Unix epoch as a `YYYY-MM-DD` date.
+fn date(days: i64) -> String {
+    // Howard Hinnant's `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
@@ -0,0 +1,200 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{decode_doppler_ix, Cluster, PAYLOAD_OFFSET};
+use solana_client::{
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient},
+    rpc_config::RpcTransactionConfig,
+    rpc_response::RpcConfirmedTransactionStatusWithSignature,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding,
+};
+
+use crate::{Payload, Result};
+
+/// Signatures requested per page, the most the RPC method returns.
+const PAGE: usize = 1_000;
+
+/// An update of an oracle found in its transaction history.
+pub struct Record {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub signature: Signature,
+    pub sequence: u64,
+    /// The payload bytes of the instruction.
+    pub payload: Vec<u8>,
+    /// Fee of the transaction carrying the update.
+    pub fee: Option<u64>,
+    /// Compute units consumed by the transaction carrying the update.
+    pub compute_units: Option<u64>,
+}
+
+/// Writes every update of `oracle` found in its transaction history as CSV,
+/// oldest first: the slot, block time, signature, sequence, each payload
+/// field, and the fee and compute units of the transaction carrying it.
+///
+/// Up to `limit` of the most recent transactions are read, stopping early
+/// at `until`. Failed transactions are skipped.
+pub fn export(
+    client: &RpcClient,
+    cluster: Cluster,
+    oracle: &Pubkey,
+    payload: Payload,
+    limit: usize,
+    until: Option<&str>,
+    out: Option<&Path>,
+) -> Result<()> {
+    let schema = payload.schema();
+    let records = history(client, cluster, oracle, payload, limit, until)?;
+
+    let mut writer: BufWriter<Box<dyn Write>> = BufWriter::new(match out {
+        Some(out) => Box::new(File::create(out)?),
+        None => Box::new(io::stdout()),
+    });
+    let fields: Vec<&str> = schema.fields.iter().map(|field| field.name).collect();
+    writeln!(
+        writer,
+        "slot,block_time,signature,sequence,{},fee_lamports,compute_units",
+        fields.join(",")
+    )?;
+
+    for record in &records {
+        let values: Vec<String> = schema
+            .read(&record.payload)
+            .map(|(_, value)| value.map(|value| value.to_string()).unwrap_or_default())
+            .collect();
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{}",
+            record.slot,
+            record
+                .block_time
+                .map(|time| time.to_string())
+                .unwrap_or_default(),
+            record.signature,
+            record.sequence,
+            values.join(","),
+            record.fee.map(|fee| fee.to_string()).unwrap_or_default(),
+            record
+                .compute_units
+                .map(|units| units.to_string())
+                .unwrap_or_default(),
+        )?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the updates of `oracle` from up to `limit` of its most recent
+/// transactions, stopping early at `until`, and returns them oldest first.
+/// Failed transactions are skipped.
+pub fn history(
+    client: &RpcClient,
+    cluster: Cluster,
+    oracle: &Pubkey,
+    payload: Payload,
+    limit: usize,
+    until: Option<&str>,
+) -> Result<Vec<Record>> {
+    let until = until.map(Signature::from_str).transpose()?;
+    let statuses = signatures(client, oracle, limit, until)?;
+
+    let mut records = vec![];
+    for status in statuses.iter().rev().filter(|status| status.err.is_none()) {
+        let signature = Signature::from_str(&status.signature)?;
+        let tx = transaction(client, &signature)?;
+        let Some(transaction) = tx.transaction.transaction.decode() else {
+            eprintln!("{signature}: cannot decode transaction");
+            continue;
+        };
+        let (fee, compute_units) = tx.transaction.meta.map_or((None, None), |meta| {
+            (
+                Some(meta.fee),
+                Option::<u64>::from(meta.compute_units_consumed),
+            )
+        });
+
+        // Updates are sent in legacy transactions, so the static keys are
+        // all the accounts there are.
+        let account_keys = transaction.message.static_account_keys();
+        for ix in transaction.message.instructions() {
+            let update = match payload {
+                Payload::Price => decode_doppler_ix::<PriceFeed>(ix, account_keys, cluster)
+                    .map(|update| (update.oracle_pubkey, update.oracle.sequence)),
+            };
+            let Ok((oracle_pubkey, sequence)) = update else {
+                continue;
+            };
+            if oracle_pubkey != *oracle {
+                continue;
+            }
+
+            records.push(Record {
+                slot: tx.slot,
+                block_time: tx.block_time,
+                signature,
+                sequence,
+                payload: ix.data[PAYLOAD_OFFSET..].to_vec(),
+                fee,
+                compute_units,
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+/// Lists up to `limit` of the most recent transactions touching `account`,
+/// failed ones included, newest first, stopping early at `until`.
+pub fn signatures(
+    client: &RpcClient,
+    account: &Pubkey,
+    limit: usize,
+    until: Option<Signature>,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let mut statuses = vec![];
+    let mut before = None;
+    while statuses.len() < limit {
+        let page = client.get_signatures_for_address_with_config(
+            account,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(PAGE.min(limit - statuses.len())),
+                commitment: Some(CommitmentConfig::confirmed()),
+            },
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(Signature::from_str(&last.signature)?);
+        statuses.extend(page);
+    }
+
+    Ok(statuses)
+}
+
+/// Fetches a confirmed transaction with its status metadata.
+pub fn transaction(
+    client: &RpcClient,
+    signature: &Signature,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    Ok(client.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?)
+}
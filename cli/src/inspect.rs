@@ -0,0 +1,108 @@
+use doppler_sdk::{
+    schema::{Field, Schema},
+    Cluster, PAYLOAD_OFFSET, SEQUENCE_OFFSET,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_pubkey::Pubkey;
+
+use crate::{Payload, Result};
+
+/// Bytes shown per hex row.
+const ROW: usize = 16;
+
+/// Prints the oracle account as hex, each region labelled with what it holds
+/// under `payload`'s layout.
+pub fn inspect(
+    client: &RpcClient,
+    cluster: Cluster,
+    oracle: &Pubkey,
+    payload: Payload,
+) -> Result<()> {
+    let account = client.get_account(oracle)?;
+    let schema = payload.schema();
+
+    println!("account:  {oracle}");
+    println!("owner:    {}", account.owner);
+    if account.owner != cluster.program_id() {
+        println!("          not the Doppler program {}", cluster.program_id());
+    }
+    println!(
+        "length:   {} bytes, {} expected for {}",
+        account.data.len(),
+        PAYLOAD_OFFSET + schema.size,
+        schema.name
+    );
+    println!();
+
+    for (offset, bytes, label) in annotate(&account.data, &schema) {
+        for (row, chunk) in bytes.chunks(ROW).enumerate() {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02x}")).collect();
+            let label = if row == 0 { label.as_str() } else { "" };
+            println!(
+                "{:#06x}  {:<width$}  {label}",
+                offset + row * ROW,
+                hex.join(" "),
+                width = ROW * 3 - 1
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Splits account data into the sequence, each payload field, the padding
+/// between them and whatever trails the payload, with a label for each.
+fn annotate<'a>(data: &'a [u8], schema: &Schema) -> Vec<(usize, &'a [u8], String)> {
+    let mut regions = vec![];
+    let mut push = |start: usize, end: usize, label: String| {
+        let (start, end) = (start.min(data.len()), end.min(data.len()));
+        if start < end {
+            regions.push((start, &data[start..end], label));
+        }
+    };
+
+    let sequence = data
+        .get(SEQUENCE_OFFSET..PAYLOAD_OFFSET)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u64::from_le_bytes);
+    push(
+        SEQUENCE_OFFSET,
+        PAYLOAD_OFFSET,
+        match sequence {
+            Some(sequence) => format!("sequence  {sequence}"),
+            None => "sequence  (truncated)".to_string(),
+        },
+    );
+    let mut cursor = PAYLOAD_OFFSET;
+
+    let payload = data.get(PAYLOAD_OFFSET..).unwrap_or_default();
+    let mut fields: Vec<&Field> = schema.fields.iter().collect();
+    fields.sort_by_key(|field| field.offset);
+    for field in fields {
+        let start = PAYLOAD_OFFSET + field.offset;
+        if cursor < start {
+            push(cursor, start, "padding".to_string());
+        }
+
+        let value = field
+            .read(payload)
+            .map_or_else(|| "(truncated)".to_string(), |value| value.to_string());
+        push(
+            start,
+            start + field.ty.size(),
+            format!("{}.{}  {value}", schema.name, field.name),
+        );
+        cursor = start + field.ty.size();
+    }
+
+    let end = PAYLOAD_OFFSET + schema.size;
+    if cursor < end {
+        push(cursor, end, "padding".to_string());
+    }
+    push(
+        end,
+        data.len(),
+        format!("trailing  {} bytes", data.len().saturating_sub(end)),
+    );
+
+    regions
+}
@@ -0,0 +1,117 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
+
+use crate::Result;
+
+const BASE58: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Grinds keypairs on `threads` threads until one's address starts with
+/// `prefix`, saves it to `outfile` and prints the `ADMIN` constant the
+/// program checks signers against. With `admin_rs`, the constant in that
+/// file is replaced too.
+pub fn keygen(
+    prefix: &str,
+    ignore_case: bool,
+    threads: usize,
+    outfile: &Path,
+    admin_rs: Option<&Path>,
+) -> Result<()> {
+    if let Some(c) = prefix.chars().find(|c| !BASE58.contains(*c)) {
+        return Err(format!("{c:?} never appears in base58 addresses").into());
+    }
+    if outfile.exists() {
+        return Err(format!("{} already exists", outfile.display()).into());
+    }
+
+    let found = AtomicBool::new(false);
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let (found, tx) = (&found, tx.clone());
+            scope.spawn(move || {
+                while !found.load(Ordering::Relaxed) {
+                    let keypair = Keypair::new();
+                    if matches(&keypair.pubkey(), prefix, ignore_case) {
+                        found.store(true, Ordering::Relaxed);
+                        let _ = tx.send(keypair);
+                    }
+                }
+            });
+        }
+    });
+    let keypair = rx.recv()?;
+
+    keypair
+        .write_to_file(outfile)
+        .map_err(|err| format!("failed to write {}: {err}", outfile.display()))?;
+    let constant = admin_constant(&keypair.pubkey());
+
+    if let Some(admin_rs) = admin_rs {
+        let source = fs::read_to_string(admin_rs)?;
+        let source = replace_constant(&source, &constant)
+            .ok_or_else(|| format!("no ADMIN constant found in {}", admin_rs.display()))?;
+        fs::write(admin_rs, source)?;
+    }
+
+    println!("wrote {} to {}", keypair.pubkey(), outfile.display());
+    println!();
+    print!("{constant}");
+    Ok(())
+}
+
+fn matches(pubkey: &Pubkey, prefix: &str, ignore_case: bool) -> bool {
+    let address = pubkey.to_string();
+    let Some(head) = address.get(..prefix.len()) else {
+        return false;
+    };
+
+    if ignore_case {
+        head.eq_ignore_ascii_case(prefix)
+    } else {
+        head == prefix
+    }
+}
+
+/// The `ADMIN` constant for `admin`, laid out as in `doppler/src/admin.rs`.
+fn admin_constant(admin: &Pubkey) -> String {
+    let mut constant = format!("// {admin}\npub const ADMIN: [u8; 32] = [\n");
+    for row in admin.to_bytes().chunks(16) {
+        let bytes: Vec<String> = row.iter().map(|byte| format!("{byte:#04x}")).collect();
+        let _ = writeln!(constant, "    {},", bytes.join(", "));
+    }
+    constant.push_str("];\n");
+    constant
+}
+
+/// Replaces the commented `ADMIN` constant in `source` with `constant`.
+fn replace_constant(source: &str, constant: &str) -> Option<String> {
+    let declaration = source.find("pub const ADMIN: [u8; 32]")?;
+    let start = source[..declaration]
+        .trim_end_matches('\n')
+        .rfind('\n')
+        .map_or(0, |newline| newline + 1);
+    let start = if source[start..].starts_with("//") {
+        start
+    } else {
+        declaration
+    };
+    let end = declaration + source[declaration..].find("];")? + "];\n".len();
+
+    Some(format!(
+        "{}{constant}{}",
+        &source[..start],
+        source.get(end..).unwrap_or_default()
+    ))
+}
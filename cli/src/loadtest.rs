@@ -0,0 +1,207 @@
+use std::{
+    collections::BTreeMap,
+    thread,
+    time::{Duration, Instant},
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{transaction::Builder, Cluster, Oracle};
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+
+use crate::{create, fetch, refuse_mainnet, Payload, Result};
+
+/// What one update cost to send.
+struct Sample {
+    latency: Duration,
+    /// The fee paid, or why the update failed.
+    outcome: std::result::Result<u64, String>,
+}
+
+/// Publishes `rate` updates per second in total across `feeds` oracles for
+/// `duration`, then prints how many landed, their confirmation latency
+/// percentiles and the fees spent.
+///
+/// The oracles are seeded `<prefix>-0`, `<prefix>-1`, ... and created first
+/// if missing. Each feed is driven by its own thread with one update in
+/// flight at a time, so a feed whose updates confirm slower than its share
+/// of the rate skips ticks instead of racing its own sequence.
+#[allow(clippy::too_many_arguments)]
+pub fn load_test(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    prefix: &str,
+    feeds: usize,
+    rate: f64,
+    duration: Duration,
+    unit_price: Option<u64>,
+) -> Result<()> {
+    refuse_mainnet(client, "load test")?;
+    if feeds == 0 {
+        return Err("at least one feed is needed".into());
+    }
+    if !rate.is_finite() || rate <= 0.0 {
+        return Err("rate must be a positive number".into());
+    }
+
+    let mut oracles = vec![];
+    for i in 0..feeds {
+        let name = format!("{prefix}-{i}");
+        let oracle = Pubkey::create_with_seed(&admin.pubkey(), &name, &cluster.program_id())?;
+        let sequence = match fetch(client, cluster, &oracle) {
+            Ok(oracle) => oracle.sequence,
+            Err(_) => {
+                create(client, admin, cluster, &name, Payload::Price)?;
+                0
+            }
+        };
+        oracles.push((oracle, sequence));
+    }
+
+    let mut template = Builder::new(admin).with_cluster(cluster);
+    if let Some(unit_price) = unit_price {
+        template = template.with_unit_price(unit_price);
+    }
+    let template = &template;
+
+    let interval = Duration::from_secs_f64(feeds as f64 / rate);
+    let start = Instant::now();
+    let end = start + duration;
+    println!(
+        "sending {rate} updates/s across {feeds} feeds for {}s, one per feed every {}ms",
+        duration.as_secs(),
+        interval.as_millis()
+    );
+
+    let (samples, skipped) = thread::scope(|scope| {
+        let threads: Vec<_> = oracles
+            .iter()
+            .enumerate()
+            .map(|(i, &(oracle, sequence))| {
+                // Stagger the feeds so their sends are spread over the interval.
+                let first = start + interval.mul_f64(i as f64 / feeds as f64);
+                scope.spawn(move || drive(client, template, oracle, sequence, first, end, interval))
+            })
+            .collect();
+
+        threads
+            .into_iter()
+            .fold((vec![], 0), |(mut samples, skipped), thread| {
+                let (feed_samples, feed_skipped) =
+                    thread.join().expect("load test thread panicked");
+                samples.extend(feed_samples);
+                (samples, skipped + feed_skipped)
+            })
+    });
+
+    report(&samples, skipped, start.elapsed());
+    Ok(())
+}
+
+/// Sends updates to one oracle every `interval` from `first` until `end`,
+/// returning a sample per send and the number of ticks skipped while a send
+/// was still confirming.
+fn drive(
+    client: &RpcClient,
+    template: &Builder,
+    oracle: Pubkey,
+    mut sequence: u64,
+    first: Instant,
+    end: Instant,
+    interval: Duration,
+) -> (Vec<Sample>, u64) {
+    let mut samples = vec![];
+    let mut skipped = 0;
+    let mut tick = first;
+
+    while tick < end {
+        thread::sleep(tick.saturating_duration_since(Instant::now()));
+
+        sequence += 1;
+        let sent = Instant::now();
+        let outcome = template
+            .clone()
+            .add_oracle_update(
+                oracle,
+                Oracle {
+                    sequence,
+                    payload: PriceFeed { price: sequence },
+                },
+            )
+            .send_and_confirm(client)
+            .map(|outcome| outcome.fee_lamports)
+            .map_err(|err| err.to_string());
+        samples.push(Sample {
+            latency: sent.elapsed(),
+            outcome,
+        });
+
+        tick += interval;
+        let now = Instant::now();
+        while tick < now && tick < end {
+            tick += interval;
+            skipped += 1;
+        }
+    }
+
+    (samples, skipped)
+}
+
+fn report(samples: &[Sample], skipped: u64, elapsed: Duration) {
+    let mut latencies: Vec<Duration> = samples
+        .iter()
+        .filter(|sample| sample.outcome.is_ok())
+        .map(|sample| sample.latency)
+        .collect();
+    latencies.sort();
+    let landed = latencies.len();
+    let failed = samples.len() - landed;
+    let fees: u64 = samples
+        .iter()
+        .filter_map(|sample| sample.outcome.as_ref().ok())
+        .sum();
+
+    println!();
+    println!("sent:      {}", samples.len());
+    println!(
+        "landed:    {landed} ({:.1}/s)",
+        landed as f64 / elapsed.as_secs_f64()
+    );
+    println!(
+        "failed:    {failed} ({:.1}%)",
+        100.0 * failed as f64 / samples.len().max(1) as f64
+    );
+    println!("skipped:   {skipped} ticks, sends confirming slower than the interval");
+
+    if !latencies.is_empty() {
+        let percentile = |p: f64| {
+            let rank = (p * landed as f64).ceil() as usize;
+            latencies[rank.clamp(1, landed) - 1].as_millis()
+        };
+        println!(
+            "latency:   p50 {}ms, p90 {}ms, p99 {}ms, max {}ms",
+            percentile(0.5),
+            percentile(0.9),
+            percentile(0.99),
+            percentile(1.0)
+        );
+        println!(
+            "fees:      {fees} lamports, {} per landed update",
+            fees / landed as u64
+        );
+    }
+
+    let mut errors = BTreeMap::<&str, usize>::new();
+    for err in samples
+        .iter()
+        .filter_map(|sample| sample.outcome.as_ref().err())
+    {
+        *errors.entry(err).or_default() += 1;
+    }
+    for (err, count) in errors {
+        println!("error:     {count}x {err}");
+    }
+}
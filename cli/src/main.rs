@@ -0,0 +1,602 @@
+use std::{
+    error::Error,
+    mem::{offset_of, size_of},
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use doppler_program::PriceFeed;
+use doppler_sdk::{
+    schema::{Field, FieldType, Schema},
+    transaction::Builder,
+    Cluster, Oracle,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
+use solana_system_interface::instruction::create_account_with_seed;
+use solana_transaction::Transaction;
+
+mod bootstrap;
+mod codegen;
+mod costs;
+mod export;
+mod inspect;
+mod keygen;
+mod loadtest;
+mod migrate;
+mod replay;
+mod snapshot;
+mod verify;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// Genesis hash of mainnet-beta, which test tooling refuses to write to.
+const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+/// Manage Doppler oracles.
+#[derive(Parser)]
+#[command(name = "doppler", version)]
+struct Cli {
+    /// RPC endpoint to use.
+    #[arg(long, short, global = true, default_value = "http://localhost:8899")]
+    url: String,
+
+    /// Admin keypair. Defaults to the Solana CLI keypair.
+    #[arg(long, short, global = true)]
+    keypair: Option<PathBuf>,
+
+    /// Program ID of a Doppler deployment other than the default one.
+    #[arg(long, global = true)]
+    program_id: Option<Pubkey>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// Feeds are given either as an oracle address or as the seed the oracle
+/// account was created with, e.g. `SOL/USDC`.
+#[derive(Subcommand)]
+enum Command {
+    /// Create the oracle account for a feed, derived from the admin and the
+    /// feed name.
+    Init {
+        feed: String,
+        #[arg(long, value_enum, default_value_t = Payload::Price)]
+        payload: Payload,
+    },
+    /// Publish a new price one sequence past the current one.
+    Update {
+        feed: String,
+        #[arg(long)]
+        price: u64,
+    },
+    /// Print the current sequence and payload of a feed.
+    Read { feed: String },
+    /// Fund the admin and create every oracle listed in a feed registry
+    /// manifest, publishing their initial prices. For devnet and local
+    /// clusters.
+    Bootstrap {
+        #[arg(long)]
+        config: PathBuf,
+        /// SOL to airdrop to the admin first, 0 to skip.
+        #[arg(long, default_value_t = 1.0)]
+        airdrop: f64,
+    },
+    /// Dump a feed's account as annotated hex: the sequence, each payload
+    /// field decoded per `--payload` and any trailing bytes.
+    Inspect {
+        feed: String,
+        #[arg(long, value_enum, default_value_t = Payload::Price)]
+        payload: Payload,
+    },
+    /// Poll a feed and print every new value.
+    Watch {
+        feed: String,
+        #[arg(long, default_value_t = 400)]
+        interval_ms: u64,
+    },
+    /// Write a feed's update history, read from its transactions, as CSV.
+    Export {
+        feed: String,
+        #[arg(long, value_enum, default_value_t = Payload::Price)]
+        payload: Payload,
+        /// Most recent transactions to read.
+        #[arg(long, default_value_t = 1_000)]
+        limit: usize,
+        /// Stop at this transaction signature, e.g. the last one exported.
+        #[arg(long)]
+        until: Option<String>,
+        /// File to write. Defaults to stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Save every oracle of a feed registry manifest at a fixed interval,
+    /// as timestamped JSON files in a directory or an object store.
+    Snapshot {
+        #[arg(long)]
+        config: PathBuf,
+        #[arg(long, default_value = "snapshots")]
+        dir: PathBuf,
+        /// Upload each snapshot with `PUT <url>/<file name>` instead of
+        /// writing it to `--dir`.
+        #[arg(long)]
+        upload_url: Option<String>,
+        /// Header sent with uploads, as `Name: value`. Repeatable.
+        #[arg(long = "header", requires = "upload_url")]
+        headers: Vec<String>,
+        #[arg(long, default_value_t = 60)]
+        interval_secs: u64,
+        /// Take a single snapshot and exit, e.g. when run from cron.
+        #[arg(long)]
+        once: bool,
+    },
+    /// Check that a feed's sequences only ever increased, read from its
+    /// transactions, and flag unusually large sequence gaps and silences.
+    Verify {
+        feed: String,
+        #[arg(long, value_enum, default_value_t = Payload::Price)]
+        payload: Payload,
+        /// Most recent transactions to read.
+        #[arg(long, default_value_t = 1_000)]
+        limit: usize,
+        /// Stop at this transaction signature.
+        #[arg(long)]
+        until: Option<String>,
+        /// Flag sequence steps and pauses this many times the median.
+        #[arg(long, default_value_t = 10)]
+        gap_factor: u64,
+        /// Flag pauses between updates longer than this instead.
+        #[arg(long)]
+        max_silence_secs: Option<i64>,
+    },
+    /// Grind an admin keypair whose address starts with `prefix` and print
+    /// the `ADMIN` constant to build the program with.
+    Keygen {
+        prefix: String,
+        #[arg(long)]
+        ignore_case: bool,
+        /// Threads to grind on. Defaults to one per core.
+        #[arg(long)]
+        threads: Option<usize>,
+        #[arg(long, default_value = "admin-keypair.json")]
+        outfile: PathBuf,
+        /// Also replace the constant in this file, e.g. `doppler/src/admin.rs`.
+        #[arg(long)]
+        write_admin: Option<PathBuf>,
+    },
+    /// Write the TypeScript SDK's payload codecs, generated from the
+    /// layouts of the payloads the program can be built with.
+    Codegen {
+        #[arg(long, default_value = "ts/src/payloads.ts")]
+        out: PathBuf,
+        /// Where to write the `@solana/kit` codecs.
+        #[arg(long, default_value = "ts/src/kit/payloads.ts")]
+        kit_out: PathBuf,
+    },
+    /// Replay recorded prices into a feed, keeping their original spacing.
+    /// Refuses to run against mainnet-beta.
+    Replay {
+        feed: String,
+        /// CSV file of `timestamp_ms,price` rows.
+        #[arg(long, required_unless_present = "binance", conflicts_with = "binance")]
+        csv: Option<PathBuf>,
+        /// Binance symbol to fetch klines of instead, e.g. `SOLUSDC`.
+        #[arg(long)]
+        binance: Option<String>,
+        /// Kline interval, e.g. `1s`, `1m` or `1h`.
+        #[arg(long, default_value = "1m")]
+        kline_interval: String,
+        /// First kline to fetch, in milliseconds since the epoch. Defaults
+        /// to the most recent ones.
+        #[arg(long)]
+        start_ms: Option<u64>,
+        /// Klines to fetch, at most 1000.
+        #[arg(long, default_value_t = 500)]
+        limit: u32,
+        /// Factor the decimal prices are multiplied by before publishing.
+        #[arg(long, default_value_t = 1e6)]
+        scale: f64,
+        /// Replay this many times faster than recorded.
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+    /// Create a Doppler oracle for each Pyth or Switchboard feed in a
+    /// migration plan, seeded with the source's current price, and write a
+    /// feed registry manifest mapping them.
+    Migrate {
+        /// TOML plan listing each symbol's `pyth` or `switchboard` account.
+        #[arg(long)]
+        plan: PathBuf,
+        /// Manifest to write.
+        #[arg(long, default_value = "feeds.toml")]
+        out: PathBuf,
+        /// Read the sources and write the manifest without sending anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Drive synthetic updates across several feeds and report landing
+    /// latency, failures and fees. Refuses to run against mainnet-beta.
+    LoadTest {
+        /// Total updates per second across all feeds.
+        #[arg(long, default_value_t = 10.0)]
+        rate: f64,
+        /// Feeds to spread the updates over, created if missing.
+        #[arg(long, default_value_t = 4)]
+        feeds: usize,
+        #[arg(long, default_value_t = 30)]
+        duration_secs: u64,
+        /// Compute unit price in micro-lamports.
+        #[arg(long)]
+        unit_price: Option<u64>,
+        /// Seed prefix of the feeds, numbered from 0.
+        #[arg(long, default_value = "load-test")]
+        prefix: String,
+    },
+    /// Track what publishing costs per feed: fees, priority fees, compute
+    /// units and fees lost to failed transactions.
+    Costs {
+        #[command(subcommand)]
+        command: CostsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum CostsCommand {
+    /// Append the transactions that updated the feeds of a feed registry
+    /// manifest since the last run to the cost database.
+    Collect {
+        #[arg(long)]
+        config: PathBuf,
+        #[arg(long, default_value = "costs.jsonl")]
+        db: PathBuf,
+        /// Most recent transactions to read per feed.
+        #[arg(long, default_value_t = 1_000)]
+        limit: usize,
+    },
+    /// Print per-feed totals from the cost database.
+    Report {
+        #[arg(long, default_value = "costs.jsonl")]
+        db: PathBuf,
+        /// Only count transactions of the last this many days.
+        #[arg(long)]
+        since_days: Option<u64>,
+        /// Break the totals down by UTC day.
+        #[arg(long)]
+        daily: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Payload {
+    /// `PriceFeed { price: u64 }`, the payload the program is built with.
+    Price,
+}
+
+const PRICE_FEED: Schema = Schema {
+    name: "PriceFeed",
+    size: size_of::<PriceFeed>(),
+    fields: &[Field {
+        name: "price",
+        offset: offset_of!(PriceFeed, price),
+        ty: FieldType::U64,
+    }],
+};
+
+impl Payload {
+    /// Layout of the payload, for tools that print its fields.
+    const fn schema(self) -> Schema {
+        match self {
+            Self::Price => PRICE_FEED,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+
+    // Doesn't use an admin keypair, as it makes one.
+    if let Command::Keygen {
+        prefix,
+        ignore_case,
+        threads,
+        outfile,
+        write_admin,
+    } = &cli.command
+    {
+        let threads = threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+        return keygen::keygen(
+            prefix,
+            *ignore_case,
+            threads,
+            outfile,
+            write_admin.as_deref(),
+        );
+    }
+
+    // Only writes a file.
+    if let Command::Codegen { out, kit_out } = &cli.command {
+        return codegen::typescript(out, kit_out);
+    }
+
+    let client = RpcClient::new(cli.url.clone());
+    let cluster = cli.program_id.map_or(Cluster::Mainnet, Cluster::Custom);
+
+    // Only reads the chain, for the feeds of a manifest.
+    if let Command::Costs { command } = &cli.command {
+        return match command {
+            CostsCommand::Collect { config, db, limit } => {
+                costs::collect(&client, config, db, *limit)
+            }
+            CostsCommand::Report {
+                db,
+                since_days,
+                daily,
+            } => costs::report(db, *since_days, *daily),
+        };
+    }
+
+    let admin = read_keypair(cli.keypair.as_ref())?;
+
+    match &cli.command {
+        Command::Init { feed, payload } => init(&client, &admin, cluster, feed, *payload),
+        Command::Update { feed, price } => update(&client, &admin, cluster, feed, *price),
+        Command::Bootstrap { config, airdrop } => {
+            bootstrap::bootstrap(&client, &admin, config, *airdrop)
+        }
+        Command::Read { feed } => read(&client, cluster, &resolve(&admin, cluster, feed)?),
+        Command::Inspect { feed, payload } => {
+            inspect::inspect(&client, cluster, &resolve(&admin, cluster, feed)?, *payload)
+        }
+        Command::Watch { feed, interval_ms } => watch(
+            &client,
+            cluster,
+            &resolve(&admin, cluster, feed)?,
+            Duration::from_millis(*interval_ms),
+        ),
+        Command::Replay {
+            feed,
+            csv,
+            binance,
+            kline_interval,
+            start_ms,
+            limit,
+            scale,
+            speed,
+        } => {
+            let points = match (csv, binance) {
+                (Some(csv), _) => replay::read_csv(csv, *scale)?,
+                (None, Some(symbol)) => {
+                    replay::fetch_binance(symbol, kline_interval, *start_ms, *limit, *scale)?
+                }
+                (None, None) => unreachable!("clap requires --csv or --binance"),
+            };
+            let oracle = resolve(&admin, cluster, feed)?;
+
+            replay::replay(&client, &admin, cluster, oracle, &points, *speed)
+        }
+        Command::Export {
+            feed,
+            payload,
+            limit,
+            until,
+            out,
+        } => export::export(
+            &client,
+            cluster,
+            &resolve(&admin, cluster, feed)?,
+            *payload,
+            *limit,
+            until.as_deref(),
+            out.as_deref(),
+        ),
+        Command::Verify {
+            feed,
+            payload,
+            limit,
+            until,
+            gap_factor,
+            max_silence_secs,
+        } => {
+            let oracle = resolve(&admin, cluster, feed)?;
+            let records = export::history(
+                &client,
+                cluster,
+                &oracle,
+                *payload,
+                *limit,
+                until.as_deref(),
+            )?;
+
+            verify::verify(&oracle, &records, *gap_factor, *max_silence_secs)
+        }
+        Command::Snapshot {
+            config,
+            dir,
+            upload_url,
+            headers,
+            interval_secs,
+            once,
+        } => {
+            let headers = headers
+                .iter()
+                .map(|header| {
+                    header
+                        .split_once(':')
+                        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+                        .ok_or_else(|| format!("header {header:?} is not `Name: value`"))
+                })
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let target = match upload_url {
+                Some(url) => snapshot::Target::Http {
+                    url,
+                    headers: &headers,
+                },
+                None => snapshot::Target::Dir(dir),
+            };
+
+            snapshot::snapshot(
+                &client,
+                config,
+                &target,
+                Duration::from_secs(*interval_secs),
+                *once,
+            )
+        }
+        Command::Migrate { plan, out, dry_run } => {
+            migrate::migrate(&client, &admin, cluster, plan, out, *dry_run)
+        }
+        Command::LoadTest {
+            rate,
+            feeds,
+            duration_secs,
+            unit_price,
+            prefix,
+        } => loadtest::load_test(
+            &client,
+            &admin,
+            cluster,
+            prefix,
+            *feeds,
+            *rate,
+            Duration::from_secs(*duration_secs),
+            *unit_price,
+        ),
+        Command::Keygen { .. } | Command::Codegen { .. } | Command::Costs { .. } => {
+            unreachable!("handled before reading the keypair")
+        }
+    }
+}
+
+fn init(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    feed: &str,
+    payload: Payload,
+) -> Result<()> {
+    println!("{}", create(client, admin, cluster, feed, payload)?);
+    Ok(())
+}
+
+/// Creates the oracle account of `feed`, returning its address.
+fn create(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    feed: &str,
+    payload: Payload,
+) -> Result<Pubkey> {
+    let space = match payload {
+        Payload::Price => Oracle::<PriceFeed>::LEN,
+    };
+    let oracle = Pubkey::create_with_seed(&admin.pubkey(), feed, &cluster.program_id())?;
+    let lamports = client.get_minimum_balance_for_rent_exemption(space)?;
+
+    let ix = create_account_with_seed(
+        &admin.pubkey(),
+        &oracle,
+        &admin.pubkey(),
+        feed,
+        lamports,
+        space as u64,
+        &cluster.program_id(),
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&admin.pubkey()),
+        &[admin],
+        client.get_latest_blockhash()?,
+    );
+    client.send_and_confirm_transaction(&tx)?;
+
+    Ok(oracle)
+}
+
+fn update(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    feed: &str,
+    price: u64,
+) -> Result<()> {
+    let oracle = resolve(admin, cluster, feed)?;
+
+    let outcome = Builder::new(admin)
+        .with_cluster(cluster)
+        .add_oracle_update_auto_seq(client, oracle, PriceFeed { price })?
+        .send_and_confirm(client)?;
+
+    println!("{}", outcome.signature);
+    Ok(())
+}
+
+fn read(client: &RpcClient, cluster: Cluster, oracle: &Pubkey) -> Result<()> {
+    let oracle = fetch(client, cluster, oracle)?;
+
+    println!("sequence: {}", oracle.sequence);
+    println!("price:    {}", oracle.payload.price);
+    Ok(())
+}
+
+fn watch(client: &RpcClient, cluster: Cluster, oracle: &Pubkey, interval: Duration) -> Result<()> {
+    let mut last_sequence = None;
+
+    loop {
+        match fetch(client, cluster, oracle) {
+            Ok(oracle) if last_sequence < Some(oracle.sequence) => {
+                last_sequence = Some(oracle.sequence);
+                println!("{} {}", oracle.sequence, oracle.payload.price);
+            }
+            Ok(_) => {}
+            Err(err) => eprintln!("{err}"),
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+/// Fails if `client` is connected to mainnet-beta, for commands that write
+/// throwaway data.
+fn refuse_mainnet(client: &RpcClient, action: &str) -> Result<()> {
+    if client.get_genesis_hash()?.to_string() == MAINNET_GENESIS_HASH {
+        return Err(format!("refusing to {action} on mainnet-beta").into());
+    }
+    Ok(())
+}
+
+/// Fetches the oracle, refusing accounts not owned by the program.
+fn fetch(client: &RpcClient, cluster: Cluster, oracle: &Pubkey) -> Result<Oracle<PriceFeed>> {
+    let account = client.get_account(oracle)?;
+
+    Ok(Oracle::try_from_account(&account, cluster)?)
+}
+
+/// Parses `feed` as an oracle address, falling back to the address derived
+/// from the admin with `feed` as the seed.
+fn resolve(admin: &Keypair, cluster: Cluster, feed: &str) -> Result<Pubkey> {
+    match feed.parse() {
+        Ok(oracle) => Ok(oracle),
+        Err(_) => Ok(Pubkey::create_with_seed(
+            &admin.pubkey(),
+            feed,
+            &cluster.program_id(),
+        )?),
+    }
+}
+
+fn read_keypair(path: Option<&PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path.clone(),
+        None => [&std::env::var("HOME")?, ".config", "solana", "id.json"]
+            .iter()
+            .collect(),
+    };
+
+    Keypair::read_from_file(&path)
+        .map_err(|err| format!("failed to read keypair {}: {err}", path.display()).into())
+}
@@ -0,0 +1,235 @@
+use std::{collections::BTreeMap, fmt::Write, fs, path::Path};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{
+    rescale,
+    switchboard::{self, PullFeedResult},
+    transaction::Builder,
+    Cluster, Oracle,
+};
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::{pubkey, Pubkey};
+use solana_signer::Signer;
+
+use crate::{create, fetch, Payload, Result};
+
+/// Pyth Solana Receiver program, owner of `PriceUpdateV2` accounts.
+const PYTH_RECEIVER_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// Anchor discriminator of `PriceUpdateV2`.
+const PRICE_UPDATE_V2: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// Offset of `verification_level`, after the discriminator and the write
+/// authority.
+const VERIFICATION_LEVEL_OFFSET: usize = 40;
+
+/// Feeds to migrate, keyed by the symbol their Doppler oracle is seeded
+/// with:
+///
+/// ```toml
+/// exponent = -6
+///
+/// [feeds."SOL/USD"]
+/// pyth = "<PriceUpdateV2 account>"
+///
+/// [feeds."JUP/USD"]
+/// switchboard = "<pull feed account>"
+/// exponent = -8
+/// ```
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Plan {
+    /// Power of ten prices are published in units of, unless a feed
+    /// overrides it.
+    #[serde(default = "default_exponent")]
+    exponent: i32,
+    feeds: BTreeMap<String, PlanFeed>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PlanFeed {
+    pyth: Option<String>,
+    switchboard: Option<String>,
+    exponent: Option<i32>,
+}
+
+const fn default_exponent() -> i32 {
+    -6
+}
+
+/// A price read from a source account.
+struct Quote {
+    price: u64,
+    published_ms: u64,
+    /// The Pyth price feed ID, for sources that have one.
+    feed_id: Option<[u8; 32]>,
+}
+
+/// Reads the Pyth and Switchboard accounts listed in `plan`, creates a
+/// Doppler oracle seeded with each symbol, publishes the source's current
+/// price in `10^exponent` units sequenced by its publish time, and writes a
+/// feed registry manifest mapping every symbol to its oracle and source to
+/// `out`.
+///
+/// Oracles that already exist are kept, and only updated if the source has
+/// published since. With `dry_run`, the sources are read and the manifest
+/// written without sending anything.
+pub fn migrate(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    plan: &Path,
+    out: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let plan = fs::read_to_string(plan)
+        .map_err(|err| format!("failed to read {}: {err}", plan.display()))?;
+    let plan: Plan = toml::from_str(&plan)?;
+
+    let mut manifest = format!("admin = \"{}\"\n", admin.pubkey());
+    if let Cluster::Custom(program_id) = cluster {
+        let _ = writeln!(manifest, "program_id = \"{program_id}\"");
+    }
+
+    for (name, feed) in &plan.feeds {
+        let exponent = feed.exponent.unwrap_or(plan.exponent);
+        let (source, account) = match (&feed.pyth, &feed.switchboard) {
+            (Some(account), None) => ("pyth", account),
+            (None, Some(account)) => ("switchboard", account),
+            _ => return Err(format!("{name}: give exactly one of pyth or switchboard").into()),
+        };
+        let account: Pubkey = account
+            .parse()
+            .map_err(|_| format!("{name}: invalid account {account}"))?;
+        let quote = match source {
+            "pyth" => read_pyth(client, &account, exponent),
+            _ => read_switchboard(client, &account, exponent),
+        }
+        .map_err(|err| format!("{name}: {err}"))?;
+        let oracle = Pubkey::create_with_seed(&admin.pubkey(), name, &cluster.program_id())?;
+
+        if dry_run {
+            println!("{name} {oracle} {} (not sent)", quote.price);
+        } else {
+            publish(client, admin, cluster, name, oracle, &quote)?;
+        }
+
+        let _ = writeln!(manifest, "\n[feeds.{}]", toml::Value::from(name.as_str()));
+        let _ = writeln!(manifest, "payload = \"PriceFeed\"");
+        let _ = writeln!(manifest, "pubkey = \"{oracle}\"");
+        let _ = writeln!(manifest, "source = \"{source}\"");
+        let _ = writeln!(manifest, "account = \"{account}\"");
+        let _ = writeln!(manifest, "exponent = {exponent}");
+        if let Some(feed_id) = quote.feed_id {
+            let hex: String = feed_id.iter().map(|byte| format!("{byte:02x}")).collect();
+            let _ = writeln!(manifest, "feed_id = \"0x{hex}\"");
+        }
+    }
+
+    fs::write(out, manifest).map_err(|err| format!("failed to write {}: {err}", out.display()))?;
+    println!("wrote {}", out.display());
+    Ok(())
+}
+
+/// Creates the oracle of `name` if it doesn't exist and publishes `quote`
+/// unless the oracle already holds something as recent.
+fn publish(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    name: &str,
+    oracle: Pubkey,
+    quote: &Quote,
+) -> Result<()> {
+    let sequence = match fetch(client, cluster, &oracle) {
+        Ok(current) => current.sequence,
+        Err(_) => {
+            create(client, admin, cluster, name, Payload::Price)?;
+            0
+        }
+    };
+
+    if sequence >= quote.published_ms {
+        println!("{name} {oracle} already at sequence {sequence}");
+        return Ok(());
+    }
+
+    let outcome = Builder::new(admin)
+        .with_cluster(cluster)
+        .add_oracle_update(
+            oracle,
+            Oracle {
+                sequence: quote.published_ms,
+                payload: PriceFeed { price: quote.price },
+            },
+        )
+        .send_and_confirm(client)?;
+    println!("{name} {oracle} {} {}", quote.price, outcome.signature);
+    Ok(())
+}
+
+fn read_pyth(client: &RpcClient, account: &Pubkey, exponent: i32) -> Result<Quote> {
+    let data = read_account(client, account, &PYTH_RECEIVER_ID)?;
+    if data.get(..8) != Some(&PRICE_UPDATE_V2[..]) {
+        return Err(format!("{account} is not a Pyth PriceUpdateV2 account").into());
+    }
+
+    decode_price_update(&data, exponent)
+        .ok_or_else(|| format!("{account}: price out of range or account truncated").into())
+}
+
+/// Reads the price message of a `PriceUpdateV2` account.
+fn decode_price_update(data: &[u8], exponent: i32) -> Option<Quote> {
+    // `verification_level` is `Partial { num_signatures: u8 }` or `Full`.
+    let message = match data.get(VERIFICATION_LEVEL_OFFSET)? {
+        0 => VERIFICATION_LEVEL_OFFSET + 2,
+        1 => VERIFICATION_LEVEL_OFFSET + 1,
+        _ => return None,
+    };
+    let field = |offset: usize, len: usize| data.get(message + offset..message + offset + len);
+
+    let feed_id: [u8; 32] = field(0, 32)?.try_into().ok()?;
+    let price = i64::from_le_bytes(field(32, 8)?.try_into().ok()?);
+    let expo = i32::from_le_bytes(field(48, 4)?.try_into().ok()?);
+    let publish_time = i64::from_le_bytes(field(52, 8)?.try_into().ok()?);
+
+    Some(Quote {
+        price: rescale(price.into(), expo, exponent)?,
+        published_ms: u64::try_from(publish_time).ok()?.checked_mul(1000)?,
+        feed_id: Some(feed_id),
+    })
+}
+
+fn read_switchboard(client: &RpcClient, account: &Pubkey, exponent: i32) -> Result<Quote> {
+    let data = read_account(client, account, &switchboard::ON_DEMAND_ID)?;
+    let result = PullFeedResult::decode(&data)
+        .ok_or_else(|| format!("{account} is not a Switchboard pull feed"))?;
+
+    let decoded = rescale(
+        result.value,
+        -(switchboard::RESULT_DECIMALS as i32),
+        exponent,
+    )
+    .zip(result.last_update_ms());
+    let (price, published_ms) =
+        decoded.ok_or_else(|| format!("{account}: price or update time out of range"))?;
+
+    Ok(Quote {
+        price,
+        published_ms,
+        feed_id: None,
+    })
+}
+
+fn read_account(client: &RpcClient, account: &Pubkey, owner: &Pubkey) -> Result<Vec<u8>> {
+    let account_data = client.get_account(account)?;
+    if account_data.owner != *owner {
+        return Err(format!(
+            "{account} is owned by {}, expected {owner}",
+            account_data.owner
+        )
+        .into());
+    }
+    Ok(account_data.data)
+}
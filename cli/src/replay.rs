@@ -0,0 +1,136 @@
+use std::{fs, path::Path, thread, time::Duration};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{transaction::Builder, Cluster};
+use serde_json::Value;
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+
+use crate::{refuse_mainnet, Result};
+
+const BINANCE_URL: &str = "https://api.binance.com";
+
+/// A recorded price and when it was observed.
+pub struct Point {
+    pub timestamp_ms: u64,
+    pub price: u64,
+}
+
+/// Reads `timestamp_ms,price` rows, skipping a header row, blank lines and
+/// `#` comments. Prices are decimals multiplied by `scale`.
+pub fn read_csv(path: &Path, scale: f64) -> Result<Vec<Point>> {
+    let csv = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+
+    let mut points = vec![];
+    for (i, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split(',').map(str::trim);
+        let (Some(timestamp), Some(price)) = (fields.next(), fields.next()) else {
+            return Err(format!("line {}: expected timestamp_ms,price", i + 1).into());
+        };
+        let Ok(timestamp_ms) = timestamp.parse() else {
+            if points.is_empty() {
+                continue;
+            }
+            return Err(format!("line {}: invalid timestamp {timestamp}", i + 1).into());
+        };
+
+        points.push(Point {
+            timestamp_ms,
+            price: scale_price(price, scale)
+                .ok_or_else(|| format!("line {}: invalid price {price}", i + 1))?,
+        });
+    }
+    Ok(points)
+}
+
+/// Fetches up to `limit` Binance klines of `symbol`, e.g. `SOLUSDC`, taking
+/// each one's close price at its close time.
+pub fn fetch_binance(
+    symbol: &str,
+    interval: &str,
+    start_ms: Option<u64>,
+    limit: u32,
+    scale: f64,
+) -> Result<Vec<Point>> {
+    let mut url =
+        format!("{BINANCE_URL}/api/v3/klines?symbol={symbol}&interval={interval}&limit={limit}");
+    if let Some(start_ms) = start_ms {
+        url.push_str(&format!("&startTime={start_ms}"));
+    }
+
+    let klines: Vec<Vec<Value>> = reqwest::blocking::get(url)?.error_for_status()?.json()?;
+
+    klines
+        .iter()
+        .map(|kline| {
+            let close_time = kline.get(6).and_then(Value::as_u64);
+            let close = kline.get(4).and_then(Value::as_str);
+
+            match (
+                close_time,
+                close.and_then(|close| scale_price(close, scale)),
+            ) {
+                (Some(timestamp_ms), Some(price)) => Ok(Point {
+                    timestamp_ms,
+                    price,
+                }),
+                _ => Err(format!("unexpected kline {kline:?}").into()),
+            }
+        })
+        .collect()
+}
+
+/// Publishes `points` to `oracle` one by one, waiting between them as long
+/// as the recording did, divided by `speed`.
+pub fn replay(
+    client: &RpcClient,
+    admin: &Keypair,
+    cluster: Cluster,
+    oracle: Pubkey,
+    points: &[Point],
+    speed: f64,
+) -> Result<()> {
+    refuse_mainnet(client, "replay prices")?;
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err("speed must be a positive number".into());
+    }
+    if points
+        .windows(2)
+        .any(|pair| pair[1].timestamp_ms < pair[0].timestamp_ms)
+    {
+        return Err("prices are not in chronological order".into());
+    }
+
+    let mut previous = None;
+    for point in points {
+        if let Some(previous) = previous {
+            let gap = Duration::from_millis(point.timestamp_ms - previous);
+            thread::sleep(gap.div_f64(speed));
+        }
+        previous = Some(point.timestamp_ms);
+
+        let outcome = Builder::new(admin)
+            .with_cluster(cluster)
+            .add_oracle_update_auto_seq(client, oracle, PriceFeed { price: point.price })?
+            .send_and_confirm(client)?;
+
+        println!(
+            "{} {} {}",
+            point.timestamp_ms, point.price, outcome.signature
+        );
+    }
+    Ok(())
+}
+
+fn scale_price(price: &str, scale: f64) -> Option<u64> {
+    let price = price.parse::<f64>().ok()? * scale;
+
+    (price.is_finite() && price >= 0.0 && price <= u64::MAX as f64).then(|| price.round() as u64)
+}
@@ -0,0 +1,155 @@
+use std::{
+    fs,
+    path::Path,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use doppler_program::PriceFeed;
+use doppler_sdk::{registry::FeedRegistry, Oracle};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+
+use crate::Result;
+
+/// Accounts requested per `getMultipleAccounts` call, the most it accepts.
+const BATCH: usize = 100;
+
+/// Where snapshots are written.
+pub enum Target<'a> {
+    /// A local directory.
+    Dir(&'a Path),
+    /// An object store, each snapshot `PUT` to `<url>/<file name>` with the
+    /// given headers, e.g. a bucket URL and an authorization header.
+    Http {
+        url: &'a str,
+        headers: &'a [(String, String)],
+    },
+}
+
+/// Fetches every oracle of the feed registry manifest at `config` every
+/// `interval` and writes each round to `target` as a JSON file named after
+/// the time it was taken, holding the slot it was read at and each
+/// oracle's raw account data along with its decoded sequence and price.
+///
+/// With `once`, a single snapshot is taken. Otherwise a failed round is
+/// reported and retried at the next tick.
+pub fn snapshot(
+    client: &RpcClient,
+    config: &Path,
+    target: &Target,
+    interval: Duration,
+    once: bool,
+) -> Result<()> {
+    let manifest = fs::read_to_string(config)
+        .map_err(|err| format!("failed to read {}: {err}", config.display()))?;
+    let registry = FeedRegistry::from_toml(&manifest)?;
+    let feeds: Vec<(&str, Pubkey)> = registry
+        .iter()
+        .map(|(name, feed)| (name, feed.pubkey))
+        .collect();
+    let http = reqwest::blocking::Client::new();
+
+    if let Target::Dir(dir) = target {
+        fs::create_dir_all(dir)?;
+    }
+
+    loop {
+        let started = Instant::now();
+        let taken = take(client, &registry, &feeds)
+            .and_then(|(name, snapshot)| write(&http, target, &name, &snapshot).map(|()| name));
+
+        match taken {
+            Ok(name) => println!("{name}"),
+            Err(err) if once => return Err(err),
+            Err(err) => eprintln!("snapshot failed: {err}"),
+        }
+        if once {
+            return Ok(());
+        }
+
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// Reads the oracles, returning the snapshot's file name and contents.
+fn take(
+    client: &RpcClient,
+    registry: &FeedRegistry,
+    feeds: &[(&str, Pubkey)],
+) -> Result<(String, Value)> {
+    let taken_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+
+    let mut slot = 0;
+    let mut oracles = vec![];
+    for chunk in feeds.chunks(BATCH) {
+        let pubkeys: Vec<Pubkey> = chunk.iter().map(|(_, pubkey)| *pubkey).collect();
+        let response = client
+            .get_multiple_accounts_with_commitment(&pubkeys, CommitmentConfig::confirmed())?;
+        slot = slot.max(response.context.slot);
+
+        for ((name, pubkey), account) in chunk.iter().zip(response.value) {
+            let Some(account) = account else {
+                oracles
+                    .push(json!({ "feed": name, "pubkey": pubkey.to_string(), "account": null }));
+                continue;
+            };
+            let decoded = Oracle::<PriceFeed>::try_from_account(&account, registry.cluster()).ok();
+
+            oracles.push(json!({
+                "feed": name,
+                "pubkey": pubkey.to_string(),
+                "account": {
+                    "owner": account.owner.to_string(),
+                    "lamports": account.lamports,
+                    "data": STANDARD.encode(&account.data),
+                },
+                "sequence": decoded.map(|oracle| oracle.sequence),
+                "price": decoded.map(|oracle| oracle.payload.price),
+            }));
+        }
+    }
+
+    Ok((
+        format!("snapshot-{taken_ms}.json"),
+        json!({
+            "taken_ms": taken_ms,
+            "slot": slot,
+            "program_id": registry.cluster().program_id().to_string(),
+            "oracles": oracles,
+        }),
+    ))
+}
+
+fn write(
+    http: &reqwest::blocking::Client,
+    target: &Target,
+    name: &str,
+    snapshot: &Value,
+) -> Result<()> {
+    let body = serde_json::to_vec_pretty(snapshot)?;
+
+    match target {
+        Target::Dir(dir) => {
+            // Write under a temporary name first so readers never see a
+            // partial snapshot.
+            let partial = dir.join(format!(".{name}.partial"));
+            fs::write(&partial, body)?;
+            fs::rename(partial, dir.join(name))?;
+        }
+        Target::Http { url, headers } => {
+            let mut request = http
+                .put(format!("{}/{name}", url.trim_end_matches('/')))
+                .header("Content-Type", "application/json")
+                .body(body);
+            for (header, value) in headers.iter() {
+                request = request.header(header, value);
+            }
+            request.send()?.error_for_status()?;
+        }
+    }
+    Ok(())
+}
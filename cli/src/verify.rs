@@ -0,0 +1,100 @@
+use solana_pubkey::Pubkey;
+
+use crate::{export::Record, Result};
+
+/// Checks the update history of `oracle`, oldest first as read by
+/// [`history`](crate::export::history), and prints a report of what it
+/// found:
+///
+/// - every sequence must exceed the one before it,
+/// - a sequence step more than `gap_factor` times the median step is
+///   flagged as a gap,
+/// - a pause between updates longer than `max_silence_secs`, or `gap_factor`
+///   times the median pause if not given, is flagged as a silence.
+///
+/// Fails if any update is out of order or anything was flagged.
+pub fn verify(
+    oracle: &Pubkey,
+    records: &[Record],
+    gap_factor: u64,
+    max_silence_secs: Option<i64>,
+) -> Result<()> {
+    let (Some(first), Some(last)) = (records.first(), records.last()) else {
+        return Err(format!("no updates of {oracle} found").into());
+    };
+
+    println!("oracle:    {oracle}");
+    println!("updates:   {}", records.len());
+    println!("slots:     {} to {}", first.slot, last.slot);
+    println!("sequences: {} to {}", first.sequence, last.sequence);
+
+    let steps: Vec<(&Record, &Record)> = records.iter().zip(records.iter().skip(1)).collect();
+    let mut findings = 0;
+
+    for (previous, record) in &steps {
+        if record.sequence <= previous.sequence {
+            findings += 1;
+            println!(
+                "out of order  slot {} {}: sequence {} after {}",
+                record.slot, record.signature, record.sequence, previous.sequence
+            );
+        }
+    }
+
+    let sequence_steps: Vec<u64> = steps
+        .iter()
+        .map(|(previous, record)| record.sequence.saturating_sub(previous.sequence))
+        .collect();
+    if let Some(median) = median(&sequence_steps) {
+        println!("median sequence step: {median}");
+        let threshold = median.max(1).saturating_mul(gap_factor);
+        for ((previous, record), step) in steps.iter().zip(&sequence_steps) {
+            if *step > threshold {
+                findings += 1;
+                println!(
+                    "gap           slot {} {}: sequence jumped by {step}, from {} to {}",
+                    record.slot, record.signature, previous.sequence, record.sequence
+                );
+            }
+        }
+    }
+
+    let pauses: Vec<u64> = steps
+        .iter()
+        .filter_map(|(previous, record)| Some(record.block_time? - previous.block_time?))
+        .filter_map(|pause| u64::try_from(pause).ok())
+        .collect();
+    let max_silence = match max_silence_secs {
+        Some(max_silence) => u64::try_from(max_silence).ok(),
+        None => median(&pauses).map(|median| median.max(1).saturating_mul(gap_factor)),
+    };
+    if let Some(max_silence) = max_silence {
+        println!("silences flagged over {max_silence}s");
+        for (previous, record) in &steps {
+            let (Some(before), Some(after)) = (previous.block_time, record.block_time) else {
+                continue;
+            };
+            if after - before > max_silence as i64 {
+                findings += 1;
+                println!(
+                    "silence       slot {} {}: no update for {}s",
+                    record.slot,
+                    record.signature,
+                    after - before
+                );
+            }
+        }
+    }
+
+    if findings > 0 {
+        return Err(format!("{findings} findings in {} updates", records.len()).into());
+    }
+    println!("OK");
+    Ok(())
+}
+
+fn median(values: &[u64]) -> Option<u64> {
+    let mut values = values.to_vec();
+    values.sort_unstable();
+    values.get(values.len() / 2).copied()
+}
@@ -0,0 +1,46 @@
+use solana_program_error::ProgramError;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsumerError {
+    /// Account is not owned by the Doppler program
+    InvalidOwner = 0x444f_0000,
+    /// Account data length does not match `Oracle<T>`
+    InvalidLayout,
+    /// Account data is not aligned for `Oracle<T>`
+    Misaligned,
+    /// Oracle sequence is older than the allowed age
+    Stale,
+    /// Account data is already mutably borrowed
+    AccountBorrowFailed,
+    /// Account is not the expected oracle
+    UnexpectedAccount,
+    /// Oracle has not received its first update
+    Uninitialized,
+    /// Clock cannot be expressed as a sequence
+    InvalidClock,
+}
+
+impl From<ConsumerError> for ProgramError {
+    fn from(error: ConsumerError) -> Self {
+        match error {
+            ConsumerError::AccountBorrowFailed => ProgramError::AccountBorrowFailed,
+            error => ProgramError::Custom(error as u32),
+        }
+    }
+}
+
+impl core::fmt::Display for ConsumerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidOwner => "oracle account is not owned by the Doppler program",
+            Self::InvalidLayout => "oracle account data does not match the payload layout",
+            Self::Misaligned => "oracle account data is misaligned for the payload",
+            Self::Stale => "oracle sequence is stale",
+            Self::AccountBorrowFailed => "oracle account data is already borrowed",
+            Self::UnexpectedAccount => "account is not the expected oracle",
+            Self::Uninitialized => "oracle has not been updated yet",
+            Self::InvalidClock => "clock cannot be expressed as a sequence",
+        })
+    }
+}
@@ -0,0 +1,275 @@
+use core::cell::Ref;
+
+use doppler_sdk::ID;
+use solana_account_info::AccountInfo;
+use solana_pubkey::Pubkey;
+
+mod error;
+pub use doppler_sdk::Oracle;
+pub use error::ConsumerError;
+
+/// # Load
+/// Borrows the oracle stored in `account` without copying it:
+/// - Checks the account is `oracle_pubkey`, since any account the Doppler
+///   program owns, another feed's or a freshly created one, passes the
+///   other checks
+/// - Checks the account is owned by the Doppler program
+/// - Checks the account data length matches `Oracle<T>`
+/// - Checks the account data is aligned for `Oracle<T>`
+/// - Checks the oracle has received an update, its sequence being non-zero
+pub fn load<'a, T: Sized + Copy>(
+    account: &'a AccountInfo<'_>,
+    oracle_pubkey: &Pubkey,
+) -> Result<Ref<'a, Oracle<T>>, ConsumerError> {
+    if account.key != oracle_pubkey {
+        return Err(ConsumerError::UnexpectedAccount);
+    }
+
+    if account.owner != &ID {
+        return Err(ConsumerError::InvalidOwner);
+    }
+
+    let data = account
+        .try_borrow_data()
+        .map_err(|_| ConsumerError::AccountBorrowFailed)?;
+
+    if data.len() != Oracle::<T>::LEN {
+        return Err(ConsumerError::InvalidLayout);
+    }
+
+    if data
+        .as_ptr()
+        .align_offset(core::mem::align_of::<Oracle<T>>())
+        != 0
+    {
+        return Err(ConsumerError::Misaligned);
+    }
+
+    // SAFETY: length and alignment were checked above and `Oracle<T>` is `repr(C)`.
+    let oracle = Ref::map(data, |data| unsafe { &*data.as_ptr().cast::<Oracle<T>>() });
+
+    if oracle.sequence == 0 {
+        return Err(ConsumerError::Uninitialized);
+    }
+
+    Ok(oracle)
+}
+
+/// # Load Fresh
+/// Same as [`load`], additionally rejecting oracles whose sequence is more
+/// than `max_age` behind `now`.
+///
+/// `now` must be expressed in the same unit the publisher uses for the
+/// sequence (e.g. [`SequenceUnit::now`] of the `Clock` sysvar, or a slot).
+pub fn load_fresh<'a, T: Sized + Copy>(
+    account: &'a AccountInfo<'_>,
+    oracle_pubkey: &Pubkey,
+    now: u64,
+    max_age: u64,
+) -> Result<Ref<'a, Oracle<T>>, ConsumerError> {
+    let oracle = load::<T>(account, oracle_pubkey)?;
+
+    if now.saturating_sub(oracle.sequence) > max_age {
+        return Err(ConsumerError::Stale);
+    }
+
+    Ok(oracle)
+}
+
+/// Unit a publisher uses for oracle sequences that are timestamps. The
+/// keeper publishes unix milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SequenceUnit {
+    /// Unix seconds
+    Seconds,
+    /// Unix milliseconds
+    Millis,
+}
+
+impl SequenceUnit {
+    /// Converts a unix timestamp in seconds, such as the `Clock` sysvar's,
+    /// to this unit. Rejects negative timestamps rather than reading them
+    /// as zero, which would make every oracle look fresh.
+    pub fn now(self, unix_timestamp: i64) -> Result<u64, ConsumerError> {
+        let secs = u64::try_from(unix_timestamp).map_err(|_| ConsumerError::InvalidClock)?;
+        self.secs(secs).ok_or(ConsumerError::InvalidClock)
+    }
+
+    /// Converts a duration in seconds to this unit.
+    #[must_use]
+    pub const fn secs(self, secs: u64) -> Option<u64> {
+        match self {
+            Self::Seconds => Some(secs),
+            Self::Millis => secs.checked_mul(1000),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PriceFeed {
+        price: u64,
+    }
+
+    #[repr(C, align(8))]
+    struct AccountData([u8; 16]);
+
+    fn oracle_data(sequence: u64, price: u64) -> AccountData {
+        let mut data = [0u8; 16];
+        data[..8].copy_from_slice(&sequence.to_le_bytes());
+        data[8..].copy_from_slice(&price.to_le_bytes());
+        AccountData(data)
+    }
+
+    #[test]
+    fn test_load_oracle() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(42, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &ID,
+            false,
+            0,
+        );
+
+        let oracle = load::<PriceFeed>(&account, &key).unwrap();
+        assert_eq!(oracle.sequence, 42);
+        assert_eq!(oracle.payload.price, 1_100_000);
+    }
+
+    /// Another oracle of the program passes every other check.
+    #[test]
+    fn test_load_rejects_other_oracle() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(42, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &ID,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            load::<PriceFeed>(&account, &Pubkey::new_unique()).err(),
+            Some(ConsumerError::UnexpectedAccount)
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_uninitialized_oracle() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(0, 0);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &ID,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            load::<PriceFeed>(&account, &key).err(),
+            Some(ConsumerError::Uninitialized)
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_wrong_owner() {
+        let key = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(42, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &owner,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            load::<PriceFeed>(&account, &key).err(),
+            Some(ConsumerError::InvalidOwner)
+        );
+    }
+
+    #[test]
+    fn test_load_rejects_wrong_layout() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(42, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0[..12],
+            &ID,
+            false,
+            0,
+        );
+
+        assert_eq!(
+            load::<PriceFeed>(&account, &key).err(),
+            Some(ConsumerError::InvalidLayout)
+        );
+    }
+
+    #[test]
+    fn test_load_fresh_rejects_stale() {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = oracle_data(100, 1_100_000);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data.0,
+            &ID,
+            false,
+            0,
+        );
+
+        assert!(load_fresh::<PriceFeed>(&account, &key, 110, 10).is_ok());
+        assert_eq!(
+            load_fresh::<PriceFeed>(&account, &key, 111, 10).err(),
+            Some(ConsumerError::Stale)
+        );
+    }
+
+    #[test]
+    fn test_sequence_unit_now() {
+        assert_eq!(SequenceUnit::Seconds.now(1_700_000_000), Ok(1_700_000_000));
+        assert_eq!(
+            SequenceUnit::Millis.now(1_700_000_000),
+            Ok(1_700_000_000_000)
+        );
+        assert_eq!(SequenceUnit::Millis.secs(30), Some(30_000));
+        assert_eq!(
+            SequenceUnit::Seconds.now(-1),
+            Err(ConsumerError::InvalidClock)
+        );
+    }
+}
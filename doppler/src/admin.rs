@@ -19,12 +19,16 @@ impl Admin {
     /// - Checks Admin is a non-duplicate signer (2 CUs)
     /// - Checks Admin address matches ADMIN (12 CUs)
     ///
+    /// On chain a failed check exits the program, so this always returns
+    /// `true`. On the host, where it cannot exit, it records the exit code
+    /// and returns `false` for the caller to stop at.
+    ///
     /// # Safety
     /// - The caller must ensure that `ptr` is a valid pointer to a memory region
     ///   that can be safely read from.
     /// - The memory region must be properly aligned and large enough to hold the
     ///   data being read.
-    pub unsafe fn check(ptr: *mut u8) {
+    pub unsafe fn check(ptr: *mut u8) -> bool {
         if crate::read::<u16>(ptr, ADMIN_HEADER) != NO_DUP_SIGNER
             || crate::read::<u64>(ptr, ADMIN_KEY) != *ADMIN.as_ptr().cast::<u64>()
             || crate::read::<u64>(ptr, ADMIN_KEY + 0x08) != *ADMIN.as_ptr().add(8).cast::<u64>()
@@ -35,6 +39,12 @@ impl Admin {
             unsafe {
                 core::arch::asm!("lddw r0, 1\nexit");
             }
+            #[cfg(any(test, fuzzing))]
+            {
+                crate::fuzzing::exit(1);
+                return false;
+            }
         }
+        true
     }
 }
@@ -0,0 +1,34 @@
+//! Exit codes for the fuzzer, which builds with `--cfg fuzzing`, and the
+//! crate's tests, both of which run the checks on the host, where they
+//! cannot `exit`. A failed check records its exit code here instead, and
+//! returns before writing anything.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::oracle::Oracle;
+
+static EXIT_CODE: AtomicU64 = AtomicU64::new(0);
+
+/// Records that the program exited with `code`.
+pub fn exit(code: u64) {
+    EXIT_CODE.store(code, Ordering::Relaxed);
+}
+
+/// The furthest an update of a `T` oracle reads into the input region: the
+/// end of the instruction payload.
+pub const fn reach<T: Copy>() -> usize {
+    Oracle::<T>::INSTRUCTION_PAYLOAD + core::mem::size_of::<T>()
+}
+
+/// Same as [`reach`] for an oracle prefixed with an Anchor discriminator.
+pub const fn anchor_reach<T: Copy>() -> usize {
+    Oracle::<T>::ANCHOR_INSTRUCTION_PAYLOAD + core::mem::size_of::<T>()
+}
+
+/// Takes the exit code recorded since the last call, if any.
+pub fn take_exit_code() -> Option<u64> {
+    match EXIT_CODE.swap(0, Ordering::Relaxed) {
+        0 => None,
+        code => Some(code),
+    }
+}
@@ -1,7 +1,9 @@
 #![cfg_attr(target_os = "solana", feature(asm_experimental_arch))]
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
 mod admin;
+#[cfg(any(test, fuzzing))]
+pub mod fuzzing;
 mod oracle;
 pub mod panic_handler;
 
@@ -31,9 +33,390 @@ where
     *ptr.add(offset).cast::<T>() = value;
 }
 
+/// Helper to copy a value between offsets byte for byte, so that padding
+/// in `T` is copied rather than left uninitialized
+///
+/// # Safety
+/// - The caller must ensure that `ptr.add(from)` and `ptr.add(to)` are valid pointers and properly aligned for type `T`.
+/// - The memory at `from` must be valid for reads of `T`, the memory at `to` must be valid for writes of `T`, and they must not overlap.
+#[inline(always)]
+unsafe fn copy<T>(ptr: *mut u8, from: usize, to: usize)
+where
+    T: core::marker::Copy,
+{
+    core::ptr::copy_nonoverlapping(ptr.add(from).cast::<T>(), ptr.add(to).cast::<T>(), 1);
+}
+
 pub mod prelude {
     pub use crate::admin::{Admin, ADMIN};
-    pub use crate::oracle::Oracle;
+    pub use crate::oracle::{Oracle, ANCHOR_DISCRIMINATOR};
     #[cfg(not(feature = "std"))]
     pub use crate::panic_handler::*;
 }
+
+#[cfg(test)]
+mod tests {
+    //! Runs the checks on input regions laid out exactly as the runtime
+    //! serializes them, in allocations exactly as long, so that Miri flags
+    //! any read or write that is misaligned, out of bounds or of
+    //! uninitialized bytes:
+    //!
+    //! ```bash
+    //! cargo +nightly miri test -p doppler
+    //! ```
+
+    use std::{
+        alloc::{alloc, dealloc, Layout},
+        ops::Range,
+        sync::Mutex,
+    };
+
+    use crate::{
+        fuzzing::take_exit_code,
+        oracle::{
+            ANCHOR_ORACLE_DISCRIMINATOR, ANCHOR_ORACLE_PAYLOAD, ANCHOR_ORACLE_SEQUENCE,
+            ORACLE_PAYLOAD, ORACLE_SEQUENCE,
+        },
+        prelude::*,
+    };
+
+    /// Bytes the runtime leaves after each account's data for it to grow into.
+    const MAX_PERMITTED_DATA_INCREASE: usize = 10_240;
+    /// Marks an account that is not a duplicate of an earlier one.
+    const NON_DUP_MARKER: u8 = u8::MAX;
+
+    /// Exit codes are recorded globally, so tests running the checks take
+    /// turns.
+    static HOST: Mutex<()> = Mutex::new(());
+
+    // Payloads used across the workspace; only their layout matters.
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PriceFeed {
+        price: u64,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PropAMM {
+        bid: u64,
+        ask: u64,
+    }
+
+    /// 20 bytes of fields and 4 of trailing padding.
+    #[allow(dead_code)]
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct MarketData {
+        price: u64,
+        volume: u64,
+        confidence: u32,
+    }
+
+    /// An 8-byte aligned allocation of exactly the region's length.
+    struct Region {
+        ptr: *mut u8,
+        layout: Layout,
+    }
+
+    impl Region {
+        fn new(bytes: &[u8]) -> Self {
+            let layout = Layout::from_size_align(bytes.len(), 8).unwrap();
+            // SAFETY: the layout is never zero-sized, and the allocation is
+            // `bytes.len()` long.
+            unsafe {
+                let ptr = alloc(layout);
+                core::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+                Self { ptr, layout }
+            }
+        }
+
+        fn bytes(&self) -> &[u8] {
+            // SAFETY: the allocation is initialized for its whole length.
+            unsafe { core::slice::from_raw_parts(self.ptr, self.layout.size()) }
+        }
+
+        /// Runs the program's checks, stopping at the first that fails, and
+        /// returns its exit code.
+        fn run<T: Copy>(&mut self) -> Option<u64> {
+            self.run_with(Oracle::<T>::check_and_update)
+        }
+
+        /// Same as [`Region::run`] for an oracle prefixed with an Anchor
+        /// discriminator.
+        fn run_anchor<T: Copy>(&mut self) -> Option<u64> {
+            self.run_with(Oracle::<T>::check_and_update_anchor)
+        }
+
+        fn run_with(&mut self, update: unsafe fn(*mut u8)) -> Option<u64> {
+            take_exit_code();
+            // SAFETY: the region is laid out as the runtime serializes an
+            // update for `update`.
+            unsafe {
+                if Admin::check(self.ptr) {
+                    update(self.ptr);
+                }
+            }
+            take_exit_code()
+        }
+    }
+
+    impl Drop for Region {
+        fn drop(&mut self) {
+            // SAFETY: allocated in `Region::new` with this layout.
+            unsafe { dealloc(self.ptr, self.layout) }
+        }
+    }
+
+    /// An update as the runtime lays it out: the admin, then the oracle, then
+    /// the instruction data and program id.
+    struct Update {
+        region: Vec<u8>,
+        oracle_data: Range<usize>,
+        instruction_data: Range<usize>,
+    }
+
+    impl Update {
+        fn new(
+            admin: [u8; 32],
+            is_signer: bool,
+            oracle_data: &[u8],
+            instruction_data: &[u8],
+        ) -> Self {
+            let mut region = 2u64.to_le_bytes().to_vec();
+            push_account(&mut region, admin, is_signer, false, &[]);
+            let oracle_start = region.len() + 88;
+            push_account(&mut region, [0x22; 32], false, true, oracle_data);
+
+            region.extend_from_slice(&(instruction_data.len() as u64).to_le_bytes());
+            let instruction_start = region.len();
+            region.extend_from_slice(instruction_data);
+            region.extend_from_slice(&[0x33; 32]);
+
+            Self {
+                region,
+                oracle_data: oracle_start..oracle_start + oracle_data.len(),
+                instruction_data: instruction_start..instruction_start + instruction_data.len(),
+            }
+        }
+
+        /// The admin updating an oracle at sequence 5 to `sequence`, with a
+        /// payload of distinct non-zero bytes.
+        fn of<T>(sequence: u64) -> Self {
+            Self::prefixed::<T>(&[], sequence)
+        }
+
+        /// Same as [`Update::of`] for an oracle whose data starts with
+        /// `prefix`.
+        fn prefixed<T>(prefix: &[u8], sequence: u64) -> Self {
+            let mut oracle_data = prefix.to_vec();
+            oracle_data.extend_from_slice(&5u64.to_le_bytes());
+            oracle_data.resize(prefix.len() + size_of::<u64>() + size_of::<T>(), 0);
+            let mut instruction_data = sequence.to_le_bytes().to_vec();
+            instruction_data.extend((1..=size_of::<T>()).map(|byte| byte as u8));
+
+            Self::new(ADMIN, true, &oracle_data, &instruction_data)
+        }
+    }
+
+    fn push_account(
+        region: &mut Vec<u8>,
+        key: [u8; 32],
+        is_signer: bool,
+        is_writable: bool,
+        data: &[u8],
+    ) {
+        region.extend_from_slice(&[
+            NON_DUP_MARKER,
+            u8::from(is_signer),
+            u8::from(is_writable),
+            0,
+            0,
+            0,
+            0,
+            0,
+        ]);
+        region.extend_from_slice(&key);
+        region.extend_from_slice(&[0x11; 32]);
+        region.extend_from_slice(&1_000_000u64.to_le_bytes());
+        region.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        region.extend_from_slice(data);
+        region.resize(
+            (region.len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(8),
+            0,
+        );
+        region.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+
+    fn assert_offsets<T: Copy>() {
+        let update = Update::of::<T>(6);
+
+        assert_eq!(ORACLE_SEQUENCE, update.oracle_data.start);
+        assert_eq!(ORACLE_PAYLOAD, update.oracle_data.start + 8);
+        assert_eq!(
+            Oracle::<T>::INSTRUCTION_SEQUENCE,
+            update.instruction_data.start
+        );
+        assert_eq!(
+            Oracle::<T>::INSTRUCTION_PAYLOAD,
+            update.instruction_data.start + 8
+        );
+        for offset in [ORACLE_PAYLOAD, Oracle::<T>::INSTRUCTION_PAYLOAD] {
+            assert_eq!(
+                offset % align_of::<T>(),
+                0,
+                "payload misaligned at {offset:#x}"
+            );
+        }
+    }
+
+    fn assert_update<T: Copy>() {
+        let _host = HOST.lock().unwrap();
+        let update = Update::of::<T>(6);
+        let mut region = Region::new(&update.region);
+
+        assert_eq!(region.run::<T>(), None);
+
+        let mut expected = update.region.clone();
+        expected[update.oracle_data].copy_from_slice(&update.region[update.instruction_data]);
+        assert_eq!(region.bytes(), expected);
+    }
+
+    fn assert_stale<T: Copy>() {
+        let _host = HOST.lock().unwrap();
+        for sequence in [5, 4, 0] {
+            let update = Update::of::<T>(sequence);
+            let mut region = Region::new(&update.region);
+
+            assert_eq!(region.run::<T>(), Some(2));
+            assert_eq!(region.bytes(), update.region);
+        }
+    }
+
+    macro_rules! payload_tests {
+        ($($name:ident: $payload:ty,)*) => {$(
+            mod $name {
+                #[test]
+                fn test_offsets_match_runtime_layout() {
+                    super::assert_offsets::<$payload>();
+                }
+
+                #[test]
+                fn test_update_writes_only_the_oracle() {
+                    super::assert_update::<$payload>();
+                }
+
+                #[test]
+                fn test_stale_update_writes_nothing() {
+                    super::assert_stale::<$payload>();
+                }
+            }
+        )*};
+    }
+
+    payload_tests! {
+        u32_payload: u32,
+        u64_payload: u64,
+        price_feed: super::PriceFeed,
+        prop_amm: super::PropAMM,
+        market_data: super::MarketData,
+        u32_array: [u32; 4],
+        u64_array: [u64; 4],
+    }
+
+    #[test]
+    fn test_anchor_offsets_match_runtime_layout() {
+        let update = Update::prefixed::<MarketData>(&[0; 8], 6);
+
+        assert_eq!(ANCHOR_ORACLE_DISCRIMINATOR, update.oracle_data.start);
+        assert_eq!(ANCHOR_ORACLE_SEQUENCE, update.oracle_data.start + 8);
+        assert_eq!(ANCHOR_ORACLE_PAYLOAD, update.oracle_data.start + 16);
+        assert_eq!(
+            Oracle::<MarketData>::ANCHOR_INSTRUCTION_SEQUENCE,
+            update.instruction_data.start
+        );
+        assert_eq!(
+            Oracle::<MarketData>::ANCHOR_INSTRUCTION_PAYLOAD,
+            update.instruction_data.start + 8
+        );
+    }
+
+    /// The discriminator is written along with the update, whether the
+    /// account is fresh or already has it.
+    #[test]
+    fn test_anchor_update_writes_the_discriminator() {
+        let _host = HOST.lock().unwrap();
+        for prefix in [[0; 8], ANCHOR_DISCRIMINATOR] {
+            let update = Update::prefixed::<MarketData>(&prefix, 6);
+            let mut region = Region::new(&update.region);
+
+            assert_eq!(region.run_anchor::<MarketData>(), None);
+
+            let mut expected = update.region.clone();
+            let oracle = update.oracle_data.start;
+            expected[oracle..oracle + 8].copy_from_slice(&ANCHOR_DISCRIMINATOR);
+            expected[oracle + 8..update.oracle_data.end]
+                .copy_from_slice(&update.region[update.instruction_data]);
+            assert_eq!(region.bytes(), expected);
+        }
+    }
+
+    #[test]
+    fn test_anchor_stale_update_writes_nothing() {
+        let _host = HOST.lock().unwrap();
+        for sequence in [5, 4, 0] {
+            let update = Update::prefixed::<MarketData>(&[0; 8], sequence);
+            let mut region = Region::new(&update.region);
+
+            assert_eq!(region.run_anchor::<MarketData>(), Some(2));
+            assert_eq!(region.bytes(), update.region);
+        }
+    }
+
+    /// Runs an update from `admin` with the region's first account header
+    /// changed by `header`, asserting the admin check rejects it untouched.
+    fn assert_rejected_admin(admin: [u8; 32], header: impl Fn(&mut [u8])) {
+        let _host = HOST.lock().unwrap();
+        let mut update = Update::new(admin, true, &[0; 16], &[1; 16]);
+        header(&mut update.region[0x08..0x10]);
+        let mut region = Region::new(&update.region);
+
+        assert_eq!(region.run::<u64>(), Some(1));
+        assert_eq!(region.bytes(), update.region);
+    }
+
+    /// Each 8-byte word of the key is compared separately, so a key that
+    /// differs only within one word must still be rejected.
+    #[test]
+    fn test_other_admin_is_rejected() {
+        for byte in [0, 7, 8, 15, 16, 23, 24, 31] {
+            let mut other = ADMIN;
+            other[byte] ^= 1;
+            assert_rejected_admin(other, |_| {});
+        }
+    }
+
+    #[test]
+    fn test_unsigned_admin_is_rejected() {
+        assert_rejected_admin(ADMIN, |header| header[1] = 0);
+    }
+
+    #[test]
+    fn test_duplicate_admin_is_rejected() {
+        assert_rejected_admin(ADMIN, |header| header[0] = 0);
+    }
+
+    /// Only the duplicate marker and signer flag are checked; the admin
+    /// may be writable.
+    #[test]
+    fn test_writable_admin_is_accepted() {
+        let _host = HOST.lock().unwrap();
+        let mut update = Update::of::<u64>(6);
+        update.region[0x0a] = 1;
+        let mut region = Region::new(&update.region);
+
+        assert_eq!(region.run::<u64>(), None);
+    }
+}
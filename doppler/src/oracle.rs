@@ -1,6 +1,18 @@
 // Account data offsets
-const ORACLE_SEQUENCE: usize = 0x28c0; // (sequence: u64)
-const ORACLE_PAYLOAD: usize = 0x28c8; // (payload: T)
+pub(crate) const ORACLE_SEQUENCE: usize = 0x28c0; // (sequence: u64)
+pub(crate) const ORACLE_PAYLOAD: usize = 0x28c8; // (payload: T)
+
+// Account data offsets when the oracle is prefixed with an Anchor discriminator
+pub(crate) const ANCHOR_ORACLE_DISCRIMINATOR: usize = 0x28c0; // (discriminator: [u8; 8])
+pub(crate) const ANCHOR_ORACLE_SEQUENCE: usize = 0x28c8; // (sequence: u64)
+pub(crate) const ANCHOR_ORACLE_PAYLOAD: usize = 0x28d0; // (payload: T)
+
+/// Anchor's discriminator for an account named `Oracle`, the first 8 bytes
+/// of `sha256("account:Oracle")`.
+pub const ANCHOR_DISCRIMINATOR: [u8; 8] = [0x8b, 0xc2, 0x83, 0xb3, 0x8c, 0xb3, 0xe5, 0xf4];
+
+// Space the runtime reserves after each account's data for it to grow into
+const MAX_PERMITTED_DATA_INCREASE: usize = 0x2800;
 
 #[repr(C)]
 pub struct Oracle<T: Sized + Copy> {
@@ -8,10 +20,24 @@ pub struct Oracle<T: Sized + Copy> {
     payload: T,
 }
 
+/// Relative offset of the instruction data's sequence for an oracle whose
+/// payload starts at `oracle_payload`: after the oracle's data and its
+/// padding, aligned to 8 bytes, then its rent epoch and the instruction data
+/// length.
+const fn instruction_sequence<T>(oracle_payload: usize) -> usize {
+    (oracle_payload + core::mem::size_of::<T>() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(8)
+        + 0x10
+}
+
 impl<T: Sized + Copy> Oracle<T> {
-    // Relative offsets for instruction data
-    const INSTRUCTION_SEQUENCE: usize = 0x50d8 + core::mem::size_of::<T>(); // (sequence: u64)
-    const INSTRUCTION_PAYLOAD: usize = 0x50e0 + core::mem::size_of::<T>(); // (payload: T)
+    pub(crate) const INSTRUCTION_SEQUENCE: usize = instruction_sequence::<T>(ORACLE_PAYLOAD); // (sequence: u64)
+    pub(crate) const INSTRUCTION_PAYLOAD: usize = Self::INSTRUCTION_SEQUENCE + 0x08; // (payload: T)
+
+    // Relative offsets for instruction data after an oracle prefixed with
+    // an Anchor discriminator
+    pub(crate) const ANCHOR_INSTRUCTION_SEQUENCE: usize =
+        instruction_sequence::<T>(ANCHOR_ORACLE_PAYLOAD); // (sequence: u64)
+    pub(crate) const ANCHOR_INSTRUCTION_PAYLOAD: usize = Self::ANCHOR_INSTRUCTION_SEQUENCE + 0x08; // (payload: T)
 
     /// # Safety
     ///
@@ -20,20 +46,68 @@ impl<T: Sized + Copy> Oracle<T> {
     /// Additionally, the memory region must not be accessed concurrently by other threads.
     #[inline(always)]
     pub unsafe fn check_and_update(ptr: *mut u8) {
+        Self::update::<false>(ptr);
+    }
+
+    /// Same as [`Oracle::check_and_update`] for an oracle account prefixed
+    /// with [`ANCHOR_DISCRIMINATOR`], which is written with every update so
+    /// that a freshly created account gets it on its first.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Oracle::check_and_update`].
+    #[inline(always)]
+    pub unsafe fn check_and_update_anchor(ptr: *mut u8) {
+        Self::update::<true>(ptr);
+    }
+
+    #[inline(always)]
+    unsafe fn update<const ANCHOR: bool>(ptr: *mut u8) {
+        // Every offset is 8-byte aligned, so the payload must not need more
+        const { assert!(core::mem::align_of::<T>() <= 8) };
+
+        let (oracle_sequence, oracle_payload, instruction_sequence, instruction_payload) = if ANCHOR
+        {
+            (
+                ANCHOR_ORACLE_SEQUENCE,
+                ANCHOR_ORACLE_PAYLOAD,
+                Self::ANCHOR_INSTRUCTION_SEQUENCE,
+                Self::ANCHOR_INSTRUCTION_PAYLOAD,
+            )
+        } else {
+            (
+                ORACLE_SEQUENCE,
+                ORACLE_PAYLOAD,
+                Self::INSTRUCTION_SEQUENCE,
+                Self::INSTRUCTION_PAYLOAD,
+            )
+        };
+
         // Check timestamp validity
-        let current_sequence = crate::read::<u64>(ptr, ORACLE_SEQUENCE);
-        let new_sequence = crate::read::<u64>(ptr, Self::INSTRUCTION_SEQUENCE);
+        let current_sequence = crate::read::<u64>(ptr, oracle_sequence);
+        let new_sequence = crate::read::<u64>(ptr, instruction_sequence);
 
         if new_sequence <= current_sequence {
             #[cfg(target_os = "solana")]
             unsafe {
                 core::arch::asm!("lddw r0, 2\nexit");
             }
+            #[cfg(any(test, fuzzing))]
+            {
+                crate::fuzzing::exit(2);
+                return;
+            }
         }
 
         // Update oracle data
-        let new_payload = crate::read::<T>(ptr, Self::INSTRUCTION_PAYLOAD);
-        crate::write(ptr, ORACLE_SEQUENCE, new_sequence);
-        crate::write(ptr, ORACLE_PAYLOAD, new_payload);
+        if ANCHOR {
+            crate::write(
+                ptr,
+                ANCHOR_ORACLE_DISCRIMINATOR,
+                u64::from_le_bytes(ANCHOR_DISCRIMINATOR),
+            );
+        }
+        crate::write(ptr, oracle_sequence, new_sequence);
+        crate::copy::<T>(ptr, instruction_payload, oracle_payload);
     }
 }
@@ -0,0 +1,255 @@
+//! Submits malformed and hostile updates to a local deployment and checks
+//! that each one fails the way the program documents:
+//!
+//! - exit code 1 when the first account is not the admin, or not a signer,
+//! - exit code 2 when the sequence does not increase,
+//!
+//! and that the oracle is left untouched. Start the cluster with
+//! `./surfpool.sh` first. Exits non-zero if any case behaves otherwise.
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{fetch, transaction::Builder, BuildError, DopplerProgramError, Oracle};
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::EncodableKey as _;
+use solana_transaction_error::TransactionError;
+use std::{path::PathBuf, process::ExitCode};
+
+mod constants;
+
+const ORACLE: Pubkey = constants::SOL_USDT_ORACLE;
+
+type Outcome = Result<String, String>;
+type Case = fn(&RpcClient, &Keypair) -> Outcome;
+
+fn main() -> ExitCode {
+    let client = RpcClient::new("http://localhost:8899".to_string());
+    let keypair_path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "keys", "admin-keypair.json"]
+        .iter()
+        .collect();
+    let admin = Keypair::read_from_file(keypair_path).expect("keypair not found at that path");
+
+    let cases: [(&str, Case); 7] = [
+        ("equal sequence", equal_sequence),
+        ("older sequence", older_sequence),
+        ("wrong admin", wrong_admin),
+        ("duplicate transaction", duplicate_transaction),
+        ("replayed update", replayed_update),
+        ("trailing payload bytes", trailing_payload_bytes),
+        ("oversized transaction", oversized_transaction),
+    ];
+
+    let mut failed = 0;
+    for (name, case) in cases {
+        match case(&client, &admin) {
+            Ok(outcome) => println!("PASS  {name}: {outcome}"),
+            Err(outcome) => {
+                failed += 1;
+                println!("FAIL  {name}: {outcome}");
+            }
+        }
+    }
+
+    if failed > 0 {
+        println!("{failed} of {} cases failed", cases.len());
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Resending the current sequence is rejected with exit code 2.
+fn equal_sequence(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let current = fetch_oracle(client);
+    let update = Oracle {
+        sequence: current.sequence,
+        payload: PriceFeed { price: 1 },
+    };
+
+    expect_program_error(
+        client,
+        Builder::new(admin).add_oracle_update(ORACLE, update),
+        2,
+    )
+}
+
+/// A sequence below the current one is rejected with exit code 2.
+fn older_sequence(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let current = fetch_oracle(client);
+    if current.sequence == 0 {
+        // Make sure there is an older sequence to send.
+        let update = next(&current, current.payload.price);
+        Builder::new(admin)
+            .add_oracle_update(ORACLE, update)
+            .send_and_confirm(client)
+            .map_err(|err| format!("setup update failed: {err}"))?;
+    }
+    let update = Oracle {
+        sequence: fetch_oracle(client).sequence - 1,
+        payload: PriceFeed { price: 1 },
+    };
+
+    expect_program_error(
+        client,
+        Builder::new(admin).add_oracle_update(ORACLE, update),
+        2,
+    )
+}
+
+/// An update signed by anyone but the admin is rejected with exit code 1,
+/// even when the admin pays the fee.
+fn wrong_admin(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let intruder = Keypair::new();
+    let update = next(&fetch_oracle(client), 1);
+    let builder = Builder::new(&intruder)
+        .with_fee_payer_keypair(admin)
+        .add_oracle_update(ORACLE, update);
+
+    expect_program_error(client, builder, 1)
+}
+
+/// The same signed transaction lands once; the runtime drops the copy as
+/// already processed before the program runs.
+fn duplicate_transaction(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let current = fetch_oracle(client);
+    let tx = Builder::new(admin)
+        .add_oracle_update(ORACLE, next(&current, current.payload.price + 1))
+        .build(
+            client
+                .get_latest_blockhash()
+                .map_err(|err| err.to_string())?,
+        )
+        .map_err(|err| err.to_string())?;
+
+    client
+        .send_and_confirm_transaction(&tx)
+        .map_err(|err| format!("first copy failed: {err}"))?;
+    let landed = fetch_oracle(client);
+
+    match client.send_and_confirm_transaction(&tx) {
+        Ok(signature) => Err(format!("second copy landed as {signature}")),
+        Err(err) => match err.get_transaction_error() {
+            Some(TransactionError::AlreadyProcessed) => {
+                unchanged(client, &landed)?;
+                Ok("second copy rejected as already processed".to_string())
+            }
+            _ => Err(format!(
+                "second copy failed with {err}, not AlreadyProcessed"
+            )),
+        },
+    }
+}
+
+/// An update that landed, sent again in a new transaction, is rejected
+/// with exit code 2 as its sequence is now the current one.
+fn replayed_update(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let current = fetch_oracle(client);
+    let update = next(&current, current.payload.price + 1);
+    Builder::new(admin)
+        .add_oracle_update(ORACLE, update)
+        .send_and_confirm(client)
+        .map_err(|err| format!("original update failed: {err}"))?;
+
+    // A different priority fee gives the replay its own signature.
+    let replay = Builder::new(admin)
+        .with_unit_price(1)
+        .add_oracle_update(ORACLE, update);
+
+    expect_program_error(client, replay, 2)
+}
+
+/// The program reads the sequence and payload at fixed offsets and never
+/// checks the instruction length, so bytes past the payload are ignored
+/// rather than rejected.
+fn trailing_payload_bytes(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let current = fetch_oracle(client);
+    let price = current.payload.price + 1;
+    let update = Oracle {
+        sequence: current.sequence + 1,
+        payload: [price, u64::MAX, u64::MAX, u64::MAX],
+    };
+
+    Builder::new(admin)
+        .add_oracle_update(ORACLE, update)
+        .send_and_confirm(client)
+        .map_err(|err| format!("update failed: {err}"))?;
+
+    let oracle = fetch_oracle(client);
+    if (oracle.sequence, oracle.payload.price) == (update.sequence, price) {
+        Ok("accepted, trailing bytes ignored".to_string())
+    } else {
+        Err(format!(
+            "oracle holds sequence {} and price {}, expected {} and {price}",
+            oracle.sequence, oracle.payload.price, update.sequence
+        ))
+    }
+}
+
+/// A payload too large for a transaction never reaches the cluster.
+fn oversized_transaction(client: &RpcClient, admin: &Keypair) -> Outcome {
+    let update = Oracle {
+        sequence: fetch_oracle(client).sequence + 1,
+        payload: [0u8; 1_232],
+    };
+    let built = Builder::new(admin).add_oracle_update(ORACLE, update).build(
+        client
+            .get_latest_blockhash()
+            .map_err(|err| err.to_string())?,
+    );
+
+    match built {
+        Err(err) if matches!(err, BuildError::TooLarge { .. }) => {
+            Ok(format!("refused to build: {err}"))
+        }
+        Err(err) => Err(format!("unexpected build error: {err}")),
+        Ok(_) => Err("built a transaction over the packet limit".to_string()),
+    }
+}
+
+/// Sends `builder`, expecting the oracle update to fail with exit `code`
+/// and leave the oracle unchanged.
+fn expect_program_error(client: &RpcClient, builder: Builder, code: u32) -> Outcome {
+    let before = fetch_oracle(client);
+
+    let err = match builder.clone().send_and_confirm(client) {
+        Ok(outcome) => return Err(format!("landed as {}", outcome.signature)),
+        Err(err) => err,
+    };
+    let outcome = match (code, builder.decode_error(client, &err)) {
+        (1, Some(err @ DopplerProgramError::InvalidAdmin { .. }))
+        | (2, Some(err @ DopplerProgramError::StaleSequence { .. })) => {
+            format!("rejected with exit code {code} ({err})")
+        }
+        (_, Some(other)) => return Err(format!("expected exit code {code}, got {other}")),
+        (_, None) => return Err(format!("expected exit code {code}, got {err}")),
+    };
+
+    unchanged(client, &before)?;
+    Ok(outcome)
+}
+
+fn unchanged(client: &RpcClient, before: &Oracle<PriceFeed>) -> Result<(), String> {
+    let after = fetch_oracle(client);
+    if (after.sequence, after.payload.price) == (before.sequence, before.payload.price) {
+        Ok(())
+    } else {
+        Err(format!(
+            "oracle changed from sequence {} to {}",
+            before.sequence, after.sequence
+        ))
+    }
+}
+
+fn fetch_oracle(client: &RpcClient) -> Oracle<PriceFeed> {
+    fetch::oracle_account::<PriceFeed>(client, &ORACLE)
+        .expect("failed to fetch oracle account")
+        .oracle
+}
+
+fn next(current: &Oracle<PriceFeed>, price: u64) -> Oracle<PriceFeed> {
+    Oracle {
+        sequence: current.sequence + 1,
+        payload: PriceFeed { price },
+    }
+}
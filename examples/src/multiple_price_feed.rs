@@ -1,12 +1,11 @@
 use doppler_program::PriceFeed;
-use doppler_sdk::{transaction::Builder, Oracle};
+use doppler_sdk::{fetch, transaction::Builder, Oracle};
 use solana_client::rpc_client::RpcClient;
 use solana_keypair::Keypair;
 use solana_signer::EncodableKey as _;
 use std::path::PathBuf;
 
 mod constants;
-mod fetch;
 
 fn main() {
     // Connect to local Solana cluster
@@ -24,13 +23,16 @@ fn main() {
 
     let sol_usdc_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDC_ORACLE)
-            .expect("failed to fetch oracle account");
+            .expect("failed to fetch oracle account")
+            .oracle;
     let sol_usdt_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDT_ORACLE)
-            .expect("failed to fetch oracle account");
+            .expect("failed to fetch oracle account")
+            .oracle;
     let bonk_sol_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::BONK_SOL_ORACLE)
-            .expect("failed to fetch oracle account");
+            .expect("failed to fetch oracle account")
+            .oracle;
 
     // Create the new price feed data
     let new_sol_usdc_price_feed = PriceFeed {
@@ -78,7 +80,9 @@ fn main() {
         );
     }
 
-    let transaction = tx_builder.build(recent_blockhash);
+    let transaction = tx_builder
+        .build(recent_blockhash)
+        .expect("too many oracle updates for one transaction");
 
     println!("Sending Tx...");
 
@@ -91,13 +95,16 @@ fn main() {
 
     let sol_usdc_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDC_ORACLE)
-            .expect("failed to fetch sol-usdc oracle account");
+            .expect("failed to fetch sol-usdc oracle account")
+            .oracle;
     let sol_usdt_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDT_ORACLE)
-            .expect("failed to fetch sol-usdt oracle account");
+            .expect("failed to fetch sol-usdt oracle account")
+            .oracle;
     let bonk_sol_oracle_data =
         fetch::oracle_account::<PriceFeed>(&client, &constants::BONK_SOL_ORACLE)
-            .expect("failed to fetch bonk-sol oracle account");
+            .expect("failed to fetch bonk-sol oracle account")
+            .oracle;
 
     println!(
         "SOL/USDC Price feed : seq : {}, price : {}",
@@ -1,12 +1,11 @@
 use doppler_program::PriceFeed;
-use doppler_sdk::{transaction::Builder, Oracle};
+use doppler_sdk::{fetch, transaction::Builder, Oracle};
 use solana_client::rpc_client::RpcClient;
 use solana_keypair::Keypair;
 use solana_signer::EncodableKey as _;
 use std::path::PathBuf;
 
 mod constants;
-mod fetch;
 
 fn main() {
     // Connect to local Solana cluster
@@ -22,7 +21,8 @@ fn main() {
 
     // Define oracle account public key (replace with actual oracle account)
     let oracle_data = fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDC_ORACLE)
-        .expect("failed to fetch oracle account");
+        .expect("failed to fetch oracle account")
+        .oracle;
 
     // Create the new price feed data
     let new_price_feed = PriceFeed {
@@ -44,7 +44,8 @@ fn main() {
             },
         )
         .with_unit_price(1_000)
-        .build(recent_blockhash);
+        .build(recent_blockhash)
+        .expect("transaction too large");
 
     println!("Sending Tx...");
 
@@ -56,7 +57,8 @@ fn main() {
     println!("Transaction successful with signature: {signature:?}");
 
     let oracle_data = fetch::oracle_account::<PriceFeed>(&client, &constants::SOL_USDC_ORACLE)
-        .expect("failed to fetch oracle account");
+        .expect("failed to fetch oracle account")
+        .oracle;
 
     println!(
         "Price feed : seq : {}, price : {}",
@@ -0,0 +1,192 @@
+//! Deploys the program to a fresh `solana-test-validator` and drives it
+//! through the SDK over RPC: creating oracles, updating one, rejecting a
+//! stale update and updating a batch. This catches what the in-process
+//! harnesses cannot, like blockhash handling and compute budget sizing
+//! against a real runtime.
+//!
+//! Ignored by default. It needs the Solana CLI on the `PATH` and the
+//! program built with `cargo build-sbf`:
+//!
+//! ```bash
+//! cargo test -p doppler-examples --test validator -- --ignored
+//! ```
+
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{fetch, transaction::Builder, DopplerProgramError, Oracle};
+use solana_client::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
+use solana_system_interface::instruction::create_account_with_seed;
+use solana_transaction::Transaction;
+
+/// Kept clear of the default port, which `./surfpool.sh` uses.
+const RPC_PORT: u16 = 18_899;
+const FAUCET_PORT: u16 = 18_900;
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A `solana-test-validator` on a throwaway ledger, with the program
+/// deployed and the admin as the mint. Killed on drop.
+struct Validator {
+    process: Child,
+    ledger: PathBuf,
+}
+
+impl Validator {
+    fn start(admin: &Pubkey) -> Self {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let program: PathBuf = [manifest_dir, "..", "target", "deploy", "doppler_program.so"]
+            .iter()
+            .collect();
+        assert!(
+            program.exists(),
+            "{} not found, build it with `cargo build-sbf`",
+            program.display()
+        );
+        let ledger = std::env::temp_dir().join(format!("doppler-e2e-{}", std::process::id()));
+
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .args(["--rpc-port", &RPC_PORT.to_string()])
+            .args(["--faucet-port", &FAUCET_PORT.to_string()])
+            .args(["--mint", &admin.to_string()])
+            .arg("--bpf-program")
+            .arg(doppler_sdk::ID.to_string())
+            .arg(&program)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start solana-test-validator, is the Solana CLI installed?");
+        let validator = Self { process, ledger };
+
+        let client = validator.client();
+        let started = Instant::now();
+        while client.get_latest_blockhash().is_err() {
+            assert!(
+                started.elapsed() < STARTUP_TIMEOUT,
+                "solana-test-validator did not start within {STARTUP_TIMEOUT:?}"
+            );
+            thread::sleep(Duration::from_millis(500));
+        }
+
+        validator
+    }
+
+    fn client(&self) -> RpcClient {
+        RpcClient::new(format!("http://127.0.0.1:{RPC_PORT}"))
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = std::fs::remove_dir_all(&self.ledger);
+    }
+}
+
+/// Creates the rent-exempt oracle seeded with `seed` under `admin`.
+fn create(client: &RpcClient, admin: &Keypair, seed: &str) -> Pubkey {
+    let oracle = Pubkey::create_with_seed(&admin.pubkey(), seed, &doppler_sdk::ID).unwrap();
+    let space = Oracle::<PriceFeed>::LEN;
+    let lamports = client
+        .get_minimum_balance_for_rent_exemption(space)
+        .unwrap();
+
+    let tx = Transaction::new_signed_with_payer(
+        &[create_account_with_seed(
+            &admin.pubkey(),
+            &oracle,
+            &admin.pubkey(),
+            seed,
+            lamports,
+            space as u64,
+            &doppler_sdk::ID,
+        )],
+        Some(&admin.pubkey()),
+        &[admin],
+        client.get_latest_blockhash().unwrap(),
+    );
+    client.send_and_confirm_transaction(&tx).unwrap();
+    oracle
+}
+
+fn price_update(sequence: u64, price: u64) -> Oracle<PriceFeed> {
+    Oracle {
+        sequence,
+        payload: PriceFeed { price },
+    }
+}
+
+fn read(client: &RpcClient, oracle: &Pubkey) -> (u64, u64) {
+    let oracle = fetch::oracle_account::<PriceFeed>(client, oracle)
+        .unwrap()
+        .oracle;
+    (oracle.sequence, oracle.payload.price)
+}
+
+#[test]
+#[ignore = "spawns solana-test-validator"]
+fn test_create_update_and_batch() {
+    let keypair_path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "keys", "admin-keypair.json"]
+        .iter()
+        .collect();
+    let admin = Keypair::read_from_file(keypair_path).unwrap();
+    let validator = Validator::start(&admin.pubkey());
+    let client = validator.client();
+
+    // Create
+    let oracle = create(&client, &admin, "SOL/USDC");
+    let account = client.get_account(&oracle).unwrap();
+    assert_eq!(account.owner, doppler_sdk::ID);
+    assert_eq!(account.data, vec![0; Oracle::<PriceFeed>::LEN]);
+
+    // Update, within the compute budget the builder requests
+    let outcome = Builder::new(&admin)
+        .with_unit_price(1_000)
+        .add_oracle_update(oracle, price_update(1, 1_100_000))
+        .send_and_confirm(&client)
+        .unwrap();
+    assert!(outcome.compute_units_consumed.is_some_and(|cu| cu > 0));
+    assert_eq!(read(&client, &oracle), (1, 1_100_000));
+
+    // A stale update is rejected and decoded
+    let stale = Builder::new(&admin).add_oracle_update(oracle, price_update(1, 1));
+    let err = stale.clone().send_and_confirm(&client).unwrap_err();
+    assert!(
+        matches!(
+            stale.decode_error(&client, &err),
+            Some(DopplerProgramError::StaleSequence {
+                current: 1,
+                sent: 1,
+                ..
+            })
+        ),
+        "expected a stale sequence, got {err}"
+    );
+    assert_eq!(read(&client, &oracle), (1, 1_100_000));
+
+    // Batch
+    let oracles: Vec<Pubkey> = (0..8)
+        .map(|index| create(&client, &admin, &format!("feed-{index}")))
+        .collect();
+    let batch = oracles
+        .iter()
+        .zip(1..)
+        .fold(Builder::new(&admin), |builder, (oracle, price)| {
+            builder.add_oracle_update(*oracle, price_update(1, price))
+        });
+    batch.send_and_confirm(&client).unwrap();
+    for (oracle, price) in oracles.iter().zip(1..) {
+        assert_eq!(read(&client, oracle), (1, price));
+    }
+}
@@ -0,0 +1,26 @@
+use std::{env, path::PathBuf};
+
+/// Set to also refresh the committed `include/doppler.h`; builds otherwise
+/// only write the header into `OUT_DIR`.
+const UPDATE_HEADER: &str = "DOPPLER_FFI_UPDATE_HEADER";
+
+fn main() {
+    let crate_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("failed to read cbindgen.toml");
+
+    let bindings = cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate C bindings");
+    bindings.write_to_file(out_dir.join("doppler.h"));
+    if env::var_os(UPDATE_HEADER).is_some() {
+        bindings.write_to_file(crate_dir.join("include").join("doppler.h"));
+    }
+
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed={UPDATE_HEADER}");
+}
@@ -0,0 +1,92 @@
+#ifndef DOPPLER_H
+#define DOPPLER_H
+
+/* Generated by cbindgen from ffi/src/lib.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Number of accounts referenced by an oracle update instruction.
+ */
+#define DOPPLER_UPDATE_ACCOUNTS 2
+
+typedef enum DopplerStatus {
+  DOPPLER_STATUS_OK = 0,
+  DOPPLER_STATUS_NULL_POINTER = 1,
+  DOPPLER_STATUS_BUFFER_TOO_SMALL = 2,
+} DopplerStatus;
+
+typedef struct DopplerPubkey {
+  uint8_t bytes[32];
+} DopplerPubkey;
+
+typedef struct DopplerAccountMeta {
+  struct DopplerPubkey pubkey;
+  bool is_signer;
+  bool is_writable;
+} DopplerAccountMeta;
+
+/**
+ * Writes the Doppler program ID into `out`.
+ *
+ * # Safety
+ * `out` must be null or valid for writes of a `DopplerPubkey`.
+ */
+enum DopplerStatus doppler_program_id(struct DopplerPubkey *out);
+
+/**
+ * Size in bytes of an oracle account holding a payload of `payload_len`
+ * bytes: the update data padded to 8 bytes, or 0 if that overflows.
+ */
+size_t doppler_oracle_len(size_t payload_len);
+
+/**
+ * Size in bytes of an update's instruction data, the sequence followed by
+ * a payload of `payload_len` bytes, or 0 if that overflows.
+ */
+size_t doppler_update_data_len(size_t payload_len);
+
+/**
+ * Serializes `sequence` and `payload` into `out`, writing the number of
+ * bytes produced, `doppler_update_data_len`, to `written`.
+ *
+ * # Safety
+ * - `payload` must be valid for reads of `payload_len` bytes.
+ * - `out` must be valid for writes of `out_len` bytes.
+ * - `written` must be valid for writes of a `usize`.
+ */
+enum DopplerStatus doppler_encode_oracle(uint64_t sequence,
+                                         const uint8_t *payload,
+                                         size_t payload_len,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Builds an oracle update instruction: writes the program ID, the
+ * `DOPPLER_UPDATE_ACCOUNTS` account metas and the instruction data.
+ *
+ * # Safety
+ * - `admin` and `oracle` must be valid for reads of a `DopplerPubkey`.
+ * - `payload` must be valid for reads of `payload_len` bytes.
+ * - `program_id` must be valid for writes of a `DopplerPubkey`.
+ * - `accounts` must be valid for writes of `DOPPLER_UPDATE_ACCOUNTS` metas.
+ * - `data` must be valid for writes of `data_len` bytes.
+ * - `data_written` must be valid for writes of a `usize`.
+ */
+enum DopplerStatus doppler_update_instruction(const struct DopplerPubkey *admin,
+                                              const struct DopplerPubkey *oracle,
+                                              uint64_t sequence,
+                                              const uint8_t *payload,
+                                              size_t payload_len,
+                                              struct DopplerPubkey *program_id,
+                                              struct DopplerAccountMeta *accounts,
+                                              uint8_t *data,
+                                              size_t data_len,
+                                              size_t *data_written);
+
+#endif  /* DOPPLER_H */
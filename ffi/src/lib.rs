@@ -0,0 +1,255 @@
+use doppler_sdk::{ID, PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+
+/// Number of accounts referenced by an oracle update instruction.
+pub const DOPPLER_UPDATE_ACCOUNTS: usize = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DopplerStatus {
+    Ok = 0,
+    NullPointer = 1,
+    BufferTooSmall = 2,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DopplerPubkey {
+    pub bytes: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DopplerAccountMeta {
+    pub pubkey: DopplerPubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Writes the Doppler program ID into `out`.
+///
+/// # Safety
+/// `out` must be null or valid for writes of a `DopplerPubkey`.
+#[no_mangle]
+pub unsafe extern "C" fn doppler_program_id(out: *mut DopplerPubkey) -> DopplerStatus {
+    let Some(out) = out.as_mut() else {
+        return DopplerStatus::NullPointer;
+    };
+    out.bytes = ID.to_bytes();
+    DopplerStatus::Ok
+}
+
+/// Size in bytes of an oracle account holding a payload of `payload_len`
+/// bytes: the update data padded to 8 bytes, or 0 if that overflows.
+#[no_mangle]
+pub extern "C" fn doppler_oracle_len(payload_len: usize) -> usize {
+    update_data_len(payload_len)
+        .and_then(|len| len.checked_next_multiple_of(8))
+        .unwrap_or(0)
+}
+
+/// Size in bytes of an update's instruction data, the sequence followed by
+/// a payload of `payload_len` bytes, or 0 if that overflows.
+#[no_mangle]
+pub extern "C" fn doppler_update_data_len(payload_len: usize) -> usize {
+    update_data_len(payload_len).unwrap_or(0)
+}
+
+fn update_data_len(payload_len: usize) -> Option<usize> {
+    PAYLOAD_OFFSET.checked_add(payload_len)
+}
+
+/// Serializes `sequence` and `payload` into `out`, writing the number of
+/// bytes produced, `doppler_update_data_len`, to `written`.
+///
+/// # Safety
+/// - `payload` must be valid for reads of `payload_len` bytes.
+/// - `out` must be valid for writes of `out_len` bytes.
+/// - `written` must be valid for writes of a `usize`.
+#[no_mangle]
+pub unsafe extern "C" fn doppler_encode_oracle(
+    sequence: u64,
+    payload: *const u8,
+    payload_len: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> DopplerStatus {
+    if payload.is_null() || out.is_null() || written.is_null() {
+        return DopplerStatus::NullPointer;
+    }
+
+    let Some(len) = update_data_len(payload_len).filter(|len| *len <= out_len) else {
+        return DopplerStatus::BufferTooSmall;
+    };
+
+    let out = core::slice::from_raw_parts_mut(out, len);
+    out[SEQUENCE_OFFSET..PAYLOAD_OFFSET].copy_from_slice(&sequence.to_le_bytes());
+    out[PAYLOAD_OFFSET..].copy_from_slice(core::slice::from_raw_parts(payload, payload_len));
+    *written = len;
+
+    DopplerStatus::Ok
+}
+
+/// Builds an oracle update instruction: writes the program ID, the
+/// `DOPPLER_UPDATE_ACCOUNTS` account metas and the instruction data.
+///
+/// # Safety
+/// - `admin` and `oracle` must be valid for reads of a `DopplerPubkey`.
+/// - `payload` must be valid for reads of `payload_len` bytes.
+/// - `program_id` must be valid for writes of a `DopplerPubkey`.
+/// - `accounts` must be valid for writes of `DOPPLER_UPDATE_ACCOUNTS` metas.
+/// - `data` must be valid for writes of `data_len` bytes.
+/// - `data_written` must be valid for writes of a `usize`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn doppler_update_instruction(
+    admin: *const DopplerPubkey,
+    oracle: *const DopplerPubkey,
+    sequence: u64,
+    payload: *const u8,
+    payload_len: usize,
+    program_id: *mut DopplerPubkey,
+    accounts: *mut DopplerAccountMeta,
+    data: *mut u8,
+    data_len: usize,
+    data_written: *mut usize,
+) -> DopplerStatus {
+    if admin.is_null() || oracle.is_null() || program_id.is_null() || accounts.is_null() {
+        return DopplerStatus::NullPointer;
+    }
+
+    let status =
+        doppler_encode_oracle(sequence, payload, payload_len, data, data_len, data_written);
+    if status != DopplerStatus::Ok {
+        return status;
+    }
+
+    let status = doppler_program_id(program_id);
+    if status != DopplerStatus::Ok {
+        return status;
+    }
+
+    let accounts = core::slice::from_raw_parts_mut(accounts, DOPPLER_UPDATE_ACCOUNTS);
+    accounts[0] = DopplerAccountMeta {
+        pubkey: *admin,
+        is_signer: true,
+        is_writable: false,
+    };
+    accounts[1] = DopplerAccountMeta {
+        pubkey: *oracle,
+        is_signer: false,
+        is_writable: true,
+    };
+
+    DopplerStatus::Ok
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_sdk::{Oracle, UpdateInstruction};
+    use solana_instruction::Instruction;
+    use solana_pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_update_instruction_matches_sdk() {
+        let admin = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+
+        let expected: Instruction = UpdateInstruction {
+            admin,
+            oracle_pubkey: oracle,
+            oracle: Oracle {
+                sequence: 42,
+                payload: 1_100_000u64,
+            },
+        }
+        .into();
+
+        let payload = 1_100_000u64.to_le_bytes();
+        let mut program_id = DopplerPubkey::default();
+        let mut accounts = [DopplerAccountMeta::default(); DOPPLER_UPDATE_ACCOUNTS];
+        let mut data = [0u8; 64];
+        let mut written = 0;
+
+        let status = unsafe {
+            doppler_update_instruction(
+                &DopplerPubkey {
+                    bytes: admin.to_bytes(),
+                },
+                &DopplerPubkey {
+                    bytes: oracle.to_bytes(),
+                },
+                42,
+                payload.as_ptr(),
+                payload.len(),
+                &mut program_id,
+                accounts.as_mut_ptr(),
+                data.as_mut_ptr(),
+                data.len(),
+                &mut written,
+            )
+        };
+
+        assert_eq!(status, DopplerStatus::Ok);
+        assert_eq!(program_id.bytes, expected.program_id.to_bytes());
+        assert_eq!(&data[..written], expected.data.as_slice());
+        for (meta, expected) in accounts.iter().zip(expected.accounts.iter()) {
+            assert_eq!(meta.pubkey.bytes, expected.pubkey.to_bytes());
+            assert_eq!(meta.is_signer, expected.is_signer);
+            assert_eq!(meta.is_writable, expected.is_writable);
+        }
+    }
+
+    #[test]
+    fn test_encode_oracle_buffer_too_small() {
+        let payload = 123u32.to_le_bytes();
+        let mut data = [0u8; 8];
+        let mut written = 0;
+
+        let status = unsafe {
+            doppler_encode_oracle(
+                1,
+                payload.as_ptr(),
+                payload.len(),
+                data.as_mut_ptr(),
+                data.len(),
+                &mut written,
+            )
+        };
+
+        assert_eq!(status, DopplerStatus::BufferTooSmall);
+
+        let status = unsafe {
+            doppler_encode_oracle(
+                1,
+                payload.as_ptr(),
+                usize::MAX,
+                data.as_mut_ptr(),
+                data.len(),
+                &mut written,
+            )
+        };
+
+        assert_eq!(status, DopplerStatus::BufferTooSmall);
+    }
+
+    #[test]
+    fn test_lengths_match_sdk() {
+        assert_eq!(doppler_oracle_len(4), Oracle::<u32>::LEN);
+        assert_eq!(doppler_update_data_len(4), Oracle::<u32>::ENCODED_LEN);
+        assert_eq!(doppler_oracle_len(8), Oracle::<u64>::LEN);
+        assert_eq!(doppler_oracle_len(usize::MAX), 0);
+        assert_eq!(doppler_update_data_len(usize::MAX), 0);
+    }
+
+    #[test]
+    fn test_committed_header_is_current() {
+        assert!(
+            include_str!("../include/doppler.h")
+                == include_str!(concat!(env!("OUT_DIR"), "/doppler.h")),
+            "include/doppler.h is stale; rebuild with DOPPLER_FFI_UPDATE_HEADER=1"
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Object, Request, Result, Schema, SchemaBuilder,
+    SimpleObject,
+};
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+
+use crate::store::{self, Store};
+
+/// Most updates `history` returns at once; page with `beforeSequence`.
+const MAX_LIMIT: u32 = 1_000;
+
+pub type IndexerSchema = Schema<Query, EmptyMutation, EmptySubscription>;
+
+/// The indexed feeds, by manifest name.
+pub type Feeds = BTreeMap<Arc<str>, Pubkey>;
+
+/// Builds the schema over `feeds`. The store is added as schema data by
+/// the caller.
+pub fn schema(feeds: Feeds) -> SchemaBuilder<Query, EmptyMutation, EmptySubscription> {
+    Schema::build(Query, EmptyMutation, EmptySubscription).data(feeds)
+}
+
+/// Serves the schema:
+///
+/// - `POST /graphql` executes a query sent as `{"query": ..., "variables": ...}`.
+/// - `GET /graphql` returns the schema in SDL, for clients to generate
+///   their types from.
+pub async fn serve(addr: SocketAddr, schema: IndexerSchema) -> std::io::Result<()> {
+    let sdl = schema.sdl();
+    let app = Router::new().route(
+        "/graphql",
+        get(move || async move { ([(header::CONTENT_TYPE, "text/plain")], sdl) }).post(
+            move |body: String| async move {
+                match serde_json::from_str::<Request>(&body) {
+                    Ok(request) => respond(
+                        StatusCode::OK,
+                        serde_json::to_value(schema.execute(request).await).unwrap_or_default(),
+                    ),
+                    Err(err) => respond(
+                        StatusCode::BAD_REQUEST,
+                        json!({ "errors": [{ "message": err.to_string() }] }),
+                    ),
+                }
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn respond(status: StatusCode, body: Value) -> impl IntoResponse {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Every indexed feed, by name.
+    async fn feeds(&self, ctx: &Context<'_>) -> Vec<Feed> {
+        ctx.data_unchecked::<Feeds>()
+            .iter()
+            .map(|(name, pubkey)| Feed {
+                name: name.clone(),
+                pubkey: *pubkey,
+            })
+            .collect()
+    }
+
+    /// The feed named `name` in the manifest, e.g. `SOL/USDC`.
+    async fn feed(&self, ctx: &Context<'_>, name: String) -> Option<Feed> {
+        ctx.data_unchecked::<Feeds>()
+            .get_key_value(name.as_str())
+            .map(|(name, pubkey)| Feed {
+                name: name.clone(),
+                pubkey: *pubkey,
+            })
+    }
+}
+
+/// A feed of the manifest and its indexed updates.
+pub struct Feed {
+    name: Arc<str>,
+    pubkey: Pubkey,
+}
+
+#[Object]
+impl Feed {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The oracle account, base58 encoded.
+    async fn pubkey(&self) -> String {
+        self.pubkey.to_string()
+    }
+
+    /// The update with the highest sequence, if any was indexed.
+    async fn latest(&self, ctx: &Context<'_>) -> Result<Option<Update>> {
+        let store = ctx.data::<Arc<Store>>()?;
+        Ok(store.latest(&self.name).await?.map(Update::from))
+    }
+
+    /// Up to `limit` updates, newest first. Pass the lowest sequence of a
+    /// page as `beforeSequence` to get the page before it.
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 100)] limit: u32,
+        before_sequence: Option<u64>,
+    ) -> Result<Vec<Update>> {
+        let store = ctx.data::<Arc<Store>>()?;
+        let updates = store
+            .history(&self.name, limit.min(MAX_LIMIT), before_sequence)
+            .await?;
+        Ok(updates.into_iter().map(Update::from).collect())
+    }
+
+    /// How often the feed was published over the last `windowSecs`, a day
+    /// by default.
+    async fn publisher_stats(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 86_400)] window_secs: u64,
+    ) -> Result<PublisherStats> {
+        let store = ctx.data::<Arc<Store>>()?;
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let since_ms = now_ms.saturating_sub(window_secs.saturating_mul(1_000));
+
+        Ok(store.stats(&self.name, since_ms).await?.into())
+    }
+}
+
+/// A value the feed held.
+#[derive(SimpleObject)]
+pub struct Update {
+    sequence: u64,
+    price: u64,
+    /// Slot the update was observed in.
+    slot: u64,
+    /// When the indexer received the update, in Unix milliseconds.
+    received_ms: u64,
+}
+
+impl From<store::Update> for Update {
+    fn from(update: store::Update) -> Self {
+        Self {
+            sequence: update.sequence,
+            price: update.price,
+            slot: update.slot,
+            received_ms: update.received_ms,
+        }
+    }
+}
+
+/// How a feed was published over a window, from the updates the indexer
+/// received.
+#[derive(SimpleObject)]
+pub struct PublisherStats {
+    updates: u64,
+    first_received_ms: Option<u64>,
+    last_received_ms: Option<u64>,
+    /// Mean time between consecutive updates.
+    mean_interval_ms: Option<f64>,
+    /// Longest time between consecutive updates, the feed's worst staleness
+    /// over the window.
+    max_interval_ms: Option<u64>,
+}
+
+impl From<store::Stats> for PublisherStats {
+    fn from(stats: store::Stats) -> Self {
+        Self {
+            updates: stats.updates,
+            first_received_ms: stats.first_received_ms,
+            last_received_ms: stats.last_received_ms,
+            mean_interval_ms: stats.mean_interval_ms,
+            max_interval_ms: stats.max_interval_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeds() -> Feeds {
+        Feeds::from([(Arc::from("SOL/USDC"), Pubkey::new_from_array([1; 32]))])
+    }
+
+    #[test]
+    fn test_schema() {
+        insta::assert_snapshot!(schema(feeds()).finish().sdl());
+    }
+
+    #[tokio::test]
+    async fn test_feeds_resolve_from_manifest() {
+        let schema = schema(feeds()).finish();
+
+        let response = schema
+            .execute(r#"{ feeds { name } feed(name: "SOL/USDC") { pubkey } missing: feed(name: "BTC/USD") { name } }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "feeds": [{ "name": "SOL/USDC" }],
+                "feed": { "pubkey": Pubkey::new_from_array([1; 32]).to_string() },
+                "missing": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{watcher::OracleWatcher, Oracle};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use tokio::sync::mpsc::UnboundedSender;
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+use crate::store::Update;
+
+/// Most accounts `getMultipleAccounts` returns per request.
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// An update of a named feed, on its way to the store.
+#[derive(Clone, Debug)]
+pub struct Ingested {
+    pub feed: Arc<str>,
+    pub update: Update,
+}
+
+/// Streams the feeds' updates over Geyser into `sender` until the returned
+/// watcher is dropped.
+pub fn geyser(
+    endpoint: String,
+    x_token: Option<String>,
+    feeds: &HashMap<Pubkey, Arc<str>>,
+    commitment: CommitmentLevel,
+    sender: UnboundedSender<Ingested>,
+) -> OracleWatcher<PriceFeed> {
+    let names = feeds.clone();
+
+    OracleWatcher::<PriceFeed>::spawn(
+        endpoint,
+        x_token,
+        feeds.keys().copied().collect(),
+        commitment,
+        move |update| {
+            if let Some(feed) = names.get(&update.pubkey) {
+                let _ = sender.send(Ingested {
+                    feed: feed.clone(),
+                    update: Update {
+                        sequence: update.oracle.sequence,
+                        price: update.oracle.payload.price,
+                        slot: update.slot,
+                        received_ms: now_ms(),
+                    },
+                });
+            }
+        },
+    )
+}
+
+/// Reads the feeds every `interval` over RPC and sends each one whose
+/// sequence advanced into `sender`, for clusters without a Geyser endpoint.
+/// Updates landing between two polls are missed, only the last is kept.
+///
+/// Runs until `sender`'s receiver is dropped.
+pub async fn poll(
+    client: RpcClient,
+    feeds: HashMap<Pubkey, Arc<str>>,
+    commitment: CommitmentConfig,
+    interval: Duration,
+    sender: UnboundedSender<Ingested>,
+) {
+    let pubkeys: Vec<Pubkey> = feeds.keys().copied().collect();
+    let mut latest = HashMap::new();
+    let mut ticks = tokio::time::interval(interval);
+
+    while !sender.is_closed() {
+        ticks.tick().await;
+
+        for chunk in pubkeys.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+            let response = match client
+                .get_multiple_accounts_with_commitment(chunk, commitment)
+                .await
+            {
+                Ok(response) => response,
+                Err(err) => {
+                    eprintln!("failed to poll oracles: {err}");
+                    continue;
+                }
+            };
+
+            let received_ms = now_ms();
+            let accounts = chunk.iter().zip(response.value);
+            for (pubkey, data) in
+                accounts.filter_map(|(pubkey, account)| Some((pubkey, account?.data)))
+            {
+                let Some(update) = advanced(
+                    &mut latest,
+                    pubkey,
+                    &data,
+                    response.context.slot,
+                    received_ms,
+                ) else {
+                    continue;
+                };
+                let _ = sender.send(Ingested {
+                    feed: feeds[pubkey].clone(),
+                    update,
+                });
+            }
+        }
+    }
+}
+
+/// Decodes a polled oracle, returning it only if its sequence advanced past
+/// the last one seen for the same account.
+fn advanced(
+    latest: &mut HashMap<Pubkey, u64>,
+    pubkey: &Pubkey,
+    data: &[u8],
+    slot: u64,
+    received_ms: u64,
+) -> Option<Update> {
+    let (oracle, _) = Oracle::<PriceFeed>::try_from_bytes(data)?;
+    if latest
+        .get(pubkey)
+        .is_some_and(|sequence| oracle.sequence <= *sequence)
+    {
+        return None;
+    }
+    latest.insert(*pubkey, oracle.sequence);
+
+    Some(Update {
+        sequence: oracle.sequence,
+        price: oracle.payload.price,
+        slot,
+        received_ms,
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oracle(sequence: u64, price: u64) -> Vec<u8> {
+        Oracle {
+            sequence,
+            payload: PriceFeed { price },
+        }
+        .to_bytes()
+    }
+
+    #[test]
+    fn test_poll_keeps_advancing_sequences() {
+        let pubkey = Pubkey::new_unique();
+        let mut latest = HashMap::new();
+
+        let update = advanced(&mut latest, &pubkey, &oracle(7, 1_100_000), 42, 1_000).unwrap();
+        assert_eq!(
+            update,
+            Update {
+                sequence: 7,
+                price: 1_100_000,
+                slot: 42,
+                received_ms: 1_000,
+            }
+        );
+        assert_eq!(
+            advanced(&mut latest, &pubkey, &oracle(7, 1_100_000), 43, 2_000),
+            None
+        );
+        assert_eq!(
+            advanced(&mut latest, &pubkey, &oracle(6, 1_000_000), 44, 3_000),
+            None
+        );
+        assert_eq!(
+            advanced(&mut latest, &pubkey, &oracle(8, 1_200_000), 45, 4_000)
+                .map(|update| update.price),
+            Some(1_200_000)
+        );
+    }
+
+    #[test]
+    fn test_poll_skips_malformed_accounts() {
+        let mut latest = HashMap::new();
+
+        assert_eq!(
+            advanced(&mut latest, &Pubkey::new_unique(), &[0; 4], 42, 1_000),
+            None
+        );
+        assert!(latest.is_empty());
+    }
+}
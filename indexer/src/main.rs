@@ -0,0 +1,154 @@
+use std::{
+    collections::HashMap, error::Error, fs, net::SocketAddr, path::PathBuf, sync::Arc,
+    time::Duration,
+};
+
+use clap::{Parser, ValueEnum};
+use doppler_sdk::registry::FeedRegistry;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use tokio::sync::mpsc;
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+use crate::{graphql::Feeds, store::Store};
+
+mod graphql;
+mod ingest;
+mod store;
+
+/// Index the updates of Doppler oracles into Postgres and serve them over
+/// GraphQL: current prices, history and publication stats per feed.
+#[derive(Parser)]
+#[command(name = "doppler-indexer", version)]
+struct Args {
+    /// Feed manifest naming the oracles to index.
+    #[arg(long, short, default_value = "feeds.toml")]
+    registry: PathBuf,
+
+    /// Postgres connection string, e.g. `postgres://doppler@localhost/doppler`.
+    #[arg(long)]
+    database_url: String,
+
+    /// Yellowstone gRPC endpoint to stream updates from.
+    #[arg(long, required_unless_present = "rpc_url", conflicts_with = "rpc_url")]
+    endpoint: Option<String>,
+
+    /// Yellowstone gRPC access token.
+    #[arg(long)]
+    x_token: Option<String>,
+
+    /// RPC endpoint to poll the oracles from instead, for clusters without
+    /// Geyser. Updates landing between two polls are not indexed.
+    #[arg(long)]
+    rpc_url: Option<String>,
+
+    /// Milliseconds between two polls of `--rpc-url`.
+    #[arg(long, default_value_t = 400)]
+    poll_interval_ms: u64,
+
+    #[arg(long, value_enum, default_value_t = Commitment::Confirmed)]
+    commitment: Commitment,
+
+    /// Address to serve the GraphQL API on.
+    #[arg(long, default_value = "0.0.0.0:8901")]
+    listen: SocketAddr,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentLevel {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => Self::Processed,
+            Commitment::Confirmed => Self::Confirmed,
+            Commitment::Finalized => Self::Finalized,
+        }
+    }
+}
+
+impl From<Commitment> for CommitmentConfig {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => Self::processed(),
+            Commitment::Confirmed => Self::confirmed(),
+            Commitment::Finalized => Self::finalized(),
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let manifest = fs::read_to_string(&args.registry)
+        .map_err(|err| format!("failed to read {}: {err}", args.registry.display()))?;
+    let registry = FeedRegistry::from_toml(&manifest)?;
+    if let Some((name, feed)) = registry
+        .iter()
+        .find(|(_, feed)| feed.payload != "PriceFeed")
+    {
+        return Err(format!("{name}: unsupported payload {}", feed.payload).into());
+    }
+    let feeds: Feeds = registry
+        .iter()
+        .map(|(name, feed)| (Arc::from(name), feed.pubkey))
+        .collect();
+    let oracles: HashMap<Pubkey, Arc<str>> = feeds
+        .iter()
+        .map(|(name, pubkey)| (*pubkey, name.clone()))
+        .collect();
+
+    let store = Arc::new(Store::connect(&args.database_url).await?);
+
+    let (sender, mut updates) = mpsc::unbounded_channel();
+    let _watcher = match args.endpoint {
+        Some(endpoint) => Some(ingest::geyser(
+            endpoint,
+            args.x_token,
+            &oracles,
+            args.commitment.into(),
+            sender,
+        )),
+        None => {
+            let client = RpcClient::new_with_commitment(
+                args.rpc_url.unwrap_or_default(),
+                args.commitment.into(),
+            );
+            tokio::spawn(ingest::poll(
+                client,
+                oracles,
+                args.commitment.into(),
+                Duration::from_millis(args.poll_interval_ms.max(1)),
+                sender,
+            ));
+            None
+        }
+    };
+
+    let writer = store.clone();
+    tokio::spawn(async move {
+        while let Some(ingested) = updates.recv().await {
+            if let Err(err) = writer.insert(&ingested.feed, &ingested.update).await {
+                eprintln!("{}: failed to store update: {err}", ingested.feed);
+            }
+        }
+    });
+
+    let schema = graphql::schema(feeds).data(store).finish();
+    println!(
+        "indexing {} feeds, GraphQL on http://{}/graphql",
+        registry.iter().count(),
+        args.listen
+    );
+
+    tokio::select! {
+        served = graphql::serve(args.listen, schema) => Ok(served?),
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
@@ -0,0 +1,164 @@
+use tokio_postgres::{Client, NoTls, Row};
+
+/// Creates the updates table on first start. Updates are keyed by feed and
+/// sequence, so re-ingesting an update after a restart or reconnect is a
+/// no-op, and the key doubles as the index history queries walk.
+const MIGRATION: &str = "
+CREATE TABLE IF NOT EXISTS doppler_updates (
+    feed TEXT NOT NULL,
+    sequence BIGINT NOT NULL,
+    price BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    received_ms BIGINT NOT NULL,
+    PRIMARY KEY (feed, sequence)
+)";
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("postgres: {0}")]
+    Postgres(#[from] tokio_postgres::Error),
+    #[error("{0} does not fit in a BIGINT column")]
+    OutOfRange(u64),
+}
+
+/// An oracle value, as stored for its feed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Update {
+    pub sequence: u64,
+    pub price: u64,
+    pub slot: u64,
+    /// When the indexer received the update, in Unix milliseconds.
+    pub received_ms: u64,
+}
+
+/// How a feed has been published over a window of updates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub updates: u64,
+    pub first_received_ms: Option<u64>,
+    pub last_received_ms: Option<u64>,
+    /// Mean time between consecutive updates.
+    pub mean_interval_ms: Option<f64>,
+    /// Longest time between consecutive updates.
+    pub max_interval_ms: Option<u64>,
+}
+
+/// The update history of every indexed feed, in Postgres.
+pub struct Store {
+    client: Client,
+}
+
+impl Store {
+    /// Connects to the database at `url`, e.g.
+    /// `postgres://doppler@localhost/doppler`, and creates the updates
+    /// table if it does not exist yet.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime.
+    pub async fn connect(url: &str) -> Result<Self, StoreError> {
+        let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                eprintln!("postgres connection failed: {err}");
+            }
+        });
+        client.batch_execute(MIGRATION).await?;
+
+        Ok(Self { client })
+    }
+
+    /// Records an update of `feed`, returning whether it was new.
+    pub async fn insert(&self, feed: &str, update: &Update) -> Result<bool, StoreError> {
+        let inserted = self
+            .client
+            .execute(
+                "INSERT INTO doppler_updates (feed, sequence, price, slot, received_ms)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT DO NOTHING",
+                &[
+                    &feed,
+                    &bigint(update.sequence)?,
+                    &bigint(update.price)?,
+                    &bigint(update.slot)?,
+                    &bigint(update.received_ms)?,
+                ],
+            )
+            .await?;
+
+        Ok(inserted == 1)
+    }
+
+    /// The update of `feed` with the highest sequence.
+    pub async fn latest(&self, feed: &str) -> Result<Option<Update>, StoreError> {
+        Ok(self.history(feed, 1, None).await?.pop())
+    }
+
+    /// Up to `limit` updates of `feed`, newest first, starting below
+    /// `before` to page through older ones.
+    pub async fn history(
+        &self,
+        feed: &str,
+        limit: u32,
+        before: Option<u64>,
+    ) -> Result<Vec<Update>, StoreError> {
+        let before = before.map(bigint).transpose()?.unwrap_or(i64::MAX);
+        let rows = self
+            .client
+            .query(
+                "SELECT sequence, price, slot, received_ms FROM doppler_updates
+                 WHERE feed = $1 AND sequence < $2
+                 ORDER BY sequence DESC
+                 LIMIT $3",
+                &[&feed, &before, &i64::from(limit)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(update).collect())
+    }
+
+    /// Publication statistics of `feed` over the updates received since
+    /// `since_ms`.
+    pub async fn stats(&self, feed: &str, since_ms: u64) -> Result<Stats, StoreError> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT count(*), min(received_ms), max(received_ms),
+                        avg(gap)::DOUBLE PRECISION, max(gap)
+                 FROM (
+                     SELECT received_ms,
+                            received_ms - lag(received_ms) OVER (ORDER BY sequence) AS gap
+                     FROM doppler_updates
+                     WHERE feed = $1 AND received_ms >= $2
+                 ) updates",
+                &[&feed, &bigint(since_ms)?],
+            )
+            .await?;
+
+        Ok(Stats {
+            updates: unsigned(row.get(0)),
+            first_received_ms: row.get::<_, Option<i64>>(1).map(unsigned),
+            last_received_ms: row.get::<_, Option<i64>>(2).map(unsigned),
+            mean_interval_ms: row.get(3),
+            max_interval_ms: row.get::<_, Option<i64>>(4).map(unsigned),
+        })
+    }
+}
+
+fn update(row: &Row) -> Update {
+    Update {
+        sequence: unsigned(row.get(0)),
+        price: unsigned(row.get(1)),
+        slot: unsigned(row.get(2)),
+        received_ms: unsigned(row.get(3)),
+    }
+}
+
+/// Postgres has no unsigned integers; every value the indexer stores is
+/// checked to fit a `BIGINT` on the way in.
+fn bigint(value: u64) -> Result<i64, StoreError> {
+    i64::try_from(value).map_err(|_| StoreError::OutOfRange(value))
+}
+
+fn unsigned(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
@@ -0,0 +1,312 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::{
+    plugin::{Plugin, DEFAULT_FUEL},
+    source::Quote,
+};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AggregateError {
+    #[error("{available} of {required} required sources available")]
+    TooFewSources { available: usize, required: usize },
+    #[error("plugin: {0}")]
+    Plugin(String),
+}
+
+/// How quotes from a feed's sources are combined into one price.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    #[default]
+    Median,
+    /// Mean of the quotes left after dropping `trim` of them at each end.
+    TrimmedMean,
+    /// Mean of the quotes weighted by `weights`.
+    Weighted,
+    /// Whatever the WebAssembly `module` computes, see [`Plugin`].
+    Wasm,
+}
+
+/// Aggregation settings of a feed:
+///
+/// ```toml
+/// aggregation = { method = "trimmed_mean", trim = 0.25, max_deviation_bps = 200, min_sources = 3 }
+/// aggregation = { method = "wasm", module = "plugins/vwap.wasm", min_sources = 2 }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Aggregation {
+    pub method: Method,
+    /// Fraction of quotes dropped at each end by [`Method::TrimmedMean`].
+    pub trim: f64,
+    /// Weight of each source for [`Method::Weighted`], in the order the
+    /// sources are listed.
+    pub weights: Vec<f64>,
+    /// Quotes further than this from the median are rejected as outliers.
+    pub max_deviation_bps: Option<u64>,
+    /// Quotes left after outlier rejection needed to publish at all.
+    pub min_sources: usize,
+    /// Module run by [`Method::Wasm`], reloaded with the configuration.
+    pub module: Option<PathBuf>,
+    /// Fuel [`Method::Wasm`]'s module may burn per aggregation.
+    pub fuel: Option<u64>,
+    #[serde(skip)]
+    plugin: Option<Plugin>,
+}
+
+impl Default for Aggregation {
+    fn default() -> Self {
+        Self {
+            method: Method::Median,
+            trim: 0.0,
+            weights: vec![],
+            max_deviation_bps: None,
+            min_sources: 1,
+            module: None,
+            fuel: None,
+            plugin: None,
+        }
+    }
+}
+
+impl Aggregation {
+    /// Checks the settings against the number of sources they apply to.
+    pub fn validate(&self, sources: usize) -> Result<(), String> {
+        if self.method == Method::Weighted && self.weights.len() != sources {
+            return Err(format!(
+                "{} weights given for {sources} sources",
+                self.weights.len()
+            ));
+        }
+        if !(0.0..0.5).contains(&self.trim) {
+            return Err(format!("trim must be in [0, 0.5), got {}", self.trim));
+        }
+        if self.min_sources > sources {
+            return Err(format!(
+                "min_sources is {} but only {sources} sources are listed",
+                self.min_sources
+            ));
+        }
+        if (self.method == Method::Wasm) != self.module.is_some() {
+            return Err("module is required by, and only used with, the wasm method".into());
+        }
+        if self.method == Method::Wasm && self.max_deviation_bps.is_some() {
+            return Err("max_deviation_bps is left to the wasm module".into());
+        }
+        Ok(())
+    }
+
+    /// Compiles the module of [`Method::Wasm`].
+    pub fn load_plugin(&mut self) -> Result<(), String> {
+        if let Some(module) = &self.module {
+            self.plugin = Some(Plugin::load(module, self.fuel.unwrap_or(DEFAULT_FUEL))?);
+        }
+        Ok(())
+    }
+
+    /// Combines the quotes of a feed's sources, `None` for sources that
+    /// failed.
+    ///
+    /// A feed with a single source publishes its quote as is, keeping the
+    /// source's publish time, unless a WebAssembly module aggregates it.
+    /// Aggregated prices have no publish time and are sequenced by the
+    /// keeper's clock.
+    pub fn aggregate(&self, quotes: &[Option<Quote>]) -> Result<Quote, AggregateError> {
+        let mut prices: Vec<(u64, f64)> = quotes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, quote)| {
+                let weight = self.weights.get(i).copied().unwrap_or(1.0);
+                quote.map(|quote| (quote.price, weight))
+            })
+            .collect();
+        self.check_count(prices.len())?;
+
+        if self.method == Method::Wasm {
+            let plugin = self
+                .plugin
+                .as_ref()
+                .ok_or_else(|| AggregateError::Plugin("module not loaded".into()))?;
+            let price = plugin
+                .aggregate(quotes)
+                .map_err(|err| AggregateError::Plugin(err.to_string()))?;
+            return Ok(Quote {
+                price,
+                published_ms: None,
+                confidence: None,
+            });
+        }
+
+        if let [Some(quote)] = quotes {
+            return Ok(*quote);
+        }
+
+        prices.sort_unstable_by_key(|(price, _)| *price);
+        if let Some(max_deviation_bps) = self.max_deviation_bps {
+            let median = u128::from(median(&prices));
+            prices.retain(|(price, _)| {
+                u128::from(*price).abs_diff(median) * 10_000
+                    <= u128::from(max_deviation_bps) * median
+            });
+            self.check_count(prices.len())?;
+        }
+
+        let price = match self.method {
+            Method::Median => median(&prices),
+            Method::TrimmedMean => {
+                let trimmed = (prices.len() as f64 * self.trim) as usize;
+                mean(&prices[trimmed..prices.len() - trimmed])
+            }
+            Method::Weighted => weighted_mean(&prices),
+            Method::Wasm => unreachable!("aggregated by the plugin"),
+        };
+
+        Ok(Quote {
+            price,
+            published_ms: None,
+            confidence: None,
+        })
+    }
+
+    fn check_count(&self, available: usize) -> Result<(), AggregateError> {
+        let required = self.min_sources.max(1);
+        if available < required {
+            return Err(AggregateError::TooFewSources {
+                available,
+                required,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Median of sorted, non-empty `prices`, rounding down between the two
+/// middle ones.
+fn median(prices: &[(u64, f64)]) -> u64 {
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        prices[mid].0
+    } else {
+        mean(&prices[mid - 1..=mid])
+    }
+}
+
+fn mean(prices: &[(u64, f64)]) -> u64 {
+    let sum: u128 = prices.iter().map(|(price, _)| u128::from(*price)).sum();
+    (sum / prices.len() as u128) as u64
+}
+
+fn weighted_mean(prices: &[(u64, f64)]) -> u64 {
+    let (sum, weights) = prices
+        .iter()
+        .fold((0.0, 0.0), |(sum, weights), (price, weight)| {
+            (sum + *price as f64 * weight, weights + weight)
+        });
+
+    if weights > 0.0 {
+        (sum / weights).round() as u64
+    } else {
+        mean(prices)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quotes(prices: &[Option<u64>]) -> Vec<Option<Quote>> {
+        prices
+            .iter()
+            .map(|price| {
+                price.map(|price| Quote {
+                    price,
+                    published_ms: Some(1),
+                    confidence: None,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_median_rejects_outliers() {
+        let aggregation = Aggregation {
+            max_deviation_bps: Some(100),
+            min_sources: 3,
+            ..Aggregation::default()
+        };
+
+        let quote = aggregation
+            .aggregate(&quotes(&[Some(1_000), Some(1_005), Some(998), Some(5_000)]))
+            .unwrap();
+        assert_eq!(quote.price, 1_000);
+        assert_eq!(quote.published_ms, None);
+
+        assert_eq!(
+            aggregation.aggregate(&quotes(&[Some(1_000), None, Some(5_000)])),
+            Err(AggregateError::TooFewSources {
+                available: 2,
+                required: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_trimmed_and_weighted_mean() {
+        let trimmed = Aggregation {
+            method: Method::TrimmedMean,
+            trim: 0.25,
+            ..Aggregation::default()
+        };
+        let prices = quotes(&[Some(1), Some(100), Some(102), Some(1_000)]);
+        assert_eq!(trimmed.aggregate(&prices).unwrap().price, 101);
+
+        let weighted = Aggregation {
+            method: Method::Weighted,
+            weights: vec![3.0, 1.0],
+            ..Aggregation::default()
+        };
+        assert_eq!(
+            weighted
+                .aggregate(&quotes(&[Some(100), Some(200)]))
+                .unwrap()
+                .price,
+            125
+        );
+        assert!(weighted.validate(3).is_err());
+    }
+
+    #[test]
+    fn test_wasm_needs_a_module() {
+        let wasm = Aggregation {
+            method: Method::Wasm,
+            ..Aggregation::default()
+        };
+        assert!(wasm.validate(2).is_err());
+
+        let with_module = Aggregation {
+            module: Some("vwap.wasm".into()),
+            ..wasm
+        };
+        assert!(with_module.validate(2).is_ok());
+        assert_eq!(
+            with_module.aggregate(&quotes(&[Some(100), Some(200)])),
+            Err(AggregateError::Plugin("module not loaded".into()))
+        );
+
+        let median = Aggregation {
+            module: Some("vwap.wasm".into()),
+            ..Aggregation::default()
+        };
+        assert!(median.validate(2).is_err());
+    }
+
+    #[test]
+    fn test_single_source_passes_through() {
+        let quote = Aggregation::default()
+            .aggregate(&quotes(&[Some(42)]))
+            .unwrap();
+        assert_eq!(quote.published_ms, Some(1));
+    }
+}
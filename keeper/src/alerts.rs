@@ -0,0 +1,214 @@
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+use serde::Deserialize;
+use serde_json::json;
+use solana_pubkey::Pubkey;
+
+use crate::config::Feed;
+
+/// Webhook alerting settings:
+///
+/// ```toml
+/// [alerts]
+/// webhook_url = "https://hooks.slack.com/services/..."
+/// failures = 3
+/// divergence_bps = 200
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertConfig {
+    /// Receives `{"text": ...}` JSON posts, the format Slack incoming
+    /// webhooks expect.
+    pub webhook_url: String,
+    /// Consecutive failed sends of a feed before alerting.
+    #[serde(default = "default_failures")]
+    pub failures: usize,
+    /// Alert when a feed's sources and its oracle differ by more than this
+    /// many basis points.
+    pub divergence_bps: Option<u64>,
+}
+
+const fn default_failures() -> usize {
+    3
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Incident {
+    /// No update landed within the feed's heartbeat window.
+    Stale,
+    /// Sends keep failing.
+    Failing,
+    /// The oracle is far from what the sources say.
+    Diverged,
+}
+
+/// Tracks feed incidents and posts to a webhook when one starts or clears,
+/// rather than on every tick it lasts.
+pub struct Alerts {
+    config: AlertConfig,
+    http: reqwest::Client,
+    started: Instant,
+    failures: HashMap<Pubkey, usize>,
+    active: HashSet<(Pubkey, Incident)>,
+    outbox: Vec<String>,
+}
+
+impl Alerts {
+    pub fn new(config: AlertConfig, http: reqwest::Client) -> Self {
+        Self {
+            config,
+            http,
+            started: Instant::now(),
+            failures: HashMap::new(),
+            active: HashSet::new(),
+            outbox: vec![],
+        }
+    }
+
+    /// Applies new settings, keeping the incidents already raised.
+    pub fn set_config(&mut self, config: AlertConfig) {
+        self.config = config;
+    }
+
+    /// Checks that a feed with a heartbeat landed an update within it,
+    /// allowing half a heartbeat for the update to land. Feeds never updated
+    /// count from startup.
+    pub fn check_stale(&mut self, feed: &Feed, updated: Option<Instant>, now: Instant) {
+        let Some(heartbeat) = feed.heartbeat else {
+            return;
+        };
+        let age = now.duration_since(updated.unwrap_or(self.started));
+
+        self.set(
+            feed,
+            Incident::Stale,
+            age > heartbeat * 3 / 2,
+            format!("no update landed for {}s", age.as_secs()),
+        );
+    }
+
+    /// Checks the oracle's price against a fresh quote.
+    pub fn check_divergence(&mut self, feed: &Feed, onchain: Option<u64>, quote: u64) {
+        let (Some(divergence_bps), Some(onchain)) = (self.config.divergence_bps, onchain) else {
+            return;
+        };
+        let diverged = u128::from(quote).abs_diff(u128::from(onchain)) * 10_000
+            > u128::from(divergence_bps) * u128::from(onchain);
+
+        self.set(
+            feed,
+            Incident::Diverged,
+            diverged,
+            format!("oracle holds {onchain}, sources say {quote}"),
+        );
+    }
+
+    pub fn landed(&mut self, feed: &Feed) {
+        self.failures.remove(&feed.pubkey);
+        self.set(feed, Incident::Failing, false, String::new());
+    }
+
+    pub fn failed(&mut self, feed: &Feed) {
+        let failures = self.failures.entry(feed.pubkey).or_default();
+        *failures += 1;
+        let failures = *failures;
+
+        self.set(
+            feed,
+            Incident::Failing,
+            failures >= self.config.failures,
+            format!("{failures} consecutive sends failed"),
+        );
+    }
+
+    /// Posts the alerts raised since the last flush, without waiting for
+    /// the webhook.
+    pub fn flush(&mut self) {
+        for text in self.outbox.drain(..) {
+            let request = self
+                .http
+                .post(&self.config.webhook_url)
+                .json(&json!({ "text": text }));
+
+            tokio::spawn(async move {
+                if let Err(err) = request.send().await.and_then(|r| r.error_for_status()) {
+                    eprintln!("failed to post alert: {err}");
+                }
+            });
+        }
+    }
+
+    fn set(&mut self, feed: &Feed, incident: Incident, firing: bool, detail: String) {
+        let key = (feed.pubkey, incident);
+        let title = match incident {
+            Incident::Stale => "stale",
+            Incident::Failing => "failing",
+            Incident::Diverged => "diverged",
+        };
+
+        if firing && self.active.insert(key) {
+            self.outbox
+                .push(format!(":rotating_light: {} {title}: {detail}", feed.name));
+        } else if !firing && self.active.remove(&key) {
+            self.outbox.push(format!(
+                ":white_check_mark: {} no longer {title}",
+                feed.name
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::config::Payload;
+
+    #[test]
+    fn test_alerts_fire_once_and_resolve() {
+        let mut alerts = Alerts::new(
+            AlertConfig {
+                webhook_url: String::new(),
+                failures: 2,
+                divergence_bps: Some(100),
+            },
+            reqwest::Client::new(),
+        );
+        let feed = Feed {
+            name: "SOL/USDC".to_string(),
+            pubkey: Pubkey::new_unique(),
+            payload: Payload::Price,
+            interval: Duration::ZERO,
+            deviation_bps: None,
+            heartbeat: Some(Duration::from_secs(10)),
+            max_unit_price: None,
+            sources: vec![],
+            aggregation: Default::default(),
+            transforms: vec![],
+        };
+
+        alerts.failed(&feed);
+        assert!(alerts.outbox.is_empty());
+        alerts.failed(&feed);
+        alerts.failed(&feed);
+        assert_eq!(alerts.outbox.len(), 1);
+        alerts.landed(&feed);
+        assert_eq!(alerts.outbox.len(), 2);
+
+        let now = Instant::now();
+        alerts.check_stale(&feed, Some(now), now + Duration::from_secs(14));
+        assert_eq!(alerts.outbox.len(), 2);
+        alerts.check_stale(&feed, Some(now), now + Duration::from_secs(16));
+        assert_eq!(alerts.outbox.len(), 3);
+
+        alerts.check_divergence(&feed, Some(1_000), 1_010);
+        assert_eq!(alerts.outbox.len(), 3);
+        alerts.check_divergence(&feed, Some(1_000), 1_011);
+        assert_eq!(alerts.outbox.len(), 4);
+        assert!(alerts.outbox[3].contains("SOL/USDC diverged"));
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::Path,
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use k256::ecdsa::SigningKey;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    chainlink::Address,
+    source::{decode_hex, encode_hex},
+};
+
+const DOMAIN_TYPE: &str =
+    "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const DOMAIN_NAME: &str = "Doppler";
+const DOMAIN_VERSION: &str = "1";
+const PRICE_TYPE: &str = "Price(bytes32 feed,uint64 sequence,uint64 price,uint64 timestamp)";
+
+/// Signs every confirmed update as EIP-712 typed data, so an EVM contract
+/// can verify Doppler prices relayed off-chain with `ecrecover`:
+///
+/// ```toml
+/// [attestation]
+/// key = "attester.hex"
+/// chain_id = 1
+/// verifying_contract = "0x..."
+/// addr = "0.0.0.0:8081"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationConfig {
+    /// File holding the secp256k1 private key as hex.
+    pub key: PathBuf,
+    /// Chain the verifier is deployed on.
+    pub chain_id: u64,
+    /// The verifier contract. Signatures are bound to it and `chain_id`, so
+    /// they cannot be replayed against another deployment.
+    pub verifying_contract: Address,
+    /// Address to serve `/attestations` on.
+    pub addr: SocketAddr,
+}
+
+/// A signed `Price(bytes32 feed, uint64 sequence, uint64 price, uint64
+/// timestamp)`, `feed` being the oracle address.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Attestation {
+    pub name: String,
+    /// The oracle address as `bytes32` hex.
+    pub feed: String,
+    pub sequence: u64,
+    pub price: u64,
+    /// When the update was confirmed, in Unix seconds.
+    pub timestamp: u64,
+    /// `r || s || v` hex, with `v` 27 or 28 as `ecrecover` expects.
+    pub signature: String,
+}
+
+/// Signs confirmed updates and keeps the latest attestation of each feed.
+pub struct Attester {
+    key: SigningKey,
+    signer: Address,
+    chain_id: u64,
+    verifying_contract: Address,
+    domain_separator: [u8; 32],
+    latest: Mutex<BTreeMap<Pubkey, Attestation>>,
+}
+
+impl Attester {
+    pub fn new(config: &AttestationConfig) -> Result<Self, String> {
+        let hex = fs::read_to_string(&config.key)
+            .map_err(|err| format!("failed to read {}: {err}", config.key.display()))?;
+        let key = decode_hex(hex.trim())
+            .and_then(|bytes| SigningKey::from_slice(&bytes).ok())
+            .ok_or_else(|| {
+                format!(
+                    "{} is not a hex secp256k1 private key",
+                    config.key.display()
+                )
+            })?;
+
+        Ok(Self {
+            signer: Address::from_key(key.verifying_key()),
+            key,
+            chain_id: config.chain_id,
+            verifying_contract: config.verifying_contract,
+            domain_separator: domain_separator(config.chain_id, &config.verifying_contract),
+            latest: Mutex::default(),
+        })
+    }
+
+    /// The address the verifier should accept signatures from.
+    pub const fn signer(&self) -> Address {
+        self.signer
+    }
+
+    /// Signs an update of `oracle` confirmed at `timestamp` and makes it the
+    /// feed's latest attestation.
+    pub fn attest(&self, name: &str, oracle: &Pubkey, sequence: u64, price: u64, timestamp: u64) {
+        let mut message = vec![0x19, 0x01];
+        message.extend_from_slice(&self.domain_separator);
+        message.extend_from_slice(&Keccak256::digest(price_struct(
+            oracle, sequence, price, timestamp,
+        )));
+        let digest = Keccak256::digest(&message);
+
+        let (signature, recovery_id) = match self.key.sign_prehash_recoverable(&digest) {
+            Ok(signed) => signed,
+            Err(err) => {
+                eprintln!("{name}: failed to sign attestation: {err}");
+                return;
+            }
+        };
+        let mut signature = signature.to_bytes().to_vec();
+        signature.push(27 + recovery_id.to_byte());
+
+        self.lock().insert(
+            *oracle,
+            Attestation {
+                name: name.to_string(),
+                feed: format!("0x{}", encode_hex(oracle.as_ref())),
+                sequence,
+                price,
+                timestamp,
+                signature: format!("0x{}", encode_hex(&signature)),
+            },
+        );
+    }
+
+    /// Forgets the attestations of oracles not in `oracles`.
+    pub fn retain(&self, oracles: &[Pubkey]) {
+        self.lock().retain(|oracle, _| oracles.contains(oracle));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<Pubkey, Attestation>> {
+        self.latest.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// `keccak256(abi.encode(typeHash, keccak256(name), keccak256(version),
+/// chainId, verifyingContract))`.
+fn domain_separator(chain_id: u64, verifying_contract: &Address) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(5 * 32);
+    encoded.extend_from_slice(&Keccak256::digest(DOMAIN_TYPE));
+    encoded.extend_from_slice(&Keccak256::digest(DOMAIN_NAME));
+    encoded.extend_from_slice(&Keccak256::digest(DOMAIN_VERSION));
+    encoded.extend_from_slice(&uint_word(chain_id));
+    encoded.extend_from_slice(&[0; 12]);
+    encoded.extend_from_slice(verifying_contract.as_bytes());
+
+    Keccak256::digest(&encoded).into()
+}
+
+/// `abi.encode(typeHash, feed, sequence, price, timestamp)`, whose hash is
+/// the EIP-712 struct hash of a `Price`.
+fn price_struct(oracle: &Pubkey, sequence: u64, price: u64, timestamp: u64) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(5 * 32);
+    encoded.extend_from_slice(&Keccak256::digest(PRICE_TYPE));
+    encoded.extend_from_slice(oracle.as_ref());
+    encoded.extend_from_slice(&uint_word(sequence));
+    encoded.extend_from_slice(&uint_word(price));
+    encoded.extend_from_slice(&uint_word(timestamp));
+    encoded
+}
+
+/// An ABI `uint` word.
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Serves `/attestations`, the latest attestation of every feed along with
+/// the signer and EIP-712 domain, and `/attestations/<oracle>`, the latest
+/// attestation of one oracle, 404 if it has none yet.
+pub async fn serve(addr: SocketAddr, attester: Arc<Attester>) -> std::io::Result<()> {
+    let all = attester.clone();
+    let app = Router::new()
+        .route(
+            "/attestations",
+            get(move || async move {
+                let attestations: Vec<Attestation> = all.lock().values().cloned().collect();
+                respond(
+                    StatusCode::OK,
+                    json!({
+                        "signer": all.signer.to_string(),
+                        "domain": {
+                            "name": DOMAIN_NAME,
+                            "version": DOMAIN_VERSION,
+                            "chainId": all.chain_id,
+                            "verifyingContract": all.verifying_contract.to_string(),
+                        },
+                        "attestations": attestations,
+                    }),
+                )
+            }),
+        )
+        .route(
+            "/attestations/:oracle",
+            get(move |Path(oracle): Path<String>| async move {
+                let attestation = oracle
+                    .parse::<Pubkey>()
+                    .ok()
+                    .and_then(|oracle| attester.lock().get(&oracle).cloned());
+                match attestation {
+                    Some(attestation) => respond(StatusCode::OK, json!(attestation)),
+                    None => respond(
+                        StatusCode::NOT_FOUND,
+                        json!({ "error": format!("no attestation for {oracle}") }),
+                    ),
+                }
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn respond(status: StatusCode, body: serde_json::Value) -> impl IntoResponse {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_struct_is_abi_encoded() {
+        let oracle = Pubkey::new_from_array([7; 32]);
+        let encoded = price_struct(&oracle, 1_700_000_000_123, 1_100_000, 1_700_000_001);
+
+        assert_eq!(encoded.len(), 5 * 32);
+        assert_eq!(&encoded[..32], Keccak256::digest(PRICE_TYPE).as_slice());
+        assert_eq!(&encoded[32..64], &[7; 32]);
+        assert_eq!(encoded[64..88], [0; 24]);
+        assert_eq!(encoded[88..96], 1_700_000_000_123u64.to_be_bytes());
+        assert_eq!(encoded[120..128], 1_100_000u64.to_be_bytes());
+        assert_eq!(encoded[152..160], 1_700_000_001u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_domain_separator_binds_chain_and_contract() {
+        let contract: Address = "0x0000000000000000000000000000000000000001"
+            .parse()
+            .unwrap();
+        let other: Address = "0x0000000000000000000000000000000000000002"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            domain_separator(1, &contract),
+            domain_separator(1, &contract)
+        );
+        assert_ne!(
+            domain_separator(1, &contract),
+            domain_separator(10, &contract)
+        );
+        assert_ne!(domain_separator(1, &contract), domain_separator(1, &other));
+    }
+}
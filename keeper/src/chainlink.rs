@@ -0,0 +1,347 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use doppler_sdk::rescale;
+use hmac::{Hmac, Mac};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+use crate::source::{decode_hex, encode_hex, from_str, Quote, SourceError};
+
+pub const DATA_STREAMS_URL: &str = "https://api.dataengine.chain.link";
+
+/// Decimals of Data Streams prices.
+const PRICE_EXPO: i32 = -18;
+/// Report schema carrying a benchmark price, bid and ask.
+const SCHEMA_V3: [u8; 2] = [0x00, 0x03];
+
+/// A Chainlink Data Streams feed, read through the REST API.
+///
+/// Reports are only published once at least `min_signatures` distinct
+/// `signers` of the DON have signed them, which is the `f + 1` the on-chain
+/// verifier requires.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stream {
+    /// Hex feed ID, e.g. `0x0003...`. Only v3 (crypto) schemas are supported.
+    pub feed_id: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub signers: Vec<Address>,
+    pub min_signatures: usize,
+    /// The price is rescaled to `10^exponent` units.
+    pub exponent: i32,
+    #[serde(default = "default_endpoint")]
+    pub endpoint: String,
+}
+
+fn default_endpoint() -> String {
+    DATA_STREAMS_URL.to_string()
+}
+
+/// An Ethereum address, the form DON signers are identified by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address([u8; 20]);
+
+impl Address {
+    /// The address of `key`: the tail of the hash of the uncompressed key,
+    /// without its 0x04 prefix.
+    pub fn from_key(key: &VerifyingKey) -> Self {
+        let point = key.to_encoded_point(false);
+        let hash = Keccak256::digest(&point.as_bytes()[1..]);
+        Self(hash[12..].try_into().expect("hash is 32 bytes"))
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", encode_hex(&self.0))
+    }
+}
+
+impl FromStr for Address {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        decode_hex(s)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(Self)
+            .ok_or("expected a 0x-prefixed 20-byte hex address")
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_str(deserializer)
+    }
+}
+
+#[derive(Deserialize)]
+struct LatestReport {
+    report: ReportResponse,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportResponse {
+    full_report: String,
+}
+
+/// A report as submitted to the verifier: the OCR report context, the
+/// encoded report and the DON signatures over both.
+struct FullReport<'a> {
+    context: &'a [u8],
+    blob: &'a [u8],
+    rs: Vec<&'a [u8]>,
+    ss: Vec<&'a [u8]>,
+    vs: &'a [u8],
+}
+
+impl Stream {
+    pub fn validate(&self) -> Result<(), String> {
+        if decode_hex(&self.feed_id).is_none_or(|id| id.len() != 32) {
+            return Err(format!("feed_id {} is not a 32-byte hex ID", self.feed_id));
+        }
+        if self.min_signatures == 0 || self.min_signatures > self.signers.len() {
+            return Err(format!(
+                "min_signatures must be between 1 and the {} signers listed",
+                self.signers.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fetches the latest report, checks its signatures and rescales its
+    /// benchmark price.
+    pub async fn fetch(&self, http: &reqwest::Client) -> Result<Quote, SourceError> {
+        let path = format!("/api/v1/reports/latest?feedID={}", self.feed_id);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let signature = self.authorization("GET", &path, b"", timestamp);
+
+        let latest: LatestReport = http
+            .get(format!("{}{path}", self.endpoint))
+            .header("Authorization", &self.client_id)
+            .header("X-Authorization-Timestamp", timestamp.to_string())
+            .header("X-Authorization-Signature-SHA256", signature)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let data = decode_hex(&latest.report.full_report)
+            .ok_or(SourceError::InvalidReport("full report is not hex"))?;
+        let report =
+            FullReport::decode(&data).ok_or(SourceError::InvalidReport("malformed full report"))?;
+        report.verify(&self.signers, self.min_signatures)?;
+
+        let feed_id =
+            decode_hex(&self.feed_id).ok_or(SourceError::InvalidReport("feed ID is not hex"))?;
+        quote(report.blob, &feed_id, self.exponent)
+    }
+
+    /// HMAC of the request the API authenticates clients with.
+    fn authorization(&self, method: &str, path: &str, body: &[u8], timestamp: u128) -> String {
+        let message = format!(
+            "{method} {path} {} {} {timestamp}",
+            encode_hex(&Sha256::digest(body)),
+            self.client_id
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.client_secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(message.as_bytes());
+        encode_hex(&mac.finalize().into_bytes())
+    }
+}
+
+impl<'a> FullReport<'a> {
+    /// Decodes `abi.encode(bytes32[3] context, bytes report, bytes32[] rs,
+    /// bytes32[] ss, bytes32 vs)`.
+    fn decode(data: &'a [u8]) -> Option<Self> {
+        Some(Self {
+            context: data.get(..96)?,
+            blob: abi_bytes(data, abi_uint(word(data, 3)?)?)?,
+            rs: abi_words(data, abi_uint(word(data, 4)?)?)?,
+            ss: abi_words(data, abi_uint(word(data, 5)?)?)?,
+            vs: word(data, 6)?,
+        })
+    }
+
+    /// Checks that at least `min_signatures` distinct `signers` signed the
+    /// report, the way the on-chain verifier does.
+    fn verify(&self, signers: &[Address], min_signatures: usize) -> Result<(), SourceError> {
+        if self.rs.len() != self.ss.len() || self.rs.len() > self.vs.len() {
+            return Err(SourceError::InvalidReport("mismatched signatures"));
+        }
+
+        let mut message = Keccak256::digest(self.blob).to_vec();
+        message.extend_from_slice(self.context);
+        let hash = Keccak256::digest(&message);
+
+        let mut signed = vec![];
+        for ((r, s), v) in self.rs.iter().zip(&self.ss).zip(self.vs) {
+            let Some(address) = recover(&hash, r, s, *v) else {
+                continue;
+            };
+            if signers.contains(&address) && !signed.contains(&address) {
+                signed.push(address);
+            }
+        }
+
+        if signed.len() < min_signatures {
+            return Err(SourceError::Unverified {
+                valid: signed.len(),
+                required: min_signatures,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Recovers the address that produced the signature `(r, s, v)` over `hash`.
+fn recover(hash: &[u8], r: &[u8], s: &[u8], v: u8) -> Option<Address> {
+    let signature = Signature::from_slice(&[r, s].concat()).ok()?;
+    let key =
+        VerifyingKey::recover_from_prehash(hash, &signature, RecoveryId::from_byte(v)?).ok()?;
+
+    Some(Address::from_key(&key))
+}
+
+/// Reads the observation time and benchmark price of a v3 report.
+fn quote(blob: &[u8], feed_id: &[u8], exponent: i32) -> Result<Quote, SourceError> {
+    let report_feed_id = word(blob, 0).ok_or(SourceError::InvalidReport("empty report"))?;
+    if report_feed_id != feed_id {
+        return Err(SourceError::InvalidReport("report is for another feed"));
+    }
+    if report_feed_id[..2] != SCHEMA_V3 {
+        return Err(SourceError::InvalidReport("unsupported report schema"));
+    }
+
+    let observed = word(blob, 2)
+        .and_then(abi_uint)
+        .ok_or(SourceError::InvalidReport("malformed report"))?;
+    let price = word(blob, 6)
+        .and_then(abi_int)
+        .ok_or(SourceError::InvalidReport("malformed report"))?;
+
+    Ok(Quote {
+        price: rescale(price, PRICE_EXPO, exponent).ok_or(SourceError::OutOfRange)?,
+        published_ms: (observed as u64).checked_mul(1000),
+        confidence: None,
+    })
+}
+
+fn word(data: &[u8], index: usize) -> Option<&[u8]> {
+    data.get(index * 32..(index + 1) * 32)
+}
+
+/// Reads an ABI uint that fits a `usize`.
+fn abi_uint(word: &[u8]) -> Option<usize> {
+    let (high, low) = word.split_at(24);
+    high.iter()
+        .all(|byte| *byte == 0)
+        .then(|| u64::from_be_bytes(low.try_into().unwrap()) as usize)
+}
+
+/// Reads an ABI int that fits an `i128`, such as Data Streams' `int192`.
+fn abi_int(word: &[u8]) -> Option<i128> {
+    let (high, low) = word.split_at(16);
+    let value = i128::from_be_bytes(low.try_into().ok()?);
+    let sign = if value < 0 { 0xff } else { 0 };
+
+    high.iter().all(|byte| *byte == sign).then_some(value)
+}
+
+/// Reads dynamic `bytes` whose head is at `offset`.
+fn abi_bytes(data: &[u8], offset: usize) -> Option<&[u8]> {
+    let len = abi_uint(data.get(offset..offset + 32)?)?;
+    data.get(offset + 32..offset + 32 + len)
+}
+
+/// Reads a dynamic `bytes32[]` whose head is at `offset`.
+fn abi_words(data: &[u8], offset: usize) -> Option<Vec<&[u8]>> {
+    let len = abi_uint(data.get(offset..offset + 32)?)?;
+    (0..len).map(|i| word(data, offset / 32 + 1 + i)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi_word(value: u128) -> [u8; 32] {
+        let mut word = [0; 32];
+        word[16..].copy_from_slice(&value.to_be_bytes());
+        word
+    }
+
+    #[test]
+    fn test_decode_full_report() {
+        let mut feed_id = [0x11; 32];
+        feed_id[..2].copy_from_slice(&SCHEMA_V3);
+
+        let mut blob = vec![];
+        blob.extend_from_slice(&feed_id);
+        blob.extend_from_slice(&abi_word(1_700_000_000));
+        blob.extend_from_slice(&abi_word(1_700_000_001));
+        blob.extend_from_slice(&[[0; 32]; 3].concat());
+        blob.extend_from_slice(&abi_word(3_000_120_000_000_000_000_000));
+        blob.extend_from_slice(&[[0; 32]; 2].concat());
+
+        let mut data = vec![];
+        data.extend_from_slice(&[[0xaa; 32]; 3].concat());
+        data.extend_from_slice(&abi_word(7 * 32));
+        data.extend_from_slice(&abi_word((8 + 9) * 32));
+        data.extend_from_slice(&abi_word((8 + 9 + 2) * 32));
+        data.extend_from_slice(&[1; 32]);
+        data.extend_from_slice(&abi_word(blob.len() as u128));
+        data.extend_from_slice(&blob);
+        data.extend_from_slice(&abi_word(1));
+        data.extend_from_slice(&[0xbb; 32]);
+        data.extend_from_slice(&abi_word(1));
+        data.extend_from_slice(&[0xcc; 32]);
+
+        let report = FullReport::decode(&data).unwrap();
+        assert_eq!(report.context, &[0xaa; 96]);
+        assert_eq!(report.blob, blob);
+        assert_eq!(report.rs, [&[0xbb; 32]]);
+        assert_eq!(report.ss, [&[0xcc; 32]]);
+
+        let micros = quote(report.blob, &feed_id, -6).unwrap();
+        assert_eq!(micros.price, 3_000_120_000);
+        assert_eq!(micros.published_ms, Some(1_700_000_001_000));
+
+        assert!(quote(report.blob, &[0; 32], -6).is_err());
+    }
+
+    #[test]
+    fn test_authorization_is_hex_hmac() {
+        let stream = Stream {
+            feed_id: String::new(),
+            client_id: "client".to_string(),
+            client_secret: "secret".to_string(),
+            signers: vec![],
+            min_signatures: 0,
+            exponent: 0,
+            endpoint: default_endpoint(),
+        };
+
+        let signature = stream.authorization("GET", "/api/v1/reports/latest", b"", 1);
+        assert_eq!(signature.len(), 64);
+        assert_ne!(
+            signature,
+            stream.authorization("GET", "/api/v1/reports/latest", b"", 2)
+        );
+    }
+}
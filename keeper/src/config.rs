@@ -0,0 +1,402 @@
+use std::{
+    collections::HashSet, fs, io, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration,
+};
+
+use doppler_sdk::Cluster;
+use serde::Deserialize;
+use solana_pubkey::Pubkey;
+
+use crate::{
+    aggregate::Aggregation,
+    alerts::AlertConfig,
+    attest::AttestationConfig,
+    fees::AdaptiveFeeConfig,
+    history::HistoryConfig,
+    sinks::SinkConfig,
+    source::Source,
+    transform::{Registry, Transform},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("failed to parse {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("{0}")]
+    Invalid(String),
+    #[error("feed {feed}: {reason}")]
+    InvalidFeed { feed: String, reason: String },
+}
+
+/// Keeper configuration, loaded from TOML:
+///
+/// ```toml
+/// rpc_url = "https://api.mainnet-beta.solana.com"
+/// keypair = "admin.json"
+/// interval_ms = 400
+/// unit_price = 1000
+/// max_compute_units = 1400
+/// metrics_addr = "0.0.0.0:9100"
+/// health_addr = "0.0.0.0:8080"
+///
+/// [[feeds]]
+/// name = "SOL/USDC"
+/// source = { type = "http", url = "https://example.com/sol", pointer = "/price", scale = 1e6 }
+///
+/// [[feeds]]
+/// name = "BTC/USD"
+/// source = { type = "pyth", feed_id = "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43", exponent = -6 }
+///
+/// [[feeds]]
+/// name = "JUP/USD"
+/// interval_ms = 5000
+/// source = { type = "switchboard", feed = "<pull feed address>", exponent = -6 }
+///
+/// [[feeds]]
+/// name = "ETH/USD"
+/// deviation_bps = 50
+/// heartbeat_ms = 60000
+/// aggregation = { method = "median", max_deviation_bps = 100, min_sources = 2 }
+/// transforms = [{ type = "circuit_breaker", max_change_bps = 1000 }]
+/// sources = [
+///     { type = "http", url = "https://example.com/eth", pointer = "/price", scale = 1e6 },
+///     { type = "pyth", feed_id = "0xff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace", exponent = -6 },
+///     { type = "switchboard", feed = "<pull feed address>", exponent = -6 },
+/// ]
+/// ```
+///
+/// Unknown keys are rejected, so a misspelled setting fails loudly instead
+/// of silently falling back to its default.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair: PathBuf,
+    /// Keypairs that pay transaction fees in turn instead of the admin.
+    #[serde(default)]
+    pub fee_payers: Vec<PathBuf>,
+    /// Lamports below which a fee payer is skipped until refunded.
+    #[serde(default = "default_min_fee_payer_balance")]
+    pub min_fee_payer_balance: u64,
+    /// Program ID of a Doppler deployment other than the default one.
+    pub program_id: Option<String>,
+    #[serde(default = "default_interval_ms")]
+    pub interval_ms: u64,
+    /// Priority fee in micro-lamports per compute unit.
+    pub unit_price: Option<u64>,
+    /// Adjusts the priority fee to how updates land, starting from
+    /// `unit_price`.
+    pub adaptive_fee: Option<AdaptiveFeeConfig>,
+    /// Compute units budgeted per transaction, which caps how many updates
+    /// are packed into one.
+    pub max_compute_units: Option<u32>,
+    /// Times a failed update is resent, with a fresh sequence, before
+    /// waiting for the next tick.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: usize,
+    /// Address to serve Prometheus metrics on at `/metrics`, e.g.
+    /// `0.0.0.0:9100`.
+    pub metrics_addr: Option<SocketAddr>,
+    /// Address to serve `/health` and `/ready` on, e.g. `0.0.0.0:8080`.
+    pub health_addr: Option<SocketAddr>,
+    /// How long a feed may go without a price, or the keeper loop without
+    /// a tick, before the keeper reports itself unhealthy.
+    #[serde(default = "default_health_max_age_ms")]
+    pub health_max_age_ms: u64,
+    pub alerts: Option<AlertConfig>,
+    /// Signs confirmed updates for EVM verifiers, read only at startup.
+    pub attestation: Option<AttestationConfig>,
+    /// Message buses every confirmed update is published to, read only at
+    /// startup.
+    #[serde(default)]
+    pub sinks: Vec<SinkConfig>,
+    /// Persists observations, decisions and update outcomes, read only at
+    /// startup.
+    pub history: Option<HistoryConfig>,
+    pub feeds: Vec<FeedConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeedConfig {
+    /// Seed the oracle account was created with.
+    pub name: String,
+    /// Oracle address, for accounts not derived from the admin and `name`.
+    pub pubkey: Option<String>,
+    #[serde(default)]
+    pub payload: Payload,
+    /// Minimum time between two fetches of this feed, for sources slower or
+    /// costlier than the keeper interval. Defaults to every tick.
+    pub interval_ms: Option<u64>,
+    /// Only publish when the price moves more than this many basis points
+    /// from the oracle's price.
+    pub deviation_bps: Option<u64>,
+    /// Publish at least this often, whatever `deviation_bps` says, so
+    /// consumers' staleness checks don't trip in quiet markets.
+    pub heartbeat_ms: Option<u64>,
+    /// Highest priority fee paid for this feed's updates, in micro-lamports
+    /// per compute unit.
+    pub max_unit_price: Option<u64>,
+    /// The feed's only source, or one more next to `sources`.
+    pub source: Option<Source>,
+    /// Sources combined according to `aggregation`.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+    #[serde(default)]
+    pub aggregation: Aggregation,
+    /// Steps applied to the aggregated price, in order, each a table with
+    /// the transform's `type` and settings.
+    #[serde(default)]
+    pub transforms: Vec<toml::Table>,
+}
+
+/// Payload type of a feed's oracle.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Payload {
+    /// `PriceFeed { price: u64 }`, the payload the program is built with.
+    #[default]
+    Price,
+}
+
+/// A feed with its oracle address resolved.
+pub struct Feed {
+    pub name: String,
+    pub pubkey: Pubkey,
+    pub payload: Payload,
+    pub interval: Duration,
+    pub deviation_bps: Option<u64>,
+    pub heartbeat: Option<Duration>,
+    pub max_unit_price: Option<u64>,
+    pub sources: Vec<Source>,
+    pub aggregation: Aggregation,
+    pub transforms: Vec<Box<dyn Transform>>,
+}
+
+const fn default_interval_ms() -> u64 {
+    400
+}
+
+const fn default_max_retries() -> usize {
+    2
+}
+
+const fn default_min_fee_payer_balance() -> u64 {
+    10_000_000
+}
+
+const fn default_health_max_age_ms() -> u64 {
+    60_000
+}
+
+impl Config {
+    pub fn load(path: &PathBuf) -> Result<Self, ConfigError> {
+        let config = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.clone(),
+            source,
+        })?;
+
+        toml::from_str(&config).map_err(|source| ConfigError::Parse {
+            path: path.clone(),
+            source,
+        })
+    }
+
+    pub fn cluster(&self) -> Result<Cluster, ConfigError> {
+        Ok(match &self.program_id {
+            Some(program_id) => {
+                Cluster::Custom(Pubkey::from_str(program_id).map_err(|err| {
+                    ConfigError::Invalid(format!("program_id {program_id}: {err}"))
+                })?)
+            }
+            None => Cluster::Mainnet,
+        })
+    }
+
+    /// Validates each feed and resolves its oracle address, deriving it from
+    /// `admin` and the feed name unless given explicitly.
+    pub fn feeds(self, admin: &Pubkey, cluster: Cluster) -> Result<Vec<Feed>, ConfigError> {
+        if self.interval_ms == 0 {
+            return Err(ConfigError::Invalid("interval_ms must be positive".into()));
+        }
+        if self.feeds.is_empty() {
+            return Err(ConfigError::Invalid("no feeds configured".into()));
+        }
+        if self
+            .alerts
+            .as_ref()
+            .is_some_and(|alerts| alerts.failures == 0)
+        {
+            return Err(ConfigError::Invalid(
+                "alerts.failures must be positive".into(),
+            ));
+        }
+        if let Some(adaptive_fee) = &self.adaptive_fee {
+            adaptive_fee.validate().map_err(ConfigError::Invalid)?;
+        }
+        for (i, sink) in self.sinks.iter().enumerate() {
+            sink.validate()
+                .map_err(|err| ConfigError::Invalid(format!("sink {i}: {err}")))?;
+        }
+
+        let registry = Registry::default();
+        let mut names = HashSet::new();
+        let mut oracles = HashSet::new();
+        self.feeds
+            .into_iter()
+            .map(|feed| {
+                let invalid = |reason: String| ConfigError::InvalidFeed {
+                    feed: feed.name.clone(),
+                    reason,
+                };
+
+                if !names.insert(feed.name.clone()) {
+                    return Err(invalid("listed twice".into()));
+                }
+
+                let pubkey = match &feed.pubkey {
+                    Some(pubkey) => Pubkey::from_str(pubkey)
+                        .map_err(|err| invalid(format!("pubkey {pubkey}: {err}")))?,
+                    None => Pubkey::create_with_seed(admin, &feed.name, &cluster.program_id())
+                        .map_err(|err| invalid(format!("cannot derive oracle address: {err}")))?,
+                };
+                if !oracles.insert(pubkey) {
+                    return Err(invalid(format!("oracle {pubkey} is used by another feed")));
+                }
+
+                let sources: Vec<Source> = feed.source.into_iter().chain(feed.sources).collect();
+                if sources.is_empty() {
+                    return Err(invalid("no source".into()));
+                }
+                for (i, source) in sources.iter().enumerate() {
+                    source
+                        .validate()
+                        .map_err(|err| invalid(format!("source {i}: {err}")))?;
+                }
+                let mut aggregation = feed.aggregation;
+                aggregation.validate(sources.len()).map_err(invalid)?;
+                aggregation.load_plugin().map_err(invalid)?;
+                let transforms = feed
+                    .transforms
+                    .into_iter()
+                    .map(|settings| registry.build(settings))
+                    .collect::<Result<_, _>>()
+                    .map_err(invalid)?;
+
+                Ok(Feed {
+                    name: feed.name,
+                    pubkey,
+                    payload: feed.payload,
+                    interval: Duration::from_millis(feed.interval_ms.unwrap_or_default()),
+                    deviation_bps: feed.deviation_bps,
+                    heartbeat: feed.heartbeat_ms.map(Duration::from_millis),
+                    max_unit_price: feed.max_unit_price,
+                    sources,
+                    aggregation,
+                    transforms,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config: Config = toml::from_str(
+            r#"
+            rpc_url = "http://localhost:8899"
+            keypair = "admin.json"
+
+            [alerts]
+            webhook_url = "https://hooks.example.com/keeper"
+
+            [[feeds]]
+            name = "SOL/USDC"
+            source = { type = "http", url = "https://example.com/sol", pointer = "/price", scale = 1e6 }
+
+            [[feeds]]
+            name = "JUP/USD"
+            interval_ms = 5000
+            source = { type = "switchboard", feed = "SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv", exponent = -6 }
+            sources = [{ type = "http", url = "https://example.com/jup", pointer = "/price" }]
+            aggregation = { method = "weighted", weights = [2.0, 1.0] }
+            transforms = [{ type = "basis", bps = 5 }]
+
+            [[feeds]]
+            name = "ETH/USD"
+            [feeds.source]
+            type = "chainlink"
+            feed_id = "0x000362205e10b3a147d02792eccee483dca6c7b44ecce7012cb8c6e0b68b3ae9"
+            client_id = "client"
+            client_secret = "secret"
+            signers = ["0x0000000000000000000000000000000000000001"]
+            min_signatures = 1
+            exponent = -6
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.interval_ms, 400);
+        assert_eq!(config.max_retries, 2);
+        assert_eq!(config.alerts.as_ref().unwrap().failures, 3);
+
+        let admin = Pubkey::new_unique();
+        let feeds = config.feeds(&admin, Cluster::Mainnet).unwrap();
+        assert_eq!(
+            feeds[0].pubkey,
+            Pubkey::create_with_seed(&admin, "SOL/USDC", &doppler_sdk::ID).unwrap()
+        );
+        assert_eq!(feeds[0].interval, Duration::ZERO);
+        assert_eq!(feeds[1].interval, Duration::from_secs(5));
+        assert_eq!(feeds[1].sources.len(), 2);
+        assert_eq!(feeds[1].transforms.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let parse = |feeds: &str| {
+            toml::from_str::<Config>(&format!(
+                "rpc_url = \"http://localhost:8899\"\nkeypair = \"admin.json\"\n{feeds}"
+            ))
+        };
+        let admin = Pubkey::new_unique();
+
+        let typo = parse("[[feeds]]\nname = \"SOL/USDC\"\ndeviation = 50\n");
+        assert!(typo
+            .unwrap_err()
+            .to_string()
+            .contains("unknown field `deviation`"));
+
+        let duplicate = parse(
+            r#"
+            [[feeds]]
+            name = "SOL/USDC"
+            source = { type = "http", url = "https://example.com/sol", pointer = "/price" }
+
+            [[feeds]]
+            name = "SOL/USDC"
+            source = { type = "http", url = "https://example.com/sol", pointer = "/price" }
+            "#,
+        );
+        assert_eq!(
+            duplicate
+                .unwrap()
+                .feeds(&admin, Cluster::Mainnet)
+                .err()
+                .unwrap()
+                .to_string(),
+            "feed SOL/USDC: listed twice"
+        );
+
+        let sourceless = parse("[[feeds]]\nname = \"SOL/USDC\"\n").unwrap();
+        assert!(sourceless.feeds(&admin, Cluster::Mainnet).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::source::SourceError;
+
+/// A price read from an exchange ticker, in `10^exponent` units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Observation {
+    /// Midpoint of the best bid and ask.
+    pub price: u64,
+    /// Half the bid/ask spread, so the market sits within
+    /// `price ± confidence`.
+    pub confidence: u64,
+    /// When the exchange stamped the ticker, in Unix milliseconds, if it
+    /// says.
+    pub timestamp_ms: Option<u64>,
+}
+
+/// A public exchange API serving best bid and ask for a symbol.
+pub trait PriceSource {
+    /// The REST endpoint of `symbol`'s ticker.
+    fn ticker_url(&self, symbol: &str) -> String;
+
+    /// Reads the best bid, best ask and ticker time from a ticker response.
+    fn parse<'a>(&self, body: &'a Value) -> Option<Ticker<'a>>;
+}
+
+/// A ticker as an exchange reports it, prices still decimal strings.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ticker<'a> {
+    pub bid: &'a str,
+    pub ask: &'a str,
+    pub timestamp_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exchange {
+    Binance,
+    Coinbase,
+    Kraken,
+}
+
+impl Exchange {
+    fn api(self) -> &'static dyn PriceSource {
+        match self {
+            Self::Binance => &Binance,
+            Self::Coinbase => &Coinbase,
+            Self::Kraken => &Kraken,
+        }
+    }
+}
+
+/// A spot market on an exchange, in the exchange's own symbol format:
+/// `SOLUSDC` on Binance, `SOL-USD` on Coinbase, `SOLUSD` on Kraken.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Market {
+    pub exchange: Exchange,
+    pub symbol: String,
+    /// Prices are published in `10^exponent` units, e.g. -6 for micro-USD.
+    pub exponent: i32,
+    /// Holds back the price while the bid/ask spread is wider than this.
+    pub max_spread_bps: Option<u64>,
+    /// Holds back the price while the ticker is older than this, for
+    /// exchanges that stamp their tickers.
+    pub max_age_ms: Option<u64>,
+}
+
+impl Market {
+    pub async fn fetch(&self, http: &reqwest::Client) -> Result<Observation, SourceError> {
+        let api = self.exchange.api();
+        let body: Value = http
+            .get(api.ticker_url(&self.symbol))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let ticker = api
+            .parse(&body)
+            .ok_or_else(|| SourceError::MissingValue(format!("{} ticker", self.symbol)))?;
+        let observation = observe(&ticker, self.exponent)
+            .ok_or_else(|| SourceError::EmptyBook(self.symbol.clone()))?;
+        self.check(&observation, now_ms())?;
+
+        Ok(observation)
+    }
+
+    fn check(&self, observation: &Observation, now_ms: u64) -> Result<(), SourceError> {
+        if let Some(max_spread_bps) = self.max_spread_bps {
+            let spread_bps =
+                u128::from(observation.confidence) * 20_000 / u128::from(observation.price);
+            if spread_bps > u128::from(max_spread_bps) {
+                return Err(SourceError::WideSpread {
+                    spread_bps,
+                    max_spread_bps,
+                });
+            }
+        }
+        if let (Some(max_age_ms), Some(timestamp_ms)) = (self.max_age_ms, observation.timestamp_ms)
+        {
+            let age_ms = now_ms.saturating_sub(timestamp_ms);
+            if age_ms > max_age_ms {
+                return Err(SourceError::Stale { age_ms, max_age_ms });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `GET /api/v3/ticker/24hr`, stamped with the close of its rolling window.
+struct Binance;
+
+impl PriceSource for Binance {
+    fn ticker_url(&self, symbol: &str) -> String {
+        format!("https://api.binance.com/api/v3/ticker/24hr?symbol={symbol}")
+    }
+
+    fn parse<'a>(&self, body: &'a Value) -> Option<Ticker<'a>> {
+        Some(Ticker {
+            bid: body["bidPrice"].as_str()?,
+            ask: body["askPrice"].as_str()?,
+            timestamp_ms: body["closeTime"].as_u64(),
+        })
+    }
+}
+
+/// `GET /products/{symbol}/ticker`, stamped with the last trade.
+struct Coinbase;
+
+impl PriceSource for Coinbase {
+    fn ticker_url(&self, symbol: &str) -> String {
+        format!("https://api.exchange.coinbase.com/products/{symbol}/ticker")
+    }
+
+    fn parse<'a>(&self, body: &'a Value) -> Option<Ticker<'a>> {
+        Some(Ticker {
+            bid: body["bid"].as_str()?,
+            ask: body["ask"].as_str()?,
+            timestamp_ms: body["time"].as_str().and_then(parse_rfc3339),
+        })
+    }
+}
+
+/// `GET /0/public/Ticker`, which is not stamped. Kraken keys the result by
+/// its own pair name, e.g. `XXBTZUSD` for `XBTUSD`, so the only entry is
+/// taken whatever its key.
+struct Kraken;
+
+impl PriceSource for Kraken {
+    fn ticker_url(&self, symbol: &str) -> String {
+        format!("https://api.kraken.com/0/public/Ticker?pair={symbol}")
+    }
+
+    fn parse<'a>(&self, body: &'a Value) -> Option<Ticker<'a>> {
+        let result = body["result"].as_object()?;
+        let ticker = match result.values().collect::<Vec<_>>()[..] {
+            [ticker] => ticker,
+            _ => return None,
+        };
+
+        Some(Ticker {
+            bid: ticker["b"][0].as_str()?,
+            ask: ticker["a"][0].as_str()?,
+            timestamp_ms: None,
+        })
+    }
+}
+
+/// Normalizes a ticker to its midpoint and half-spread in `10^exponent`
+/// units, or `None` for a crossed or empty book.
+pub fn observe(ticker: &Ticker, exponent: i32) -> Option<Observation> {
+    let bid = parse_decimal(ticker.bid, exponent)?;
+    let ask = parse_decimal(ticker.ask, exponent)?;
+    if bid == 0 || bid > ask {
+        return None;
+    }
+
+    Some(Observation {
+        price: u64::try_from((bid + ask) / 2).ok()?,
+        confidence: u64::try_from((ask - bid) / 2).ok()?,
+        timestamp_ms: ticker.timestamp_ms,
+    })
+}
+
+/// Parses a decimal string such as `"142.35000000"` into `10^exponent`
+/// units, truncating digits that don't fit.
+fn parse_decimal(s: &str, exponent: i32) -> Option<u128> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let digits = [whole, fraction].concat();
+    if whole.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    // `s` is `digits * 10^-fraction.len()`, shifted to `10^exponent`.
+    let shift = i64::from(exponent) + fraction.len() as i64;
+    let kept = match usize::try_from(shift) {
+        Ok(dropped) => &digits[..digits.len().saturating_sub(dropped)],
+        Err(_) => &digits,
+    };
+    let value: u128 = if kept.is_empty() {
+        0
+    } else {
+        kept.parse().ok()?
+    };
+
+    match u32::try_from(-shift) {
+        Ok(pad) => value.checked_mul(10u128.checked_pow(pad)?),
+        Err(_) => Some(value),
+    }
+}
+
+/// Parses a UTC timestamp such as `2024-05-01T12:30:00.123456Z` into Unix
+/// milliseconds.
+fn parse_rfc3339(s: &str) -> Option<u64> {
+    let s = s.strip_suffix('Z')?;
+    let (date, time) = s.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<i64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    let millis = format!("{fraction:0<3}").get(..3)?.parse::<i64>().ok()?;
+
+    // Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    u64::try_from(seconds * 1_000 + millis).ok()
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_tickers() {
+        let binance = json!({
+            "symbol": "SOLUSDC",
+            "bidPrice": "142.35000000",
+            "askPrice": "142.37000000",
+            "closeTime": 1_714_566_600_000u64,
+        });
+        let coinbase = json!({
+            "bid": "142.35",
+            "ask": "142.37",
+            "price": "142.36",
+            "time": "2024-05-01T12:30:00.123456Z",
+        });
+        let kraken = json!({
+            "error": [],
+            "result": { "SOLUSD": { "a": ["142.37", "1", "1.000"], "b": ["142.35", "3", "3.000"] } },
+        });
+
+        let ticker = |bid, ask, timestamp_ms| Ticker {
+            bid,
+            ask,
+            timestamp_ms,
+        };
+        assert_eq!(
+            Binance.parse(&binance),
+            Some(ticker(
+                "142.35000000",
+                "142.37000000",
+                Some(1_714_566_600_000)
+            ))
+        );
+        assert_eq!(
+            Coinbase.parse(&coinbase),
+            Some(ticker("142.35", "142.37", Some(1_714_566_600_123)))
+        );
+        assert_eq!(
+            Kraken.parse(&kraken),
+            Some(ticker("142.35", "142.37", None))
+        );
+        assert_eq!(
+            Kraken.parse(&json!({ "error": ["EQuery:Unknown asset pair"] })),
+            None
+        );
+    }
+
+    #[test]
+    fn test_observe_normalizes_prices() {
+        let ticker = Ticker {
+            bid: "142.35000000",
+            ask: "142.37000001",
+            timestamp_ms: None,
+        };
+        assert_eq!(
+            observe(&ticker, -6),
+            Some(Observation {
+                price: 142_360_000,
+                confidence: 10_000,
+                timestamp_ms: None,
+            })
+        );
+        assert_eq!(
+            observe(&ticker, 0).map(|observation| observation.price),
+            Some(142)
+        );
+
+        let crossed = Ticker {
+            bid: "142.38",
+            ask: "142.37",
+            timestamp_ms: None,
+        };
+        assert_eq!(observe(&crossed, -6), None);
+
+        assert_eq!(parse_decimal("0.5", -2), Some(50));
+        assert_eq!(parse_decimal("12", 1), Some(1));
+        assert_eq!(parse_decimal("0.001", 0), Some(0));
+        assert_eq!(parse_decimal("-1", 0), None);
+        assert_eq!(parse_decimal("1e3", 0), None);
+    }
+
+    #[test]
+    fn test_check_spread_and_age() {
+        let market = Market {
+            exchange: Exchange::Coinbase,
+            symbol: "SOL-USD".to_string(),
+            exponent: -6,
+            max_spread_bps: Some(10),
+            max_age_ms: Some(5_000),
+        };
+        let observation = |confidence, timestamp_ms| Observation {
+            price: 100_000_000,
+            confidence,
+            timestamp_ms,
+        };
+
+        assert!(market
+            .check(&observation(50_000, Some(10_000)), 12_000)
+            .is_ok());
+        assert!(market.check(&observation(50_000, None), 60_000).is_ok());
+        assert!(matches!(
+            market.check(&observation(55_000, None), 0),
+            Err(SourceError::WideSpread { spread_bps: 11, .. })
+        ));
+        assert!(matches!(
+            market.check(&observation(0, Some(10_000)), 15_001),
+            Err(SourceError::Stale { age_ms: 5_001, .. })
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use std::{collections::VecDeque, time::Duration};
+
+use serde::Deserialize;
+
+/// Smallest raise of the unit price, in micro-lamports, so a price starting
+/// from zero can climb.
+const MIN_STEP: u64 = 1_000;
+/// Share of failed sends in the window above which the price is raised.
+const MAX_FAILURE_RATE: f64 = 0.1;
+
+/// Adaptive priority fee settings:
+///
+/// ```toml
+/// [adaptive_fee]
+/// min_unit_price = 0
+/// max_unit_price = 500_000
+/// target_latency_ms = 2_000
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AdaptiveFeeConfig {
+    /// Micro-lamports per compute unit the price never drops below.
+    #[serde(default)]
+    pub min_unit_price: u64,
+    /// Micro-lamports per compute unit the price never exceeds, lowered per
+    /// feed by its `max_unit_price`.
+    pub max_unit_price: u64,
+    /// Mean landing latency above which the price is raised.
+    #[serde(default = "default_target_latency_ms")]
+    pub target_latency_ms: u64,
+    /// Recent sends the latency and failure rate are measured over.
+    #[serde(default = "default_window")]
+    pub window: usize,
+}
+
+const fn default_target_latency_ms() -> u64 {
+    2_000
+}
+
+const fn default_window() -> usize {
+    20
+}
+
+impl AdaptiveFeeConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.min_unit_price > self.max_unit_price {
+            return Err("adaptive_fee.min_unit_price exceeds max_unit_price".into());
+        }
+        if self.window == 0 {
+            return Err("adaptive_fee.window must be positive".into());
+        }
+        Ok(())
+    }
+}
+
+/// A compute unit price that rises while sends land slowly or fail, and
+/// decays while they land well within the target latency.
+pub struct AdaptiveFee {
+    config: AdaptiveFeeConfig,
+    unit_price: u64,
+    /// Latency of each recent send, `None` for the ones that failed.
+    outcomes: VecDeque<Option<Duration>>,
+    /// Whether a send was recorded since the last adjustment.
+    recorded: bool,
+}
+
+impl AdaptiveFee {
+    /// Starts from `unit_price`, the configured static price, if any.
+    pub fn new(config: AdaptiveFeeConfig, unit_price: Option<u64>) -> Self {
+        let unit_price = unit_price
+            .unwrap_or_default()
+            .clamp(config.min_unit_price, config.max_unit_price);
+
+        Self {
+            config,
+            unit_price,
+            outcomes: VecDeque::new(),
+            recorded: false,
+        }
+    }
+
+    pub const fn unit_price(&self) -> u64 {
+        self.unit_price
+    }
+
+    /// Applies new settings, keeping the current price within them.
+    pub fn set_config(&mut self, config: AdaptiveFeeConfig) {
+        self.unit_price = self
+            .unit_price
+            .clamp(config.min_unit_price, config.max_unit_price);
+        self.config = config;
+    }
+
+    pub fn landed(&mut self, latency: Duration) {
+        self.record(Some(latency));
+    }
+
+    pub fn failed(&mut self) {
+        self.record(None);
+    }
+
+    /// Moves the price according to the sends recorded so far, once per
+    /// tick that sent anything.
+    pub fn adjust(&mut self) {
+        if !std::mem::take(&mut self.recorded) {
+            return;
+        }
+
+        let failures = self.outcomes.iter().filter(|o| o.is_none()).count();
+        let latencies: Vec<Duration> = self.outcomes.iter().flatten().copied().collect();
+        let mean = match latencies.len() {
+            0 => Duration::MAX,
+            len => latencies.iter().sum::<Duration>() / len as u32,
+        };
+        let target = Duration::from_millis(self.config.target_latency_ms);
+
+        let unit_price =
+            if failures as f64 > self.outcomes.len() as f64 * MAX_FAILURE_RATE || mean > target {
+                self.unit_price
+                    .saturating_add((self.unit_price / 4).max(MIN_STEP))
+            } else if failures == 0 && mean < target / 2 {
+                self.unit_price - self.unit_price / 10
+            } else {
+                self.unit_price
+            };
+
+        self.unit_price = unit_price.clamp(self.config.min_unit_price, self.config.max_unit_price);
+    }
+
+    fn record(&mut self, outcome: Option<Duration>) {
+        while self.outcomes.len() >= self.config.window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(outcome);
+        self.recorded = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_follows_landing() {
+        let mut fee = AdaptiveFee::new(
+            AdaptiveFeeConfig {
+                min_unit_price: 0,
+                max_unit_price: 2_000,
+                target_latency_ms: 2_000,
+                window: 4,
+            },
+            None,
+        );
+
+        fee.failed();
+        fee.adjust();
+        assert_eq!(fee.unit_price(), 1_000);
+        fee.adjust();
+        assert_eq!(fee.unit_price(), 1_000);
+
+        fee.landed(Duration::from_secs(3));
+        fee.adjust();
+        assert_eq!(fee.unit_price(), 2_000);
+
+        for _ in 0..4 {
+            fee.landed(Duration::from_millis(500));
+        }
+        fee.adjust();
+        assert_eq!(fee.unit_price(), 1_800);
+    }
+}
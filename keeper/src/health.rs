@@ -0,0 +1,194 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use serde_json::{json, Value};
+
+/// What the health server reports, updated by the keeper as it runs.
+pub struct Health {
+    /// How long a feed may go without a quote, or the loop without a tick,
+    /// before the keeper reports itself unhealthy.
+    max_age: Duration,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    ticked: Option<Instant>,
+    /// Whether the last RPC request succeeded, and when.
+    rpc: Option<(bool, Instant)>,
+    feeds: BTreeMap<String, FeedHealth>,
+}
+
+#[derive(Default)]
+struct FeedHealth {
+    /// Last time the feed's sources produced a price.
+    observed: Option<Instant>,
+    /// Last time an update to the feed's oracle was confirmed.
+    updated: Option<Instant>,
+}
+
+impl Health {
+    pub fn new<'a>(feeds: impl IntoIterator<Item = &'a str>, max_age: Duration) -> Self {
+        let feeds = feeds
+            .into_iter()
+            .map(|feed| (feed.to_string(), FeedHealth::default()))
+            .collect();
+
+        Self {
+            max_age,
+            inner: Mutex::new(Inner {
+                feeds,
+                ..Inner::default()
+            }),
+        }
+    }
+
+    /// Replaces the feeds reported on, keeping the state of those that stay.
+    pub fn set_feeds<'a>(&self, feeds: impl IntoIterator<Item = &'a str>) {
+        let mut inner = self.lock();
+        let mut current = std::mem::take(&mut inner.feeds);
+        inner.feeds = feeds
+            .into_iter()
+            .map(|feed| (feed.to_string(), current.remove(feed).unwrap_or_default()))
+            .collect();
+    }
+
+    pub fn ticked(&self) {
+        self.lock().ticked = Some(Instant::now());
+    }
+
+    pub fn rpc(&self, ok: bool) {
+        self.lock().rpc = Some((ok, Instant::now()));
+    }
+
+    pub fn observed(&self, feed: &str) {
+        if let Some(health) = self.lock().feeds.get_mut(feed) {
+            health.observed = Some(Instant::now());
+        }
+    }
+
+    pub fn updated(&self, feed: &str) {
+        if let Some(health) = self.lock().feeds.get_mut(feed) {
+            health.updated = Some(Instant::now());
+        }
+    }
+
+    /// Whether the keeper loop is running.
+    fn live(&self, now: Instant) -> bool {
+        self.lock()
+            .ticked
+            .is_some_and(|ticked| now.duration_since(ticked) <= self.max_age)
+    }
+
+    /// Whether the RPC node is reachable and every feed has a fresh price.
+    fn ready(&self, now: Instant) -> bool {
+        let inner = self.lock();
+        let fresh =
+            |at: Option<Instant>| at.is_some_and(|at| now.duration_since(at) <= self.max_age);
+
+        inner.rpc.is_some_and(|(ok, _)| ok) && inner.feeds.values().all(|feed| fresh(feed.observed))
+    }
+
+    fn status(&self, now: Instant) -> Value {
+        let inner = self.lock();
+        let age = |at: Option<Instant>| at.map(|at| now.duration_since(at).as_secs_f64());
+
+        json!({
+            "rpc": inner.rpc.map(|(ok, at)| json!({
+                "ok": ok,
+                "age_secs": now.duration_since(at).as_secs_f64(),
+            })),
+            "tick_age_secs": age(inner.ticked),
+            "feeds": inner.feeds.iter().map(|(name, feed)| json!({
+                "name": name,
+                "observation_age_secs": age(feed.observed),
+                "update_age_secs": age(feed.updated),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Serves `/health`, answering 200 while the keeper loop runs, and
+/// `/ready`, answering 200 while the RPC node is reachable and every feed
+/// has a fresh price. Both return 503 otherwise, with per-feed status as
+/// JSON.
+pub async fn serve(addr: SocketAddr, health: Arc<Health>) -> std::io::Result<()> {
+    let live = health.clone();
+    let app = Router::new()
+        .route(
+            "/health",
+            get(move || async move { respond(&live, Health::live) }),
+        )
+        .route(
+            "/ready",
+            get(move || async move { respond(&health, Health::ready) }),
+        );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn respond(health: &Health, check: fn(&Health, Instant) -> bool) -> impl IntoResponse {
+    let now = Instant::now();
+    let status = if check(health, now) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        health.status(now).to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ready_needs_rpc_and_fresh_feeds() {
+        let health = Health::new(["SOL/USDC", "BTC/USD"], Duration::from_secs(60));
+        let now = Instant::now();
+        assert!(!health.live(now));
+        assert!(!health.ready(now));
+
+        health.ticked();
+        health.rpc(true);
+        health.observed("SOL/USDC");
+        assert!(health.live(Instant::now()));
+        assert!(!health.ready(Instant::now()));
+
+        health.observed("BTC/USD");
+        assert!(health.ready(Instant::now()));
+        assert!(!health.ready(Instant::now() + Duration::from_secs(61)));
+
+        health.rpc(false);
+        assert!(!health.ready(Instant::now()));
+
+        let status = health.status(Instant::now());
+        assert_eq!(status["feeds"][0]["name"], "BTC/USD");
+        assert_eq!(status["feeds"][0]["update_age_secs"], Value::Null);
+
+        health.rpc(true);
+        health.set_feeds(["SOL/USDC", "ETH/USD"]);
+        assert!(!health.ready(Instant::now()));
+        health.observed("ETH/USD");
+        assert!(health.ready(Instant::now()));
+    }
+}
@@ -0,0 +1,660 @@
+use std::{net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use rustls::{pki_types::pem::PemObject, pki_types::CertificateDer, ClientConfig, RootCertStore};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::{
+    mpsc::{self, error::TrySendError, Sender},
+    RwLock,
+};
+use tokio_postgres::{types::ToSql, Client, Row};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+use crate::metrics::Metrics;
+
+/// Time between two deletions of records past their retention.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3_600);
+
+/// Records waiting for the database before new ones are dropped.
+const QUEUE_CAPACITY: usize = 10_000;
+
+/// Delays between two attempts to reconnect to the database, doubling from
+/// the first up to the second.
+const RECONNECT_DELAYS: (Duration, Duration) = (Duration::from_secs(1), Duration::from_secs(60));
+
+/// Records returned by the query API when no `limit` is given.
+const DEFAULT_LIMIT: u32 = 100;
+
+const MILLIS_PER_DAY: u64 = 86_400_000;
+
+/// Creates the history tables on first start, each indexed by feed and time
+/// for the query API and by time alone for retention.
+const MIGRATION: &str = "
+CREATE TABLE IF NOT EXISTS keeper_observations (
+    id BIGSERIAL PRIMARY KEY,
+    feed TEXT NOT NULL,
+    source INTEGER NOT NULL,
+    price BIGINT,
+    published_ms BIGINT,
+    error TEXT,
+    observed_ms BIGINT NOT NULL
+);
+ALTER TABLE keeper_observations ADD COLUMN IF NOT EXISTS confidence BIGINT;
+CREATE INDEX IF NOT EXISTS keeper_observations_feed ON keeper_observations (feed, observed_ms);
+CREATE INDEX IF NOT EXISTS keeper_observations_time ON keeper_observations (observed_ms);
+
+CREATE TABLE IF NOT EXISTS keeper_decisions (
+    id BIGSERIAL PRIMARY KEY,
+    feed TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    price BIGINT,
+    published_ms BIGINT,
+    reason TEXT,
+    decided_ms BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS keeper_decisions_feed ON keeper_decisions (feed, decided_ms);
+CREATE INDEX IF NOT EXISTS keeper_decisions_time ON keeper_decisions (decided_ms);
+
+CREATE TABLE IF NOT EXISTS keeper_confirmations (
+    id BIGSERIAL PRIMARY KEY,
+    feed TEXT NOT NULL,
+    oracle TEXT NOT NULL,
+    sequence BIGINT NOT NULL,
+    price BIGINT NOT NULL,
+    signature TEXT,
+    error TEXT,
+    unit_price BIGINT,
+    fee_lamports BIGINT NOT NULL,
+    latency_ms BIGINT NOT NULL,
+    confirmed_ms BIGINT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS keeper_confirmations_feed ON keeper_confirmations (feed, confirmed_ms);
+CREATE INDEX IF NOT EXISTS keeper_confirmations_time ON keeper_confirmations (confirmed_ms);
+";
+
+/// Persists every source observation, aggregation decision and update
+/// outcome to Postgres, so what the keeper published and why can be
+/// audited afterwards:
+///
+/// ```toml
+/// [history]
+/// database_url = "postgres://doppler@db.example.com/doppler?sslmode=require"
+/// addr = "0.0.0.0:8083"
+/// retention = { observations_days = 7, decisions_days = 30 }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Connection string of the database. Its `sslmode` decides whether TLS
+    /// is used: `prefer` when unset, `disable` or `require`.
+    pub database_url: String,
+    /// PEM certificates of the authorities trusted to sign the server's
+    /// certificate, in addition to the public ones.
+    pub ca_cert: Option<PathBuf>,
+    /// Address to serve the query API on.
+    pub addr: Option<SocketAddr>,
+    #[serde(default)]
+    pub retention: Retention,
+}
+
+/// Days each kind of record is kept for, forever when unset.
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Retention {
+    pub observations_days: Option<u64>,
+    pub decisions_days: Option<u64>,
+    pub confirmations_days: Option<u64>,
+}
+
+impl Retention {
+    /// Each kind of record with a retention, and the time in Unix
+    /// milliseconds before which its records are deleted at `now_ms`.
+    fn cutoffs(&self, now_ms: u64) -> Vec<(Kind, u64)> {
+        [
+            (Kind::Observations, self.observations_days),
+            (Kind::Decisions, self.decisions_days),
+            (Kind::Confirmations, self.confirmations_days),
+        ]
+        .into_iter()
+        .filter_map(|(kind, days)| {
+            Some((
+                kind,
+                now_ms.saturating_sub(days?.saturating_mul(MILLIS_PER_DAY)),
+            ))
+        })
+        .collect()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("postgres: {0}")]
+    Postgres(#[from] tokio_postgres::Error),
+    #[error("ca_cert: {0}")]
+    Certificate(String),
+    #[error("{0} does not fit in a BIGINT column")]
+    OutOfRange(u64),
+}
+
+/// A price a source returned for a feed, or why it failed to.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Observation {
+    pub feed: String,
+    /// Index of the source in the feed's configuration.
+    pub source: u32,
+    pub price: Option<u64>,
+    pub published_ms: Option<u64>,
+    pub confidence: Option<u64>,
+    pub error: Option<String>,
+    pub observed_ms: u64,
+}
+
+/// What the keeper made of a feed's observations on a tick.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    /// The aggregated price was sent to the oracle.
+    Publish,
+    /// The aggregated price was not worth sending.
+    Skip,
+    /// The observations could not be aggregated into a price.
+    Reject,
+}
+
+impl Outcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Skip => "skip",
+            Self::Reject => "reject",
+        }
+    }
+
+    fn parse(outcome: &str) -> Self {
+        match outcome {
+            "publish" => Self::Publish,
+            "skip" => Self::Skip,
+            _ => Self::Reject,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Decision {
+    pub feed: String,
+    pub outcome: Outcome,
+    /// The aggregated price, unless rejected.
+    pub price: Option<u64>,
+    pub published_ms: Option<u64>,
+    /// Why the price was skipped or rejected.
+    pub reason: Option<String>,
+    pub decided_ms: u64,
+}
+
+/// An update sent to an oracle: its signature once confirmed, or why it
+/// failed.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct Confirmation {
+    pub feed: String,
+    pub oracle: String,
+    pub sequence: u64,
+    pub price: u64,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+    /// Compute unit price the transaction paid, in micro-lamports.
+    pub unit_price: Option<u64>,
+    pub fee_lamports: u64,
+    pub latency_ms: u64,
+    pub confirmed_ms: u64,
+}
+
+pub enum Record {
+    Observation(Observation),
+    Decision(Decision),
+    Confirmation(Confirmation),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Observations,
+    Decisions,
+    Confirmations,
+}
+
+impl Kind {
+    fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "observations" => Some(Self::Observations),
+            "decisions" => Some(Self::Decisions),
+            "confirmations" => Some(Self::Confirmations),
+            _ => None,
+        }
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Self::Observations => "keeper_observations",
+            Self::Decisions => "keeper_decisions",
+            Self::Confirmations => "keeper_confirmations",
+        }
+    }
+
+    /// The column a record's time is stored in.
+    fn time(self) -> &'static str {
+        match self {
+            Self::Observations => "observed_ms",
+            Self::Decisions => "decided_ms",
+            Self::Confirmations => "confirmed_ms",
+        }
+    }
+}
+
+/// The keeper's history, in Postgres.
+pub struct Store {
+    url: String,
+    tls: MakeRustlsConnect,
+    client: RwLock<Client>,
+}
+
+impl Store {
+    /// Connects to the database of `config` and creates the history tables
+    /// if they do not exist yet.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime.
+    pub async fn connect(config: &HistoryConfig) -> Result<Self, StoreError> {
+        let tls = MakeRustlsConnect::new(tls_config(config.ca_cert.as_deref())?);
+        let client = open(&config.database_url, &tls).await?;
+
+        Ok(Self {
+            url: config.database_url.clone(),
+            tls,
+            client: RwLock::new(client),
+        })
+    }
+
+    /// Whether the connection to the database was lost.
+    async fn is_closed(&self) -> bool {
+        self.client.read().await.is_closed()
+    }
+
+    /// Replaces a lost connection with a new one.
+    async fn reconnect(&self) -> Result<(), StoreError> {
+        let client = open(&self.url, &self.tls).await?;
+        *self.client.write().await = client;
+
+        Ok(())
+    }
+
+    pub async fn insert(&self, record: &Record) -> Result<(), StoreError> {
+        let client = self.client.read().await;
+        match record {
+            Record::Observation(observation) => {
+                client
+                    .execute(
+                        "INSERT INTO keeper_observations
+                         (feed, source, price, published_ms, confidence, error, observed_ms)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                        &[
+                            &observation.feed,
+                            &i32::try_from(observation.source).unwrap_or(i32::MAX),
+                            &observation.price.map(bigint).transpose()?,
+                            &observation.published_ms.map(bigint).transpose()?,
+                            &observation.confidence.map(bigint).transpose()?,
+                            &observation.error,
+                            &bigint(observation.observed_ms)?,
+                        ],
+                    )
+                    .await?;
+            }
+            Record::Decision(decision) => {
+                client
+                    .execute(
+                        "INSERT INTO keeper_decisions
+                         (feed, outcome, price, published_ms, reason, decided_ms)
+                         VALUES ($1, $2, $3, $4, $5, $6)",
+                        &[
+                            &decision.feed,
+                            &decision.outcome.as_str(),
+                            &decision.price.map(bigint).transpose()?,
+                            &decision.published_ms.map(bigint).transpose()?,
+                            &decision.reason,
+                            &bigint(decision.decided_ms)?,
+                        ],
+                    )
+                    .await?;
+            }
+            Record::Confirmation(confirmation) => {
+                client
+                    .execute(
+                        "INSERT INTO keeper_confirmations
+                         (feed, oracle, sequence, price, signature, error, unit_price,
+                          fee_lamports, latency_ms, confirmed_ms)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+                        &[
+                            &confirmation.feed,
+                            &confirmation.oracle,
+                            &bigint(confirmation.sequence)?,
+                            &bigint(confirmation.price)?,
+                            &confirmation.signature,
+                            &confirmation.error,
+                            &confirmation.unit_price.map(bigint).transpose()?,
+                            &bigint(confirmation.fee_lamports)?,
+                            &bigint(confirmation.latency_ms)?,
+                            &bigint(confirmation.confirmed_ms)?,
+                        ],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the records past their retention at `now_ms`, returning how
+    /// many were deleted.
+    pub async fn prune(&self, retention: &Retention, now_ms: u64) -> Result<u64, StoreError> {
+        let client = self.client.read().await;
+        let mut deleted = 0;
+        for (kind, cutoff) in retention.cutoffs(now_ms) {
+            deleted += client
+                .execute(
+                    &format!("DELETE FROM {} WHERE {} < $1", kind.table(), kind.time()),
+                    &[&bigint(cutoff)?],
+                )
+                .await?;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Records of `kind` matching `filter`, newest first.
+    async fn query(&self, kind: Kind, filter: &Filter) -> Result<serde_json::Value, StoreError> {
+        let columns = match kind {
+            Kind::Observations => {
+                "feed, source, price, published_ms, confidence, error, observed_ms"
+            }
+            Kind::Decisions => "feed, outcome, price, published_ms, reason, decided_ms",
+            Kind::Confirmations => {
+                "feed, oracle, sequence, price, signature, error, unit_price, fee_lamports, \
+                 latency_ms, confirmed_ms"
+            }
+        };
+        let since = bigint(filter.since_ms.unwrap_or_default())?;
+        let until = filter.until_ms.map(bigint).transpose()?.unwrap_or(i64::MAX);
+        let limit = i64::from(filter.limit.unwrap_or(DEFAULT_LIMIT));
+        let params: [&(dyn ToSql + Sync); 4] = [&filter.feed, &since, &until, &limit];
+
+        let rows = self
+            .client
+            .read()
+            .await
+            .query(
+                &format!(
+                    "SELECT {columns} FROM {table}
+                     WHERE ($1::TEXT IS NULL OR feed = $1) AND {time} >= $2 AND {time} < $3
+                     ORDER BY {time} DESC, id DESC
+                     LIMIT $4",
+                    table = kind.table(),
+                    time = kind.time(),
+                ),
+                &params,
+            )
+            .await?;
+
+        Ok(match kind {
+            Kind::Observations => json!(rows.iter().map(observation).collect::<Vec<_>>()),
+            Kind::Decisions => json!(rows.iter().map(decision).collect::<Vec<_>>()),
+            Kind::Confirmations => json!(rows.iter().map(confirmation).collect::<Vec<_>>()),
+        })
+    }
+}
+
+/// Connects to the database at `url`, over TLS unless its `sslmode` is
+/// `disable`, and creates the history tables if they do not exist yet.
+async fn open(url: &str, tls: &MakeRustlsConnect) -> Result<Client, StoreError> {
+    let (client, connection) = tokio_postgres::connect(url, tls.clone()).await?;
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            eprintln!("postgres connection failed: {err}");
+        }
+    });
+    client.batch_execute(MIGRATION).await?;
+
+    Ok(client)
+}
+
+/// Trusts the public certificate authorities, and those in `ca_cert`.
+fn tls_config(ca_cert: Option<&std::path::Path>) -> Result<ClientConfig, StoreError> {
+    let mut roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    if let Some(path) = ca_cert {
+        let invalid = |err: String| StoreError::Certificate(format!("{}: {err}", path.display()));
+        for cert in CertificateDer::pem_file_iter(path).map_err(|err| invalid(err.to_string()))? {
+            let cert = cert.map_err(|err| invalid(err.to_string()))?;
+            roots.add(cert).map_err(|err| invalid(err.to_string()))?;
+        }
+    }
+
+    Ok(
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|err| StoreError::Certificate(err.to_string()))?
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    )
+}
+
+fn observation(row: &Row) -> Observation {
+    Observation {
+        feed: row.get(0),
+        source: unsigned(row.get::<_, i32>(1).into()) as u32,
+        price: row.get::<_, Option<i64>>(2).map(unsigned),
+        published_ms: row.get::<_, Option<i64>>(3).map(unsigned),
+        confidence: row.get::<_, Option<i64>>(4).map(unsigned),
+        error: row.get(5),
+        observed_ms: unsigned(row.get(6)),
+    }
+}
+
+fn decision(row: &Row) -> Decision {
+    Decision {
+        feed: row.get(0),
+        outcome: Outcome::parse(row.get(1)),
+        price: row.get::<_, Option<i64>>(2).map(unsigned),
+        published_ms: row.get::<_, Option<i64>>(3).map(unsigned),
+        reason: row.get(4),
+        decided_ms: unsigned(row.get(5)),
+    }
+}
+
+fn confirmation(row: &Row) -> Confirmation {
+    Confirmation {
+        feed: row.get(0),
+        oracle: row.get(1),
+        sequence: unsigned(row.get(2)),
+        price: unsigned(row.get(3)),
+        signature: row.get(4),
+        error: row.get(5),
+        unit_price: row.get::<_, Option<i64>>(6).map(unsigned),
+        fee_lamports: unsigned(row.get(7)),
+        latency_ms: unsigned(row.get(8)),
+        confirmed_ms: unsigned(row.get(9)),
+    }
+}
+
+/// Postgres has no unsigned integers; every value the keeper stores is
+/// checked to fit a `BIGINT` on the way in.
+fn bigint(value: u64) -> Result<i64, StoreError> {
+    i64::try_from(value).map_err(|_| StoreError::OutOfRange(value))
+}
+
+fn unsigned(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
+
+/// Hands records to a background writer, so a slow database never holds up
+/// the keeper loop, and deletes records past their retention every hour.
+///
+/// The writer reconnects to the database when the connection is lost,
+/// backing off up to a minute between attempts, and keeps the record it was
+/// writing until it is stored. Records arriving while the queue is full are
+/// dropped and counted in the metrics.
+pub struct History {
+    sender: Sender<Record>,
+    metrics: Arc<Metrics>,
+}
+
+impl History {
+    pub fn spawn(store: Arc<Store>, retention: Retention, metrics: Arc<Metrics>) -> Self {
+        let (sender, mut records) = mpsc::channel(QUEUE_CAPACITY);
+
+        let writer = store.clone();
+        tokio::spawn(async move {
+            while let Some(record) = records.recv().await {
+                let mut delay = RECONNECT_DELAYS.0;
+                while let Err(err) = writer.insert(&record).await {
+                    if !writer.is_closed().await {
+                        eprintln!("failed to store history: {err}");
+                        break;
+                    }
+
+                    eprintln!("history database disconnected, reconnecting in {delay:?}: {err}");
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(RECONNECT_DELAYS.1);
+                    if let Err(err) = writer.reconnect().await {
+                        eprintln!("failed to reconnect to the history database: {err}");
+                    }
+                }
+            }
+        });
+
+        if retention != Retention::default() {
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(err) = store.prune(&retention, crate::unix_millis()).await {
+                        eprintln!("failed to prune history: {err}");
+                    }
+                }
+            });
+        }
+
+        Self { sender, metrics }
+    }
+
+    pub fn record(&self, record: Record) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(record) {
+            self.metrics.history_dropped();
+        }
+    }
+}
+
+/// Query parameters of the history API.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Filter {
+    feed: Option<String>,
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+    limit: Option<u32>,
+}
+
+/// Serves `/history/observations`, `/history/decisions` and
+/// `/history/confirmations`, newest first, filtered by the query parameters
+/// `feed`, `since_ms`, `until_ms` (exclusive) and `limit` (100 by default).
+pub async fn serve(addr: SocketAddr, store: Arc<Store>) -> std::io::Result<()> {
+    let app = Router::new().route(
+        "/history/:kind",
+        get(
+            move |Path(kind): Path<String>, Query(filter): Query<Filter>| async move {
+                let Some(kind) = Kind::parse(&kind) else {
+                    return respond(
+                        StatusCode::NOT_FOUND,
+                        json!({ "error": format!("unknown record kind {kind}") }),
+                    );
+                };
+                match store.query(kind, &filter).await {
+                    Ok(records) => respond(StatusCode::OK, records),
+                    Err(err) => respond(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        json!({ "error": err.to_string() }),
+                    ),
+                }
+            },
+        ),
+    );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn respond(status: StatusCode, body: serde_json::Value) -> impl IntoResponse {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_cutoffs() {
+        let retention: Retention =
+            toml::from_str("observations_days = 7\nconfirmations_days = 365").unwrap();
+        let now_ms = 400 * MILLIS_PER_DAY;
+
+        assert_eq!(
+            retention.cutoffs(now_ms),
+            [
+                (Kind::Observations, 393 * MILLIS_PER_DAY),
+                (Kind::Confirmations, 35 * MILLIS_PER_DAY),
+            ]
+        );
+        assert_eq!(retention.cutoffs(MILLIS_PER_DAY)[1].1, 0);
+        assert!(Retention::default().cutoffs(now_ms).is_empty());
+    }
+
+    #[test]
+    fn test_tls_config_reads_ca_cert() {
+        assert!(tls_config(None).is_ok());
+        assert!(matches!(
+            tls_config(Some(std::path::Path::new("/nonexistent/ca.pem"))),
+            Err(StoreError::Certificate(_))
+        ));
+    }
+
+    #[test]
+    fn test_records_serialize_for_the_api() {
+        let decision = Decision {
+            feed: "SOL/USDC".to_string(),
+            outcome: Outcome::Skip,
+            price: Some(1_100_000),
+            published_ms: None,
+            reason: Some("within deviation_bps".to_string()),
+            decided_ms: 1_700_000_000_000,
+        };
+
+        let json = json!(decision);
+        assert_eq!(json["outcome"], "skip");
+        assert_eq!(json["published_ms"], serde_json::Value::Null);
+        assert_eq!(Outcome::parse(decision.outcome.as_str()), Outcome::Skip);
+        assert_eq!(
+            Kind::parse("decisions").map(Kind::table),
+            Some("keeper_decisions")
+        );
+        assert_eq!(Kind::parse("quotes"), None);
+    }
+}
@@ -0,0 +1,833 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use doppler_program::PriceFeed;
+use doppler_sdk::{transaction::Builder, Cluster, Oracle, Sequence};
+use futures::future::join_all;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    time::MissedTickBehavior,
+};
+
+use crate::{
+    alerts::Alerts,
+    attest::Attester,
+    config::{Config, Feed},
+    fees::AdaptiveFee,
+    health::Health,
+    history::{Confirmation, Decision, History, Observation, Outcome, Record, Store},
+    metrics::Metrics,
+    payers::FeePayers,
+    sinks::{PricePayload, Sinks, UpdateEvent},
+    source::Quote,
+    transform::Context,
+};
+
+/// Fee of each signature on a transaction.
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+mod aggregate;
+mod alerts;
+mod attest;
+mod chainlink;
+mod config;
+mod exchange;
+mod fees;
+mod health;
+mod history;
+mod metrics;
+mod payers;
+mod plugin;
+mod pyth;
+mod sinks;
+mod source;
+mod switchboard;
+mod transform;
+
+/// Publish prices from configured sources to Doppler oracles.
+#[derive(Parser)]
+#[command(name = "doppler-keeper", version)]
+struct Args {
+    /// Path to the keeper configuration.
+    #[arg(long, short, default_value = "keeper.toml")]
+    config: PathBuf,
+
+    /// Validate the configuration, print the resolved feeds and exit.
+    #[arg(long)]
+    check: bool,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let mut config = Config::load(&args.config)?;
+
+    let admin = Keypair::read_from_file(&config.keypair)
+        .map_err(|err| format!("failed to read keypair {}: {err}", config.keypair.display()))?;
+    let fee_payers = config
+        .fee_payers
+        .iter()
+        .map(|path| {
+            Keypair::read_from_file(path)
+                .map_err(|err| format!("failed to read fee payer {}: {err}", path.display()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let cluster = config.cluster()?;
+    let client = RpcClient::new(config.rpc_url.clone());
+    let http = reqwest::Client::new();
+
+    let builder = builder(&admin, cluster, &config);
+    let mut interval = tokio::time::interval(Duration::from_millis(config.interval_ms));
+    interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let max_retries = config.max_retries;
+    let unit_price = config.unit_price;
+    let adaptive_fee = config
+        .adaptive_fee
+        .take()
+        .map(|adaptive_fee| AdaptiveFee::new(adaptive_fee, unit_price));
+    let min_fee_payer_balance = config.min_fee_payer_balance;
+    let metrics_addr = config.metrics_addr;
+    let health_addr = config.health_addr;
+    let alerts = config
+        .alerts
+        .take()
+        .map(|alerts| Alerts::new(alerts, http.clone()));
+    let health_max_age = Duration::from_millis(config.health_max_age_ms);
+    let attestation = config.attestation.take();
+    let sinks = std::mem::take(&mut config.sinks);
+    let history = config.history.take();
+    let feeds = config.feeds(&admin.pubkey(), cluster)?;
+
+    if args.check {
+        for feed in &feeds {
+            println!(
+                "{} {} {:?} payload, {} source(s)",
+                feed.name,
+                feed.pubkey,
+                feed.payload,
+                feed.sources.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let metrics = Arc::new(Metrics::default());
+    if let Some(addr) = metrics_addr {
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(err) = metrics::serve(addr, metrics).await {
+                eprintln!("metrics server on {addr} failed: {err}");
+            }
+        });
+    }
+
+    let health = Arc::new(Health::new(
+        feeds.iter().map(|feed| feed.name.as_str()),
+        health_max_age,
+    ));
+    if let Some(addr) = health_addr {
+        let health = health.clone();
+        tokio::spawn(async move {
+            if let Err(err) = health::serve(addr, health).await {
+                eprintln!("health server on {addr} failed: {err}");
+            }
+        });
+    }
+
+    let attester = match attestation {
+        Some(attestation) => {
+            let attester = Arc::new(Attester::new(&attestation)?);
+            println!("signing attestations as {}", attester.signer());
+
+            let addr = attestation.addr;
+            let server = attester.clone();
+            tokio::spawn(async move {
+                if let Err(err) = attest::serve(addr, server).await {
+                    eprintln!("attestation server on {addr} failed: {err}");
+                }
+            });
+            Some(attester)
+        }
+        None => None,
+    };
+
+    let sinks = (!sinks.is_empty()).then(|| Sinks::spawn(sinks, http.clone()));
+
+    let history = match history {
+        Some(history) => {
+            let store = Arc::new(Store::connect(&history).await?);
+            if let Some(addr) = history.addr {
+                let store = store.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = history::serve(addr, store).await {
+                        eprintln!("history server on {addr} failed: {err}");
+                    }
+                });
+            }
+            Some(History::spawn(store, history.retention, metrics.clone()))
+        }
+        None => None,
+    };
+
+    let mut keeper = Keeper {
+        builder,
+        client,
+        http,
+        feeds,
+        max_retries,
+        unit_price,
+        adaptive_fee,
+        fee_payers: FeePayers::new(&fee_payers, min_fee_payer_balance),
+        metrics,
+        health,
+        alerts,
+        attester,
+        sinks,
+        history,
+        state: State::default(),
+    };
+    keeper.load_prices(cluster).await;
+
+    let shutdown = tokio::signal::ctrl_c();
+    tokio::pin!(shutdown);
+    let mut hangup = signal(SignalKind::hangup())?;
+
+    loop {
+        // A tick in progress is finished before shutting down or reloading,
+        // so no update is left half-sent.
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = hangup.recv() => {
+                if let Err(err) = keeper.reload(&args.config, &admin, cluster).await {
+                    eprintln!("keeping the current configuration: {err}");
+                }
+            }
+            _ = interval.tick() => keeper.tick().await,
+        }
+    }
+
+    println!("shutting down");
+    Ok(())
+}
+
+/// Builds transactions signed by `admin` with the configured compute budget.
+fn builder<'a>(admin: &'a Keypair, cluster: Cluster, config: &Config) -> Builder<'a> {
+    let mut builder = Builder::new(admin).with_cluster(cluster);
+    if let Some(unit_price) = config.unit_price {
+        builder = builder.with_unit_price(unit_price);
+    }
+    if let Some(max_compute_units) = config.max_compute_units {
+        builder = builder.with_max_compute_units(max_compute_units);
+    }
+    builder
+}
+
+struct Keeper<'a> {
+    builder: Builder<'a>,
+    client: RpcClient,
+    http: reqwest::Client,
+    feeds: Vec<Feed>,
+    max_retries: usize,
+    /// Configured compute unit price, which `adaptive_fee` replaces.
+    unit_price: Option<u64>,
+    adaptive_fee: Option<AdaptiveFee>,
+    fee_payers: FeePayers<'a>,
+    metrics: Arc<Metrics>,
+    health: Arc<Health>,
+    alerts: Option<Alerts>,
+    attester: Option<Arc<Attester>>,
+    sinks: Option<Sinks>,
+    history: Option<History>,
+    state: State,
+}
+
+/// What the keeper remembers between ticks.
+#[derive(Default)]
+struct State {
+    /// Last source publish time sent to each oracle.
+    published: HashMap<Pubkey, u64>,
+    /// Last price each oracle holds, read at startup and then tracked as
+    /// updates land.
+    prices: HashMap<Pubkey, u64>,
+    /// Last sequence each oracle holds, tracked like `prices`.
+    sequences: HashMap<Pubkey, u64>,
+    /// When each feed was last fetched.
+    fetched: HashMap<Pubkey, Instant>,
+    /// When the keeper last updated each oracle.
+    updated: HashMap<Pubkey, Instant>,
+}
+
+impl State {
+    /// Forgets the oracles of feeds no longer in `feeds`.
+    fn retain(&mut self, feeds: &[Feed]) {
+        let keep = |pubkey: &Pubkey| feeds.iter().any(|feed| feed.pubkey == *pubkey);
+        self.published.retain(|pubkey, _| keep(pubkey));
+        self.prices.retain(|pubkey, _| keep(pubkey));
+        self.sequences.retain(|pubkey, _| keep(pubkey));
+        self.fetched.retain(|pubkey, _| keep(pubkey));
+        self.updated.retain(|pubkey, _| keep(pubkey));
+    }
+
+    /// Why `quote` is not worth sending to the oracle of `feed` at `now`,
+    /// `None` if it is. A due heartbeat is always sent, even when the
+    /// source has nothing newer, so consumers' staleness checks hold.
+    fn skip_reason(&self, feed: &Feed, quote: &Quote, now: Instant) -> Option<&'static str> {
+        if let Some(heartbeat) = feed.heartbeat {
+            let expired = self
+                .updated
+                .get(&feed.pubkey)
+                .is_none_or(|updated| now.duration_since(*updated) >= heartbeat);
+            if expired {
+                return None;
+            }
+        }
+
+        if let Some(published_ms) = quote.published_ms {
+            if self.published.get(&feed.pubkey) >= Some(&published_ms) {
+                return Some("already published");
+            }
+        }
+
+        match (feed.deviation_bps, self.prices.get(&feed.pubkey)) {
+            (Some(deviation_bps), Some(last)) => {
+                let last = u128::from(*last);
+                let moved = u128::from(quote.price).abs_diff(last) * 10_000
+                    > u128::from(deviation_bps) * last;
+                (!moved).then_some("within deviation_bps")
+            }
+            _ => None,
+        }
+    }
+
+    /// The sequence to send `quote` to `oracle` with: its source publish
+    /// time, or now if it has none, moved past the oracle's last sequence
+    /// so a heartbeat repeating a publish time is not rejected as stale.
+    fn sequence(&self, oracle: &Pubkey, quote: &Quote) -> u64 {
+        let sequence = quote.published_ms.unwrap_or_else(Sequence::now_millis);
+
+        match self.sequences.get(oracle) {
+            Some(last) => sequence.max(last.saturating_add(1)),
+            None => sequence,
+        }
+    }
+}
+
+impl<'a> Keeper<'a> {
+    /// Reads the price each oracle holds, so deviation thresholds apply
+    /// from the first tick. Oracles that can't be read are published to
+    /// unconditionally.
+    async fn load_prices(&mut self, cluster: Cluster) {
+        for feed in &self.feeds {
+            if self.state.prices.contains_key(&feed.pubkey) {
+                continue;
+            }
+
+            let account = self.client.get_account(&feed.pubkey).await;
+            self.health.rpc(account.is_ok());
+
+            let oracle = match account {
+                Ok(account) => Oracle::<PriceFeed>::try_from_account(&account, cluster)
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+
+            match oracle {
+                Ok(oracle) => {
+                    self.state.prices.insert(feed.pubkey, oracle.payload.price);
+                    self.state.sequences.insert(feed.pubkey, oracle.sequence);
+                }
+                Err(err) => eprintln!("{}: {err}", feed.name),
+            }
+        }
+    }
+
+    /// Applies the configuration at `path` without restarting: feeds are
+    /// added and removed, and feed policies, retries, the compute budget and
+    /// alerting take effect from the next tick. Feeds that stay keep their
+    /// state, so their updates continue without a gap.
+    ///
+    /// The RPC node, keypairs, program, tick interval and server addresses
+    /// are only read at startup.
+    async fn reload(
+        &mut self,
+        path: &PathBuf,
+        admin: &'a Keypair,
+        cluster: Cluster,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut config = Config::load(path)?;
+        if config.cluster()?.program_id() != cluster.program_id() {
+            return Err("program_id cannot change without a restart".into());
+        }
+
+        let builder = builder(admin, cluster, &config);
+        let max_retries = config.max_retries;
+        let unit_price = config.unit_price;
+        let adaptive_fee = config.adaptive_fee.take();
+        let alerts = config.alerts.take();
+        let feeds = config.feeds(&admin.pubkey(), cluster)?;
+
+        let added = feeds
+            .iter()
+            .filter(|feed| self.feeds.iter().all(|old| old.pubkey != feed.pubkey))
+            .count();
+        let removed = self
+            .feeds
+            .iter()
+            .filter(|old| feeds.iter().all(|feed| feed.pubkey != old.pubkey))
+            .count();
+
+        self.builder = builder;
+        self.max_retries = max_retries;
+        self.unit_price = unit_price;
+        self.adaptive_fee = match (self.adaptive_fee.take(), adaptive_fee) {
+            (Some(mut current), Some(config)) => {
+                current.set_config(config);
+                Some(current)
+            }
+            (_, config) => config.map(|config| AdaptiveFee::new(config, unit_price)),
+        };
+        self.alerts = match (self.alerts.take(), alerts) {
+            (Some(mut current), Some(config)) => {
+                current.set_config(config);
+                Some(current)
+            }
+            (_, config) => config.map(|config| Alerts::new(config, self.http.clone())),
+        };
+        self.health
+            .set_feeds(feeds.iter().map(|feed| feed.name.as_str()));
+        self.feeds = feeds;
+        self.state.retain(&self.feeds);
+        if let Some(attester) = &self.attester {
+            let oracles: Vec<Pubkey> = self.feeds.iter().map(|feed| feed.pubkey).collect();
+            attester.retain(&oracles);
+        }
+        self.load_prices(cluster).await;
+
+        println!(
+            "reloaded {}: {} feeds, {added} added, {removed} removed",
+            path.display(),
+            self.feeds.len()
+        );
+        Ok(())
+    }
+
+    /// Pulls the price of every feed that is due and publishes it, resending
+    /// failed updates up to `max_retries` times.
+    ///
+    /// Quotes carrying a publish time are sequenced by it and skipped when
+    /// already published; the rest get a fresh millisecond sequence on every
+    /// attempt. Feeds with a deviation threshold skip quotes too close to the
+    /// oracle's price, unless their heartbeat is due.
+    async fn tick(&mut self) {
+        self.health.ticked();
+        let now = Instant::now();
+        let due: Vec<&Feed> = self
+            .feeds
+            .iter()
+            .filter(|feed| {
+                self.state
+                    .fetched
+                    .get(&feed.pubkey)
+                    .is_none_or(|fetched| now.duration_since(*fetched) >= feed.interval)
+            })
+            .collect();
+        for feed in &due {
+            self.state.fetched.insert(feed.pubkey, now);
+        }
+
+        if let Some(alerts) = &mut self.alerts {
+            for feed in &self.feeds {
+                alerts.check_stale(feed, self.state.updated.get(&feed.pubkey).copied(), now);
+            }
+        }
+
+        let quotes = join_all(due.iter().map(|feed| self.quote(feed))).await;
+
+        let quotes: Vec<(&Feed, Quote)> = due
+            .into_iter()
+            .zip(quotes)
+            .filter_map(|(feed, quote)| Some((feed, quote?)))
+            .collect();
+        if let Some(alerts) = &mut self.alerts {
+            for (feed, quote) in &quotes {
+                let onchain = self.state.prices.get(&feed.pubkey).copied();
+                alerts.check_divergence(feed, onchain, quote.price);
+            }
+        }
+
+        let mut pending: Vec<(&Feed, Quote)> = vec![];
+        for (feed, quote) in quotes {
+            let skipped = self.state.skip_reason(feed, &quote, now);
+            if let Some(history) = &self.history {
+                history.record(Record::Decision(Decision {
+                    feed: feed.name.clone(),
+                    outcome: match skipped {
+                        Some(_) => Outcome::Skip,
+                        None => Outcome::Publish,
+                    },
+                    price: Some(quote.price),
+                    published_ms: quote.published_ms,
+                    reason: skipped.map(str::to_string),
+                    decided_ms: unix_millis(),
+                }));
+            }
+            if skipped.is_none() {
+                pending.push((feed, quote));
+            }
+        }
+
+        if !pending.is_empty() {
+            self.fee_payers.refresh(&self.client, &self.metrics).await;
+        }
+
+        for _ in 0..=self.max_retries {
+            if pending.is_empty() {
+                break;
+            }
+
+            // Every update goes in its own transaction, paid by the next fee
+            // payer or the admin. Feeds capped below the current unit price
+            // are sent separately at their cap.
+            let fee_payer = self.fee_payers.next();
+            let mut groups: BTreeMap<Option<u64>, Vec<(&Feed, Quote)>> = BTreeMap::new();
+            for (feed, quote) in pending {
+                groups
+                    .entry(self.unit_price(feed))
+                    .or_default()
+                    .push((feed, quote));
+            }
+
+            let mut failed = vec![];
+            for (unit_price, group) in groups {
+                let mut builder = self.builder.clone();
+                if let Some(unit_price) = unit_price {
+                    builder = builder.with_unit_price(unit_price);
+                }
+                let signatures = match fee_payer {
+                    Some(fee_payer) => {
+                        builder = builder.with_fee_payer_keypair(fee_payer);
+                        2
+                    }
+                    None => 1,
+                };
+                let fee = signatures * LAMPORTS_PER_SIGNATURE + builder.priority_fee();
+
+                let updates: Vec<(Pubkey, Oracle<PriceFeed>)> = group
+                    .iter()
+                    .map(|(feed, quote)| {
+                        let oracle = Oracle {
+                            sequence: self.state.sequence(&feed.pubkey, quote),
+                            payload: PriceFeed { price: quote.price },
+                        };
+                        (feed.pubkey, oracle)
+                    })
+                    .collect();
+
+                let sequences: Vec<u64> =
+                    updates.iter().map(|(_, oracle)| oracle.sequence).collect();
+
+                let sent = Instant::now();
+                let results = builder.send_updates(&self.client, updates).await;
+                self.health.rpc(results.is_ok());
+                let results = match results {
+                    Ok(results) => results,
+                    Err(err) => {
+                        eprintln!("failed to fetch blockhash: {err}");
+                        for ((feed, quote), sequence) in group.iter().zip(&sequences) {
+                            if let Some(history) = &self.history {
+                                history.record(Record::Confirmation(Confirmation {
+                                    feed: feed.name.clone(),
+                                    oracle: feed.pubkey.to_string(),
+                                    sequence: *sequence,
+                                    price: quote.price,
+                                    signature: None,
+                                    error: Some(format!("failed to fetch blockhash: {err}")),
+                                    unit_price,
+                                    fee_lamports: fee,
+                                    latency_ms: 0,
+                                    confirmed_ms: unix_millis(),
+                                }));
+                            }
+                            self.metrics.error(&feed.name, "rpc");
+                            if let Some(alerts) = &mut self.alerts {
+                                alerts.failed(feed);
+                            }
+                        }
+                        failed.extend(group);
+                        continue;
+                    }
+                };
+                let latency = sent.elapsed();
+
+                for (((pubkey, result), (feed, quote)), sequence) in results
+                    .into_iter()
+                    .zip(group)
+                    .zip(sequences.iter().copied())
+                {
+                    if let Some(history) = &self.history {
+                        history.record(Record::Confirmation(Confirmation {
+                            feed: feed.name.clone(),
+                            oracle: pubkey.to_string(),
+                            sequence,
+                            price: quote.price,
+                            signature: result.as_ref().ok().map(ToString::to_string),
+                            error: result.as_ref().err().map(ToString::to_string),
+                            unit_price,
+                            fee_lamports: fee,
+                            latency_ms: latency.as_millis() as u64,
+                            confirmed_ms: unix_millis(),
+                        }));
+                    }
+                    match result {
+                        Ok(signature) => {
+                            println!("{pubkey}: {} ({signature})", quote.price);
+                            if let Some(attester) = &self.attester {
+                                attester.attest(
+                                    &feed.name,
+                                    &pubkey,
+                                    sequence,
+                                    quote.price,
+                                    unix_secs(),
+                                );
+                            }
+                            if let Some(sinks) = &self.sinks {
+                                sinks.publish(&UpdateEvent {
+                                    feed: feed.name.clone(),
+                                    oracle: pubkey.to_string(),
+                                    sequence,
+                                    payload: PricePayload { price: quote.price },
+                                    signature: signature.to_string(),
+                                    unit_price,
+                                    fee_lamports: fee,
+                                    latency_ms: latency.as_millis() as u64,
+                                    confirmed_ms: unix_millis(),
+                                });
+                            }
+                            self.metrics.landed(&feed.name, latency, fee);
+                            if let Some(fee_payer) = fee_payer {
+                                self.fee_payers.spent(fee_payer, fee);
+                            }
+                            if let Some(adaptive_fee) = &mut self.adaptive_fee {
+                                adaptive_fee.landed(latency);
+                            }
+                            self.health.updated(&feed.name);
+                            if let Some(alerts) = &mut self.alerts {
+                                alerts.landed(feed);
+                            }
+                            if let Some(published_ms) = quote.published_ms {
+                                self.state.published.insert(pubkey, published_ms);
+                            }
+                            self.state.prices.insert(pubkey, quote.price);
+                            self.state.sequences.insert(pubkey, sequence);
+                            self.state.updated.insert(pubkey, Instant::now());
+                        }
+                        Err(err) => {
+                            eprintln!("{pubkey}: {err}");
+                            self.metrics.failed(&feed.name);
+                            if let Some(adaptive_fee) = &mut self.adaptive_fee {
+                                adaptive_fee.failed();
+                            }
+                            if let Some(alerts) = &mut self.alerts {
+                                alerts.failed(feed);
+                            }
+                            failed.push((feed, quote));
+                        }
+                    }
+                }
+            }
+            pending = failed;
+        }
+
+        if let Some(adaptive_fee) = &mut self.adaptive_fee {
+            adaptive_fee.adjust();
+            self.metrics.unit_price(adaptive_fee.unit_price());
+        }
+        if let Some(alerts) = &mut self.alerts {
+            alerts.flush();
+        }
+    }
+
+    /// Compute unit price of `feed`'s updates: the adaptive price if enabled,
+    /// else the configured one, capped by the feed's `max_unit_price`.
+    fn unit_price(&self, feed: &Feed) -> Option<u64> {
+        let unit_price = self
+            .adaptive_fee
+            .as_ref()
+            .map(AdaptiveFee::unit_price)
+            .or(self.unit_price)?;
+
+        Some(
+            feed.max_unit_price
+                .map_or(unit_price, |max| unit_price.min(max)),
+        )
+    }
+
+    /// Fetches every source of `feed`, aggregates their quotes and runs the
+    /// feed's transforms over the result, logging the sources that failed.
+    async fn quote(&self, feed: &Feed) -> Option<Quote> {
+        let quotes = join_all(
+            feed.sources
+                .iter()
+                .map(|source| source.fetch(&self.http, &self.client)),
+        )
+        .await;
+
+        let observed_ms = unix_millis();
+        let quotes: Vec<Option<Quote>> = quotes
+            .into_iter()
+            .enumerate()
+            .map(|(i, quote)| {
+                if let Some(history) = &self.history {
+                    history.record(Record::Observation(Observation {
+                        feed: feed.name.clone(),
+                        source: i as u32,
+                        price: quote.as_ref().ok().map(|quote| quote.price),
+                        published_ms: quote.as_ref().ok().and_then(|quote| quote.published_ms),
+                        confidence: quote.as_ref().ok().and_then(|quote| quote.confidence),
+                        error: quote.as_ref().err().map(ToString::to_string),
+                        observed_ms,
+                    }));
+                }
+                (i, quote)
+            })
+            .map(|(i, quote)| match quote {
+                Ok(quote) => {
+                    self.metrics.observe(&feed.name, i, quote.published_ms);
+                    Some(quote)
+                }
+                Err(err) => {
+                    eprintln!("{} source {i}: {err}", feed.name);
+                    self.metrics.error(&feed.name, "source");
+                    None
+                }
+            })
+            .collect();
+
+        let aggregated = feed
+            .aggregation
+            .aggregate(&quotes)
+            .inspect(|_| self.health.observed(&feed.name))
+            .map_err(|err| ("aggregate", None, err.to_string()));
+        let transformed = aggregated.and_then(|quote| {
+            let context = Context {
+                last_price: self.state.prices.get(&feed.pubkey).copied(),
+            };
+            transform::apply(&feed.transforms, quote.price, &context)
+                .map(|price| Quote { price, ..quote })
+                .map_err(|err| ("transform", Some(quote), err.to_string()))
+        });
+
+        transformed
+            .inspect_err(|(stage, quote, err)| {
+                eprintln!("{}: {err}", feed.name);
+                self.metrics.error(&feed.name, stage);
+                if let Some(history) = &self.history {
+                    history.record(Record::Decision(Decision {
+                        feed: feed.name.clone(),
+                        outcome: Outcome::Reject,
+                        price: quote.map(|quote| quote.price),
+                        published_ms: quote.and_then(|quote| quote.published_ms),
+                        reason: Some(err.clone()),
+                        decided_ms: unix_millis(),
+                    }));
+                }
+            })
+            .ok()
+    }
+}
+
+fn unix_secs() -> u64 {
+    unix_millis() / 1_000
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_unless_deviation_or_heartbeat() {
+        let feed = Feed {
+            name: "SOL/USDC".to_string(),
+            pubkey: Pubkey::new_unique(),
+            interval: Duration::ZERO,
+            payload: config::Payload::Price,
+            deviation_bps: Some(50),
+            heartbeat: Some(Duration::from_secs(60)),
+            max_unit_price: None,
+            sources: vec![],
+            aggregation: Default::default(),
+            transforms: vec![],
+        };
+        let quote = |price| Quote {
+            price,
+            published_ms: None,
+            confidence: None,
+        };
+
+        let now = Instant::now();
+
+        let mut state = State::default();
+        state.prices.insert(feed.pubkey, 100_000);
+        assert_eq!(state.skip_reason(&feed, &quote(100_000), now), None);
+
+        state.updated.insert(feed.pubkey, now);
+        assert_eq!(
+            state.skip_reason(&feed, &quote(100_500), now),
+            Some("within deviation_bps")
+        );
+        assert_eq!(state.skip_reason(&feed, &quote(100_501), now), None);
+        assert_eq!(state.skip_reason(&feed, &quote(99_499), now), None);
+
+        let later = now + Duration::from_secs(60);
+        assert_eq!(state.skip_reason(&feed, &quote(100_000), later), None);
+
+        state.published.insert(feed.pubkey, 7);
+        let republished = Quote {
+            price: 200_000,
+            published_ms: Some(7),
+            confidence: None,
+        };
+        assert_eq!(
+            state.skip_reason(&feed, &republished, now),
+            Some("already published")
+        );
+        assert_eq!(state.skip_reason(&feed, &republished, later), None);
+    }
+
+    /// A heartbeat resending a publish time must still advance the
+    /// oracle's sequence.
+    #[test]
+    fn test_sequence_advances_past_last_landed() {
+        let oracle = Pubkey::new_unique();
+        let quote = Quote {
+            price: 100_000,
+            published_ms: Some(7),
+            confidence: None,
+        };
+
+        let mut state = State::default();
+        assert_eq!(state.sequence(&oracle, &quote), 7);
+
+        state.sequences.insert(oracle, 7);
+        assert_eq!(state.sequence(&oracle, &quote), 8);
+
+        state.sequences.insert(oracle, 3);
+        assert_eq!(state.sequence(&oracle, &quote), 7);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{routing::get, Router};
+use solana_pubkey::Pubkey;
+
+/// Upper bounds, in seconds, of the landing latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 8] = [0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+
+/// Keeper metrics, rendered in the Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// Updates sent, by feed and outcome.
+    updates: BTreeMap<(String, &'static str), u64>,
+    /// Errors, by feed and stage.
+    errors: BTreeMap<(String, &'static str), u64>,
+    /// Age of each source's latest quote at fetch time, by feed and source.
+    source_age: BTreeMap<(String, usize), f64>,
+    /// Cumulative counts of landed updates per latency bucket, then the
+    /// `+Inf` count.
+    latency_buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    latency_sum: f64,
+    /// Estimated lamports spent on landed updates.
+    fee_lamports: u64,
+    /// Last balance read of each fee payer.
+    fee_payer_balances: BTreeMap<String, u64>,
+    /// Compute unit price the adaptive fee settled on, if enabled.
+    unit_price: Option<u64>,
+    /// History records dropped because the database fell behind.
+    history_dropped: u64,
+}
+
+impl Metrics {
+    /// Records a quote a source returned, `published_ms` being when the
+    /// source published it if it says.
+    pub fn observe(&self, feed: &str, source: usize, published_ms: Option<u64>) {
+        let age = published_ms.map_or(0.0, |published_ms| {
+            now_ms().saturating_sub(published_ms) as f64 / 1000.0
+        });
+
+        self.lock()
+            .source_age
+            .insert((feed.to_string(), source), age);
+    }
+
+    /// Counts an error in `stage`, one of `source`, `aggregate` or `rpc`.
+    pub fn error(&self, feed: &str, stage: &'static str) {
+        *self
+            .lock()
+            .errors
+            .entry((feed.to_string(), stage))
+            .or_default() += 1;
+    }
+
+    /// Records an update that landed after `latency`, paying `fee` lamports.
+    pub fn landed(&self, feed: &str, latency: Duration, fee: u64) {
+        let mut inner = self.lock();
+        *inner
+            .updates
+            .entry((feed.to_string(), "landed"))
+            .or_default() += 1;
+
+        let latency = latency.as_secs_f64();
+        for (bucket, le) in LATENCY_BUCKETS.iter().enumerate() {
+            if latency <= *le {
+                inner.latency_buckets[bucket] += 1;
+            }
+        }
+        inner.latency_buckets[LATENCY_BUCKETS.len()] += 1;
+        inner.latency_sum += latency;
+        inner.fee_lamports += fee;
+    }
+
+    /// Records an update that failed to land.
+    pub fn failed(&self, feed: &str) {
+        *self
+            .lock()
+            .updates
+            .entry((feed.to_string(), "failed"))
+            .or_default() += 1;
+    }
+
+    pub fn fee_payer_balance(&self, payer: &Pubkey, lamports: u64) {
+        self.lock()
+            .fee_payer_balances
+            .insert(payer.to_string(), lamports);
+    }
+
+    pub fn unit_price(&self, micro_lamports: u64) {
+        self.lock().unit_price = Some(micro_lamports);
+    }
+
+    /// Counts a history record dropped because the queue was full.
+    pub fn history_dropped(&self) {
+        self.lock().history_dropped += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let inner = self.lock();
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "updates_total",
+            "counter",
+            "Oracle updates sent, by outcome.",
+        );
+        for ((feed, result), count) in &inner.updates {
+            let _ = writeln!(
+                out,
+                "doppler_keeper_updates_total{{feed=\"{}\",result=\"{result}\"}} {count}",
+                escape(feed)
+            );
+        }
+
+        header(&mut out, "errors_total", "counter", "Errors, by stage.");
+        for ((feed, stage), count) in &inner.errors {
+            let _ = writeln!(
+                out,
+                "doppler_keeper_errors_total{{feed=\"{}\",stage=\"{stage}\"}} {count}",
+                escape(feed)
+            );
+        }
+
+        header(
+            &mut out,
+            "source_age_seconds",
+            "gauge",
+            "Age of each source's latest quote when fetched, 0 for sources without a publish time.",
+        );
+        for ((feed, source), age) in &inner.source_age {
+            let _ = writeln!(
+                out,
+                "doppler_keeper_source_age_seconds{{feed=\"{}\",source=\"{source}\"}} {age}",
+                escape(feed)
+            );
+        }
+
+        header(
+            &mut out,
+            "landing_latency_seconds",
+            "histogram",
+            "Time from sending a tick's updates to their confirmation.",
+        );
+        for (le, count) in LATENCY_BUCKETS.iter().zip(inner.latency_buckets) {
+            let _ = writeln!(
+                out,
+                "doppler_keeper_landing_latency_seconds_bucket{{le=\"{le}\"}} {count}"
+            );
+        }
+        let count = inner.latency_buckets[LATENCY_BUCKETS.len()];
+        let _ = writeln!(
+            out,
+            "doppler_keeper_landing_latency_seconds_bucket{{le=\"+Inf\"}} {count}"
+        );
+        let _ = writeln!(
+            out,
+            "doppler_keeper_landing_latency_seconds_sum {}",
+            inner.latency_sum
+        );
+        let _ = writeln!(out, "doppler_keeper_landing_latency_seconds_count {count}");
+
+        header(
+            &mut out,
+            "fees_lamports_total",
+            "counter",
+            "Estimated signature and priority fees paid for landed updates.",
+        );
+        let _ = writeln!(
+            out,
+            "doppler_keeper_fees_lamports_total {}",
+            inner.fee_lamports
+        );
+
+        header(
+            &mut out,
+            "fee_payer_balance_lamports",
+            "gauge",
+            "Balance of each fee payer when last read.",
+        );
+        for (payer, lamports) in &inner.fee_payer_balances {
+            let _ = writeln!(
+                out,
+                "doppler_keeper_fee_payer_balance_lamports{{payer=\"{payer}\"}} {lamports}"
+            );
+        }
+
+        header(
+            &mut out,
+            "history_dropped_total",
+            "counter",
+            "History records dropped because the database fell behind.",
+        );
+        let _ = writeln!(
+            out,
+            "doppler_keeper_history_dropped_total {}",
+            inner.history_dropped
+        );
+
+        if let Some(unit_price) = inner.unit_price {
+            header(
+                &mut out,
+                "unit_price_micro_lamports",
+                "gauge",
+                "Compute unit price set by the adaptive priority fee.",
+            );
+            let _ = writeln!(out, "doppler_keeper_unit_price_micro_lamports {unit_price}");
+        }
+
+        out
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Serves the metrics at `/metrics` until the keeper exits.
+pub async fn serve(addr: SocketAddr, metrics: Arc<Metrics>) -> std::io::Result<()> {
+    let app = Router::new().route("/metrics", get(move || async move { metrics.render() }));
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP doppler_keeper_{name} {help}");
+    let _ = writeln!(out, "# TYPE doppler_keeper_{name} {kind}");
+}
+
+/// Escapes a label value.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.landed("SOL/USDC", Duration::from_millis(700), 5_000);
+        metrics.failed("SOL/USDC");
+        metrics.error("SOL/\"USDC\"", "source");
+        metrics.history_dropped();
+
+        let out = metrics.render();
+        assert!(out.contains("doppler_keeper_updates_total{feed=\"SOL/USDC\",result=\"landed\"} 1"));
+        assert!(out.contains("doppler_keeper_updates_total{feed=\"SOL/USDC\",result=\"failed\"} 1"));
+        assert!(out
+            .contains("doppler_keeper_errors_total{feed=\"SOL/\\\"USDC\\\"\",stage=\"source\"} 1"));
+        assert!(out.contains("doppler_keeper_landing_latency_seconds_bucket{le=\"0.5\"} 0"));
+        assert!(out.contains("doppler_keeper_landing_latency_seconds_bucket{le=\"1\"} 1"));
+        assert!(out.contains("doppler_keeper_landing_latency_seconds_count 1"));
+        assert!(out.contains("doppler_keeper_fees_lamports_total 5000"));
+        assert!(out.contains("doppler_keeper_history_dropped_total 1"));
+    }
+}
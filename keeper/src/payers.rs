@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+use crate::metrics::Metrics;
+
+/// How often fee payer balances are read from the RPC node.
+const BALANCE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fee payers the keeper rotates through, skipping those whose balance fell
+/// below `min_balance`.
+pub struct FeePayers<'a> {
+    keypairs: &'a [Keypair],
+    min_balance: u64,
+    /// Last known balance of each payer, lowered by the fees spent since it
+    /// was read. `None` until read successfully.
+    balances: Vec<Option<u64>>,
+    checked: Option<Instant>,
+    next: usize,
+}
+
+impl<'a> FeePayers<'a> {
+    pub fn new(keypairs: &'a [Keypair], min_balance: u64) -> Self {
+        Self {
+            keypairs,
+            min_balance,
+            balances: vec![None; keypairs.len()],
+            checked: None,
+            next: 0,
+        }
+    }
+
+    /// Reads every payer's balance if the last read is older than
+    /// [`BALANCE_CHECK_INTERVAL`], warning about the ones running low.
+    pub async fn refresh(&mut self, client: &RpcClient, metrics: &Metrics) {
+        if self
+            .checked
+            .is_some_and(|checked| checked.elapsed() < BALANCE_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.checked = Some(Instant::now());
+
+        for (keypair, balance) in self.keypairs.iter().zip(&mut self.balances) {
+            let pubkey = keypair.pubkey();
+            match client.get_balance(&pubkey).await {
+                Ok(lamports) => {
+                    metrics.fee_payer_balance(&pubkey, lamports);
+                    if lamports < self.min_balance {
+                        eprintln!("fee payer {pubkey} is low: {lamports} lamports");
+                    }
+                    *balance = Some(lamports);
+                }
+                Err(err) => eprintln!("failed to read the balance of {pubkey}: {err}"),
+            }
+        }
+    }
+
+    /// The next payer with enough balance, taking turns so fees are spread
+    /// across the pool. Payers whose balance couldn't be read are tried too.
+    ///
+    /// Returns `None` when no payers are configured or all of them are
+    /// drained, leaving the admin to pay.
+    pub fn next(&mut self) -> Option<&'a Keypair> {
+        let len = self.keypairs.len();
+        let index = (0..len)
+            .map(|offset| (self.next + offset) % len)
+            .find(|index| {
+                self.balances[*index].is_none_or(|lamports| lamports >= self.min_balance)
+            });
+
+        match index {
+            Some(index) => {
+                self.next = index + 1;
+                Some(&self.keypairs[index])
+            }
+            None => {
+                if len > 0 {
+                    eprintln!("every fee payer is below its minimum balance, the admin pays");
+                }
+                None
+            }
+        }
+    }
+
+    /// Deducts `lamports` spent by `payer` from its known balance, so a
+    /// payer is rotated out before its next balance read.
+    pub fn spent(&mut self, payer: &Keypair, lamports: u64) {
+        let pubkey = payer.pubkey();
+        if let Some(index) = self.keypairs.iter().position(|k| k.pubkey() == pubkey) {
+            if let Some(balance) = &mut self.balances[index] {
+                *balance = balance.saturating_sub(lamports);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_skips_drained_payers() {
+        let keypairs = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let mut payers = FeePayers::new(&keypairs, 10_000);
+        payers.balances = vec![Some(50_000), Some(5_000), None];
+
+        let pubkey = |payer: Option<&Keypair>| payer.map(Keypair::pubkey);
+        assert_eq!(pubkey(payers.next()), Some(keypairs[0].pubkey()));
+        assert_eq!(pubkey(payers.next()), Some(keypairs[2].pubkey()));
+        assert_eq!(pubkey(payers.next()), Some(keypairs[0].pubkey()));
+
+        payers.spent(&keypairs[0], 45_000);
+        payers.balances[2] = Some(0);
+        assert_eq!(payers.next(), None);
+        assert_eq!(FeePayers::new(&[], 0).next(), None);
+    }
+}
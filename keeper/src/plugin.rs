@@ -0,0 +1,196 @@
+use std::{
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use wasmi::{Config, Engine, Linker, Memory, Module, Store, TypedFunc};
+
+use crate::source::Quote;
+
+/// Fuel a plugin gets per aggregation when its feed sets none, enough for
+/// a few million instructions.
+pub const DEFAULT_FUEL: u64 = 10_000_000;
+
+/// Bytes each quote takes in a plugin's memory.
+const QUOTE_LEN: usize = 24;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum PluginError {
+    #[error("{0}")]
+    Trap(String),
+    #[error("module does not export {0}")]
+    MissingExport(&'static str),
+    #[error("module declined with code {0}")]
+    Declined(i64),
+}
+
+/// An aggregation method compiled to WebAssembly, so pricing logic can be
+/// deployed to a shared keeper as a binary module.
+///
+/// The module imports nothing, so it can only compute on the quotes it is
+/// handed, and runs on a fuel budget, so a runaway module fails its
+/// aggregation instead of stalling the keeper. It exports:
+///
+/// - `memory`.
+/// - `alloc(len: u32) -> u32`, returning where the keeper may write `len`
+///   bytes.
+/// - `aggregate(ptr: u32, count: u32) -> i64`, reading `count` quotes of 24
+///   bytes at `ptr` and returning the price to publish, or a negative code
+///   to publish nothing. Each quote is three little-endian `u64`s: 1 if the
+///   source answered and 0 if it failed, its price, and its publish time in
+///   Unix milliseconds, 0 if unknown.
+///
+/// Every aggregation runs in a fresh instance, so no state is carried from
+/// one tick to the next.
+pub struct Plugin {
+    path: PathBuf,
+    engine: Engine,
+    module: Module,
+    fuel: u64,
+}
+
+impl fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("fuel", &self.fuel)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An instance of a plugin, ready to aggregate once.
+struct Instance {
+    store: Store<()>,
+    memory: Memory,
+    alloc: TypedFunc<u32, u32>,
+    aggregate: TypedFunc<(u32, u32), i64>,
+}
+
+impl Plugin {
+    /// Compiles the module at `path` and checks that it instantiates with
+    /// the exports the keeper calls.
+    pub fn load(path: &Path, fuel: u64) -> Result<Self, String> {
+        let wasm =
+            fs::read(path).map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+
+        let mut config = Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, &wasm[..])
+            .map_err(|err| format!("{} is not a valid module: {err}", path.display()))?;
+
+        let plugin = Self {
+            path: path.to_path_buf(),
+            engine,
+            module,
+            fuel,
+        };
+        plugin
+            .instantiate()
+            .map_err(|err| format!("{}: {err}", path.display()))?;
+        Ok(plugin)
+    }
+
+    /// Runs the module's `aggregate` over the quotes of a feed's sources,
+    /// `None` for sources that failed.
+    pub fn aggregate(&self, quotes: &[Option<Quote>]) -> Result<u64, PluginError> {
+        let Instance {
+            mut store,
+            memory,
+            alloc,
+            aggregate,
+        } = self.instantiate()?;
+
+        let input = encode(quotes);
+        let ptr = alloc.call(&mut store, input.len() as u32).map_err(trap)?;
+        memory
+            .write(&mut store, ptr as usize, &input)
+            .map_err(trap)?;
+        let price = aggregate
+            .call(&mut store, (ptr, quotes.len() as u32))
+            .map_err(trap)?;
+
+        u64::try_from(price).map_err(|_| PluginError::Declined(price))
+    }
+
+    fn instantiate(&self) -> Result<Instance, PluginError> {
+        let mut store = Store::new(&self.engine, ());
+        store.set_fuel(self.fuel).map_err(trap)?;
+        let instance = Linker::<()>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(trap)?;
+
+        let memory = instance
+            .get_memory(&store, "memory")
+            .ok_or(PluginError::MissingExport("memory"))?;
+        let alloc = instance
+            .get_typed_func(&store, "alloc")
+            .map_err(|_| PluginError::MissingExport("alloc(u32) -> u32"))?;
+        let aggregate = instance
+            .get_typed_func(&store, "aggregate")
+            .map_err(|_| PluginError::MissingExport("aggregate(u32, u32) -> i64"))?;
+
+        Ok(Instance {
+            store,
+            memory,
+            alloc,
+            aggregate,
+        })
+    }
+}
+
+fn trap(err: impl Display) -> PluginError {
+    PluginError::Trap(err.to_string())
+}
+
+/// The quotes as a plugin reads them.
+fn encode(quotes: &[Option<Quote>]) -> Vec<u8> {
+    let mut input = Vec::with_capacity(quotes.len() * QUOTE_LEN);
+    for quote in quotes {
+        let (answered, price, published_ms) = match quote {
+            Some(quote) => (1u64, quote.price, quote.published_ms.unwrap_or_default()),
+            None => (0, 0, 0),
+        };
+        input.extend_from_slice(&answered.to_le_bytes());
+        input.extend_from_slice(&price.to_le_bytes());
+        input.extend_from_slice(&published_ms.to_le_bytes());
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_quotes() {
+        let input = encode(&[
+            Some(Quote {
+                price: 1_100_000,
+                published_ms: Some(1_700_000_000_000),
+                confidence: Some(500),
+            }),
+            None,
+            Some(Quote {
+                price: 1_200_000,
+                published_ms: None,
+                confidence: None,
+            }),
+        ]);
+
+        assert_eq!(input.len(), 3 * QUOTE_LEN);
+        let word = |i: usize| u64::from_le_bytes(input[i * 8..i * 8 + 8].try_into().unwrap());
+        assert_eq!(
+            (0..9).map(word).collect::<Vec<_>>(),
+            [1, 1_100_000, 1_700_000_000_000, 0, 0, 0, 1, 1_200_000, 0]
+        );
+    }
+
+    #[test]
+    fn test_load_missing_module() {
+        let err = Plugin::load(Path::new("does-not-exist.wasm"), DEFAULT_FUEL).unwrap_err();
+        assert!(err.starts_with("failed to read does-not-exist.wasm"));
+    }
+}
@@ -0,0 +1,91 @@
+use doppler_sdk::rescale;
+use serde::Deserialize;
+
+use crate::source::{from_str, Quote, SourceError};
+
+pub const HERMES_URL: &str = "https://hermes.pyth.network";
+
+#[derive(Deserialize)]
+struct LatestPrices {
+    parsed: Vec<PriceUpdate>,
+}
+
+#[derive(Deserialize)]
+struct PriceUpdate {
+    id: String,
+    price: Price,
+}
+
+#[derive(Deserialize)]
+struct Price {
+    #[serde(deserialize_with = "from_str")]
+    price: i64,
+    expo: i32,
+    publish_time: u64,
+}
+
+/// Fetches the latest Hermes price for `feed_id` and rescales it to
+/// `10^exponent` units, e.g. an exponent of -6 publishes micro-dollars.
+pub async fn fetch(
+    http: &reqwest::Client,
+    endpoint: &str,
+    feed_id: &str,
+    exponent: i32,
+) -> Result<Quote, SourceError> {
+    let latest: LatestPrices = http
+        .get(format!("{endpoint}/v2/updates/price/latest"))
+        .query(&[("ids[]", feed_id), ("parsed", "true")])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let id = feed_id.trim_start_matches("0x");
+    let update = latest
+        .parsed
+        .into_iter()
+        .find(|update| update.id.trim_start_matches("0x").eq_ignore_ascii_case(id))
+        .ok_or_else(|| SourceError::MissingValue(feed_id.to_string()))?;
+
+    quote(&update.price, exponent).ok_or(SourceError::OutOfRange)
+}
+
+fn quote(price: &Price, exponent: i32) -> Option<Quote> {
+    Some(Quote {
+        price: rescale(price.price.into(), price.expo, exponent)?,
+        published_ms: Some(price.publish_time.checked_mul(1000)?),
+        confidence: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_rescales_to_exponent() {
+        let update: PriceUpdate = serde_json::from_str(
+            r#"{
+                "id": "ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d",
+                "price": { "price": "16145000000", "conf": "4125000", "expo": -8, "publish_time": 1700000000 }
+            }"#,
+        )
+        .unwrap();
+
+        let micros = quote(&update.price, -6).unwrap();
+        assert_eq!(micros.price, 161_450_000);
+        assert_eq!(micros.published_ms, Some(1_700_000_000_000));
+
+        assert_eq!(quote(&update.price, -10).unwrap().price, 1_614_500_000_000);
+        assert!(quote(
+            &Price {
+                price: -1,
+                expo: -8,
+                publish_time: 0
+            },
+            -6
+        )
+        .is_none());
+    }
+}
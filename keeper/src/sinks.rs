@@ -0,0 +1,389 @@
+use std::{io, sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+
+/// Time to wait before reconnecting to a message bus that failed.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// A message bus confirmed updates are published to:
+///
+/// ```toml
+/// [[sinks]]
+/// type = "nats"
+/// url = "nats://localhost:4222"
+/// subject = "doppler.updates"
+///
+/// [[sinks]]
+/// type = "kafka"
+/// rest_url = "http://localhost:8082"
+/// topic = "doppler-updates"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SinkConfig {
+    /// A NATS server, published to with core NATS `PUB`.
+    Nats {
+        /// `nats://host:port`.
+        url: String,
+        subject: String,
+        /// Token for servers with token authentication.
+        token: Option<String>,
+    },
+    /// A Kafka cluster, produced to through a Confluent REST Proxy, keyed by
+    /// oracle address so a feed's updates stay ordered in one partition.
+    Kafka { rest_url: String, topic: String },
+}
+
+impl SinkConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Nats { url, subject, .. } => {
+                if nats_address(url).is_none() {
+                    return Err(format!("{url} is not a nats://host:port URL"));
+                }
+                if subject.is_empty() || subject.contains(char::is_whitespace) {
+                    return Err(format!("invalid NATS subject {subject:?}"));
+                }
+            }
+            Self::Kafka { topic, .. } => {
+                if topic.is_empty() {
+                    return Err("empty Kafka topic".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A confirmed update, as published to every sink.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct UpdateEvent {
+    pub feed: String,
+    pub oracle: String,
+    pub sequence: u64,
+    pub payload: PricePayload,
+    pub signature: String,
+    /// Compute unit price the transaction paid, in micro-lamports.
+    pub unit_price: Option<u64>,
+    /// Estimated lamports the transaction cost.
+    pub fee_lamports: u64,
+    /// Time from sending the transaction to its confirmation.
+    pub latency_ms: u64,
+    /// When the keeper saw the update confirmed, in Unix milliseconds.
+    pub confirmed_ms: u64,
+}
+
+/// The decoded `PriceFeed` payload.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct PricePayload {
+    pub price: u64,
+}
+
+/// Hands confirmed updates to a background task per sink, so a slow or
+/// unreachable bus never holds up the keeper loop.
+pub struct Sinks {
+    senders: Vec<UnboundedSender<Arc<Value>>>,
+}
+
+impl Sinks {
+    pub fn spawn(configs: Vec<SinkConfig>, http: reqwest::Client) -> Self {
+        let senders = configs
+            .into_iter()
+            .map(|config| {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                match config {
+                    SinkConfig::Nats {
+                        url,
+                        subject,
+                        token,
+                    } => tokio::spawn(nats(url, subject, token, receiver)),
+                    SinkConfig::Kafka { rest_url, topic } => {
+                        tokio::spawn(kafka(http.clone(), rest_url, topic, receiver))
+                    }
+                };
+                sender
+            })
+            .collect();
+
+        Self { senders }
+    }
+
+    pub fn publish(&self, event: &UpdateEvent) {
+        let event = match serde_json::to_value(event) {
+            Ok(event) => Arc::new(event),
+            Err(err) => {
+                eprintln!("{}: failed to encode update: {err}", event.feed);
+                return;
+            }
+        };
+
+        for sender in &self.senders {
+            let _ = sender.send(event.clone());
+        }
+    }
+}
+
+/// Publishes each event as JSON to `subject`, reconnecting whenever the
+/// connection drops. An event whose publish fails is dropped.
+async fn nats(
+    url: String,
+    subject: String,
+    token: Option<String>,
+    mut events: UnboundedReceiver<Arc<Value>>,
+) {
+    let Some(addr) = nats_address(&url) else {
+        return;
+    };
+
+    loop {
+        let mut connection = match Nats::connect(addr, token.as_deref()).await {
+            Ok(connection) => connection,
+            Err(err) => {
+                eprintln!("failed to connect to NATS at {url}: {err}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let Some(event) = event else {
+                        return;
+                    };
+                    if let Err(err) = connection.publish(&subject, event.to_string().as_bytes()).await {
+                        eprintln!("failed to publish to NATS subject {subject}: {err}");
+                        break;
+                    }
+                }
+                served = connection.serve() => {
+                    if let Err(err) = served {
+                        eprintln!("NATS connection to {url} closed: {err}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn nats_address(url: &str) -> Option<&str> {
+    let addr = url.strip_prefix("nats://")?;
+    (!addr.is_empty() && !addr.contains('/')).then_some(addr)
+}
+
+/// A core NATS connection, only publishing.
+struct Nats {
+    reader: BufReader<tokio::net::tcp::OwnedReadHalf>,
+    writer: tokio::net::tcp::OwnedWriteHalf,
+    line: Vec<u8>,
+}
+
+impl Nats {
+    async fn connect(addr: &str, token: Option<&str>) -> io::Result<Self> {
+        let (reader, writer) = TcpStream::connect(addr).await?.into_split();
+        let mut connection = Self {
+            reader: BufReader::new(reader),
+            writer,
+            line: vec![],
+        };
+
+        // The server greets with `INFO {...}`
+        let info = connection.read_line().await?;
+        if !info.starts_with("INFO ") {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected greeting {info:?}"),
+            ));
+        }
+
+        let mut options = json!({
+            "verbose": false,
+            "pedantic": false,
+            "name": "doppler-keeper",
+            "lang": "rust",
+            "version": env!("CARGO_PKG_VERSION"),
+        });
+        if let Some(token) = token {
+            options["auth_token"] = json!(token);
+        }
+        connection
+            .writer
+            .write_all(format!("CONNECT {options}\r\n").as_bytes())
+            .await?;
+
+        Ok(connection)
+    }
+
+    async fn publish(&mut self, subject: &str, payload: &[u8]) -> io::Result<()> {
+        let mut message = format!("PUB {subject} {}\r\n", payload.len()).into_bytes();
+        message.extend_from_slice(payload);
+        message.extend_from_slice(b"\r\n");
+
+        self.writer.write_all(&message).await
+    }
+
+    /// Answers the server until the connection fails: `PONG` to its pings,
+    /// and logging its errors.
+    async fn serve(&mut self) -> io::Result<()> {
+        loop {
+            let line = self.read_line().await?;
+            if line == "PING" {
+                self.writer.write_all(b"PONG\r\n").await?;
+            } else if let Some(err) = line.strip_prefix("-ERR ") {
+                eprintln!("NATS error: {err}");
+            }
+        }
+    }
+
+    /// Reads the next line. A read cut short by `select!` stays in `line`
+    /// and is completed by the next call.
+    async fn read_line(&mut self) -> io::Result<String> {
+        if self.reader.read_until(b'\n', &mut self.line).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let line = String::from_utf8_lossy(&self.line).trim_end().to_string();
+        self.line.clear();
+
+        Ok(line)
+    }
+}
+
+/// Produces events to `topic` through the REST Proxy at `rest_url`,
+/// batching those that queued up while a request was in flight.
+async fn kafka(
+    http: reqwest::Client,
+    rest_url: String,
+    topic: String,
+    mut events: UnboundedReceiver<Arc<Value>>,
+) {
+    let url = format!("{}/topics/{topic}", rest_url.trim_end_matches('/'));
+
+    while let Some(event) = events.recv().await {
+        let mut batch = vec![event];
+        while let Ok(event) = events.try_recv() {
+            batch.push(event);
+        }
+
+        let sent = http
+            .post(&url)
+            .header("Content-Type", "application/vnd.kafka.json.v2+json")
+            .body(kafka_records(&batch).to_string())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        if let Err(err) = sent {
+            eprintln!(
+                "failed to produce {} updates to Kafka topic {topic}: {err}",
+                batch.len()
+            );
+        }
+    }
+}
+
+/// The REST Proxy's v2 produce request for `events`.
+fn kafka_records(events: &[Arc<Value>]) -> Value {
+    json!({
+        "records": events
+            .iter()
+            .map(|event| json!({ "key": event["oracle"], "value": event.as_ref() }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn event() -> UpdateEvent {
+        UpdateEvent {
+            feed: "SOL/USDC".to_string(),
+            oracle: "11111111111111111111111111111112".to_string(),
+            sequence: 7,
+            payload: PricePayload { price: 1_100_000 },
+            signature: "sig".to_string(),
+            unit_price: Some(1_000),
+            fee_lamports: 5_021,
+            latency_ms: 420,
+            confirmed_ms: 1_700_000_000_000,
+        }
+    }
+
+    #[test]
+    fn test_kafka_records_key_by_oracle() {
+        let event = Arc::new(serde_json::to_value(event()).unwrap());
+
+        let records = kafka_records(&[event.clone(), event]);
+        assert_eq!(records["records"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            records["records"][0]["key"],
+            "11111111111111111111111111111112"
+        );
+        assert_eq!(
+            records["records"][0]["value"]["payload"]["price"],
+            1_100_000
+        );
+    }
+
+    #[test]
+    fn test_validate_sinks() {
+        let nats = |url: &str, subject: &str| SinkConfig::Nats {
+            url: url.to_string(),
+            subject: subject.to_string(),
+            token: None,
+        };
+
+        assert!(nats("nats://localhost:4222", "doppler.updates")
+            .validate()
+            .is_ok());
+        assert!(nats("localhost:4222", "doppler.updates")
+            .validate()
+            .is_err());
+        assert!(nats("nats://localhost:4222", "doppler updates")
+            .validate()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nats_publishes_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("nats://{}", listener.local_addr().unwrap());
+
+        let sinks = Sinks::spawn(
+            vec![SinkConfig::Nats {
+                url,
+                subject: "doppler.updates".to_string(),
+                token: Some("secret".to_string()),
+            }],
+            reqwest::Client::new(),
+        );
+        sinks.publish(&event());
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        writer.write_all(b"INFO {}\r\n").await.unwrap();
+        let mut lines = BufReader::new(reader).lines();
+
+        let connect = lines.next_line().await.unwrap().unwrap();
+        assert!(connect.starts_with("CONNECT "));
+        assert!(connect.contains(r#""auth_token":"secret""#));
+
+        let payload = serde_json::to_value(event()).unwrap().to_string();
+        assert_eq!(
+            lines.next_line().await.unwrap().unwrap(),
+            format!("PUB doppler.updates {}", payload.len())
+        );
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), payload);
+
+        writer.write_all(b"PING\r\n").await.unwrap();
+        assert_eq!(lines.next_line().await.unwrap().unwrap(), "PONG");
+    }
+}
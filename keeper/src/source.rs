@@ -0,0 +1,214 @@
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use serde::{de, Deserialize, Deserializer};
+use serde_json::Value;
+use solana_client::{client_error::ClientError, nonblocking::rpc_client::RpcClient};
+use solana_pubkey::Pubkey;
+
+use crate::{chainlink, exchange, pyth, switchboard};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SourceError {
+    #[error("request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("no numeric value at {0}")]
+    MissingValue(String),
+    #[error("crossed or empty book for {0}")]
+    EmptyBook(String),
+    #[error("spread of {spread_bps} bps is over {max_spread_bps}")]
+    WideSpread {
+        spread_bps: u128,
+        max_spread_bps: u64,
+    },
+    #[error("ticker is {age_ms} ms old, over {max_age_ms}")]
+    Stale { age_ms: u64, max_age_ms: u64 },
+    #[error("price does not fit a u64")]
+    OutOfRange,
+    #[error("RPC request failed: {0}")]
+    Rpc(#[from] ClientError),
+    #[error("{0} is not a Switchboard pull feed")]
+    InvalidAccount(Pubkey),
+    #[error("invalid report: {0}")]
+    InvalidReport(&'static str),
+    #[error("report has {valid} valid signatures, {required} required")]
+    Unverified { valid: usize, required: usize },
+}
+
+/// A price pulled from a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quote {
+    pub price: u64,
+    /// When the source published the price, in Unix milliseconds. Used as
+    /// the oracle sequence so a price is never republished as a newer one.
+    pub published_ms: Option<u64>,
+    /// How far either side of `price` the source puts the market, in the
+    /// same units, for sources that report it.
+    pub confidence: Option<u64>,
+}
+
+/// Where a feed's price comes from.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Source {
+    /// A JSON endpoint, read at a JSON pointer such as `/data/price`. The
+    /// value may be a number or a numeric string and is multiplied by
+    /// `scale` before being rounded to an integer price.
+    Http {
+        url: String,
+        pointer: String,
+        #[serde(default = "default_scale")]
+        scale: f64,
+    },
+    /// The bid/ask midpoint of a spot market on Binance, Coinbase or
+    /// Kraken, with half the spread as its confidence.
+    Exchange(exchange::Market),
+    /// A Pyth price feed mirrored from Hermes. `feed_id` is the hex price
+    /// feed ID and the price is rescaled to `10^exponent` units.
+    Pyth {
+        feed_id: String,
+        exponent: i32,
+        #[serde(default = "default_hermes_url")]
+        endpoint: String,
+    },
+    /// A Switchboard On-Demand pull feed account, read over RPC. The price
+    /// is rescaled to `10^exponent` units.
+    Switchboard {
+        #[serde(deserialize_with = "from_str")]
+        feed: Pubkey,
+        exponent: i32,
+    },
+    /// A Chainlink Data Streams feed whose reports are verified against the
+    /// DON signers before being published.
+    Chainlink(chainlink::Stream),
+}
+
+const fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_hermes_url() -> String {
+    pyth::HERMES_URL.to_string()
+}
+
+impl Source {
+    /// Checks settings that parse but can never produce a price.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Http { scale, .. } if !scale.is_finite() || *scale <= 0.0 => {
+                Err(format!("scale must be positive, got {scale}"))
+            }
+            Self::Pyth { feed_id, .. } if decode_hex(feed_id).is_none_or(|id| id.len() != 32) => {
+                Err(format!("feed_id {feed_id} is not a 32-byte hex ID"))
+            }
+            Self::Chainlink(stream) => stream.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn fetch(
+        &self,
+        http: &reqwest::Client,
+        rpc: &RpcClient,
+    ) -> Result<Quote, SourceError> {
+        match self {
+            Self::Http {
+                url,
+                pointer,
+                scale,
+            } => {
+                let body: Value = http
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                let price = extract(&body, pointer, *scale)
+                    .ok_or_else(|| SourceError::MissingValue(pointer.clone()))?;
+
+                Ok(Quote {
+                    price,
+                    published_ms: None,
+                    confidence: None,
+                })
+            }
+            Self::Exchange(market) => {
+                let observation = market.fetch(http).await?;
+
+                Ok(Quote {
+                    price: observation.price,
+                    published_ms: observation.timestamp_ms,
+                    confidence: Some(observation.confidence),
+                })
+            }
+            Self::Pyth {
+                feed_id,
+                exponent,
+                endpoint,
+            } => pyth::fetch(http, endpoint, feed_id, *exponent).await,
+            Self::Switchboard { feed, exponent } => switchboard::fetch(rpc, feed, *exponent).await,
+            Self::Chainlink(stream) => stream.fetch(http).await,
+        }
+    }
+}
+
+fn extract(body: &Value, pointer: &str, scale: f64) -> Option<u64> {
+    let value = match body.pointer(pointer)? {
+        Value::Number(number) => number.as_f64()?,
+        Value::String(string) => string.parse().ok()?,
+        _ => return None,
+    };
+    let price = (value * scale).round();
+
+    (price.is_finite() && price >= 0.0 && price <= u64::MAX as f64).then_some(price as u64)
+}
+
+/// Deserializes a value from its string form, e.g. a base58 pubkey or a
+/// decimal string.
+pub fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+pub fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_extract_scales_price() {
+        let body = json!({ "data": { "price": 123.456789, "quoted": "0.5" } });
+
+        assert_eq!(extract(&body, "/data/price", 1e6), Some(123_456_789));
+        assert_eq!(extract(&body, "/data/quoted", 100.0), Some(50));
+        assert_eq!(extract(&body, "/data/missing", 1.0), None);
+        assert_eq!(extract(&json!({ "price": -1 }), "/price", 1.0), None);
+    }
+}
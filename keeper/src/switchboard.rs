@@ -0,0 +1,51 @@
+use doppler_sdk::{
+    rescale,
+    switchboard::{PullFeedResult, ON_DEMAND_ID, RESULT_DECIMALS},
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_pubkey::Pubkey;
+
+use crate::source::{Quote, SourceError};
+
+/// Reads the current result of a Switchboard On-Demand pull feed and
+/// rescales it to `10^exponent` units.
+pub async fn fetch(rpc: &RpcClient, feed: &Pubkey, exponent: i32) -> Result<Quote, SourceError> {
+    let account = rpc.get_account(feed).await?;
+    if account.owner != ON_DEMAND_ID {
+        return Err(SourceError::InvalidAccount(*feed));
+    }
+
+    quote(&account.data, exponent).ok_or(SourceError::InvalidAccount(*feed))
+}
+
+fn quote(data: &[u8], exponent: i32) -> Option<Quote> {
+    let result = PullFeedResult::decode(data)?;
+
+    Some(Quote {
+        price: rescale(result.value, -(RESULT_DECIMALS as i32), exponent)?,
+        published_ms: Some(result.last_update_ms()?),
+        confidence: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_sdk::switchboard::{LAST_UPDATE_OFFSET, PULL_FEED_LEN, RESULT_OFFSET};
+
+    use super::*;
+
+    #[test]
+    fn test_quote_reads_result() {
+        let mut data = vec![0; PULL_FEED_LEN];
+        data[LAST_UPDATE_OFFSET..LAST_UPDATE_OFFSET + 8]
+            .copy_from_slice(&1_700_000_000i64.to_le_bytes());
+        data[RESULT_OFFSET..RESULT_OFFSET + 16]
+            .copy_from_slice(&161_450_000_000_000_000_000i128.to_le_bytes());
+
+        let micros = quote(&data, -6).unwrap();
+        assert_eq!(micros.price, 161_450_000);
+        assert_eq!(micros.published_ms, Some(1_700_000_000_000));
+
+        assert!(quote(&data[..PULL_FEED_LEN - 1], -6).is_none());
+    }
+}
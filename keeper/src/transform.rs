@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+
+use serde::{de::DeserializeOwned, Deserialize};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum TransformError {
+    #[error("cannot invert a zero price")]
+    Zero,
+    #[error("price does not fit a u64")]
+    OutOfRange,
+    #[error("{price} is outside [{min}, {max}]")]
+    OutOfBounds { price: u64, min: u64, max: u64 },
+    #[error("{price} moved {change_bps} bps from {last}, over {max_change_bps}")]
+    CircuitBreaker {
+        price: u64,
+        last: u64,
+        change_bps: u128,
+        max_change_bps: u64,
+    },
+}
+
+/// What a transform may look at besides the price.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Context {
+    /// The price the oracle holds, if the keeper knows it.
+    pub last_price: Option<u64>,
+}
+
+/// A step applied to a feed's aggregated price before it is published,
+/// listed under the feed in the order they run:
+///
+/// ```toml
+/// transforms = [
+///     { type = "invert", decimals = 6 },
+///     { type = "basis", bps = -15 },
+///     { type = "circuit_breaker", max_change_bps = 1000 },
+/// ]
+/// ```
+///
+/// A transform that fails holds back the feed's price for the tick.
+pub trait Transform: Send + Sync {
+    fn apply(&self, price: u64, context: &Context) -> Result<u64, TransformError>;
+}
+
+/// Builds a transform from its settings, the table without `type`.
+pub type Factory = fn(toml::Table) -> Result<Box<dyn Transform>, String>;
+
+/// The transforms feeds can list, by `type`. New ones are added by
+/// registering a [`Factory`] in [`Registry::default`].
+pub struct Registry {
+    factories: BTreeMap<&'static str, Factory>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self {
+            factories: BTreeMap::new(),
+        };
+        registry.register("invert", factory::<Invert>);
+        registry.register("basis", factory::<Basis>);
+        registry.register("bounds", factory::<Bounds>);
+        registry.register("circuit_breaker", factory::<CircuitBreaker>);
+        registry
+    }
+}
+
+impl Registry {
+    pub fn register(&mut self, name: &'static str, factory: Factory) {
+        self.factories.insert(name, factory);
+    }
+
+    /// Builds the transform a feed lists, e.g. `{ type = "basis", bps = 5 }`.
+    pub fn build(&self, mut settings: toml::Table) -> Result<Box<dyn Transform>, String> {
+        let name = match settings.remove("type") {
+            Some(toml::Value::String(name)) => name,
+            Some(_) => return Err("transform type must be a string".into()),
+            None => return Err("transform has no type".into()),
+        };
+        let factory = self.factories.get(name.as_str()).ok_or_else(|| {
+            let known: Vec<&str> = self.factories.keys().copied().collect();
+            format!(
+                "unknown transform {name}, expected one of {}",
+                known.join(", ")
+            )
+        })?;
+
+        factory(settings).map_err(|err| format!("{name} transform: {err}"))
+    }
+}
+
+/// Deserializes a transform's settings into `T`.
+fn factory<T>(settings: toml::Table) -> Result<Box<dyn Transform>, String>
+where
+    T: Transform + DeserializeOwned + 'static,
+{
+    let transform: T = toml::Value::Table(settings)
+        .try_into()
+        .map_err(|err: toml::de::Error| err.message().to_string())?;
+    Ok(Box::new(transform))
+}
+
+/// Runs `transforms` over `price` in order.
+pub fn apply(
+    transforms: &[Box<dyn Transform>],
+    price: u64,
+    context: &Context,
+) -> Result<u64, TransformError> {
+    transforms
+        .iter()
+        .try_fold(price, |price, transform| transform.apply(price, context))
+}
+
+/// Turns a quote of A in B into one of B in A, e.g. USDC/SOL from SOL/USDC,
+/// both with `decimals` decimals.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Invert {
+    decimals: u32,
+}
+
+impl Transform for Invert {
+    fn apply(&self, price: u64, _: &Context) -> Result<u64, TransformError> {
+        if price == 0 {
+            return Err(TransformError::Zero);
+        }
+        let one = 10u128
+            .checked_pow(2 * self.decimals)
+            .ok_or(TransformError::OutOfRange)?;
+        u64::try_from(one / u128::from(price)).map_err(|_| TransformError::OutOfRange)
+    }
+}
+
+/// Adjusts the price by `bps` basis points, then adds `offset`, e.g. for the
+/// basis between a spot source and the market a feed tracks.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Basis {
+    #[serde(default)]
+    bps: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+impl Transform for Basis {
+    fn apply(&self, price: u64, _: &Context) -> Result<u64, TransformError> {
+        let scaled = i128::from(price) * (10_000 + i128::from(self.bps)) / 10_000;
+        u64::try_from(scaled + i128::from(self.offset)).map_err(|_| TransformError::OutOfRange)
+    }
+}
+
+/// Holds back prices outside `[min, max]`, for feeds with known sane
+/// ranges.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Bounds {
+    #[serde(default)]
+    min: u64,
+    #[serde(default = "max_price")]
+    max: u64,
+}
+
+const fn max_price() -> u64 {
+    u64::MAX
+}
+
+impl Transform for Bounds {
+    fn apply(&self, price: u64, _: &Context) -> Result<u64, TransformError> {
+        if !(self.min..=self.max).contains(&price) {
+            return Err(TransformError::OutOfBounds {
+                price,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        Ok(price)
+    }
+}
+
+/// Holds back prices more than `max_change_bps` away from the oracle's
+/// price. The feed stays halted until its sources come back within the band
+/// or the breaker is lifted from the configuration.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CircuitBreaker {
+    max_change_bps: u64,
+}
+
+impl Transform for CircuitBreaker {
+    fn apply(&self, price: u64, context: &Context) -> Result<u64, TransformError> {
+        let Some(last) = context.last_price.filter(|last| *last > 0) else {
+            return Ok(price);
+        };
+        let change_bps = u128::from(price.abs_diff(last)) * 10_000 / u128::from(last);
+        if change_bps > u128::from(self.max_change_bps) {
+            return Err(TransformError::CircuitBreaker {
+                price,
+                last,
+                change_bps,
+                max_change_bps: self.max_change_bps,
+            });
+        }
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transforms(toml: &str) -> Result<Vec<Box<dyn Transform>>, String> {
+        #[derive(Deserialize)]
+        struct Feed {
+            transforms: Vec<toml::Table>,
+        }
+        let feed: Feed = toml::from_str(toml).unwrap();
+        let registry = Registry::default();
+        feed.transforms
+            .into_iter()
+            .map(|settings| registry.build(settings))
+            .collect()
+    }
+
+    #[test]
+    fn test_transforms_run_in_order() {
+        let transforms = transforms(
+            r#"transforms = [
+                { type = "invert", decimals = 6 },
+                { type = "basis", bps = -100, offset = 10 },
+                { type = "bounds", min = 1000 },
+            ]"#,
+        )
+        .unwrap();
+        let context = Context::default();
+
+        // 1 / 125.0 = 0.008, less 1%, plus 10
+        assert_eq!(apply(&transforms, 125_000_000, &context), Ok(7_930));
+        assert_eq!(apply(&transforms, 0, &context), Err(TransformError::Zero));
+        assert_eq!(
+            apply(&transforms, 1_000_000_000_000, &context),
+            Err(TransformError::OutOfBounds {
+                price: 10,
+                min: 1000,
+                max: u64::MAX
+            })
+        );
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let transforms =
+            transforms(r#"transforms = [{ type = "circuit_breaker", max_change_bps = 500 }]"#)
+                .unwrap();
+        let last = |last_price| Context { last_price };
+
+        assert_eq!(apply(&transforms, 2_000, &last(None)), Ok(2_000));
+        assert_eq!(apply(&transforms, 1_050, &last(Some(1_000))), Ok(1_050));
+        assert!(matches!(
+            apply(&transforms, 1_051, &last(Some(1_000))),
+            Err(TransformError::CircuitBreaker {
+                change_bps: 510,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_transforms() {
+        let err = |toml: &str| transforms(toml).err().unwrap();
+
+        assert!(err(r#"transforms = [{ type = "sqrt" }]"#).starts_with("unknown transform sqrt"));
+        assert_eq!(
+            err(r#"transforms = [{ bps = 5 }]"#),
+            "transform has no type"
+        );
+        assert!(
+            err(r#"transforms = [{ type = "basis", bsp = 5 }]"#).contains("unknown field `bsp`")
+        );
+        assert!(err(r#"transforms = [{ type = "invert" }]"#).contains("missing field `decimals`"));
+    }
+}
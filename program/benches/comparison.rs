@@ -0,0 +1,266 @@
+//! Measures Doppler's update next to the equivalent operations of other
+//! oracle programs, all under the same mollusk runtime, and writes the
+//! results to `benches/comparison.md` and `benches/comparison.json`.
+//!
+//! Other oracles are measured from fixtures in `benches/fixtures/`, one JSON
+//! file per operation holding the instruction and every account it reads,
+//! as captured from a cluster. Program binaries are not checked in; dump
+//! each one next to the fixtures with `solana program dump <id>
+//! benches/fixtures/<name>.so`. Fixtures whose binaries are missing are
+//! skipped.
+
+use std::{fs, path::Path};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use doppler::prelude::*;
+use doppler_program::PriceFeed;
+use doppler_sdk::{Oracle, UpdateInstruction};
+use doppler_test_utils::{keyed_account_for_admin, keyed_account_for_oracle};
+use mollusk_svm::{program::loader_keys::LOADER_V3, Mollusk};
+use serde::Deserialize;
+use solana_account::Account;
+use solana_clock::Epoch;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+const FIXTURES: &str = "benches/fixtures";
+
+/// A captured operation of another oracle program.
+///
+/// ```json
+/// {
+///   "oracle": "Pyth",
+///   "operation": "update",
+///   "programs": [{ "program_id": "rec5EK...", "name": "pyth_receiver" }],
+///   "instruction": {
+///     "program_id": "rec5EK...",
+///     "data": "<base64>",
+///     "accounts": [{ "pubkey": "...", "is_signer": true, "is_writable": true }]
+///   },
+///   "accounts": [<`solana account <pubkey> --output json` of each account>]
+/// }
+/// ```
+#[derive(Deserialize)]
+struct Fixture {
+    oracle: String,
+    operation: String,
+    /// Every program the instruction runs, CPI targets included.
+    programs: Vec<FixtureProgram>,
+    instruction: FixtureInstruction,
+    accounts: Vec<FixtureAccount>,
+}
+
+#[derive(Deserialize)]
+struct FixtureProgram {
+    program_id: String,
+    /// File name of the binary in [`FIXTURES`], without `.so`.
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct FixtureInstruction {
+    program_id: String,
+    data: String,
+    accounts: Vec<FixtureMeta>,
+}
+
+#[derive(Deserialize)]
+struct FixtureMeta {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+/// The JSON output of `solana account --output json`.
+#[derive(Deserialize)]
+struct FixtureAccount {
+    pubkey: String,
+    account: CliAccount,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CliAccount {
+    lamports: u64,
+    /// Data and its encoding, which must be `base64`.
+    data: (String, String),
+    owner: String,
+    executable: bool,
+}
+
+struct Measurement {
+    oracle: String,
+    operation: String,
+    compute_units: u64,
+}
+
+fn main() {
+    let mut measurements = vec![doppler_update()];
+
+    let mut paths: Vec<_> = fs::read_dir(FIXTURES)
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+    {
+        let fixture: Fixture = serde_json::from_str(&fs::read_to_string(path).unwrap())
+            .unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+        match measure(&fixture) {
+            Some(measurement) => measurements.push(measurement),
+            None => println!("skipping {}: program binary missing", path.display()),
+        }
+    }
+
+    write_report(&measurements);
+}
+
+/// A single `PriceFeed` update, as in the `compute_units` bench.
+fn doppler_update() -> Measurement {
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    let admin = Pubkey::from(ADMIN);
+    let (oracle, oracle_account) =
+        keyed_account_for_oracle(&mollusk, admin, "SOL/USDC", PriceFeed { price: 100_000 });
+
+    let instruction: Instruction = UpdateInstruction {
+        admin,
+        oracle_pubkey: oracle,
+        oracle: Oracle {
+            sequence: 1,
+            payload: PriceFeed { price: 1_100_000 },
+        },
+    }
+    .into();
+    let result = mollusk.process_instruction(
+        &instruction,
+        &[keyed_account_for_admin(admin), (oracle, oracle_account)],
+    );
+    assert!(!result.program_result.is_err(), "Doppler update failed");
+
+    Measurement {
+        oracle: "Doppler".to_string(),
+        operation: "update".to_string(),
+        compute_units: result.compute_units_consumed,
+    }
+}
+
+/// Runs a fixture, or returns `None` if one of its programs isn't dumped.
+fn measure(fixture: &Fixture) -> Option<Measurement> {
+    let pubkey = |key: &str| {
+        key.parse::<Pubkey>()
+            .unwrap_or_else(|_| panic!("invalid pubkey {key}"))
+    };
+
+    let (first, rest) = fixture.programs.split_first()?;
+    let binary = |program: &FixtureProgram| format!("{FIXTURES}/{}", program.name);
+    if fixture
+        .programs
+        .iter()
+        .any(|program| !Path::new(&format!("{}.so", binary(program))).exists())
+    {
+        return None;
+    }
+
+    let mut mollusk = Mollusk::new(&pubkey(&first.program_id), &binary(first));
+    for program in rest {
+        mollusk.add_program(&pubkey(&program.program_id), &binary(program), &LOADER_V3);
+    }
+
+    let instruction = Instruction {
+        program_id: pubkey(&fixture.instruction.program_id),
+        accounts: fixture
+            .instruction
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: pubkey(&meta.pubkey),
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect(),
+        data: STANDARD.decode(&fixture.instruction.data).unwrap(),
+    };
+    let accounts: Vec<(Pubkey, Account)> = fixture
+        .accounts
+        .iter()
+        .map(|fixture_account| {
+            let account = &fixture_account.account;
+            assert_eq!(account.data.1, "base64", "account data must be base64");
+            (
+                pubkey(&fixture_account.pubkey),
+                Account {
+                    lamports: account.lamports,
+                    data: STANDARD.decode(&account.data.0).unwrap(),
+                    owner: pubkey(&account.owner),
+                    executable: account.executable,
+                    rent_epoch: Epoch::default(),
+                },
+            )
+        })
+        .collect();
+
+    let result = mollusk.process_instruction(&instruction, &accounts);
+    assert!(
+        !result.program_result.is_err(),
+        "{} {} failed: {:?}",
+        fixture.oracle,
+        fixture.operation,
+        result.program_result
+    );
+
+    Some(Measurement {
+        oracle: fixture.oracle.clone(),
+        operation: fixture.operation.clone(),
+        compute_units: result.compute_units_consumed,
+    })
+}
+
+/// Writes every measurement with its ratio to Doppler's update.
+fn write_report(measurements: &[Measurement]) {
+    let baseline = measurements[0].compute_units as f64;
+    let ratio = |measurement: &Measurement| measurement.compute_units as f64 / baseline;
+
+    let rows: Vec<String> = measurements
+        .iter()
+        .map(|measurement| {
+            format!(
+                "| {} | {} | {} | {:.1}x |",
+                measurement.oracle,
+                measurement.operation,
+                measurement.compute_units,
+                ratio(measurement)
+            )
+        })
+        .collect();
+    let markdown = format!(
+        "<!-- @generated by `cargo bench -p doppler-program --bench comparison`. Do not edit. -->\n\
+         \n\
+         | Oracle | Operation | CUs | vs Doppler update |\n\
+         | ------ | --------- | --- | ----------------- |\n\
+         {}\n",
+        rows.join("\n")
+    );
+
+    let entries: Vec<String> = measurements
+        .iter()
+        .map(|measurement| {
+            format!(
+                "    {{ \"oracle\": {:?}, \"operation\": {:?}, \"compute_units\": {}, \"ratio\": {:.2} }}",
+                measurement.oracle,
+                measurement.operation,
+                measurement.compute_units,
+                ratio(measurement)
+            )
+        })
+        .collect();
+    let json = format!(
+        "{{\n  \"measurements\": [\n{}\n  ]\n}}\n",
+        entries.join(",\n")
+    );
+
+    fs::write("benches/comparison.md", markdown).unwrap();
+    fs::write("benches/comparison.json", json).unwrap();
+    println!("{}", rows.join("\n"));
+}
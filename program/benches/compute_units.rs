@@ -1,58 +1,24 @@
+use std::fs;
+
 use doppler::prelude::*;
-use doppler_program::PriceFeed;
-use doppler_sdk::{Oracle, UpdateInstruction};
-use mollusk_svm::{program::keyed_account_for_system_program, Mollusk};
+use doppler_program::{PriceFeed, PythPriceUpdate, SwitchboardResult};
+use doppler_sdk::{Cluster, Oracle, UpdateInstruction};
+use doppler_test_utils::{keyed_account_for_admin, keyed_account_for_oracle};
+use mollusk_svm::{
+    program::{keyed_account_for_system_program, loader_keys::LOADER_V3},
+    Mollusk,
+};
 use mollusk_svm_bencher::MolluskComputeUnitBencher;
 use solana_account::Account;
-use solana_clock::Epoch;
 use solana_instruction::Instruction;
 use solana_pubkey::Pubkey;
 
-#[must_use]
-pub fn keyed_account_for_admin(key: Pubkey) -> (Pubkey, Account) {
-    (
-        key,
-        Account::new(10_000_000_000, 0, &solana_sdk_ids::system_program::ID),
-    )
-}
-
-pub fn keyed_account_for_oracle<T: Sized + Copy>(
-    mollusk: &mut Mollusk,
-    admin: Pubkey,
-    seed: &str,
-    payload: T,
-) -> (Pubkey, Account) {
-    let oracle_account = Oracle {
-        sequence: 0,
-        payload,
-    };
-
-    let key = Pubkey::create_with_seed(&admin, seed, &doppler_sdk::ID).unwrap();
-
-    let lamports = mollusk
-        .sysvars
-        .rent
-        .minimum_balance(core::mem::size_of::<Oracle<T>>());
-
-    let data = oracle_account.to_bytes();
-
-    let account = Account {
-        lamports,
-        data,
-        owner: doppler_sdk::ID,
-        executable: false,
-        rent_epoch: Epoch::default(),
-    };
-
-    (key, account)
-}
-
 fn main() {
     // Create Mollusk instance
-    let mut mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
 
     let (oracle, oracle_account) = keyed_account_for_oracle::<PriceFeed>(
-        &mut mollusk,
+        &mollusk,
         ADMIN.into(),
         "SOL/USDC",
         PriceFeed { price: 100_000 },
@@ -105,4 +71,311 @@ fn main() {
         .must_pass(true)
         .out_dir("benches/")
         .execute();
+
+    write_cu_table();
+}
+
+/// Largest number of updates measured in one transaction.
+const MAX_BATCH: usize = 16;
+/// Batch sizes reported in `benches/batch_updates.md`, the ones a keeper
+/// sends in practice.
+const REPORT_BATCH: usize = 8;
+
+/// A build of the program, one per payload it can store.
+#[derive(Clone, Copy)]
+enum Build {
+    PriceFeed,
+    Pyth,
+    Switchboard,
+}
+
+impl Build {
+    const ALL: [Self; 3] = [Self::PriceFeed, Self::Pyth, Self::Switchboard];
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::PriceFeed => "PriceFeed",
+            Self::Pyth => "PythPriceUpdate",
+            Self::Switchboard => "SwitchboardResult",
+        }
+    }
+
+    const fn payload_size(self) -> usize {
+        match self {
+            Self::PriceFeed => size_of::<PriceFeed>(),
+            Self::Pyth => size_of::<PythPriceUpdate>(),
+            Self::Switchboard => size_of::<SwitchboardResult>(),
+        }
+    }
+
+    /// Where `cargo build-sbf` writes this build, with `--sbf-out-dir` for
+    /// the feature builds.
+    const fn program(self) -> &'static str {
+        match self {
+            Self::PriceFeed => "../target/deploy/doppler_program",
+            Self::Pyth => "../target/deploy/pyth/doppler_program",
+            Self::Switchboard => "../target/deploy/switchboard/doppler_program",
+        }
+    }
+
+    /// A fresh oracle seeded with `seed` under the build deployed at
+    /// `program_id`, and the update that takes it to sequence 1.
+    fn update(
+        self,
+        mollusk: &Mollusk,
+        program_id: Pubkey,
+        seed: &str,
+    ) -> ((Pubkey, Account), Instruction) {
+        match self {
+            Self::PriceFeed => {
+                oracle_update(mollusk, program_id, seed, PriceFeed { price: 100_000 })
+            }
+            Self::Pyth => oracle_update(mollusk, program_id, seed, PYTH_PRICE_UPDATE),
+            Self::Switchboard => oracle_update(mollusk, program_id, seed, SWITCHBOARD_RESULT),
+        }
+    }
+}
+
+const PYTH_PRICE_UPDATE: PythPriceUpdate = PythPriceUpdate {
+    write_authority: [1; 32],
+    verification_level: PythPriceUpdate::VERIFICATION_LEVEL_FULL,
+    feed_id: [2; 32],
+    price: 14_512_345_678,
+    conf: 3_456_789,
+    exponent: -8,
+    publish_time: 1_700_000_000,
+    prev_publish_time: 1_699_999_999,
+    ema_price: 14_500_000_000,
+    ema_conf: 4_000_000,
+    posted_slot: 250_000_000,
+};
+
+const SWITCHBOARD_RESULT: SwitchboardResult = SwitchboardResult {
+    value: 145_123_456_780_000_000_000,
+    std_dev: 10_000_000_000_000_000,
+    mean: 145_120_000_000_000_000_000,
+    range: 50_000_000_000_000_000,
+    min_value: 145_100_000_000_000_000_000,
+    max_value: 145_150_000_000_000_000_000,
+    num_samples: 5,
+    submission_idx: 0,
+    padding: [0; 6],
+    slot: 250_000_000,
+    min_slot: 249_999_998,
+    max_slot: 250_000_000,
+};
+
+fn oracle_update<T: Sized + Copy>(
+    mollusk: &Mollusk,
+    program_id: Pubkey,
+    seed: &str,
+    payload: T,
+) -> ((Pubkey, Account), Instruction) {
+    let admin = Pubkey::from(ADMIN);
+    let (_, mut account) = keyed_account_for_oracle(mollusk, admin, seed, payload);
+    account.owner = program_id;
+    let oracle = Pubkey::create_with_seed(&admin, seed, &program_id).unwrap();
+
+    let instruction = UpdateInstruction {
+        admin,
+        oracle_pubkey: oracle,
+        oracle: Oracle {
+            sequence: 1,
+            payload,
+        },
+    }
+    .into_instruction(Cluster::Custom(program_id));
+
+    ((oracle, account), instruction)
+}
+
+/// Compute units consumed by `updates` chained in one transaction.
+fn measure(mollusk: &Mollusk, updates: Vec<((Pubkey, Account), Instruction)>) -> u64 {
+    let count = updates.len();
+    let (mut accounts, instructions): (Vec<_>, Vec<_>) = updates.into_iter().unzip();
+    accounts.push(keyed_account_for_admin(ADMIN.into()));
+
+    let result = mollusk.process_instruction_chain(&instructions, &accounts);
+    assert!(
+        !result.program_result.is_err(),
+        "batch of {count} updates failed"
+    );
+    result.compute_units_consumed
+}
+
+/// Measures updates of every build chained in a single transaction, for
+/// every batch size up to [`MAX_BATCH`], then writes the results as the
+/// SDK's `cu_table` module, as JSON for non-Rust clients and, up to
+/// [`REPORT_BATCH`], as a markdown table with the cost per update. The
+/// markdown also covers batches mixing the payload sizes, next to what
+/// their updates cost in single-size batches.
+///
+/// Each payload needs its own build of the program; the builds are loaded
+/// side by side under their own program IDs.
+fn write_cu_table() {
+    let mut mollusk = Mollusk::new(&doppler_sdk::ID, Build::PriceFeed.program());
+    let program_ids = Build::ALL.map(|build| match build {
+        Build::PriceFeed => doppler_sdk::ID,
+        build => {
+            let program_id = Pubkey::new_unique();
+            mollusk.add_program(&program_id, build.program(), &LOADER_V3);
+            program_id
+        }
+    });
+
+    let tables: Vec<(Build, Vec<u64>)> = Build::ALL
+        .into_iter()
+        .zip(program_ids)
+        .map(|(build, program_id)| {
+            let table = (1..=MAX_BATCH)
+                .map(|batch| {
+                    let updates = (0..batch)
+                        .map(|index| build.update(&mollusk, program_id, &format!("feed-{index}")))
+                        .collect();
+                    measure(&mollusk, updates)
+                })
+                .collect();
+            (build, table)
+        })
+        .collect();
+
+    // Two or more updates cycling through the builds, so every mixed batch
+    // holds at least two payload sizes
+    let mixed: Vec<(Vec<Build>, u64)> = (2..=REPORT_BATCH)
+        .map(|batch| {
+            let builds: Vec<Build> = (0..batch)
+                .map(|index| Build::ALL[index % Build::ALL.len()])
+                .collect();
+            let updates = builds
+                .iter()
+                .enumerate()
+                .map(|(index, build)| {
+                    build.update(
+                        &mollusk,
+                        program_ids[*build as usize],
+                        &format!("feed-{index}"),
+                    )
+                })
+                .collect();
+            (builds, measure(&mollusk, updates))
+        })
+        .collect();
+
+    let rust = format!(
+        "// Written by `cargo bench -p doppler-program --bench compute_units`, which\n\
+         // replaces these values with measurements of the built programs.\n\
+         \n\
+         /// Whether the tables hold measurements of the built programs rather than\n\
+         /// `UpdateInstruction::compute_units` of one update times the batch size.\n\
+         pub const MEASURED: bool = true;\n\
+         \n\
+         /// Largest number of updates measured in one transaction.\n\
+         pub const MAX_BATCH: usize = {MAX_BATCH};\n\
+         \n\
+         /// Payload sizes in bytes the tables are for, one per build of the program:\n\
+         /// {}.\n\
+         pub const PAYLOAD_SIZES: [usize; {}] = [{}];\n\
+         \n\
+         /// Compute units consumed by `n` updates of the payload at `PAYLOAD_SIZES[i]`\n\
+         /// in one transaction, at `UPDATE_CU[i][n - 1]`.\n\
+         pub const UPDATE_CU: [[u32; MAX_BATCH]; {}] = [\n{}\n];\n",
+        tables
+            .iter()
+            .map(|(build, _)| format!("`{}`", build.name()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        tables.len(),
+        tables
+            .iter()
+            .map(|(build, _)| build.payload_size().to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+        tables.len(),
+        tables
+            .iter()
+            .map(|(_, table)| format!(
+                "    [{}],",
+                table
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    let json = format!(
+        "{{\n  \"measured\": true,\n  \"payloads\": [\n{}\n  ]\n}}\n",
+        tables
+            .iter()
+            .map(|(build, table)| format!(
+                "    {{\n      \"payload\": \"{}\",\n      \"payload_size\": {},\n      \"updates\": [\n{}\n      ]\n    }}",
+                build.name(),
+                build.payload_size(),
+                table
+                    .iter()
+                    .enumerate()
+                    .map(|(index, cu)| format!(
+                        "        {{ \"batch\": {}, \"compute_units\": {cu} }}",
+                        index + 1
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(",\n"),
+            ))
+            .collect::<Vec<_>>()
+            .join(",\n"),
+    );
+
+    let markdown = format!(
+        "<!-- @generated by `cargo bench -p doppler-program --bench compute_units`. Do not edit. -->\n\
+         \n\
+         Updates of one payload chained in one transaction.\n\
+         \n\
+         | Payload | Payload bytes | Updates | CUs | CUs per update |\n\
+         | ------- | ------------- | ------- | --- | -------------- |\n\
+         {}\n\
+         \n\
+         Updates of mixed payloads chained in one transaction, against the sum of\n\
+         the same updates each sent alone.\n\
+         \n\
+         | Updates | Payload bytes | CUs | Sum of single updates |\n\
+         | ------- | ------------- | --- | --------------------- |\n\
+         {}\n",
+        tables
+            .iter()
+            .flat_map(|(build, table)| {
+                table[..REPORT_BATCH].iter().enumerate().map(|(index, cu)| {
+                    let batch = index + 1;
+                    format!(
+                        "| {} | {} | {batch} | {cu} | {:.1} |",
+                        build.name(),
+                        build.payload_size(),
+                        *cu as f64 / batch as f64
+                    )
+                })
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        mixed
+            .iter()
+            .map(|(builds, cu)| {
+                let single: u64 = builds.iter().map(|build| tables[*build as usize].1[0]).sum();
+                format!(
+                    "| {} | {} | {cu} | {single} |",
+                    builds.len(),
+                    builds
+                        .iter()
+                        .map(|build| build.payload_size().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+
+    fs::write("../sdk/src/cu_table.rs", rust).unwrap();
+    fs::write("../sdk/cu_table.json", json).unwrap();
+    fs::write("benches/batch_updates.md", markdown).unwrap();
 }
@@ -0,0 +1,239 @@
+//! Serializes arbitrary accounts and instruction data into an input region
+//! exactly as the runtime lays it out, runs the program's entrypoint on it
+//! and asserts that:
+//!
+//! - an update only gets through when the first account is the admin, as a
+//!   signer and not a duplicate, whatever else the region holds,
+//! - a failed check leaves the region untouched,
+//! - an update laid out as the SDK sends it lands if and only if its
+//!   sequence increases, writing the instruction data into the oracle and
+//!   nothing else.
+//!
+//! The program reads at fixed offsets without checking the region's length.
+//! Reading past the end of the region faults on chain, so such inputs count
+//! as rejected; the host buffer is padded to keep those reads in bounds.
+//!
+//! The entrypoint is built for the payload and layout selected by the
+//! program's features, which this crate forwards, so each is fuzzed with
+//! its own build.
+
+#![no_main]
+
+use std::ops::Range;
+
+use arbitrary::Arbitrary;
+use doppler::{fuzzing, fuzzing::take_exit_code, prelude::*};
+use doppler_program::{entrypoint, Payload};
+use libfuzzer_sys::fuzz_target;
+
+/// Bytes the runtime leaves after each account's data for it to grow into.
+const MAX_PERMITTED_DATA_INCREASE: usize = 10_240;
+/// Marks an account that is not a duplicate of an earlier one.
+const NON_DUP_MARKER: u8 = u8::MAX;
+/// Most instruction data a transaction can carry.
+const MAX_INSTRUCTION_DATA: usize = 1_232;
+/// Enough accounts to shift every fixed offset past the oracle's.
+const MAX_ACCOUNTS: usize = 4;
+const MAX_ACCOUNT_DATA: usize = 64;
+
+/// What the oracle account data holds before its sequence.
+#[cfg(not(feature = "anchor"))]
+const PREFIX: [u8; 0] = [];
+#[cfg(feature = "anchor")]
+const PREFIX: [u8; 8] = ANCHOR_DISCRIMINATOR;
+
+/// Size of an update's instruction data.
+const UPDATE_LEN: usize = size_of::<u64>() + size_of::<Payload>();
+/// Size of the oracle account data.
+const ORACLE_LEN: usize = PREFIX.len() + UPDATE_LEN;
+/// The furthest the program reads: the end of the instruction payload.
+#[cfg(not(feature = "anchor"))]
+const REACH: usize = fuzzing::reach::<Payload>();
+#[cfg(feature = "anchor")]
+const REACH: usize = fuzzing::anchor_reach::<Payload>();
+
+/// Where each account's data starts and ends in the region, `None` for a
+/// duplicate.
+type DataRanges = Vec<Option<Range<usize>>>;
+
+#[derive(Arbitrary, Debug)]
+struct Input {
+    accounts: Vec<Account>,
+    instruction_data: Vec<u8>,
+    program_id: [u8; 32],
+}
+
+#[derive(Arbitrary, Debug)]
+enum Account {
+    /// A repeat of the account at this position.
+    Duplicate(u8),
+    Unique {
+        is_signer: bool,
+        is_writable: bool,
+        executable: bool,
+        /// Use the admin's key rather than `key`.
+        is_admin: bool,
+        key: [u8; 32],
+        owner: [u8; 32],
+        lamports: u64,
+        data: Vec<u8>,
+    },
+}
+
+impl Account {
+    fn key(&self) -> Option<[u8; 32]> {
+        match self {
+            Self::Duplicate(_) => None,
+            Self::Unique { is_admin: true, .. } => Some(ADMIN),
+            Self::Unique { key, .. } => Some(*key),
+        }
+    }
+}
+
+fuzz_target!(|input: Input| {
+    let Some((region, data)) = serialize(&input) else {
+        return;
+    };
+
+    let mut buffer = vec![0u64; region.len().max(REACH).div_ceil(8)];
+    // SAFETY: the buffer is `buffer.len() * 8` initialized bytes.
+    let bytes = unsafe {
+        core::slice::from_raw_parts_mut(buffer.as_mut_ptr().cast::<u8>(), buffer.len() * 8)
+    };
+    bytes[..region.len()].copy_from_slice(&region);
+    let ptr = bytes.as_mut_ptr();
+
+    take_exit_code();
+    // SAFETY: the buffer is 8-byte aligned and spans every offset read.
+    unsafe { entrypoint(ptr) };
+    let exit_code = take_exit_code();
+    let after = &bytes[..region.len()];
+    let landed = exit_code.is_none() && region.len() >= REACH;
+
+    if exit_code.is_some() {
+        assert_eq!(after, region, "a failed check wrote to the region");
+    }
+    if landed {
+        assert!(
+            matches!(
+                input.accounts.first(),
+                Some(account @ Account::Unique { is_signer: true, .. })
+                    if account.key() == Some(ADMIN)
+            ),
+            "update landed without the admin's signature: {input:?}"
+        );
+    }
+
+    if let Some((current, oracle_data)) = sdk_update(&input, &data) {
+        let sent = u64::from_le_bytes(input.instruction_data[..8].try_into().unwrap());
+        if sent > current {
+            assert!(
+                landed,
+                "update from {current} to {sent} failed: {exit_code:?}"
+            );
+            let mut expected = region.clone();
+            let (prefix, oracle) = expected[oracle_data].split_at_mut(PREFIX.len());
+            prefix.copy_from_slice(&PREFIX);
+            oracle.copy_from_slice(&input.instruction_data);
+            assert_eq!(after, expected, "update wrote outside the oracle data");
+        } else {
+            assert_eq!(exit_code, Some(2), "stale update from {current} to {sent}");
+        }
+    }
+});
+
+/// Lays out `input` as the runtime's aligned serializer does, returning the
+/// region and where each account's data starts and ends in it. Returns
+/// `None` for inputs the runtime never produces or that are too large to
+/// be worth generating.
+fn serialize(input: &Input) -> Option<(Vec<u8>, DataRanges)> {
+    if input.accounts.len() > MAX_ACCOUNTS || input.instruction_data.len() > MAX_INSTRUCTION_DATA {
+        return None;
+    }
+
+    let mut region = (input.accounts.len() as u64).to_le_bytes().to_vec();
+    let mut data_ranges = vec![];
+
+    for (position, account) in input.accounts.iter().enumerate() {
+        match account {
+            Account::Duplicate(index) => {
+                if usize::from(*index) >= position {
+                    return None;
+                }
+                region.push(*index);
+                region.extend_from_slice(&[0; 7]);
+                data_ranges.push(None);
+            }
+            Account::Unique {
+                is_signer,
+                is_writable,
+                executable,
+                owner,
+                lamports,
+                data,
+                ..
+            } => {
+                if data.len() > MAX_ACCOUNT_DATA {
+                    return None;
+                }
+                region.extend_from_slice(&[
+                    NON_DUP_MARKER,
+                    u8::from(*is_signer),
+                    u8::from(*is_writable),
+                    u8::from(*executable),
+                    0,
+                    0,
+                    0,
+                    0,
+                ]);
+                region.extend_from_slice(&account.key()?);
+                region.extend_from_slice(owner);
+                region.extend_from_slice(&lamports.to_le_bytes());
+                region.extend_from_slice(&(data.len() as u64).to_le_bytes());
+
+                let start = region.len();
+                region.extend_from_slice(data);
+                data_ranges.push(Some(start..region.len()));
+
+                region.resize(
+                    (region.len() + MAX_PERMITTED_DATA_INCREASE).next_multiple_of(8),
+                    0,
+                );
+                // Rent epoch.
+                region.extend_from_slice(&u64::MAX.to_le_bytes());
+            }
+        }
+    }
+
+    region.extend_from_slice(&(input.instruction_data.len() as u64).to_le_bytes());
+    region.extend_from_slice(&input.instruction_data);
+    region.extend_from_slice(&input.program_id);
+
+    Some((region, data_ranges))
+}
+
+/// If `input` is an admin-signed update laid out as the SDK sends it, the
+/// oracle's current sequence and where its data is in the region.
+fn sdk_update(input: &Input, data: &[Option<Range<usize>>]) -> Option<(u64, Range<usize>)> {
+    let [admin @ Account::Unique {
+        is_signer: true,
+        data: admin_data,
+        ..
+    }, Account::Unique {
+        data: oracle_data, ..
+    }] = input.accounts.as_slice()
+    else {
+        return None;
+    };
+
+    if admin.key() != Some(ADMIN)
+        || !admin_data.is_empty()
+        || oracle_data.len() != ORACLE_LEN
+        || input.instruction_data.len() != UPDATE_LEN
+    {
+        return None;
+    }
+
+    let current = u64::from_le_bytes(oracle_data[PREFIX.len()..][..8].try_into().unwrap());
+    Some((current, data[1].clone()?))
+}
@@ -10,6 +10,78 @@ pub struct PriceFeed {
     pub price: u64,
 }
 
+/// The fields of a Pyth receiver `PriceUpdateV2` account after its 8-byte
+/// discriminator, laid out as Borsh encodes them for a fully verified
+/// update. Built with the `pyth` feature, the program stores this payload
+/// so every field sits at the offset Pyth consumers read it from.
+///
+/// The oracle's sequence takes the discriminator's place and the account is
+/// not `PriceUpdateV2::LEN` bytes, so this serves readers that take fields
+/// at raw offsets or Borsh-decode from byte 8 only. Pyth's
+/// `Account<PriceUpdateV2>` and `try_deserialize` reject it.
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+pub struct PythPriceUpdate {
+    pub write_authority: [u8; 32],
+    /// Always [`PythPriceUpdate::VERIFICATION_LEVEL_FULL`].
+    pub verification_level: u8,
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub prev_publish_time: i64,
+    pub ema_price: i64,
+    pub ema_conf: u64,
+    pub posted_slot: u64,
+}
+
+impl PythPriceUpdate {
+    /// Borsh encoding of `VerificationLevel::Full`.
+    pub const VERIFICATION_LEVEL_FULL: u8 = 1;
+}
+
+/// A Switchboard on-demand `CurrentResult`, the value, spread and slots of
+/// a pull feed's latest aggregate. Built with the `switchboard` feature, the
+/// program stores this payload and the oracle's sequence carries the
+/// update's unix timestamp, so a feed can replace a Switchboard one without
+/// changing the fields its consumers read.
+///
+/// Packed to 8 bytes so the `i128` fields keep Switchboard's offsets while
+/// the oracle stays 8-byte aligned.
+#[repr(C, packed(8))]
+#[derive(Clone, Copy)]
+pub struct SwitchboardResult {
+    pub value: i128,
+    pub std_dev: i128,
+    pub mean: i128,
+    pub range: i128,
+    pub min_value: i128,
+    pub max_value: i128,
+    pub num_samples: u8,
+    pub submission_idx: u8,
+    pub padding: [u8; 6],
+    pub slot: u64,
+    pub min_slot: u64,
+    pub max_slot: u64,
+}
+
+impl SwitchboardResult {
+    /// Decimal places of the fixed-point `i128` fields.
+    pub const PRECISION: u32 = 18;
+}
+
+// `pyth` wins if both are enabled, so `--all-features` still builds.
+/// The payload this build of the program stores.
+#[cfg(not(any(feature = "pyth", feature = "switchboard")))]
+pub type Payload = PriceFeed;
+/// The payload this build of the program stores.
+#[cfg(feature = "pyth")]
+pub type Payload = PythPriceUpdate;
+/// The payload this build of the program stores.
+#[cfg(all(feature = "switchboard", not(feature = "pyth")))]
+pub type Payload = SwitchboardResult;
+
 nostd_panic_handler!();
 
 #[no_mangle]
@@ -18,6 +90,11 @@ nostd_panic_handler!();
 /// This is a permissioned entrypoint only invokable by the
 /// ADMIN keypair. It is as safe as you choose it to be.
 pub unsafe extern "C" fn entrypoint(input: *mut u8) {
-    Admin::check(input);
-    Oracle::<PriceFeed>::check_and_update(input);
+    if !Admin::check(input) {
+        return;
+    }
+    #[cfg(not(feature = "anchor"))]
+    Oracle::<Payload>::check_and_update(input);
+    #[cfg(feature = "anchor")]
+    Oracle::<Payload>::check_and_update_anchor(input);
 }
@@ -0,0 +1,69 @@
+//! Measures updates against the built program and asserts they cost what
+//! the SDK budgets for: `UpdateInstruction::compute_units`, modelled from
+//! the SDK's per-check constants, and the `cu_table` generated by the
+//! `compute_units` bench. A program change that moves either fails here
+//! rather than leaving keepers with a stale compute budget.
+
+use doppler::prelude::*;
+use doppler_program::PriceFeed;
+use doppler_sdk::{cu_table, Oracle, UpdateInstruction};
+use doppler_test_utils::update_chain;
+use mollusk_svm::Mollusk;
+use solana_pubkey::Pubkey;
+
+const UPDATE: Oracle<PriceFeed> = Oracle {
+    sequence: 1,
+    payload: PriceFeed { price: 1_100_000 },
+};
+
+/// Compute units consumed by `count` chained `PriceFeed` updates.
+fn measure(mollusk: &Mollusk, count: usize) -> u64 {
+    let (accounts, instructions) = update_chain(
+        mollusk,
+        Pubkey::from(ADMIN),
+        count,
+        PriceFeed { price: 100_000 },
+        UPDATE,
+    );
+    let result = mollusk.process_instruction_chain(&instructions, &accounts);
+    assert!(
+        !result.program_result.is_err(),
+        "batch of {count} updates failed"
+    );
+    result.compute_units_consumed
+}
+
+#[test]
+fn test_update_matches_sdk_model() {
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    let model = UpdateInstruction {
+        admin: Pubkey::from(ADMIN),
+        oracle_pubkey: Pubkey::default(),
+        oracle: UPDATE,
+    }
+    .compute_units();
+
+    assert_eq!(
+        measure(&mollusk, 1),
+        u64::from(model),
+        "SEQUENCE_CHECK_CU, ADMIN_VERIFICATION_CU or PAYLOAD_WRITE_CU no longer match the program"
+    );
+}
+
+/// Covers the `PriceFeed` row, the payload of the default build the tests
+/// run against.
+#[test]
+fn test_batches_match_cu_table() {
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    assert_eq!(cu_table::PAYLOAD_SIZES[0], size_of::<PriceFeed>());
+
+    for (index, expected) in cu_table::UPDATE_CU[0].iter().enumerate() {
+        assert_eq!(
+            measure(&mollusk, index + 1),
+            u64::from(*expected),
+            "cu_table is stale for {} updates, regenerate it with \
+             `cargo bench -p doppler-program --bench compute_units`",
+            index + 1
+        );
+    }
+}
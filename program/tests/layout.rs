@@ -0,0 +1,201 @@
+//! Compares the exact bytes of oracle accounts and update instructions with
+//! the annotated hex fixtures in `tests/fixtures`. A layout change, such as
+//! a shifted offset or reordered field, fails here instead of corrupting
+//! deployed feeds; if it is intended, update the fixture in the same change.
+
+use std::{fs, mem::offset_of};
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize};
+use doppler::prelude::*;
+use doppler_program::{PriceFeed, PythPriceUpdate, SwitchboardResult};
+use doppler_sdk::{Oracle, UpdateInstruction};
+use doppler_test_utils::{create_oracle_instruction, keyed_account_for_admin, oracle_address};
+use mollusk_svm::{program::keyed_account_for_system_program, result::Check, Mollusk};
+use pyth_solana_receiver_sdk::price_update::{PriceUpdateV2, VerificationLevel};
+use solana_account::{Account, ReadableAccount};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+const SEED: &str = "SOL/USDC";
+
+/// Bytes in every field of the update, so a field that moves or flips
+/// endianness cannot go unnoticed.
+const UPDATE: Oracle<PriceFeed> = Oracle {
+    sequence: 0x0102_0304_0506_0708,
+    payload: PriceFeed {
+        price: 0x1112_1314_1516_1718,
+    },
+};
+
+/// Reads a fixture, skipping `#` comments and whitespace between bytes.
+fn golden(name: &str) -> Vec<u8> {
+    let path = format!("tests/fixtures/{name}");
+    let text = fs::read_to_string(&path).unwrap_or_else(|err| panic!("{path}: {err}"));
+    let digits: String = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split_whitespace())
+        .collect();
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x} ")).collect()
+}
+
+fn assert_golden(actual: &[u8], name: &str) {
+    assert_eq!(
+        hex(actual),
+        hex(&golden(name)),
+        "bytes differ from tests/fixtures/{name}"
+    );
+}
+
+/// Creates the oracle, then runs `updates` on it, returning its data.
+fn oracle_data(updates: &[Instruction]) -> Vec<u8> {
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    let admin = Pubkey::from(ADMIN);
+    let oracle = oracle_address(&admin, SEED);
+    let (system, system_account) = keyed_account_for_system_program();
+
+    let create = create_oracle_instruction::<PriceFeed>(&mollusk, &admin, SEED);
+    let checks = [Check::success()];
+    let chain: Vec<(&Instruction, &[Check])> = [&create]
+        .into_iter()
+        .chain(updates)
+        .map(|ix| (ix, &checks[..]))
+        .collect();
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &chain,
+        &[
+            keyed_account_for_admin(admin),
+            (oracle, Account::default()),
+            (system, system_account),
+        ],
+    );
+
+    result
+        .get_account(&oracle)
+        .expect("Missing oracle account")
+        .data()
+        .to_vec()
+}
+
+fn update_instruction() -> Instruction {
+    let admin = Pubkey::from(ADMIN);
+    UpdateInstruction {
+        admin,
+        oracle_pubkey: oracle_address(&admin, SEED),
+        oracle: UPDATE,
+    }
+    .into()
+}
+
+#[test]
+fn test_created_oracle_layout() {
+    assert_golden(&oracle_data(&[]), "oracle_created.hex");
+}
+
+#[test]
+fn test_updated_oracle_layout() {
+    assert_golden(&oracle_data(&[update_instruction()]), "oracle_updated.hex");
+}
+
+#[test]
+fn test_update_instruction_layout() {
+    let ix = update_instruction();
+    let admin = Pubkey::from(ADMIN);
+
+    assert_eq!(ix.program_id, doppler_sdk::ID);
+    assert_eq!(
+        ix.accounts,
+        [
+            AccountMeta::new_readonly(admin, true),
+            AccountMeta::new(oracle_address(&admin, SEED), false),
+        ]
+    );
+    assert_golden(&ix.data, "update_instruction.hex");
+}
+
+/// Offsets of each field in a Pyth receiver `PriceUpdateV2` account, as
+/// its consumers deserialize it.
+#[test]
+fn test_pyth_price_update_layout() {
+    let account = |offset: usize| size_of::<u64>() + offset;
+
+    assert_eq!(account(offset_of!(PythPriceUpdate, write_authority)), 8);
+    assert_eq!(account(offset_of!(PythPriceUpdate, verification_level)), 40);
+    assert_eq!(account(offset_of!(PythPriceUpdate, feed_id)), 41);
+    assert_eq!(account(offset_of!(PythPriceUpdate, price)), 73);
+    assert_eq!(account(offset_of!(PythPriceUpdate, conf)), 81);
+    assert_eq!(account(offset_of!(PythPriceUpdate, exponent)), 89);
+    assert_eq!(account(offset_of!(PythPriceUpdate, publish_time)), 93);
+    assert_eq!(account(offset_of!(PythPriceUpdate, prev_publish_time)), 101);
+    assert_eq!(account(offset_of!(PythPriceUpdate, ema_price)), 109);
+    assert_eq!(account(offset_of!(PythPriceUpdate, ema_conf)), 117);
+    assert_eq!(account(offset_of!(PythPriceUpdate, posted_slot)), 125);
+    assert_eq!(account(size_of::<PythPriceUpdate>()), 133);
+}
+
+/// Pyth's own `PriceUpdateV2` decodes the fields from byte 8, past the
+/// sequence, but rejects the account as a whole: the sequence is not its
+/// discriminator.
+#[test]
+fn test_pyth_price_update_decodes_as_price_update_v2() {
+    let data = Oracle {
+        sequence: 7,
+        payload: PythPriceUpdate {
+            write_authority: [1; 32],
+            verification_level: PythPriceUpdate::VERIFICATION_LEVEL_FULL,
+            feed_id: [2; 32],
+            price: 14_512_345_678,
+            conf: 3_456_789,
+            exponent: -8,
+            publish_time: 1_700_000_000,
+            prev_publish_time: 1_699_999_999,
+            ema_price: 14_500_000_000,
+            ema_conf: 4_000_000,
+            posted_slot: 250_000_000,
+        },
+    }
+    .to_bytes();
+
+    let update = PriceUpdateV2::deserialize(&mut &data[8..]).unwrap();
+    assert_eq!(update.write_authority, Pubkey::new_from_array([1; 32]));
+    assert_eq!(update.verification_level, VerificationLevel::Full);
+    assert_eq!(update.price_message.feed_id, [2; 32]);
+    assert_eq!(update.price_message.price, 14_512_345_678);
+    assert_eq!(update.price_message.conf, 3_456_789);
+    assert_eq!(update.price_message.exponent, -8);
+    assert_eq!(update.price_message.publish_time, 1_700_000_000);
+    assert_eq!(update.price_message.prev_publish_time, 1_699_999_999);
+    assert_eq!(update.price_message.ema_price, 14_500_000_000);
+    assert_eq!(update.price_message.ema_conf, 4_000_000);
+    assert_eq!(update.posted_slot, 250_000_000);
+
+    assert!(PriceUpdateV2::try_deserialize(&mut &data[..]).is_err());
+    assert_ne!(data.len(), PriceUpdateV2::LEN);
+}
+
+/// Offsets of each field in a Switchboard on-demand `CurrentResult`.
+#[test]
+fn test_switchboard_result_layout() {
+    assert_eq!(offset_of!(SwitchboardResult, value), 0);
+    assert_eq!(offset_of!(SwitchboardResult, std_dev), 16);
+    assert_eq!(offset_of!(SwitchboardResult, mean), 32);
+    assert_eq!(offset_of!(SwitchboardResult, range), 48);
+    assert_eq!(offset_of!(SwitchboardResult, min_value), 64);
+    assert_eq!(offset_of!(SwitchboardResult, max_value), 80);
+    assert_eq!(offset_of!(SwitchboardResult, num_samples), 96);
+    assert_eq!(offset_of!(SwitchboardResult, submission_idx), 97);
+    assert_eq!(offset_of!(SwitchboardResult, slot), 104);
+    assert_eq!(offset_of!(SwitchboardResult, min_slot), 112);
+    assert_eq!(offset_of!(SwitchboardResult, max_slot), 120);
+    assert_eq!(size_of::<SwitchboardResult>(), 128);
+    assert_eq!(Oracle::<SwitchboardResult>::LEN, 136);
+}
@@ -0,0 +1,233 @@
+//! End-to-end flows under LiteSVM, which runs whole signed transactions
+//! through the runtime: signature checks, rent, account ownership and
+//! atomic batches that the mollusk tests do not reach.
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{transaction::Builder, Oracle};
+use doppler_test_utils::{
+    checks::Failure,
+    litesvm::{assert_failure, create_oracle, read_oracle, send},
+    oracle_address, ADMIN_LAMPORTS,
+};
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
+use solana_system_interface::instruction::{create_account_with_seed, transfer_with_seed};
+use solana_transaction_error::TransactionError;
+
+/// Loads the deployed program and funds the admin the program is built
+/// with.
+fn setup() -> (LiteSVM, Keypair) {
+    let mut svm = LiteSVM::new();
+    svm.add_program_from_file(doppler_sdk::ID, "../target/deploy/doppler_program.so")
+        .unwrap();
+    let admin = Keypair::read_from_file("../examples/keys/admin-keypair.json").unwrap();
+    svm.airdrop(&admin.pubkey(), ADMIN_LAMPORTS).unwrap();
+
+    (svm, admin)
+}
+
+fn update(svm: &mut LiteSVM, builder: Builder) -> Result<(), TransactionError> {
+    let tx = builder.build(svm.latest_blockhash()).unwrap();
+    svm.send_transaction(tx)
+        .map(|_| ())
+        .map_err(|failed| failed.err)
+}
+
+fn price_update(sequence: u64, price: u64) -> Oracle<PriceFeed> {
+    Oracle {
+        sequence,
+        payload: PriceFeed { price },
+    }
+}
+
+fn read(svm: &LiteSVM, oracle: &Pubkey) -> (u64, u64) {
+    let oracle = read_oracle::<PriceFeed>(svm, oracle);
+    (oracle.sequence, oracle.payload.price)
+}
+
+#[test]
+fn test_create_is_rent_exempt_and_zeroed() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+
+    let account = svm.get_account(&oracle).unwrap();
+    assert_eq!(account.owner, doppler_sdk::ID);
+    assert_eq!(
+        account.lamports,
+        svm.minimum_balance_for_rent_exemption(Oracle::<PriceFeed>::LEN)
+    );
+    assert_eq!(account.data, vec![0; Oracle::<PriceFeed>::LEN]);
+}
+
+#[test]
+fn test_create_below_rent_exemption_fails() {
+    let (mut svm, admin) = setup();
+    let oracle = oracle_address(&admin.pubkey(), "SOL/USDC");
+    let space = Oracle::<PriceFeed>::LEN;
+    let lamports = svm.minimum_balance_for_rent_exemption(space) - 1;
+
+    let result = send(
+        &mut svm,
+        &admin,
+        &[create_account_with_seed(
+            &admin.pubkey(),
+            &oracle,
+            &admin.pubkey(),
+            "SOL/USDC",
+            lamports,
+            space as u64,
+            &doppler_sdk::ID,
+        )],
+    );
+
+    assert!(
+        matches!(
+            result,
+            Err(TransactionError::InsufficientFundsForRent { .. })
+        ),
+        "expected a rent error, got {result:?}"
+    );
+    assert!(svm
+        .get_account(&oracle)
+        .is_none_or(|account| account.lamports == 0));
+}
+
+#[test]
+fn test_oracle_update() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+
+    update(
+        &mut svm,
+        Builder::new(&admin).add_oracle_update(oracle, price_update(1, 1_100_000)),
+    )
+    .unwrap();
+
+    assert_eq!(read(&svm, &oracle), (1, 1_100_000));
+}
+
+#[test]
+fn test_stale_sequence_is_rejected() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+    update(
+        &mut svm,
+        Builder::new(&admin).add_oracle_update(oracle, price_update(5, 1_100_000)),
+    )
+    .unwrap();
+
+    for sequence in [5, 4] {
+        let result = update(
+            &mut svm,
+            Builder::new(&admin).add_oracle_update(oracle, price_update(sequence, 1)),
+        );
+        assert_failure(result, Failure::StaleSequence);
+    }
+    assert_eq!(read(&svm, &oracle), (5, 1_100_000));
+}
+
+#[test]
+fn test_other_signer_is_rejected() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+    let intruder = Keypair::new();
+
+    let result = update(
+        &mut svm,
+        Builder::new(&intruder)
+            .with_fee_payer_keypair(&admin)
+            .add_oracle_update(oracle, price_update(1, 1)),
+    );
+
+    assert_failure(result, Failure::WrongAdmin);
+    assert_eq!(read(&svm, &oracle), (0, 0));
+}
+
+#[test]
+fn test_batch_update() {
+    let (mut svm, admin) = setup();
+    let oracles: Vec<Pubkey> = (0..8)
+        .map(|index| create_oracle::<PriceFeed>(&mut svm, &admin, &format!("feed-{index}"), 0))
+        .collect();
+
+    let builder = oracles
+        .iter()
+        .zip(1..)
+        .fold(Builder::new(&admin), |builder, (oracle, price)| {
+            builder.add_oracle_update(*oracle, price_update(1, price))
+        });
+    update(&mut svm, builder).unwrap();
+
+    for (oracle, price) in oracles.iter().zip(1..) {
+        assert_eq!(read(&svm, oracle), (1, price));
+    }
+}
+
+#[test]
+fn test_batch_with_a_stale_update_is_atomic() {
+    let (mut svm, admin) = setup();
+    let fresh = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+    let stale = create_oracle::<PriceFeed>(&mut svm, &admin, "BTC/USDC", 0);
+    update(
+        &mut svm,
+        Builder::new(&admin).add_oracle_update(stale, price_update(3, 100)),
+    )
+    .unwrap();
+
+    let result = update(
+        &mut svm,
+        Builder::new(&admin)
+            .add_oracle_update(fresh, price_update(1, 200))
+            .add_oracle_update(stale, price_update(2, 300)),
+    );
+
+    assert_failure(result, Failure::StaleSequence);
+    assert_eq!(read(&svm, &fresh), (0, 0));
+    assert_eq!(read(&svm, &stale), (3, 100));
+}
+
+#[test]
+fn test_update_leaves_extra_space_untouched() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 32);
+
+    update(
+        &mut svm,
+        Builder::new(&admin).add_oracle_update(oracle, price_update(1, 1_100_000)),
+    )
+    .unwrap();
+
+    let data = svm.get_account(&oracle).unwrap().data;
+    assert_eq!(data.len(), Oracle::<PriceFeed>::LEN + 32);
+    assert_eq!(read(&svm, &oracle), (1, 1_100_000));
+    assert!(data[Oracle::<PriceFeed>::LEN..]
+        .iter()
+        .all(|byte| *byte == 0));
+}
+
+/// The program has no close instruction and owns its oracles, so not even
+/// the admin can withdraw an oracle's rent through the system program.
+#[test]
+fn test_oracle_cannot_be_drained() {
+    let (mut svm, admin) = setup();
+    let oracle = create_oracle::<PriceFeed>(&mut svm, &admin, "SOL/USDC", 0);
+    let lamports = svm.get_account(&oracle).unwrap().lamports;
+
+    let result = send(
+        &mut svm,
+        &admin,
+        &[transfer_with_seed(
+            &oracle,
+            &admin.pubkey(),
+            "SOL/USDC".to_string(),
+            &doppler_sdk::ID,
+            &admin.pubkey(),
+            lamports,
+        )],
+    );
+
+    assert!(result.is_err(), "drained the oracle");
+    assert_eq!(svm.get_account(&oracle).unwrap().lamports, lamports);
+}
@@ -0,0 +1,147 @@
+//! Every way an update is rejected, each asserting its exact error and that
+//! the oracle is left as it was.
+
+use doppler::prelude::*;
+use doppler_program::PriceFeed;
+use doppler_sdk::{Oracle, UpdateInstruction};
+use doppler_test_utils::{
+    checks::Failure, keyed_account_for_admin, keyed_account_for_oracle,
+    keyed_account_for_oracle_state,
+};
+use mollusk_svm::{result::Check, Mollusk};
+use solana_account::Account;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+fn mollusk() -> Mollusk {
+    Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program")
+}
+
+fn update(admin: Pubkey, oracle: Pubkey, sequence: u64) -> Instruction {
+    UpdateInstruction {
+        admin,
+        oracle_pubkey: oracle,
+        oracle: Oracle {
+            sequence,
+            payload: PriceFeed { price: 1_100_000 },
+        },
+    }
+    .into()
+}
+
+/// Runs `instruction` and asserts it fails with `failure` without touching
+/// the oracle, which is its second account.
+fn assert_rejected(
+    mollusk: &Mollusk,
+    instruction: &Instruction,
+    accounts: &[(Pubkey, Account)],
+    failure: Failure,
+) {
+    let (oracle, oracle_account) = &accounts[1];
+    mollusk.process_and_validate_instruction(
+        instruction,
+        accounts,
+        &[
+            failure.check(),
+            Check::account(oracle).data(&oracle_account.data).build(),
+        ],
+    );
+}
+
+#[test]
+fn test_wrong_admin_is_rejected() {
+    let mollusk = mollusk();
+    let intruder = Pubkey::new_unique();
+    let (oracle, oracle_account) = keyed_account_for_oracle(
+        &mollusk,
+        ADMIN.into(),
+        "SOL/USDC",
+        PriceFeed { price: 100_000 },
+    );
+
+    assert_rejected(
+        &mollusk,
+        &update(intruder, oracle, 1),
+        &[keyed_account_for_admin(intruder), (oracle, oracle_account)],
+        Failure::WrongAdmin,
+    );
+}
+
+#[test]
+fn test_missing_signature_is_rejected() {
+    let mollusk = mollusk();
+    let (admin, admin_account) = keyed_account_for_admin(ADMIN.into());
+    let (oracle, oracle_account) =
+        keyed_account_for_oracle(&mollusk, admin, "SOL/USDC", PriceFeed { price: 100_000 });
+
+    let mut instruction = update(admin, oracle, 1);
+    instruction.accounts[0].is_signer = false;
+
+    assert_rejected(
+        &mollusk,
+        &instruction,
+        &[(admin, admin_account), (oracle, oracle_account)],
+        Failure::MissingSignature,
+    );
+}
+
+#[test]
+fn test_stale_sequence_is_rejected() {
+    let mollusk = mollusk();
+    let (admin, admin_account) = keyed_account_for_admin(ADMIN.into());
+    let (oracle, oracle_account) = keyed_account_for_oracle_state(
+        &mollusk,
+        admin,
+        "SOL/USDC",
+        Oracle {
+            sequence: 5,
+            payload: PriceFeed { price: 100_000 },
+        },
+    );
+
+    for sequence in [5, 4, 0] {
+        assert_rejected(
+            &mollusk,
+            &update(admin, oracle, sequence),
+            &[
+                (admin, admin_account.clone()),
+                (oracle, oracle_account.clone()),
+            ],
+            Failure::StaleSequence,
+        );
+    }
+}
+
+#[test]
+fn test_wrong_owner_is_rejected() {
+    let mollusk = mollusk();
+    let (admin, admin_account) = keyed_account_for_admin(ADMIN.into());
+    let (oracle, mut oracle_account) =
+        keyed_account_for_oracle(&mollusk, admin, "SOL/USDC", PriceFeed { price: 100_000 });
+    oracle_account.owner = solana_sdk_ids::system_program::ID;
+
+    assert_rejected(
+        &mollusk,
+        &update(admin, oracle, 1),
+        &[(admin, admin_account), (oracle, oracle_account)],
+        Failure::WrongOwner,
+    );
+}
+
+#[test]
+fn test_readonly_oracle_is_rejected() {
+    let mollusk = mollusk();
+    let (admin, admin_account) = keyed_account_for_admin(ADMIN.into());
+    let (oracle, oracle_account) =
+        keyed_account_for_oracle(&mollusk, admin, "SOL/USDC", PriceFeed { price: 100_000 });
+
+    let mut instruction = update(admin, oracle, 1);
+    instruction.accounts[1].is_writable = false;
+
+    assert_rejected(
+        &mollusk,
+        &instruction,
+        &[(admin, admin_account), (oracle, oracle_account)],
+        Failure::ReadonlyOracle,
+    );
+}
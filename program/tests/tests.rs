@@ -1,59 +1,20 @@
 use doppler::prelude::*;
 use doppler_program::PriceFeed;
 use doppler_sdk::{Oracle, UpdateInstruction};
+use doppler_test_utils::{keyed_account_for_admin, keyed_account_for_oracle};
 use mollusk_svm::result::Check;
 use mollusk_svm::{program::keyed_account_for_system_program, Mollusk};
 use solana_account::{Account, ReadableAccount};
-use solana_clock::Epoch;
 use solana_instruction::Instruction;
-use solana_pubkey::Pubkey;
-
-#[must_use] pub fn keyed_account_for_admin(key: Pubkey) -> (Pubkey, Account) {
-    (
-        key,
-        Account::new(10_000_000_000, 0, &solana_sdk_ids::system_program::ID),
-    )
-}
-
-pub fn keyed_account_for_oracle<T: Sized + Copy>(
-    mollusk: &mut Mollusk,
-    admin: Pubkey,
-    seed: &str,
-    payload: T,
-) -> (Pubkey, Account) {
-    let oracle_account = Oracle {
-        sequence: 0,
-        payload,
-    };
-
-    let key = Pubkey::create_with_seed(&admin, seed, &doppler_sdk::ID).unwrap();
-
-    let lamports = mollusk
-        .sysvars
-        .rent
-        .minimum_balance(core::mem::size_of::<Oracle<T>>());
-
-    let data = oracle_account.to_bytes();
-
-    let account = Account {
-        lamports,
-        data,
-        owner: doppler_sdk::ID,
-        executable: false,
-        rent_epoch: Epoch::default(),
-    };
-
-    (key, account)
-}
 
 #[test]
 fn test_oracle_update() {
     // Create Mollusk instance
-    let mut mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
+    let mollusk = Mollusk::new(&doppler_sdk::ID, "../target/deploy/doppler_program");
     // Accounts
     let (admin, admin_account) = keyed_account_for_admin(ADMIN.into());
     let (oracle, oracle_account) = keyed_account_for_oracle::<PriceFeed>(
-        &mut mollusk,
+        &mollusk,
         ADMIN.into(),
         "SOL/USDC",
         PriceFeed { price: 100_000 },
@@ -91,7 +52,7 @@ fn test_oracle_update() {
             (&create_price_feed_instruction, &[Check::success()]),
             (&price_feed_update_instruction, &[Check::success()]),
         ],
-        &vec![
+        &[
             (admin, admin_account),
             (oracle, Account::default()),
             (system, system_account),
@@ -0,0 +1,194 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use axum::{
+    extract::{Path, RawQuery},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use serde_json::{json, Value};
+
+use crate::hub::Hub;
+
+/// Values returned by `/history` when no `limit` is given.
+const DEFAULT_LIMIT: usize = 100;
+
+/// Serves the latest value and recent history of each feed as JSON:
+///
+/// - `GET /feeds` lists every feed with its latest value, if any.
+/// - `GET /feeds/{name}` returns the feed's latest value and its age.
+/// - `GET /feeds/{name}/history?limit=N` returns its last `N` values,
+///   oldest first.
+///
+/// Feed names may be given as is, `/feeds/SOL/USDC`, or percent-encoded.
+pub async fn serve(addr: SocketAddr, hub: Arc<Hub>) -> std::io::Result<()> {
+    let list = hub.clone();
+    let app = Router::new()
+        .route(
+            "/feeds",
+            get(move || async move { respond(StatusCode::OK, feeds(&list)) }),
+        )
+        .route(
+            "/feeds/*path",
+            get(
+                move |Path(path): Path<String>, RawQuery(query): RawQuery| async move {
+                    let (status, body) = feed(&hub, &path, query.as_deref());
+                    respond(status, body)
+                },
+            ),
+        );
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+
+    axum::serve(listener, app).await
+}
+
+fn respond(status: StatusCode, body: Value) -> impl IntoResponse {
+    (
+        status,
+        [(header::CONTENT_TYPE, "application/json")],
+        body.to_string(),
+    )
+}
+
+fn feeds(hub: &Hub) -> Value {
+    hub.feeds()
+        .map(|(name, pubkey)| {
+            let latest = hub.snapshot(name);
+            json!({
+                "feed": name,
+                "pubkey": pubkey.to_string(),
+                "sequence": latest.map(|(point, _)| point.sequence),
+                "price": latest.map(|(point, _)| point.price),
+                "age_ms": latest.map(|(_, age)| age.as_millis() as u64),
+            })
+        })
+        .collect()
+}
+
+/// Answers `/feeds/{path}`, where the path is either a feed name or a feed
+/// name followed by `/history`.
+fn feed(hub: &Hub, path: &str, query: Option<&str>) -> (StatusCode, Value) {
+    if let Some(pubkey) = hub.pubkey(path) {
+        let Some((point, age)) = hub.snapshot(path) else {
+            return error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("no update to {path} received yet"),
+            );
+        };
+        return (
+            StatusCode::OK,
+            json!({
+                "feed": path,
+                "pubkey": pubkey.to_string(),
+                "slot": point.slot,
+                "sequence": point.sequence,
+                "price": point.price,
+                "received_ms": point.received_ms,
+                "age_ms": age.as_millis() as u64,
+            }),
+        );
+    }
+
+    let Some(name) = path
+        .strip_suffix("/history")
+        .filter(|name| hub.pubkey(name).is_some())
+    else {
+        return error(StatusCode::NOT_FOUND, format!("unknown feed {path}"));
+    };
+    let limit = match limit(query) {
+        Ok(limit) => limit,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err),
+    };
+
+    (
+        StatusCode::OK,
+        json!({
+            "feed": name,
+            "history": hub.history(name, limit),
+        }),
+    )
+}
+
+fn limit(query: Option<&str>) -> Result<usize, String> {
+    let Some(limit) = query
+        .unwrap_or_default()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("limit="))
+    else {
+        return Ok(DEFAULT_LIMIT);
+    };
+
+    limit
+        .parse()
+        .map_err(|_| format!("limit must be a non-negative integer, got {limit:?}"))
+}
+
+fn error(status: StatusCode, message: String) -> (StatusCode, Value) {
+    (status, json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_program::PriceFeed;
+    use doppler_sdk::{geyser::OracleUpdate, registry::FeedRegistry, Oracle};
+    use solana_pubkey::Pubkey;
+
+    use super::*;
+
+    #[test]
+    fn test_feed_routes() {
+        let mut registry = FeedRegistry::new(Pubkey::new_unique());
+        let oracle = registry.register("SOL/USDC", "PriceFeed").unwrap();
+        registry.register("BTC/USD", "PriceFeed").unwrap();
+        let hub = Hub::new(&registry, 16, 10);
+
+        assert_eq!(
+            feed(&hub, "SOL/USDC", None).0,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        for sequence in 1..=3 {
+            hub.publish(OracleUpdate {
+                pubkey: oracle,
+                slot: 40 + sequence,
+                oracle: Oracle {
+                    sequence,
+                    payload: PriceFeed {
+                        price: 100 + sequence,
+                    },
+                },
+            });
+        }
+
+        let (status, latest) = feed(&hub, "SOL/USDC", None);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(latest["pubkey"], oracle.to_string());
+        assert_eq!(latest["slot"], 43);
+        assert_eq!(latest["sequence"], 3);
+        assert_eq!(latest["price"], 103);
+
+        let (status, history) = feed(&hub, "SOL/USDC/history", Some("limit=2"));
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(history["history"][0]["sequence"], 2);
+        assert_eq!(history["history"][1]["sequence"], 3);
+        assert_eq!(
+            feed(&hub, "SOL/USDC/history", None).1["history"]
+                .as_array()
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(
+            feed(&hub, "SOL/USDC/history", Some("limit=-1")).0,
+            StatusCode::BAD_REQUEST
+        );
+
+        assert_eq!(feed(&hub, "ETH/USD", None).0, StatusCode::NOT_FOUND);
+        assert_eq!(feed(&hub, "ETH/USD/history", None).0, StatusCode::NOT_FOUND);
+
+        let list = feeds(&hub);
+        assert_eq!(list[0]["feed"], "BTC/USD");
+        assert_eq!(list[0]["price"], Value::Null);
+        assert_eq!(list[1]["price"], 103);
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::HashSet, net::SocketAddr, sync::Arc};
+
+use futures::{stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{codegen::BoxStream, transport::Server, Request, Response, Status};
+
+use crate::{
+    hub::{Hub, Point},
+    pb::doppler::relay::v1::{
+        oracles_server::{Oracles, OraclesServer},
+        Feed, FeedValue, GetFeedRequest, ListFeedsRequest, ListFeedsResponse, WatchFeedsRequest,
+    },
+};
+
+/// Serves the `doppler.relay.v1.Oracles` service of
+/// `relay/proto/doppler/relay/v1/relay.proto` from the hub's cached values.
+pub async fn serve(addr: SocketAddr, hub: Arc<Hub>) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_service(OraclesServer::new(OracleService { hub }))
+        .serve(addr)
+        .await
+}
+
+struct OracleService {
+    hub: Arc<Hub>,
+}
+
+#[tonic::async_trait]
+impl Oracles for OracleService {
+    async fn list_feeds(
+        &self,
+        _request: Request<ListFeedsRequest>,
+    ) -> Result<Response<ListFeedsResponse>, Status> {
+        let feeds = self
+            .hub
+            .feeds()
+            .map(|(name, pubkey)| Feed {
+                name: name.to_string(),
+                pubkey: pubkey.to_string(),
+            })
+            .collect();
+
+        Ok(Response::new(ListFeedsResponse { feeds }))
+    }
+
+    async fn get_feed(
+        &self,
+        request: Request<GetFeedRequest>,
+    ) -> Result<Response<FeedValue>, Status> {
+        let name = request.into_inner().name;
+        if self.hub.pubkey(&name).is_none() {
+            return Err(Status::not_found(format!("unknown feed {name}")));
+        }
+
+        let (point, _) = self
+            .hub
+            .snapshot(&name)
+            .ok_or_else(|| Status::not_found(format!("{name} has no value yet")))?;
+        Ok(Response::new(value(&self.hub, &name, point)))
+    }
+
+    type WatchFeedsStream = BoxStream<FeedValue>;
+
+    /// Sends the latest value of each watched feed, then its updates. An
+    /// update landing while the watch starts may be sent twice, once in each.
+    async fn watch_feeds(
+        &self,
+        request: Request<WatchFeedsRequest>,
+    ) -> Result<Response<Self::WatchFeedsStream>, Status> {
+        let names = request.into_inner().names;
+        if let Some(name) = names.iter().find(|name| self.hub.pubkey(name).is_none()) {
+            return Err(Status::not_found(format!("unknown feed {name}")));
+        }
+        let feeds: HashSet<String> = if names.is_empty() {
+            self.hub.feeds().map(|(name, _)| name.to_string()).collect()
+        } else {
+            names.into_iter().collect()
+        };
+
+        // Subscribe before reading the latest values so no update falls
+        // between the two.
+        let receiver = self.hub.subscribe();
+        let latest: Vec<_> = feeds
+            .iter()
+            .filter_map(|feed| {
+                let (point, _) = self.hub.snapshot(feed)?;
+                Some(Ok(value(&self.hub, feed, point)))
+            })
+            .collect();
+
+        let updates = stream::unfold(
+            (receiver, feeds, self.hub.clone()),
+            |(mut receiver, feeds, hub)| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(update) if feeds.contains(&*update.feed) => {
+                            let value = value(&hub, &update.feed, update.point);
+                            return Some((Ok(value), (receiver, feeds, hub)));
+                        }
+                        Ok(_) | Err(RecvError::Lagged(_)) => {}
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        );
+
+        Ok(Response::new(Box::pin(stream::iter(latest).chain(updates))))
+    }
+}
+
+fn value(hub: &Hub, feed: &str, point: Point) -> FeedValue {
+    FeedValue {
+        feed: feed.to_string(),
+        pubkey: hub
+            .pubkey(feed)
+            .map(|pubkey| pubkey.to_string())
+            .unwrap_or_default(),
+        slot: point.slot,
+        sequence: point.sequence,
+        price: point.price,
+        received_ms: point.received_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_program::PriceFeed;
+    use doppler_sdk::{geyser::OracleUpdate, registry::FeedRegistry, Oracle};
+    use solana_pubkey::Pubkey;
+    use tonic::Code;
+
+    use super::*;
+
+    fn service() -> (OracleService, Pubkey) {
+        let mut registry = FeedRegistry::new(Pubkey::new_unique());
+        let oracle = registry.register("SOL/USDC", "PriceFeed").unwrap();
+        registry.register("BTC/USD", "PriceFeed").unwrap();
+        let hub = Arc::new(Hub::new(&registry, 16, 2));
+        (OracleService { hub }, oracle)
+    }
+
+    fn publish(hub: &Hub, pubkey: Pubkey, sequence: u64, price: u64) {
+        hub.publish(OracleUpdate {
+            pubkey,
+            slot: 7,
+            oracle: Oracle {
+                sequence,
+                payload: PriceFeed { price },
+            },
+        });
+    }
+
+    fn get(name: &str) -> Request<GetFeedRequest> {
+        Request::new(GetFeedRequest {
+            name: name.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_and_get_feeds() {
+        let (service, oracle) = service();
+
+        let feeds = service
+            .list_feeds(Request::new(ListFeedsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .feeds;
+        assert_eq!(
+            feeds.iter().map(|feed| &*feed.name).collect::<Vec<_>>(),
+            ["BTC/USD", "SOL/USDC"]
+        );
+        assert_eq!(feeds[1].pubkey, oracle.to_string());
+
+        let missing = service.get_feed(get("SOL/USDC")).await.unwrap_err();
+        assert_eq!(missing.code(), Code::NotFound);
+        let unknown = service.get_feed(get("ETH/USD")).await.unwrap_err();
+        assert_eq!(unknown.message(), "unknown feed ETH/USD");
+
+        publish(&service.hub, oracle, 1, 100);
+        let value = service
+            .get_feed(get("SOL/USDC"))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(value.pubkey, oracle.to_string());
+        assert_eq!((value.slot, value.sequence, value.price), (7, 1, 100));
+    }
+
+    #[tokio::test]
+    async fn test_watch_sends_latest_then_updates() {
+        let (service, oracle) = service();
+        let other = service.hub.pubkey("BTC/USD").unwrap();
+        publish(&service.hub, oracle, 1, 100);
+
+        let watch = |names: &[&str]| {
+            Request::new(WatchFeedsRequest {
+                names: names.iter().map(|name| name.to_string()).collect(),
+            })
+        };
+        assert_eq!(
+            service
+                .watch_feeds(watch(&["ETH/USD"]))
+                .await
+                .err()
+                .map(|status| status.code()),
+            Some(Code::NotFound)
+        );
+
+        let mut values = service
+            .watch_feeds(watch(&["SOL/USDC"]))
+            .await
+            .unwrap()
+            .into_inner();
+        publish(&service.hub, other, 2, 200);
+        publish(&service.hub, oracle, 3, 101);
+
+        let latest = values.next().await.unwrap().unwrap();
+        assert_eq!((latest.feed.as_str(), latest.sequence), ("SOL/USDC", 1));
+        let update = values.next().await.unwrap().unwrap();
+        assert_eq!((update.sequence, update.price), (3, 101));
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use doppler_program::PriceFeed;
+use doppler_sdk::{geyser::OracleUpdate, registry::FeedRegistry};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_pubkey::Pubkey;
+use tokio::sync::broadcast;
+
+/// A decoded oracle write, serialized once for every client that receives it.
+#[derive(Clone, Debug)]
+pub struct Update {
+    pub feed: Arc<str>,
+    pub message: Arc<str>,
+    pub point: Point,
+}
+
+/// A value a feed held, as kept in its history.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Point {
+    pub slot: u64,
+    pub sequence: u64,
+    pub price: u64,
+    /// When the relay received the update, in Unix milliseconds.
+    pub received_ms: u64,
+}
+
+struct FeedState {
+    latest: Update,
+    received: Instant,
+    history: VecDeque<Point>,
+}
+
+/// Fans the updates of the single upstream subscription out to clients, and
+/// keeps each feed's latest update and recent history for clients that
+/// subscribe or ask later.
+pub struct Hub {
+    oracles: HashMap<Pubkey, Arc<str>>,
+    feeds: BTreeMap<Arc<str>, Pubkey>,
+    history: usize,
+    state: Mutex<HashMap<Arc<str>, FeedState>>,
+    sender: broadcast::Sender<Update>,
+}
+
+impl Hub {
+    /// Creates a hub for every feed of `registry`, buffering up to
+    /// `capacity` updates for clients that fall behind and keeping the last
+    /// `history` values of each feed, at least the latest.
+    pub fn new(registry: &FeedRegistry, capacity: usize, history: usize) -> Self {
+        let feeds: BTreeMap<Arc<str>, Pubkey> = registry
+            .iter()
+            .map(|(name, feed)| (Arc::from(name), feed.pubkey))
+            .collect();
+        let oracles = feeds
+            .iter()
+            .map(|(name, pubkey)| (*pubkey, name.clone()))
+            .collect();
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            oracles,
+            feeds,
+            history,
+            state: Mutex::default(),
+            sender,
+        }
+    }
+
+    pub fn oracles(&self) -> Vec<Pubkey> {
+        self.oracles.keys().copied().collect()
+    }
+
+    /// Feed names and their oracles, by name.
+    pub fn feeds(&self) -> impl Iterator<Item = (&str, Pubkey)> {
+        self.feeds.iter().map(|(name, pubkey)| (&**name, *pubkey))
+    }
+
+    pub fn pubkey(&self, feed: &str) -> Option<Pubkey> {
+        self.feeds.get(feed).copied()
+    }
+
+    /// Caches `update` as its feed's latest and sends it to every client.
+    pub fn publish(&self, update: OracleUpdate<PriceFeed>) {
+        let Some(feed) = self.oracles.get(&update.pubkey) else {
+            return;
+        };
+        let message = json!({
+            "feed": &**feed,
+            "pubkey": update.pubkey.to_string(),
+            "slot": update.slot,
+            "sequence": update.oracle.sequence,
+            "price": update.oracle.payload.price,
+        })
+        .to_string();
+        let point = Point {
+            slot: update.slot,
+            sequence: update.oracle.sequence,
+            price: update.oracle.payload.price,
+            received_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |now| now.as_millis() as u64),
+        };
+        let update = Update {
+            feed: feed.clone(),
+            message: message.into(),
+            point,
+        };
+
+        {
+            let mut state = self.lock();
+            let mut history = state
+                .remove(feed)
+                .map(|state| state.history)
+                .unwrap_or_default();
+            history.push_back(point);
+            while history.len() > self.history.max(1) {
+                history.pop_front();
+            }
+            state.insert(
+                feed.clone(),
+                FeedState {
+                    latest: update.clone(),
+                    received: Instant::now(),
+                    history,
+                },
+            );
+        }
+        // Sending only fails while no client is connected.
+        let _ = self.sender.send(update);
+    }
+
+    pub fn latest(&self, feed: &str) -> Option<Update> {
+        self.lock().get(feed).map(|state| state.latest.clone())
+    }
+
+    /// The feed's latest value and how long ago it was received.
+    pub fn snapshot(&self, feed: &str) -> Option<(Point, Duration)> {
+        let state = self.lock();
+        let state = state.get(feed)?;
+        Some((*state.history.back()?, state.received.elapsed()))
+    }
+
+    /// Up to the `limit` most recent values of the feed, oldest first.
+    pub fn history(&self, feed: &str, limit: usize) -> Vec<Point> {
+        self.lock().get(feed).map_or_else(Vec::new, |state| {
+            let skip = state.history.len().saturating_sub(limit);
+            state.history.iter().skip(skip).copied().collect()
+        })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Update> {
+        self.sender.subscribe()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Arc<str>, FeedState>> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// A message from a client, e.g.
+/// `{"op": "subscribe", "feeds": ["SOL/USDC"]}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Request {
+    Subscribe { feeds: Vec<String> },
+    Unsubscribe { feeds: Vec<String> },
+}
+
+/// The feeds a client is subscribed to.
+#[derive(Default)]
+pub struct Subscriptions {
+    feeds: HashSet<String>,
+}
+
+impl Subscriptions {
+    pub fn contains(&self, feed: &str) -> bool {
+        self.feeds.contains(feed)
+    }
+
+    /// Applies a client message, returning the feeds it newly subscribed to.
+    /// A message naming a feed the hub doesn't relay changes nothing.
+    pub fn apply(&mut self, hub: &Hub, message: &str) -> Result<Vec<String>, String> {
+        let request: Request =
+            serde_json::from_str(message).map_err(|err| format!("invalid request: {err}"))?;
+
+        match request {
+            Request::Subscribe { feeds } => {
+                if let Some(feed) = feeds.iter().find(|feed| hub.pubkey(feed).is_none()) {
+                    return Err(format!("unknown feed {feed}"));
+                }
+                Ok(feeds
+                    .into_iter()
+                    .filter(|feed| self.feeds.insert(feed.clone()))
+                    .collect())
+            }
+            Request::Unsubscribe { feeds } => {
+                for feed in &feeds {
+                    self.feeds.remove(feed);
+                }
+                Ok(vec![])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_sdk::Oracle;
+
+    use super::*;
+
+    fn hub() -> (Hub, Pubkey) {
+        let mut registry = FeedRegistry::new(Pubkey::new_unique());
+        let oracle = registry.register("SOL/USDC", "PriceFeed").unwrap();
+        registry.register("BTC/USD", "PriceFeed").unwrap();
+        (Hub::new(&registry, 16, 2), oracle)
+    }
+
+    fn update(pubkey: Pubkey, sequence: u64, price: u64) -> OracleUpdate<PriceFeed> {
+        OracleUpdate {
+            pubkey,
+            slot: 7,
+            oracle: Oracle {
+                sequence,
+                payload: PriceFeed { price },
+            },
+        }
+    }
+
+    #[test]
+    fn test_publish_caches_and_broadcasts() {
+        let (hub, oracle) = hub();
+        let mut receiver = hub.subscribe();
+
+        hub.publish(update(oracle, 1, 100));
+        hub.publish(update(oracle, 2, 101));
+        hub.publish(update(Pubkey::new_unique(), 3, 102));
+
+        let first = receiver.try_recv().unwrap();
+        assert_eq!(&*first.feed, "SOL/USDC");
+        let message: serde_json::Value = serde_json::from_str(&first.message).unwrap();
+        assert_eq!(message["pubkey"], oracle.to_string());
+        assert_eq!(message["slot"], 7);
+        assert_eq!(message["sequence"], 1);
+        assert_eq!(message["price"], 100);
+        assert!(receiver.try_recv().unwrap().message.contains("101"));
+        assert!(receiver.try_recv().is_err());
+
+        assert!(hub.latest("SOL/USDC").unwrap().message.contains("101"));
+        assert!(hub.latest("BTC/USD").is_none());
+
+        hub.publish(update(oracle, 3, 102));
+        let (latest, _) = hub.snapshot("SOL/USDC").unwrap();
+        assert_eq!((latest.sequence, latest.price), (3, 102));
+        let history = hub.history("SOL/USDC", 10);
+        assert_eq!(
+            history
+                .iter()
+                .map(|point| point.sequence)
+                .collect::<Vec<_>>(),
+            [2, 3]
+        );
+        assert_eq!(hub.history("SOL/USDC", 1), [latest]);
+        assert!(hub.snapshot("BTC/USD").is_none());
+    }
+
+    #[test]
+    fn test_subscriptions() {
+        let (hub, _) = hub();
+        let mut subscriptions = Subscriptions::default();
+
+        assert_eq!(
+            subscriptions
+                .apply(&hub, r#"{"op":"subscribe","feeds":["SOL/USDC"]}"#)
+                .unwrap(),
+            ["SOL/USDC"]
+        );
+        assert_eq!(
+            subscriptions
+                .apply(&hub, r#"{"op":"subscribe","feeds":["SOL/USDC","BTC/USD"]}"#)
+                .unwrap(),
+            ["BTC/USD"]
+        );
+        assert!(subscriptions.contains("SOL/USDC"));
+
+        assert_eq!(
+            subscriptions.apply(&hub, r#"{"op":"subscribe","feeds":["ETH/USD","SOL/USDC"]}"#),
+            Err("unknown feed ETH/USD".to_string())
+        );
+        assert!(subscriptions
+            .apply(&hub, r#"{"op":"publish","feeds":[]}"#)
+            .is_err());
+
+        subscriptions
+            .apply(&hub, r#"{"op":"unsubscribe","feeds":["SOL/USDC"]}"#)
+            .unwrap();
+        assert!(!subscriptions.contains("SOL/USDC"));
+        assert!(subscriptions.contains("BTC/USD"));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{error::Error, fs, net::SocketAddr, path::PathBuf, sync::Arc};
+
+use clap::{Parser, ValueEnum};
+use doppler_program::PriceFeed;
+use doppler_sdk::{registry::FeedRegistry, watcher::OracleWatcher};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::broadcast::error::RecvError,
+};
+use tokio_tungstenite::tungstenite::Message;
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+use crate::hub::{Hub, Subscriptions};
+
+mod api;
+mod grpc;
+mod hub;
+mod pb;
+
+/// Subscribe to Doppler oracles once over Geyser and rebroadcast their
+/// updates to WebSocket clients, one topic per feed.
+#[derive(Parser)]
+#[command(name = "doppler-relay", version)]
+struct Args {
+    /// Feed manifest naming the oracles to relay.
+    #[arg(long, short, default_value = "feeds.toml")]
+    registry: PathBuf,
+
+    /// Yellowstone gRPC endpoint.
+    #[arg(long)]
+    endpoint: String,
+
+    /// Yellowstone gRPC access token.
+    #[arg(long)]
+    x_token: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = Commitment::Processed)]
+    commitment: Commitment,
+
+    /// Address to accept WebSocket clients on.
+    #[arg(long, default_value = "0.0.0.0:8900")]
+    listen: SocketAddr,
+
+    /// Updates buffered for a slow client before it skips ahead.
+    #[arg(long, default_value_t = 1_024)]
+    buffer: usize,
+
+    /// Address to serve the HTTP price API on.
+    #[arg(long)]
+    http: Option<SocketAddr>,
+
+    /// Address to serve the gRPC `doppler.relay.v1.Oracles` service on.
+    #[arg(long)]
+    grpc: Option<SocketAddr>,
+
+    /// Values of each feed kept for the HTTP history endpoint.
+    #[arg(long, default_value_t = 1_000)]
+    history: usize,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentLevel {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => Self::Processed,
+            Commitment::Confirmed => Self::Confirmed,
+            Commitment::Finalized => Self::Finalized,
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+
+    let manifest = fs::read_to_string(&args.registry)
+        .map_err(|err| format!("failed to read {}: {err}", args.registry.display()))?;
+    let registry = FeedRegistry::from_toml(&manifest)?;
+    if let Some((name, feed)) = registry
+        .iter()
+        .find(|(_, feed)| feed.payload != "PriceFeed")
+    {
+        return Err(format!("{name}: unsupported payload {}", feed.payload).into());
+    }
+
+    let hub = Arc::new(Hub::new(&registry, args.buffer.max(1), args.history));
+    let _watcher = OracleWatcher::<PriceFeed>::spawn(
+        args.endpoint,
+        args.x_token,
+        hub.oracles(),
+        args.commitment.into(),
+        {
+            let hub = hub.clone();
+            move |update| hub.publish(update)
+        },
+    );
+
+    if let Some(addr) = args.http {
+        let hub = hub.clone();
+        tokio::spawn(async move {
+            if let Err(err) = api::serve(addr, hub).await {
+                eprintln!("HTTP API on {addr} failed: {err}");
+            }
+        });
+    }
+
+    if let Some(addr) = args.grpc {
+        let hub = hub.clone();
+        tokio::spawn(async move {
+            if let Err(err) = grpc::serve(addr, hub).await {
+                eprintln!("gRPC service on {addr} failed: {err}");
+            }
+        });
+    }
+
+    let listener = TcpListener::bind(args.listen).await?;
+    println!(
+        "relaying {} feeds on ws://{}",
+        registry.iter().count(),
+        args.listen
+    );
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let hub = hub.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = serve(stream, &hub).await {
+                        eprintln!("{peer}: {err}");
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Relays the updates of the feeds a client subscribes to until it
+/// disconnects. A client that falls more than the buffer behind skips to
+/// the newest updates.
+async fn serve(stream: TcpStream, hub: &Hub) -> Result<(), Box<dyn Error>> {
+    let socket = tokio_tungstenite::accept_async(stream).await?;
+    let (mut sink, mut requests) = socket.split();
+    let mut updates = hub.subscribe();
+    let mut subscriptions = Subscriptions::default();
+
+    loop {
+        tokio::select! {
+            request = requests.next() => {
+                let text = match request.transpose()? {
+                    Some(Message::Text(text)) => text,
+                    Some(Message::Close(_)) | None => return Ok(()),
+                    Some(_) => continue,
+                };
+
+                match subscriptions.apply(hub, &text) {
+                    Ok(feeds) => {
+                        for update in feeds.iter().filter_map(|feed| hub.latest(feed)) {
+                            sink.send(Message::Text(update.message.to_string())).await?;
+                        }
+                    }
+                    Err(err) => {
+                        sink.send(Message::Text(json!({ "error": err }).to_string())).await?;
+                    }
+                }
+            }
+            update = updates.recv() => match update {
+                Ok(update) if subscriptions.contains(&update.feed) => {
+                    sink.send(Message::Text(update.message.to_string())).await?;
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        }
+    }
+}
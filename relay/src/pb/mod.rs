@@ -0,0 +1,8 @@
+// @generated
+pub mod doppler {
+    pub mod relay {
+        pub mod v1 {
+            include!("doppler.relay.v1.rs");
+        }
+    }
+}
@@ -1,7 +1,22 @@
+#[cfg(feature = "client")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "client")]
+use solana_account::Account;
+#[cfg(feature = "client")]
 use solana_instruction::{AccountMeta, Instruction};
+#[cfg(feature = "client")]
+use solana_message::compiled_instruction::CompiledInstruction;
 use solana_pubkey::Pubkey;
+#[cfg(feature = "zerocopy")]
+use zerocopy::{FromBytes, Immutable, IntoBytes};
 
-use crate::constants::{ADMIN_VERIFICATION_CU, ID, PAYLOAD_WRITE_CU, SEQUENCE_CHECK_CU};
+#[cfg(feature = "client")]
+use crate::cluster::Cluster;
+use crate::constants::{
+    ADMIN_VERIFICATION_CU, PAYLOAD_OFFSET, PAYLOAD_WRITE_CU, SEQUENCE_CHECK_CU, SEQUENCE_OFFSET,
+};
+#[cfg(feature = "client")]
+use crate::error::DecodeError;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug)]
@@ -11,36 +26,140 @@ pub struct Oracle<T: Sized + Copy> {
 }
 
 impl<T: Sized + Copy> Oracle<T> {
+    /// Size of the oracle account data.
+    pub const LEN: usize = core::mem::size_of::<Self>();
+
+    /// Length of the encoded update written by [`Oracle::encode_into`].
+    pub const ENCODED_LEN: usize = PAYLOAD_OFFSET + core::mem::size_of::<T>();
+
+    #[cfg(feature = "client")]
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut data = Vec::with_capacity(core::mem::size_of::<Self>());
+        let mut data = vec![0; Self::ENCODED_LEN];
+        self.encode_into(&mut data);
+        data
+    }
+
+    /// Writes the sequence and payload into the front of `buf` without
+    /// allocating, returning the number of bytes written.
+    ///
+    /// # Panics
+    /// Panics if `buf` is shorter than [`Oracle::ENCODED_LEN`].
+    pub fn encode_into(&self, buf: &mut [u8]) -> usize {
         // write sequence bytes
-        data.extend_from_slice(&self.sequence.to_le_bytes());
+        buf[SEQUENCE_OFFSET..PAYLOAD_OFFSET].copy_from_slice(&self.sequence.to_le_bytes());
         // write payload bytes
-        data.extend_from_slice(unsafe {
+        buf[PAYLOAD_OFFSET..Self::ENCODED_LEN].copy_from_slice(unsafe {
             core::slice::from_raw_parts(
                 core::ptr::from_ref(&self.payload).cast::<u8>(),
                 core::mem::size_of::<T>(),
             )
         });
-        data
+        Self::ENCODED_LEN
     }
 
+    /// Decodes an oracle from the front of `data`.
+    ///
+    /// # Panics
+    /// Panics if `data` is shorter than [`Oracle::LEN`].
     #[must_use]
     pub fn from_bytes(data: &[u8]) -> Self {
-        assert!(data.len() == core::mem::size_of::<Self>());
+        Self::try_from_bytes(data)
+            .expect("account data is too small for this oracle")
+            .0
+    }
+
+    /// Decodes an oracle from the front of `data`, returning it along with
+    /// the number of trailing bytes past [`Oracle::LEN`], or `None` if `data`
+    /// is too short. Accounts created with extra padding stay readable.
+    #[must_use]
+    pub fn try_from_bytes(data: &[u8]) -> Option<(Self, usize)> {
+        let trailing_len = data.len().checked_sub(Self::LEN)?;
+
+        Some((Self::read(data), trailing_len))
+    }
+
+    /// Reads the sequence and payload from the front of `data`, which holds
+    /// at least [`Oracle::ENCODED_LEN`] bytes.
+    fn read(data: &[u8]) -> Self {
+        debug_assert!(data.len() >= Self::ENCODED_LEN);
 
         // read u64 sequence from first 8 bytes
         let mut seq_bytes = [0u8; 8];
-        seq_bytes.copy_from_slice(&data[..8]);
+        seq_bytes.copy_from_slice(&data[SEQUENCE_OFFSET..PAYLOAD_OFFSET]);
         let sequence = u64::from_le_bytes(seq_bytes);
 
         // read payload from remaining bytes
-        let payload = unsafe { *data[8..].as_ptr().cast::<T>() };
+        let payload = unsafe { data[PAYLOAD_OFFSET..].as_ptr().cast::<T>().read_unaligned() };
 
         Self { sequence, payload }
     }
 }
 
+#[cfg(feature = "client")]
+impl<T: Sized + Copy> Oracle<T> {
+    /// Decodes an oracle account, first checking that it is owned by the
+    /// Doppler deployment on `cluster` so a look-alike account owned by
+    /// another program is rejected.
+    pub fn try_from_account(account: &Account, cluster: Cluster) -> Result<Self, DecodeError> {
+        if account.owner != cluster.program_id() {
+            return Err(DecodeError::InvalidOwner(account.owner));
+        }
+
+        Self::try_from_bytes(&account.data)
+            .map(|(oracle, _)| oracle)
+            .ok_or(DecodeError::AccountTooSmall {
+                min: Self::LEN,
+                actual: account.data.len(),
+            })
+    }
+}
+
+#[cfg(feature = "client")]
+impl<T: Sized + Copy> TryFrom<&Account> for Oracle<T> {
+    type Error = DecodeError;
+
+    fn try_from(account: &Account) -> Result<Self, Self::Error> {
+        Self::try_from_account(account, Cluster::Mainnet)
+    }
+}
+
+/// Encoding through the payload's `zerocopy` impls rather than pointer casts,
+/// for payloads that derive them.
+#[cfg(feature = "zerocopy")]
+impl<T: Sized + Copy> Oracle<T> {
+    /// Like [`Oracle::encode_into`], returning `None` instead of panicking
+    /// if `buf` is shorter than [`Oracle::ENCODED_LEN`].
+    pub fn write_to_prefix(&self, buf: &mut [u8]) -> Option<usize>
+    where
+        T: IntoBytes + Immutable,
+    {
+        let (sequence, payload) = buf.split_at_mut_checked(PAYLOAD_OFFSET)?;
+        sequence.copy_from_slice(&self.sequence.to_le_bytes());
+        self.payload.write_to_prefix(payload).ok()?;
+
+        Some(Self::ENCODED_LEN)
+    }
+
+    /// Like [`Oracle::try_from_bytes`].
+    #[must_use]
+    pub fn read_from_prefix(data: &[u8]) -> Option<(Self, usize)>
+    where
+        T: FromBytes,
+    {
+        let trailing_len = data.len().checked_sub(Self::LEN)?;
+        let (sequence, payload) = data.split_at(PAYLOAD_OFFSET);
+
+        Some((
+            Self {
+                sequence: u64::from_le_bytes(sequence.try_into().ok()?),
+                payload: T::read_from_prefix(payload).ok()?.0,
+            },
+            trailing_len,
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
 pub struct UpdateInstruction<T: Sized + Copy> {
     pub admin: Pubkey,
     pub oracle_pubkey: Pubkey,
@@ -58,21 +177,112 @@ impl<T: Sized + Copy> UpdateInstruction<T> {
     pub const fn loaded_accounts_data_size_limit(&self) -> u32 {
         core::mem::size_of::<Oracle<T>>() as u32
     }
+
+    /// Builds the update instruction for the Doppler deployment on
+    /// `cluster`.
+    #[cfg(feature = "client")]
+    pub fn into_instruction(self, cluster: Cluster) -> Instruction {
+        Instruction {
+            program_id: cluster.program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.admin, true),
+                AccountMeta::new(self.oracle_pubkey, false),
+            ],
+            data: self.oracle.to_bytes(),
+        }
+    }
+
+    /// Decodes an update instruction sent to the Doppler deployment on
+    /// `cluster`.
+    #[cfg(feature = "client")]
+    pub fn try_from_instruction(ix: &Instruction, cluster: Cluster) -> Result<Self, DecodeError> {
+        if ix.program_id != cluster.program_id() {
+            return Err(DecodeError::InvalidProgramId(ix.program_id));
+        }
+        let [admin, oracle] = ix.accounts.as_slice() else {
+            return Err(DecodeError::InvalidAccounts(ix.accounts.len()));
+        };
+
+        decode_update(admin.pubkey, oracle.pubkey, &ix.data)
+    }
+
+    /// Overwrites `ix` with this update, reusing its account and data
+    /// buffers so a keeper can rebuild the same instruction every tick
+    /// without allocating.
+    #[cfg(feature = "client")]
+    pub fn write_instruction(&self, ix: &mut Instruction, cluster: Cluster) {
+        ix.program_id = cluster.program_id();
+        ix.accounts.clear();
+        ix.accounts.extend([
+            AccountMeta::new_readonly(self.admin, true),
+            AccountMeta::new(self.oracle_pubkey, false),
+        ]);
+        ix.data.resize(Oracle::<T>::ENCODED_LEN, 0);
+        self.oracle.encode_into(&mut ix.data);
+    }
 }
 
+#[cfg(feature = "client")]
 impl<T: Sized + Copy> From<UpdateInstruction<T>> for Instruction {
     fn from(update: UpdateInstruction<T>) -> Self {
-        let data = update.oracle.to_bytes();
+        update.into_instruction(Cluster::Mainnet)
+    }
+}
 
-        Self {
-            program_id: ID,
-            accounts: vec![
-                AccountMeta::new_readonly(update.admin, true),
-                AccountMeta::new(update.oracle_pubkey, false),
-            ],
-            data,
-        }
+#[cfg(feature = "client")]
+impl<T: Sized + Copy> TryFrom<&Instruction> for UpdateInstruction<T> {
+    type Error = DecodeError;
+
+    fn try_from(ix: &Instruction) -> Result<Self, Self::Error> {
+        Self::try_from_instruction(ix, Cluster::Mainnet)
+    }
+}
+
+/// Decodes a compiled update sent to the Doppler deployment on `cluster`,
+/// resolving its account indexes against the message's `account_keys`.
+#[cfg(feature = "client")]
+pub fn decode_doppler_ix<T: Sized + Copy>(
+    ix: &CompiledInstruction,
+    account_keys: &[Pubkey],
+    cluster: Cluster,
+) -> Result<UpdateInstruction<T>, DecodeError> {
+    let key = |index: u8| {
+        account_keys
+            .get(usize::from(index))
+            .copied()
+            .ok_or(DecodeError::AccountIndexOutOfBounds(index))
+    };
+
+    let program_id = key(ix.program_id_index)?;
+    if program_id != cluster.program_id() {
+        return Err(DecodeError::InvalidProgramId(program_id));
+    }
+    let [admin, oracle] = ix.accounts.as_slice() else {
+        return Err(DecodeError::InvalidAccounts(ix.accounts.len()));
+    };
+
+    decode_update(key(*admin)?, key(*oracle)?, &ix.data)
+}
+
+#[cfg(feature = "client")]
+fn decode_update<T: Sized + Copy>(
+    admin: Pubkey,
+    oracle_pubkey: Pubkey,
+    data: &[u8],
+) -> Result<UpdateInstruction<T>, DecodeError> {
+    // Updates carry the payload without the account's trailing padding.
+    if data.len() != Oracle::<T>::ENCODED_LEN {
+        return Err(DecodeError::InvalidDataLength {
+            expected: Oracle::<T>::ENCODED_LEN,
+            actual: data.len(),
+        });
     }
+
+    Ok(UpdateInstruction {
+        admin,
+        oracle_pubkey,
+        oracle: Oracle::read(data),
+    })
 }
 
 #[cfg(test)]
@@ -81,6 +291,7 @@ mod tests {
     use solana_pubkey::Pubkey;
 
     use super::*;
+    use crate::constants::ID;
 
     #[repr(C)]
     #[derive(Clone, Copy)]
@@ -97,6 +308,47 @@ mod tests {
         pub confidence: u32,
     }
 
+    /// The program, accounts and data of `ix`, with the data split into its
+    /// sequence and payload, as pinned by the snapshots in `snapshots/`.
+    fn wire_format(ix: &Instruction) -> String {
+        let hex = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let accounts: String = ix
+            .accounts
+            .iter()
+            .map(|meta| {
+                format!(
+                    "  {} signer={} writable={}\n",
+                    meta.pubkey, meta.is_signer, meta.is_writable
+                )
+            })
+            .collect();
+
+        format!(
+            "program: {}\naccounts:\n{accounts}sequence: {}\npayload: {}\n",
+            ix.program_id,
+            hex(&ix.data[..PAYLOAD_OFFSET]),
+            hex(&ix.data[PAYLOAD_OFFSET..]),
+        )
+    }
+
+    fn snapshot_update<T: Sized + Copy>(payload: T) -> Instruction {
+        UpdateInstruction {
+            admin: Pubkey::from([0x11; 32]),
+            oracle_pubkey: Pubkey::from([0x22; 32]),
+            oracle: Oracle {
+                sequence: 0x0102_0304_0506_0708,
+                payload,
+            },
+        }
+        .into()
+    }
+
     #[test]
     fn test_oracle_to_bytes() {
         let oracle = Oracle {
@@ -200,4 +452,301 @@ mod tests {
 
         assert_eq!(compute_instruction, 25);
     }
+
+    #[test]
+    fn test_decode_instruction_roundtrip() {
+        let admin = Pubkey::new_unique();
+        let oracle_pubkey = Pubkey::new_unique();
+
+        let ix: Instruction = UpdateInstruction {
+            admin,
+            oracle_pubkey,
+            oracle: Oracle {
+                sequence: 7,
+                payload: PriceFeed { price: 1_100_000 },
+            },
+        }
+        .into();
+
+        let decoded = UpdateInstruction::<PriceFeed>::try_from(&ix).unwrap();
+        assert_eq!(decoded.admin, admin);
+        assert_eq!(decoded.oracle_pubkey, oracle_pubkey);
+        assert_eq!(decoded.oracle.sequence, 7);
+        assert_eq!(decoded.oracle.payload.price, 1_100_000);
+
+        assert_eq!(
+            UpdateInstruction::<[u64; 3]>::try_from(&ix).unwrap_err(),
+            DecodeError::InvalidDataLength {
+                expected: 32,
+                actual: 16
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_unpadded_payloads_roundtrip() {
+        let admin = Pubkey::new_unique();
+        let oracle_pubkey = Pubkey::new_unique();
+
+        let ix: Instruction = UpdateInstruction {
+            admin,
+            oracle_pubkey,
+            oracle: Oracle {
+                sequence: 3,
+                payload: 123u32,
+            },
+        }
+        .into();
+        assert_eq!(ix.data.len(), 12);
+        let decoded = UpdateInstruction::<u32>::try_from(&ix).unwrap();
+        assert_eq!((decoded.oracle.sequence, decoded.oracle.payload), (3, 123));
+
+        let ix: Instruction = UpdateInstruction {
+            admin,
+            oracle_pubkey,
+            oracle: Oracle {
+                sequence: 4,
+                payload: MarketData {
+                    price: 45_000_000,
+                    volume: 150_000_000,
+                    confidence: 300,
+                },
+            },
+        }
+        .into();
+        let decoded = UpdateInstruction::<MarketData>::try_from(&ix).unwrap();
+        let MarketData {
+            price,
+            volume,
+            confidence,
+        } = decoded.oracle.payload;
+        assert_eq!(decoded.oracle.sequence, 4);
+        assert_eq!((price, volume, confidence), (45_000_000, 150_000_000, 300));
+
+        let account_keys = [admin, oracle_pubkey, ID];
+        let compiled = CompiledInstruction::new_from_raw_parts(2, ix.data, vec![0, 1]);
+        let decoded =
+            decode_doppler_ix::<MarketData>(&compiled, &account_keys, Cluster::Mainnet).unwrap();
+        assert_eq!(decoded.oracle.payload.confidence, 300);
+    }
+
+    #[test]
+    fn test_decode_compiled_instruction() {
+        let admin = Pubkey::new_unique();
+        let oracle_pubkey = Pubkey::new_unique();
+        let account_keys = [admin, oracle_pubkey, ID];
+
+        let ix = CompiledInstruction::new_from_raw_parts(
+            2,
+            Oracle {
+                sequence: 9,
+                payload: 42u64,
+            }
+            .to_bytes(),
+            vec![0, 1],
+        );
+
+        let decoded = decode_doppler_ix::<u64>(&ix, &account_keys, Cluster::Mainnet).unwrap();
+        assert_eq!(decoded.admin, admin);
+        assert_eq!(decoded.oracle_pubkey, oracle_pubkey);
+        assert_eq!(decoded.oracle.sequence, 9);
+        assert_eq!(decoded.oracle.payload, 42);
+
+        assert_eq!(
+            decode_doppler_ix::<u64>(&ix, &account_keys[..2], Cluster::Mainnet).unwrap_err(),
+            DecodeError::AccountIndexOutOfBounds(2)
+        );
+    }
+
+    #[test]
+    fn test_custom_cluster_program_id() {
+        let program_id = Pubkey::new_unique();
+        let update = UpdateInstruction {
+            admin: Pubkey::new_unique(),
+            oracle_pubkey: Pubkey::new_unique(),
+            oracle: Oracle {
+                sequence: 1,
+                payload: 42u64,
+            },
+        };
+
+        let ix = update.into_instruction(Cluster::Custom(program_id));
+        assert_eq!(ix.program_id, program_id);
+        assert!(
+            UpdateInstruction::<u64>::try_from_instruction(&ix, Cluster::Custom(program_id))
+                .is_ok()
+        );
+        assert_eq!(
+            UpdateInstruction::<u64>::try_from(&ix).unwrap_err(),
+            DecodeError::InvalidProgramId(program_id)
+        );
+    }
+
+    #[test]
+    fn test_compute_units_cover_cu_table() {
+        use crate::cu_table::{PAYLOAD_SIZES, UPDATE_CU};
+
+        fn model<T: Sized + Copy>(payload: T) -> u32 {
+            UpdateInstruction {
+                admin: Pubkey::new_unique(),
+                oracle_pubkey: Pubkey::new_unique(),
+                oracle: Oracle {
+                    sequence: 1,
+                    payload,
+                },
+            }
+            .compute_units()
+        }
+
+        // The mollusk bencher's record of a single update of the built program
+        let bench = include_str!("../../program/benches/compute_units.md");
+        let measured: u32 = bench
+            .lines()
+            .find_map(|line| {
+                let mut cells = line.split('|').map(str::trim).skip(1);
+                (cells.next()? == "PriceFeedUpdate").then(|| cells.next()?.parse().ok())?
+            })
+            .unwrap();
+
+        // A payload of each size the program is built for
+        let models = [
+            model(PriceFeed { price: 1_100_000 }),
+            model([0u8; 125]),
+            model([0u8; 128]),
+        ];
+
+        assert_eq!(PAYLOAD_SIZES, [core::mem::size_of::<PriceFeed>(), 125, 128]);
+        assert_eq!(UPDATE_CU[0][0], measured);
+        assert_eq!(models[0], measured);
+        // Every batch fits the budget the SDK requests for its updates
+        for (table, model) in UPDATE_CU.iter().zip(models) {
+            for (index, cu) in table.iter().enumerate() {
+                assert!(*cu <= model * (index as u32 + 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_try_from_account_checks_owner() {
+        let mut account = Account {
+            lamports: 1,
+            data: Oracle {
+                sequence: 5,
+                payload: 42u64,
+            }
+            .to_bytes(),
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let oracle = Oracle::<u64>::try_from(&account).unwrap();
+        assert_eq!((oracle.sequence, oracle.payload), (5, 42));
+
+        account.data.truncate(8);
+        assert_eq!(
+            Oracle::<u64>::try_from(&account).unwrap_err(),
+            DecodeError::AccountTooSmall { min: 16, actual: 8 }
+        );
+
+        let impostor = Pubkey::new_unique();
+        account.owner = impostor;
+        assert_eq!(
+            Oracle::<u64>::try_from(&account).unwrap_err(),
+            DecodeError::InvalidOwner(impostor)
+        );
+    }
+
+    #[test]
+    fn test_write_instruction_reuses_buffers() {
+        let admin = Pubkey::new_unique();
+        let oracle_pubkey = Pubkey::new_unique();
+        let update = |sequence| UpdateInstruction {
+            admin,
+            oracle_pubkey,
+            oracle: Oracle {
+                sequence,
+                payload: PriceFeed { price: 1_100_000 },
+            },
+        };
+
+        let mut ix: Instruction = update(1).into();
+        let data_ptr = ix.data.as_ptr();
+        let accounts_ptr = ix.accounts.as_ptr();
+
+        update(2).write_instruction(&mut ix, Cluster::Mainnet);
+
+        assert_eq!(ix, update(2).into());
+        assert_eq!(ix.data.as_ptr(), data_ptr);
+        assert_eq!(ix.accounts.as_ptr(), accounts_ptr);
+    }
+
+    #[test]
+    fn test_encode_into_stack_buffer() {
+        let oracle = Oracle {
+            sequence: 42,
+            payload: 123u32,
+        };
+        let mut buf = [0u8; 32];
+
+        let len = oracle.encode_into(&mut buf);
+
+        assert_eq!(&buf[..len], oracle.to_bytes().as_slice());
+    }
+
+    #[test]
+    fn test_from_bytes_tolerates_trailing_bytes() {
+        let mut data = Oracle {
+            sequence: 42,
+            payload: 7u64,
+        }
+        .to_bytes();
+        data.extend_from_slice(&[0; 5]);
+
+        let (oracle, trailing_len) = Oracle::<u64>::try_from_bytes(&data).unwrap();
+        assert_eq!(oracle.sequence, 42);
+        assert_eq!(oracle.payload, 7);
+        assert_eq!(trailing_len, 5);
+
+        assert!(Oracle::<u64>::try_from_bytes(&data[..15]).is_none());
+    }
+
+    #[cfg(feature = "zerocopy")]
+    #[test]
+    fn test_zerocopy_matches_pointer_encoding() {
+        let oracle = Oracle {
+            sequence: 42,
+            payload: [1u32, 2, 3, 4],
+        };
+
+        let mut buf = [0u8; 32];
+        assert_eq!(oracle.write_to_prefix(&mut buf), Some(24));
+        assert_eq!(buf[..24], oracle.to_bytes());
+        assert_eq!(oracle.write_to_prefix(&mut buf[..23]), None);
+
+        let (decoded, trailing_len) = Oracle::<[u32; 4]>::read_from_prefix(&buf).unwrap();
+        assert_eq!((decoded.sequence, decoded.payload), (42, [1, 2, 3, 4]));
+        assert_eq!(trailing_len, 8);
+        assert!(Oracle::<[u32; 4]>::read_from_prefix(&buf[..23]).is_none());
+    }
+
+    #[test]
+    fn test_update_instruction_snapshot_u32() {
+        insta::assert_snapshot!(wire_format(&snapshot_update(0x1112_1314u32)));
+    }
+
+    #[test]
+    fn test_update_instruction_snapshot_price_feed() {
+        insta::assert_snapshot!(wire_format(&snapshot_update(PriceFeed {
+            price: 1_100_000
+        })));
+    }
+
+    #[test]
+    fn test_update_instruction_snapshot_prop_amm() {
+        insta::assert_snapshot!(wire_format(&snapshot_update(PropAMM {
+            bid: 10_500_000,
+            ask: 10_550_000,
+        })));
+    }
 }
@@ -0,0 +1,25 @@
+use solana_pubkey::Pubkey;
+
+use crate::constants::ID;
+
+/// The Doppler deployment to target.
+///
+/// The program runs at [`ID`] on mainnet. It has no canonical deployment on
+/// devnet or local validators, so use [`Cluster::Custom`] with the address
+/// the program was deployed at there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Cluster {
+    #[default]
+    Mainnet,
+    Custom(Pubkey),
+}
+
+impl Cluster {
+    #[must_use]
+    pub const fn program_id(&self) -> Pubkey {
+        match self {
+            Self::Mainnet => ID,
+            Self::Custom(program_id) => *program_id,
+        }
+    }
+}
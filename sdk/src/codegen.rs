@@ -0,0 +1,366 @@
+//! Generates payload codecs for other languages from [`Schema`]s, so the
+//! TypeScript SDK agrees with the Rust layouts byte for byte, for both
+//! `@solana/web3.js` and `@solana/kit`.
+
+use std::{collections::BTreeSet, fmt::Write};
+
+use crate::schema::{Field, FieldType, Schema};
+
+/// Header of every generated file.
+const GENERATED: &str =
+    "// Generated by `doppler codegen` from the Rust payload layouts. Do not edit.";
+
+/// Generates a TypeScript module with an interface and a `PayloadCodec`
+/// for each schema, for the `ts` package.
+///
+/// Fields are camelCased. 64 and 128-bit integers are `bigint`s, pubkeys
+/// are `PublicKey`s and byte arrays are `Uint8Array`s.
+#[must_use]
+pub fn typescript(schemas: &[Schema]) -> String {
+    let helpers: BTreeSet<&str> = schemas
+        .iter()
+        .flat_map(|schema| schema.fields)
+        .flat_map(|field| match field.ty {
+            FieldType::U128 => ["readU128", "writeU128"].as_slice(),
+            FieldType::I128 => ["readI128", "writeI128"].as_slice(),
+            _ => [].as_slice(),
+        })
+        .copied()
+        .collect();
+    let pubkeys = schemas
+        .iter()
+        .flat_map(|schema| schema.fields)
+        .any(|field| field.ty == FieldType::Pubkey);
+
+    let mut out = format!("{GENERATED}\n\n");
+    if pubkeys {
+        out.push_str("import { PublicKey } from \"@solana/web3.js\";\n");
+    }
+    let imports: Vec<&str> = ["PayloadCodec"]
+        .into_iter()
+        .chain(helpers.iter().copied())
+        .collect();
+    let _ = writeln!(
+        out,
+        "import {{ {} }} from \"./codec.js\";",
+        imports.join(", ")
+    );
+
+    for schema in schemas {
+        typescript_codec(&mut out, schema);
+    }
+
+    out
+}
+
+/// Generates a TypeScript module with the decoded and encoder argument
+/// types and a `get<Name>Codec` function for each schema, built from
+/// `@solana/kit` codecs for the `ts` package's `kit` entry point.
+///
+/// Padding between fields and after the last one is kept with
+/// `padLeftCodec` and `padRightCodec`, so every codec has the payload's
+/// `size_of`. Pubkeys are `Address`es.
+#[must_use]
+pub fn kit(schemas: &[Schema]) -> String {
+    let mut types = BTreeSet::from(["FixedSizeCodec"]);
+    let mut functions = BTreeSet::from(["getStructCodec"]);
+    for schema in schemas {
+        let (fields, tail) = kit_padding(schema);
+        for (field, padding) in fields {
+            if padding > 0 {
+                functions.insert("padLeftCodec");
+            }
+            match field.ty {
+                FieldType::Pubkey => {
+                    types.insert("Address");
+                }
+                FieldType::Bytes(_) => {
+                    types.insert("ReadonlyUint8Array");
+                    functions.insert("fixCodecSize");
+                }
+                _ => {}
+            }
+            functions.insert(kit_codec(field.ty));
+        }
+        if tail > 0 {
+            functions.insert("padRightCodec");
+        }
+    }
+
+    let mut out = format!("{GENERATED}\n\nimport {{\n");
+    for ty in types {
+        let _ = writeln!(out, "  type {ty},");
+    }
+    for function in functions {
+        let _ = writeln!(out, "  {function},");
+    }
+    out.push_str("} from \"@solana/kit\";\n");
+
+    for schema in schemas {
+        kit_codec_function(&mut out, schema);
+    }
+
+    out
+}
+
+fn kit_codec_function(out: &mut String, schema: &Schema) {
+    let name = schema.name;
+
+    for (suffix, args) in [("", false), ("Args", true)] {
+        let _ = writeln!(out, "\nexport type {name}{suffix} = {{");
+        for field in schema.fields {
+            let _ = writeln!(
+                out,
+                "  {}: {};",
+                camel_case(field.name),
+                kit_type(field.ty, args)
+            );
+        }
+        out.push_str("};\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "\n/** `{name}`, {} bytes. */\nexport function get{name}Codec(): FixedSizeCodec<{name}Args, {name}> {{",
+        schema.size
+    );
+    let (fields, tail) = kit_padding(schema);
+    let indent = if tail > 0 { "      " } else { "    " };
+    let mut entries = String::new();
+    for (field, padding) in fields {
+        let mut codec = match field.ty {
+            FieldType::Bytes(len) => format!("fixCodecSize(getBytesCodec(), {len})"),
+            ty => format!("{}()", kit_codec(ty)),
+        };
+        if padding > 0 {
+            codec = format!("padLeftCodec({codec}, {padding})");
+        }
+        let _ = writeln!(
+            entries,
+            "{indent}[\"{}\", {codec}],",
+            camel_case(field.name)
+        );
+    }
+    if tail > 0 {
+        let _ = writeln!(
+            out,
+            "  return padRightCodec(\n    getStructCodec([\n{entries}    ]),\n    {tail},\n  );"
+        );
+    } else {
+        let _ = writeln!(out, "  return getStructCodec([\n{entries}  ]);");
+    }
+    out.push_str("}\n");
+}
+
+/// Each field with the padding before it, and the padding after the last
+/// field.
+fn kit_padding(schema: &Schema) -> (Vec<(&Field, usize)>, usize) {
+    let mut cursor = 0;
+    let fields = schema
+        .fields
+        .iter()
+        .map(|field| {
+            let padding = field.offset.saturating_sub(cursor);
+            cursor = field.offset + field.ty.size();
+            (field, padding)
+        })
+        .collect();
+
+    (fields, schema.size.saturating_sub(cursor))
+}
+
+/// The `@solana/kit` function returning the codec of `ty`.
+fn kit_codec(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8 => "getU8Codec",
+        FieldType::U16 => "getU16Codec",
+        FieldType::U32 => "getU32Codec",
+        FieldType::U64 => "getU64Codec",
+        FieldType::U128 => "getU128Codec",
+        FieldType::I8 => "getI8Codec",
+        FieldType::I16 => "getI16Codec",
+        FieldType::I32 => "getI32Codec",
+        FieldType::I64 => "getI64Codec",
+        FieldType::I128 => "getI128Codec",
+        FieldType::F32 => "getF32Codec",
+        FieldType::F64 => "getF64Codec",
+        FieldType::Bool => "getBooleanCodec",
+        FieldType::Pubkey => "getAddressCodec",
+        FieldType::Bytes(_) => "getBytesCodec",
+    }
+}
+
+/// The decoded type of `ty`, or the type its encoder accepts if `args`.
+fn kit_type(ty: FieldType, args: bool) -> &'static str {
+    match ty {
+        FieldType::U64 | FieldType::I64 | FieldType::U128 | FieldType::I128 if args => {
+            "number | bigint"
+        }
+        FieldType::Bytes(_) => "ReadonlyUint8Array",
+        FieldType::Pubkey => "Address",
+        ty => typescript_type(ty),
+    }
+}
+
+fn typescript_codec(out: &mut String, schema: &Schema) {
+    let name = schema.name;
+    let size = schema.size;
+
+    let _ = writeln!(out, "\nexport interface {name} {{");
+    for field in schema.fields {
+        let _ = writeln!(
+            out,
+            "  {}: {};",
+            camel_case(field.name),
+            typescript_type(field.ty)
+        );
+    }
+    out.push_str("}\n");
+
+    let _ = writeln!(
+        out,
+        "\nexport const {name}Codec: PayloadCodec<{name}> = {{\n  size: {size},\n  encode(value) {{\n    const data = new Uint8Array({size});\n    const view = new DataView(data.buffer);"
+    );
+    for field in schema.fields {
+        let _ = writeln!(out, "    {};", typescript_write(field));
+    }
+    let _ = writeln!(
+        out,
+        "    return data;\n  }},\n  decode(data) {{\n    if (data.length < {size}) {{\n      throw new RangeError(`{name} needs {size} bytes, got ${{data.length}}`);\n    }}\n    const view = new DataView(data.buffer, data.byteOffset, data.byteLength);\n    return {{"
+    );
+    for field in schema.fields {
+        let _ = writeln!(
+            out,
+            "      {}: {},",
+            camel_case(field.name),
+            typescript_read(field)
+        );
+    }
+    out.push_str("    };\n  },\n};\n");
+}
+
+fn typescript_type(ty: FieldType) -> &'static str {
+    match ty {
+        FieldType::U8
+        | FieldType::U16
+        | FieldType::U32
+        | FieldType::I8
+        | FieldType::I16
+        | FieldType::I32
+        | FieldType::F32
+        | FieldType::F64 => "number",
+        FieldType::U64 | FieldType::I64 | FieldType::U128 | FieldType::I128 => "bigint",
+        FieldType::Bool => "boolean",
+        FieldType::Pubkey => "PublicKey",
+        FieldType::Bytes(_) => "Uint8Array",
+    }
+}
+
+/// The `DataView` accessor suffix and whether it takes an endianness.
+fn data_view(ty: FieldType) -> Option<(&'static str, bool)> {
+    Some(match ty {
+        FieldType::U8 => ("Uint8", false),
+        FieldType::I8 => ("Int8", false),
+        FieldType::U16 => ("Uint16", true),
+        FieldType::I16 => ("Int16", true),
+        FieldType::U32 => ("Uint32", true),
+        FieldType::I32 => ("Int32", true),
+        FieldType::U64 => ("BigUint64", true),
+        FieldType::I64 => ("BigInt64", true),
+        FieldType::F32 => ("Float32", true),
+        FieldType::F64 => ("Float64", true),
+        _ => return None,
+    })
+}
+
+fn typescript_read(field: &Field) -> String {
+    let offset = field.offset;
+    let end = offset + field.ty.size();
+
+    if let Some((accessor, endian)) = data_view(field.ty) {
+        let endian = if endian { ", true" } else { "" };
+        return format!("view.get{accessor}({offset}{endian})");
+    }
+    match field.ty {
+        FieldType::U128 => format!("readU128(view, {offset})"),
+        FieldType::I128 => format!("readI128(view, {offset})"),
+        FieldType::Bool => format!("view.getUint8({offset}) !== 0"),
+        FieldType::Pubkey => format!("new PublicKey(data.slice({offset}, {end}))"),
+        _ => format!("data.slice({offset}, {end})"),
+    }
+}
+
+fn typescript_write(field: &Field) -> String {
+    let offset = field.offset;
+    let value = format!("value.{}", camel_case(field.name));
+
+    if let Some((accessor, endian)) = data_view(field.ty) {
+        let endian = if endian { ", true" } else { "" };
+        return format!("view.set{accessor}({offset}, {value}{endian})");
+    }
+    match field.ty {
+        FieldType::U128 => format!("writeU128(view, {offset}, {value})"),
+        FieldType::I128 => format!("writeI128(view, {offset}, {value})"),
+        FieldType::Bool => format!("view.setUint8({offset}, {value} ? 1 : 0)"),
+        FieldType::Pubkey => format!("data.set({value}.toBytes(), {offset})"),
+        FieldType::Bytes(len) => format!("data.set({value}.subarray(0, {len}), {offset})"),
+        _ => unreachable!("handled by DataView"),
+    }
+}
+
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = !out.is_empty();
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Quote {
+        flags: u8,
+        mid_price: u64,
+        mint: [u8; 32],
+    }
+
+    crate::payload_schema!(Quote {
+        flags: U8,
+        mid_price: U64,
+        mint: Pubkey,
+    });
+
+    #[test]
+    fn test_typescript_codec() {
+        use crate::schema::PayloadSchema;
+
+        insta::assert_snapshot!(typescript(&[Quote::SCHEMA]));
+    }
+
+    /// `flags` is followed by 7 bytes of padding, kept before `mid_price`.
+    #[test]
+    fn test_kit_codec() {
+        use crate::schema::PayloadSchema;
+
+        insta::assert_snapshot!(kit(&[Quote::SCHEMA]));
+    }
+
+    #[test]
+    fn test_camel_case() {
+        assert_eq!(camel_case("price"), "price");
+        assert_eq!(camel_case("prev_publish_time"), "prevPublishTime");
+        assert_eq!(camel_case("_padding"), "padding");
+    }
+}
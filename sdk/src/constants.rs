@@ -6,13 +6,62 @@ pub const ID: Pubkey = Pubkey::new_from_array([
     0x15, 0x14, 0x33, 0x02, 0xac, 0x6e, 0x98, 0x5f, 0x70, 0x85, 0x53, 0xe1, 0x0a, 0xb6, 0xf9, 0x22,
 ]);
 
+// Oracle account data offsets
+pub const SEQUENCE_OFFSET: usize = 0x00; // (sequence: u64)
+pub const PAYLOAD_OFFSET: usize = 0x08; // (payload: T)
+
+/// Anchor's discriminator for an account named `Oracle`, the first 8 bytes
+/// of `sha256("account:Oracle")`. Oracles of a program built with the
+/// `anchor` feature start with it, moving the sequence and payload 8 bytes
+/// along.
+pub const ANCHOR_DISCRIMINATOR: [u8; 8] = [0x8b, 0xc2, 0x83, 0xb3, 0x8c, 0xb3, 0xe5, 0xf4];
+
+// Program exit codes
+#[cfg(feature = "blocking")]
+pub(crate) const ADMIN_CHECK_EXIT_CODE: u32 = 1;
+#[cfg(feature = "blocking")]
+pub(crate) const INVALID_SEQUENCE_EXIT_CODE: u32 = 2;
+
 pub(crate) const SEQUENCE_CHECK_CU: u32 = 5;
 pub(crate) const ADMIN_VERIFICATION_CU: u32 = 6;
 pub(crate) const PAYLOAD_WRITE_CU: u32 = 6;
 
+// Maximum serialized transaction size accepted by validators
+#[cfg(feature = "client")]
+pub(crate) const PACKET_DATA_SIZE: usize = 1232;
+
+// Maximum compute units a single transaction can request
+#[cfg(feature = "client")]
+pub(crate) const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+#[cfg(feature = "client")]
 pub(crate) const COMPUTE_BUDGET_IX_CU: u32 = 150;
+#[cfg(feature = "client")]
 pub(crate) const COMPUTE_BUDGET_UNIT_PRICE_SIZE: u32 = 9;
+#[cfg(feature = "client")]
 pub(crate) const COMPUTE_BUDGET_UNIT_LIMIT_SIZE: u32 = 5;
+#[cfg(feature = "client")]
 pub(crate) const COMPUTE_BUDGET_DATA_LIMIT_SIZE: u32 = 5;
+#[cfg(feature = "client")]
 pub(crate) const COMPUTE_BUDGET_PROGRAM_SIZE: u32 = 22;
+#[cfg(feature = "client")]
 pub(crate) const ORACLE_PROGRAM_SIZE: u32 = 36;
+#[cfg(any(feature = "jito", feature = "wormhole"))]
+pub(crate) const SYSTEM_TRANSFER_CU: u32 = 150;
+#[cfg(any(feature = "jito", feature = "nonce", feature = "wormhole"))]
+pub(crate) const SYSTEM_PROGRAM_SIZE: u32 = 14;
+#[cfg(feature = "nonce")]
+pub(crate) const SYSTEM_ADVANCE_NONCE_CU: u32 = 150;
+#[cfg(feature = "nonce")]
+pub(crate) const NONCE_ACCOUNT_SIZE: u32 = 80;
+#[cfg(feature = "nonce")]
+pub(crate) const RECENT_BLOCKHASHES_SYSVAR_SIZE: u32 = 6008;
+#[cfg(feature = "wormhole")]
+pub(crate) const WORMHOLE_POST_MESSAGE_CU: u32 = 30_000;
+// Core bridge program, `Bridge` config, emitter sequence, clock and rent
+// sysvars; the fee collector holds no data
+#[cfg(feature = "wormhole")]
+pub(crate) const WORMHOLE_ACCOUNTS_SIZE: u32 = 36 + 32 + 8 + 40 + 17;
+// Posted message header before its payload
+#[cfg(feature = "wormhole")]
+pub(crate) const WORMHOLE_MESSAGE_HEADER_SIZE: u32 = 95;
@@ -0,0 +1,21 @@
+// Written by `cargo bench -p doppler-program --bench compute_units`, which
+// replaces these values with measurements of the built programs.
+
+/// Whether the tables hold measurements of the built programs rather than
+/// `UpdateInstruction::compute_units` of one update times the batch size.
+pub const MEASURED: bool = false;
+
+/// Largest number of updates measured in one transaction.
+pub const MAX_BATCH: usize = 16;
+
+/// Payload sizes in bytes the tables are for, one per build of the program:
+/// `PriceFeed`, `PythPriceUpdate`, `SwitchboardResult`.
+pub const PAYLOAD_SIZES: [usize; 3] = [8, 125, 128];
+
+/// Compute units consumed by `n` updates of the payload at `PAYLOAD_SIZES[i]`
+/// in one transaction, at `UPDATE_CU[i][n - 1]`.
+pub const UPDATE_CU: [[u32; MAX_BATCH]; 3] = [
+    [21, 42, 63, 84, 105, 126, 147, 168, 189, 210, 231, 252, 273, 294, 315, 336],
+    [51, 102, 153, 204, 255, 306, 357, 408, 459, 510, 561, 612, 663, 714, 765, 816],
+    [51, 102, 153, 204, 255, 306, 357, 408, 459, 510, 561, 612, 663, 714, 765, 816],
+];
@@ -0,0 +1,62 @@
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    client_error::Result as ClientResult,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::RpcFilterType,
+};
+use solana_pubkey::Pubkey;
+
+use crate::accounts::Oracle;
+use crate::cluster::Cluster;
+use crate::constants::{PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+
+/// Lists every account owned by the Doppler program on `cluster` with its
+/// current sequence. Only the sequence bytes are downloaded, so this stays cheap
+/// regardless of payload sizes.
+///
+/// Oracle accounts carry no layout tag, so accounts are matched by owner
+/// alone; use [`oracles_of`] to narrow down to one payload type.
+pub fn all_oracles(client: &RpcClient, cluster: Cluster) -> ClientResult<Vec<(Pubkey, u64)>> {
+    let config = RpcProgramAccountsConfig {
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice: Some(UiDataSliceConfig {
+                offset: SEQUENCE_OFFSET,
+                length: PAYLOAD_OFFSET - SEQUENCE_OFFSET,
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    Ok(client
+        .get_program_accounts_with_config(&cluster.program_id(), config)?
+        .into_iter()
+        .filter_map(|(pubkey, account)| {
+            let sequence = account.data.get(SEQUENCE_OFFSET..PAYLOAD_OFFSET)?;
+            Some((pubkey, u64::from_le_bytes(sequence.try_into().unwrap())))
+        })
+        .collect())
+}
+
+/// Lists and decodes every oracle whose account size matches `Oracle<T>`.
+pub fn oracles_of<T: Sized + Copy>(
+    client: &RpcClient,
+    cluster: Cluster,
+) -> ClientResult<Vec<(Pubkey, Oracle<T>)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::DataSize(Oracle::<T>::LEN as u64)]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    Ok(client
+        .get_program_accounts_with_config(&cluster.program_id(), config)?
+        .into_iter()
+        .map(|(pubkey, account)| (pubkey, Oracle::from_bytes(&account.data)))
+        .collect())
+}
@@ -0,0 +1,87 @@
+#[cfg(any(feature = "blocking", feature = "nonblocking"))]
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_pubkey::Pubkey;
+use solana_signer::SignerError;
+#[cfg(feature = "blocking")]
+use solana_transaction_error::TransactionError;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum SigningError {
+    #[error("{0} is not a required signer of this message")]
+    UnexpectedSigner(Pubkey),
+    #[error("signature from {0} does not verify against this message")]
+    InvalidSignature(Pubkey),
+    #[error("missing signature from {0}")]
+    MissingSignature(Pubkey),
+    #[error(transparent)]
+    Signer(#[from] SignerError),
+}
+
+/// Why the builder could not produce a sendable transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BuildError {
+    #[error("transaction is {size} bytes, over the {max}-byte packet limit")]
+    TooLarge { size: usize, max: usize },
+    #[error("transaction references more accounts than a message can index")]
+    TooManyAccounts,
+    #[cfg(feature = "nonce")]
+    #[error("a durable nonce signs a single transaction, but the updates need {transactions}")]
+    NonceReused { transactions: usize },
+}
+
+#[cfg(any(feature = "blocking", feature = "nonblocking"))]
+impl From<BuildError> for ClientError {
+    fn from(error: BuildError) -> Self {
+        ClientErrorKind::Custom(error.to_string()).into()
+    }
+}
+
+/// Why [`Builder::simulate_and_send`](crate::transaction::Builder::simulate_and_send)
+/// refused to broadcast a transaction.
+#[cfg(feature = "blocking")]
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    #[error("simulation failed: {0}")]
+    Failed(TransactionError),
+    #[error("simulation did not return oracle account {0}")]
+    MissingAccount(Pubkey),
+    #[error("simulated state of oracle {0} does not match the update")]
+    Mismatch(Pubkey),
+}
+
+/// A Doppler program exit code decoded from a failed transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DopplerProgramError {
+    #[error("admin check failed: {admin} is not the admin of oracle {oracle}")]
+    InvalidAdmin { admin: Pubkey, oracle: Pubkey },
+    #[error("stale sequence for oracle {oracle} (have {current}, sent {sent})")]
+    StaleSequence {
+        oracle: Pubkey,
+        current: u64,
+        sent: u64,
+    },
+    #[error("unknown doppler exit code {0:#x}")]
+    Unknown(u32),
+}
+
+/// Why an instruction or account could not be decoded as a Doppler oracle
+/// update or oracle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error("instruction targets {0}, not the doppler program")]
+    InvalidProgramId(Pubkey),
+    #[error("expected 2 accounts, found {0}")]
+    InvalidAccounts(usize),
+    #[error("account index {0} is out of bounds")]
+    AccountIndexOutOfBounds(u8),
+    #[error("expected {expected} bytes of instruction data, found {actual}")]
+    InvalidDataLength { expected: usize, actual: usize },
+    #[error("account is owned by {0}, not the doppler program")]
+    InvalidOwner(Pubkey),
+    #[error("expected at least {min} bytes of account data, found {actual}")]
+    AccountTooSmall { min: usize, actual: usize },
+}
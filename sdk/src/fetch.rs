@@ -0,0 +1,201 @@
+use solana_account::Account;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+    rpc_config::RpcAccountInfoConfig,
+};
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+
+use crate::accounts::Oracle;
+use crate::cluster::Cluster;
+use crate::constants::{PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+use crate::error::DecodeError;
+
+/// An oracle read along with the slot the RPC node served it at.
+#[derive(Clone, Copy, Debug)]
+pub struct OracleSnapshot<T: Sized + Copy> {
+    pub slot: u64,
+    pub oracle: Oracle<T>,
+}
+
+/// Reads an oracle of the mainnet deployment, failing if the account is
+/// owned by another program or too small for `Oracle<T>`.
+pub fn oracle_account<T: Sized + Copy>(
+    client: &RpcClient,
+    oracle_pubkey: &Pubkey,
+) -> ClientResult<OracleSnapshot<T>> {
+    oracle_account_with_config(
+        client,
+        oracle_pubkey,
+        Cluster::Mainnet,
+        client.commitment(),
+        None,
+    )
+}
+
+/// Like [`oracle_account`], for the deployment on `cluster`, read at
+/// `commitment` from a node that has reached at least `min_context_slot`.
+pub fn oracle_account_with_config<T: Sized + Copy>(
+    client: &RpcClient,
+    oracle_pubkey: &Pubkey,
+    cluster: Cluster,
+    commitment: CommitmentConfig,
+    min_context_slot: Option<u64>,
+) -> ClientResult<OracleSnapshot<T>> {
+    let (slot, account) = account(client, oracle_pubkey, commitment, min_context_slot)?;
+
+    Ok(OracleSnapshot {
+        slot,
+        oracle: decode_oracle(oracle_pubkey, &account, cluster)?,
+    })
+}
+
+/// Reads only the sequence of an oracle of the mainnet deployment,
+/// regardless of its payload, failing if the account is owned by another
+/// program or too small to hold a sequence.
+pub fn sequence(client: &RpcClient, oracle_pubkey: &Pubkey) -> ClientResult<u64> {
+    sequence_with_config(
+        client,
+        oracle_pubkey,
+        Cluster::Mainnet,
+        client.commitment(),
+        None,
+    )
+}
+
+/// Like [`sequence`], for the deployment on `cluster`, read at `commitment`
+/// from a node that has reached at least `min_context_slot`.
+pub fn sequence_with_config(
+    client: &RpcClient,
+    oracle_pubkey: &Pubkey,
+    cluster: Cluster,
+    commitment: CommitmentConfig,
+    min_context_slot: Option<u64>,
+) -> ClientResult<u64> {
+    let (_, account) = account(client, oracle_pubkey, commitment, min_context_slot)?;
+
+    decode_sequence(oracle_pubkey, &account, cluster)
+}
+
+/// Fetches the account and the slot it was read at.
+fn account(
+    client: &RpcClient,
+    oracle_pubkey: &Pubkey,
+    commitment: CommitmentConfig,
+    min_context_slot: Option<u64>,
+) -> ClientResult<(u64, Account)> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(commitment),
+        min_context_slot,
+        ..Default::default()
+    };
+
+    let response = client.get_account_with_config(oracle_pubkey, config)?;
+
+    match response.value {
+        Some(account) => Ok((response.context.slot, account)),
+        None => Err(ClientErrorKind::Custom(format!("account {oracle_pubkey} not found")).into()),
+    }
+}
+
+fn decode_oracle<T: Sized + Copy>(
+    oracle_pubkey: &Pubkey,
+    account: &Account,
+    cluster: Cluster,
+) -> ClientResult<Oracle<T>> {
+    Oracle::try_from_account(account, cluster)
+        .map_err(|err| ClientErrorKind::Custom(format!("account {oracle_pubkey}: {err}")).into())
+}
+
+fn decode_sequence(
+    oracle_pubkey: &Pubkey,
+    account: &Account,
+    cluster: Cluster,
+) -> ClientResult<u64> {
+    let sequence = if account.owner == cluster.program_id() {
+        account
+            .data
+            .get(SEQUENCE_OFFSET..PAYLOAD_OFFSET)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or(DecodeError::AccountTooSmall {
+                min: PAYLOAD_OFFSET,
+                actual: account.data.len(),
+            })
+    } else {
+        Err(DecodeError::InvalidOwner(account.owner))
+    };
+
+    sequence
+        .map_err(|err| ClientErrorKind::Custom(format!("account {oracle_pubkey}: {err}")).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ID;
+
+    #[test]
+    fn test_decode_sequence() {
+        let oracle_pubkey = Pubkey::new_unique();
+        let mut data = 7u64.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0xff; 8]);
+        let mut account = Account {
+            lamports: 1,
+            data,
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        assert_eq!(
+            decode_sequence(&oracle_pubkey, &account, Cluster::Mainnet).unwrap(),
+            7
+        );
+
+        let custom = Cluster::Custom(Pubkey::new_unique());
+        assert!(decode_sequence(&oracle_pubkey, &account, custom).is_err());
+
+        account.data.truncate(4);
+        assert!(decode_sequence(&oracle_pubkey, &account, Cluster::Mainnet).is_err());
+    }
+
+    #[test]
+    fn test_decode_oracle_checks_owner_and_size() {
+        let oracle_pubkey = Pubkey::new_unique();
+        let mut account = Account {
+            lamports: 1,
+            data: Oracle {
+                sequence: 3,
+                payload: 42u64,
+            }
+            .to_bytes(),
+            owner: ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let oracle = decode_oracle::<u64>(&oracle_pubkey, &account, Cluster::Mainnet).unwrap();
+        assert_eq!((oracle.sequence, oracle.payload), (3, 42));
+
+        let message = |account: &Account| match decode_oracle::<u64>(
+            &oracle_pubkey,
+            account,
+            Cluster::Mainnet,
+        )
+        .unwrap_err()
+        .kind()
+        {
+            ClientErrorKind::Custom(message) => message.clone(),
+            kind => panic!("unexpected error {kind:?}"),
+        };
+
+        account.data.truncate(12);
+        assert!(message(&account).ends_with("expected at least 16 bytes of account data, found 12"));
+
+        account.owner = Pubkey::new_unique();
+        assert!(message(&account).ends_with("not the doppler program"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use futures::{Stream, StreamExt};
+use solana_pubkey::Pubkey;
+use tonic::{service::Interceptor, transport::ClientTlsConfig, Status};
+use yellowstone_grpc_client::{GeyserGrpcBuilderError, GeyserGrpcClient, GeyserGrpcClientError};
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterAccounts, SubscribeUpdate,
+};
+
+use crate::accounts::Oracle;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GeyserError {
+    #[error("failed to connect to geyser endpoint: {0}")]
+    Builder(#[from] GeyserGrpcBuilderError),
+    #[error("geyser subscription failed: {0}")]
+    Client(Box<GeyserGrpcClientError>),
+    #[error("geyser stream error: {0}")]
+    Status(Box<Status>),
+    #[error("geyser update has an invalid account pubkey")]
+    InvalidPubkey,
+    #[error("oracle account {0} does not match the expected payload layout")]
+    InvalidLayout(Pubkey),
+}
+
+impl From<GeyserGrpcClientError> for GeyserError {
+    fn from(error: GeyserGrpcClientError) -> Self {
+        Self::Client(Box::new(error))
+    }
+}
+
+impl From<Status> for GeyserError {
+    fn from(status: Status) -> Self {
+        Self::Status(Box::new(status))
+    }
+}
+
+/// An oracle account write delivered over Geyser.
+#[derive(Clone, Copy, Debug)]
+pub struct OracleUpdate<T: Sized + Copy> {
+    pub pubkey: Pubkey,
+    pub slot: u64,
+    pub oracle: Oracle<T>,
+}
+
+/// Connects to a Yellowstone gRPC endpoint over TLS.
+pub async fn connect(
+    endpoint: impl Into<String>,
+    x_token: Option<String>,
+) -> Result<GeyserGrpcClient<impl Interceptor>, GeyserError> {
+    Ok(GeyserGrpcClient::build_from_shared(endpoint.into())?
+        .x_token(x_token)?
+        .tls_config(ClientTlsConfig::new().with_native_roots())?
+        .connect()
+        .await?)
+}
+
+/// Subscribes to writes of `oracles`, yielding each one decoded as an
+/// `Oracle<T>` along with the slot it landed in.
+///
+/// Pings and other non-account updates are skipped.
+pub async fn subscribe<'a, T: Sized + Copy>(
+    client: &'a mut GeyserGrpcClient<impl Interceptor>,
+    oracles: &[Pubkey],
+    commitment: CommitmentLevel,
+) -> Result<impl Stream<Item = Result<OracleUpdate<T>, GeyserError>> + 'a, GeyserError> {
+    let request = SubscribeRequest {
+        accounts: HashMap::from([(
+            "doppler".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: oracles.iter().map(Pubkey::to_string).collect(),
+                ..Default::default()
+            },
+        )]),
+        commitment: Some(commitment as i32),
+        ..Default::default()
+    };
+
+    let (_sink, updates) = client.subscribe_with_request(Some(request)).await?;
+
+    Ok(updates.filter_map(|update| async move { decode(update).transpose() }))
+}
+
+fn decode<T: Sized + Copy>(
+    update: Result<SubscribeUpdate, Status>,
+) -> Result<Option<OracleUpdate<T>>, GeyserError> {
+    let Some(UpdateOneof::Account(update)) = update?.update_oneof else {
+        return Ok(None);
+    };
+    let Some(account) = update.account else {
+        return Ok(None);
+    };
+
+    let pubkey =
+        Pubkey::try_from(account.pubkey.as_slice()).map_err(|_| GeyserError::InvalidPubkey)?;
+
+    let (oracle, _) =
+        Oracle::try_from_bytes(&account.data).ok_or(GeyserError::InvalidLayout(pubkey))?;
+
+    Ok(Some(OracleUpdate {
+        pubkey,
+        slot: update.slot,
+        oracle,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::{SubscribeUpdateAccount, SubscribeUpdateAccountInfo};
+
+    fn account_update(pubkey: Pubkey, data: Vec<u8>) -> SubscribeUpdate {
+        SubscribeUpdate {
+            update_oneof: Some(UpdateOneof::Account(SubscribeUpdateAccount {
+                account: Some(SubscribeUpdateAccountInfo {
+                    pubkey: pubkey.to_bytes().to_vec(),
+                    data,
+                    ..Default::default()
+                }),
+                slot: 42,
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_decode_account_update() {
+        let pubkey = Pubkey::new_unique();
+        let oracle = Oracle {
+            sequence: 7,
+            payload: 1_100_000u64,
+        };
+
+        let update = decode::<u64>(Ok(account_update(pubkey, oracle.to_bytes())))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(update.pubkey, pubkey);
+        assert_eq!(update.slot, 42);
+        assert_eq!(update.oracle.sequence, 7);
+        assert_eq!(update.oracle.payload, 1_100_000);
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_layout() {
+        let pubkey = Pubkey::new_unique();
+
+        assert!(matches!(
+            decode::<u64>(Ok(account_update(pubkey, vec![0; 4]))),
+            Err(GeyserError::InvalidLayout(p)) if p == pubkey
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde_json::{json, Value};
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+
+pub const MAINNET_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
+
+/// Mainnet Jito tip accounts, any of which can receive a bundle tip.
+pub const TIP_ACCOUNTS: [Pubkey; 8] = [
+    Pubkey::from_str_const("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    Pubkey::from_str_const("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    Pubkey::from_str_const("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    Pubkey::from_str_const("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    Pubkey::from_str_const("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    Pubkey::from_str_const("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    Pubkey::from_str_const("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    Pubkey::from_str_const("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum JitoError {
+    #[error("failed to serialize bundle transaction: {0}")]
+    Serialize(#[from] bincode::Error),
+    #[error("block engine request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("block engine returned error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("unexpected block engine response: {0}")]
+    InvalidResponse(Value),
+}
+
+/// Minimal JSON-RPC client for a Jito block engine.
+pub struct JitoClient {
+    url: String,
+    http: reqwest::Client,
+}
+
+impl JitoClient {
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn mainnet() -> Self {
+        Self::new(MAINNET_BLOCK_ENGINE_URL)
+    }
+
+    /// Submits `bundle` through `sendBundle`, returning the bundle id.
+    pub async fn send_bundle(&self, bundle: &[Transaction]) -> Result<String, JitoError> {
+        let txs = bundle
+            .iter()
+            .map(|tx| bincode::serialize(tx).map(|bytes| STANDARD.encode(bytes)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let response: Value = self
+            .http
+            .post(&self.url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "sendBundle",
+                "params": [txs, { "encoding": "base64" }],
+            }))
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(JitoError::Rpc {
+                code: error
+                    .get("code")
+                    .and_then(Value::as_i64)
+                    .unwrap_or_default(),
+                message: error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+            });
+        }
+
+        match response.get("result").and_then(Value::as_str) {
+            Some(bundle_id) => Ok(bundle_id.to_string()),
+            None => Err(JitoError::InvalidResponse(response)),
+        }
+    }
+}
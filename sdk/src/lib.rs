@@ -1,5 +1,52 @@
+#![cfg_attr(not(feature = "client"), no_std)]
+
+#[cfg(feature = "client")]
+extern crate alloc;
+
 mod accounts;
+mod cluster;
+#[cfg(feature = "client")]
+pub mod codegen;
 mod constants;
+#[rustfmt::skip]
+pub mod cu_table;
+#[cfg(feature = "blocking")]
+pub mod discover;
+#[cfg(feature = "client")]
+mod error;
+#[cfg(feature = "blocking")]
+pub mod fetch;
+#[cfg(feature = "geyser")]
+pub mod geyser;
+#[cfg(feature = "jito")]
+pub mod jito;
+#[cfg(feature = "nonce")]
+pub mod nonce;
+#[cfg(feature = "blocking")]
+pub mod pool;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod schema;
+#[cfg(feature = "client")]
+mod sequence;
+#[cfg(any(feature = "nonblocking", feature = "geyser"))]
+pub mod stream;
+pub mod switchboard;
+#[cfg(feature = "client")]
 pub mod transaction;
+#[cfg(feature = "watcher")]
+pub mod watcher;
+#[cfg(feature = "wormhole")]
+pub mod wormhole;
+#[cfg(feature = "client")]
+pub use accounts::decode_doppler_ix;
 pub use accounts::{Oracle, UpdateInstruction};
-pub use constants::ID;
+pub use cluster::Cluster;
+pub use constants::{ANCHOR_DISCRIMINATOR, ID, PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+#[cfg(feature = "blocking")]
+pub use error::SimulationError;
+#[cfg(feature = "client")]
+pub use error::{BuildError, DecodeError, DopplerProgramError, SigningError};
+#[cfg(feature = "client")]
+pub use sequence::Sequence;
+pub use switchboard::rescale;
@@ -0,0 +1,110 @@
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+};
+use solana_hash::Hash;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_nonce::{
+    state::{Data, State},
+    versions::Versions,
+};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_signer::Signer;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction::Transaction;
+
+/// Creates and initializes a rent-exempt durable nonce account controlled by
+/// `authority`, paid for by `payer`.
+pub fn create(
+    client: &RpcClient,
+    payer: &Keypair,
+    nonce_account: &Keypair,
+    authority: &Pubkey,
+) -> ClientResult<Signature> {
+    let lamports = client.get_minimum_balance_for_rent_exemption(State::size())?;
+    let ixs = system_instruction::create_nonce_account(
+        &payer.pubkey(),
+        &nonce_account.pubkey(),
+        authority,
+        lamports,
+    );
+
+    send(client, &ixs, payer, &[payer, nonce_account])
+}
+
+/// Fetches the nonce account's authority and stored blockhash.
+pub fn fetch(client: &RpcClient, nonce_pubkey: &Pubkey) -> ClientResult<Data> {
+    let data = client.get_account_data(nonce_pubkey)?;
+
+    match bincode::deserialize::<Versions>(&data).map(|versions| versions.state().clone()) {
+        Ok(State::Initialized(data)) => Ok(data),
+        _ => Err(ClientErrorKind::Custom(format!(
+            "{nonce_pubkey} is not an initialized nonce account"
+        ))
+        .into()),
+    }
+}
+
+/// Tops up the nonce account with `lamports` from `payer`.
+pub fn fund(
+    client: &RpcClient,
+    payer: &Keypair,
+    nonce_pubkey: &Pubkey,
+    lamports: u64,
+) -> ClientResult<Signature> {
+    let ix = system_instruction::transfer(&payer.pubkey(), nonce_pubkey, lamports);
+
+    send(client, &[ix], payer, &[payer])
+}
+
+/// Advances the nonce, returning the new blockhash to sign against.
+///
+/// This also recovers a stuck nonce: any transaction signed against the
+/// previous value, such as an offline-signed update that never landed, can
+/// no longer execute.
+pub fn advance(
+    client: &RpcClient,
+    payer: &Keypair,
+    nonce_pubkey: &Pubkey,
+    authority: &Keypair,
+) -> ClientResult<Hash> {
+    let ix = system_instruction::advance_nonce_account(nonce_pubkey, &authority.pubkey());
+
+    send(client, &[ix], payer, &[payer, authority])?;
+
+    Ok(fetch(client, nonce_pubkey)?.blockhash())
+}
+
+/// Withdraws `lamports` from the nonce account to `to`. Withdrawing the full
+/// balance closes the account.
+pub fn withdraw(
+    client: &RpcClient,
+    payer: &Keypair,
+    nonce_pubkey: &Pubkey,
+    authority: &Keypair,
+    to: &Pubkey,
+    lamports: u64,
+) -> ClientResult<Signature> {
+    let ix =
+        system_instruction::withdraw_nonce_account(nonce_pubkey, &authority.pubkey(), to, lamports);
+
+    send(client, &[ix], payer, &[payer, authority])
+}
+
+fn send(
+    client: &RpcClient,
+    ixs: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> ClientResult<Signature> {
+    let tx = Transaction::new_signed_with_payer(
+        ixs,
+        Some(&payer.pubkey()),
+        signers,
+        client.get_latest_blockhash()?,
+    );
+
+    client.send_and_confirm_transaction(&tx)
+}
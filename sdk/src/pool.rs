@@ -0,0 +1,132 @@
+use std::thread;
+
+use solana_client::{client_error::Result as ClientResult, rpc_client::RpcClient};
+use solana_commitment_config::CommitmentConfig;
+use solana_hash::Hash;
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+
+/// A set of RPC endpoints used together so a single provider outage does not
+/// stop oracle updates from landing.
+///
+/// Reads fall back through the endpoints in order, sends are broadcast to
+/// all of them at once.
+pub struct RpcPool {
+    clients: Vec<RpcClient>,
+}
+
+impl RpcPool {
+    /// # Panics
+    /// Panics if `urls` is empty.
+    pub fn new(urls: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self::from_clients(
+            urls.into_iter()
+                .map(|url| RpcClient::new(url.into()))
+                .collect(),
+        )
+    }
+
+    /// # Panics
+    /// Panics if `urls` is empty.
+    pub fn new_with_commitment(
+        urls: impl IntoIterator<Item = impl Into<String>>,
+        commitment: CommitmentConfig,
+    ) -> Self {
+        Self::from_clients(
+            urls.into_iter()
+                .map(|url| RpcClient::new_with_commitment(url.into(), commitment))
+                .collect(),
+        )
+    }
+
+    /// # Panics
+    /// Panics if `clients` is empty.
+    pub fn from_clients(clients: Vec<RpcClient>) -> Self {
+        assert!(!clients.is_empty(), "RpcPool needs at least one endpoint");
+        Self { clients }
+    }
+
+    #[must_use]
+    pub fn clients(&self) -> &[RpcClient] {
+        &self.clients
+    }
+
+    /// Fetches the latest blockhash from the first endpoint that answers.
+    pub fn get_latest_blockhash(&self) -> ClientResult<Hash> {
+        self.first_ok(RpcClient::get_latest_blockhash)
+    }
+
+    /// Sends `tx` through every endpoint concurrently, succeeding if any of
+    /// them accepts it.
+    pub fn send_transaction(&self, tx: &Transaction) -> ClientResult<Signature> {
+        let results: Vec<_> = thread::scope(|scope| {
+            let sends: Vec<_> = self
+                .clients
+                .iter()
+                .map(|client| scope.spawn(move || client.send_transaction(tx)))
+                .collect();
+
+            sends
+                .into_iter()
+                .map(|send| send.join().expect("send thread panicked"))
+                .collect()
+        });
+
+        first_ok(results)
+    }
+
+    /// Broadcasts `tx`, then waits for confirmation from the first endpoint
+    /// that can report it. A transaction error is returned as-is rather than
+    /// retried against the remaining endpoints.
+    pub fn send_and_confirm_transaction(&self, tx: &Transaction) -> ClientResult<Signature> {
+        let signature = self.send_transaction(tx)?;
+
+        let mut last_err = None;
+        for client in &self.clients {
+            match client.poll_for_signature(&signature) {
+                Ok(()) => return Ok(signature),
+                Err(err) if err.get_transaction_error().is_some() => return Err(err),
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        Err(last_err.expect("RpcPool has at least one endpoint"))
+    }
+
+    fn first_ok<T>(&self, f: impl Fn(&RpcClient) -> ClientResult<T>) -> ClientResult<T> {
+        first_ok(self.clients.iter().map(f))
+    }
+}
+
+fn first_ok<T>(results: impl IntoIterator<Item = ClientResult<T>>) -> ClientResult<T> {
+    let mut last_err = None;
+    for result in results {
+        match result {
+            Ok(value) => return Ok(value),
+            Err(err) => last_err = Some(err),
+        }
+    }
+
+    Err(last_err.expect("RpcPool has at least one endpoint"))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_client::client_error::{ClientError, ClientErrorKind};
+
+    use super::*;
+
+    fn err(message: &str) -> ClientResult<u64> {
+        Err(ClientError::from(ClientErrorKind::Custom(
+            message.to_string(),
+        )))
+    }
+
+    #[test]
+    fn test_first_ok_falls_back() {
+        assert_eq!(first_ok([err("down"), Ok(2), Ok(3)]).unwrap(), 2);
+
+        let last = first_ok([err("down"), err("timeout")]).unwrap_err();
+        assert!(matches!(last.kind(), ClientErrorKind::Custom(message) if message == "timeout"));
+    }
+}
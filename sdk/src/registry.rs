@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::Deserialize;
+use solana_pubkey::{Pubkey, PubkeyError};
+
+use crate::cluster::Cluster;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RegistryError {
+    #[error("invalid feed manifest: {0}")]
+    Manifest(#[from] toml::de::Error),
+    #[error("invalid pubkey {0}")]
+    InvalidPubkey(String),
+    #[error("cannot derive address for feed {name}: {source}")]
+    InvalidSeed { name: String, source: PubkeyError },
+}
+
+/// A named oracle feed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Feed {
+    pub pubkey: Pubkey,
+    /// Name of the payload type the feed publishes, e.g. `PriceFeed`.
+    pub payload: String,
+}
+
+/// Resolves feed names such as `SOL/USDC` to oracle accounts created with
+/// `create_account_with_seed`, using the feed name as the seed.
+#[derive(Clone, Debug)]
+pub struct FeedRegistry {
+    admin: Pubkey,
+    cluster: Cluster,
+    feeds: BTreeMap<String, Feed>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    admin: String,
+    program_id: Option<String>,
+    #[serde(default)]
+    feeds: BTreeMap<String, ManifestFeed>,
+}
+
+#[derive(Deserialize)]
+struct ManifestFeed {
+    payload: String,
+    pubkey: Option<String>,
+}
+
+impl FeedRegistry {
+    #[must_use]
+    pub const fn new(admin: Pubkey) -> Self {
+        Self::new_with_cluster(admin, Cluster::Mainnet)
+    }
+
+    /// Creates a registry deriving oracle addresses for the Doppler
+    /// deployment on `cluster`.
+    #[must_use]
+    pub const fn new_with_cluster(admin: Pubkey, cluster: Cluster) -> Self {
+        Self {
+            admin,
+            cluster,
+            feeds: BTreeMap::new(),
+        }
+    }
+
+    /// Loads a registry from a TOML manifest:
+    ///
+    /// ```toml
+    /// admin = "<admin pubkey>"
+    /// program_id = "<program id>" # optional, for custom deployments
+    ///
+    /// [feeds."SOL/USDC"]
+    /// payload = "PriceFeed"
+    ///
+    /// [feeds.legacy]
+    /// payload = "PriceFeed"
+    /// pubkey = "<oracle pubkey>" # for accounts not derived from the name
+    /// ```
+    pub fn from_toml(manifest: &str) -> Result<Self, RegistryError> {
+        let manifest: Manifest = toml::from_str(manifest)?;
+        let cluster = match manifest.program_id {
+            Some(program_id) => Cluster::Custom(parse_pubkey(&program_id)?),
+            None => Cluster::Mainnet,
+        };
+        let mut registry = Self::new_with_cluster(parse_pubkey(&manifest.admin)?, cluster);
+
+        for (name, feed) in manifest.feeds {
+            match feed.pubkey {
+                Some(pubkey) => {
+                    registry.insert(name, parse_pubkey(&pubkey)?, feed.payload);
+                }
+                None => {
+                    registry.register(name, feed.payload)?;
+                }
+            }
+        }
+
+        Ok(registry)
+    }
+
+    #[must_use]
+    pub const fn admin(&self) -> Pubkey {
+        self.admin
+    }
+
+    #[must_use]
+    pub const fn cluster(&self) -> Cluster {
+        self.cluster
+    }
+
+    /// Adds a feed whose oracle address is derived from the admin and
+    /// `name`, returning that address.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        payload: impl Into<String>,
+    ) -> Result<Pubkey, RegistryError> {
+        let name = name.into();
+        let pubkey = Pubkey::create_with_seed(&self.admin, &name, &self.cluster.program_id())
+            .map_err(|source| RegistryError::InvalidSeed {
+                name: name.clone(),
+                source,
+            })?;
+
+        self.insert(name, pubkey, payload.into());
+
+        Ok(pubkey)
+    }
+
+    /// Adds a feed at an explicit address.
+    pub fn insert(&mut self, name: impl Into<String>, pubkey: Pubkey, payload: impl Into<String>) {
+        self.feeds.insert(
+            name.into(),
+            Feed {
+                pubkey,
+                payload: payload.into(),
+            },
+        );
+    }
+
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Feed> {
+        self.feeds.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Feed)> {
+        self.feeds.iter().map(|(name, feed)| (name.as_str(), feed))
+    }
+}
+
+fn parse_pubkey(pubkey: &str) -> Result<Pubkey, RegistryError> {
+    Pubkey::from_str(pubkey).map_err(|_| RegistryError::InvalidPubkey(pubkey.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::ID;
+
+    #[test]
+    fn test_from_toml() {
+        let admin = Pubkey::new_unique();
+        let legacy = Pubkey::new_unique();
+        let manifest = format!(
+            r#"
+            admin = "{admin}"
+
+            [feeds."SOL/USDC"]
+            payload = "PriceFeed"
+
+            [feeds.legacy]
+            payload = "PropAMM"
+            pubkey = "{legacy}"
+            "#
+        );
+
+        let registry = FeedRegistry::from_toml(&manifest).unwrap();
+
+        assert_eq!(
+            registry.get("SOL/USDC"),
+            Some(&Feed {
+                pubkey: Pubkey::create_with_seed(&admin, "SOL/USDC", &ID).unwrap(),
+                payload: "PriceFeed".to_string(),
+            })
+        );
+        assert_eq!(registry.get("legacy").unwrap().pubkey, legacy);
+        assert_eq!(registry.get("BONK/SOL"), None);
+    }
+
+    #[test]
+    fn test_register_rejects_long_seed() {
+        let mut registry = FeedRegistry::new(Pubkey::new_unique());
+
+        assert!(matches!(
+            registry.register("A".repeat(33), "PriceFeed"),
+            Err(RegistryError::InvalidSeed { .. })
+        ));
+    }
+}
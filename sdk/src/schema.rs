@@ -0,0 +1,224 @@
+use core::fmt;
+
+use solana_pubkey::Pubkey;
+
+/// Describes the layout of a payload so tooling can render it without
+/// knowing the Rust type, e.g. to print any oracle listed in a registry.
+///
+/// Implement it with [`payload_schema!`](crate::payload_schema).
+pub trait PayloadSchema: Sized + Copy {
+    const SCHEMA: Schema;
+}
+
+/// Name, size and fields of a payload type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Schema {
+    pub name: &'static str,
+    pub size: usize,
+    pub fields: &'static [Field],
+}
+
+impl Schema {
+    /// Reads every field from an encoded payload, in declaration order.
+    /// Fields that fall outside `payload` are `None`.
+    pub fn read<'a>(
+        &self,
+        payload: &'a [u8],
+    ) -> impl Iterator<Item = (&'static str, Option<Value<'a>>)> + 'a {
+        let fields: &'static [Field] = self.fields;
+
+        fields
+            .iter()
+            .map(move |field| (field.name, field.read(payload)))
+    }
+}
+
+/// A field of a payload, at `offset` bytes from the start of the payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub offset: usize,
+    pub ty: FieldType,
+}
+
+impl Field {
+    /// Reads this field from an encoded payload.
+    #[must_use]
+    pub fn read<'a>(&self, payload: &'a [u8]) -> Option<Value<'a>> {
+        let bytes = payload.get(self.offset..self.offset + self.ty.size())?;
+
+        Some(match self.ty {
+            FieldType::U8 => Value::U8(bytes[0]),
+            FieldType::U16 => Value::U16(u16::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::U32 => Value::U32(u32::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::U64 => Value::U64(u64::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::U128 => Value::U128(u128::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::I8 => Value::I8(i8::from_le_bytes([bytes[0]])),
+            FieldType::I16 => Value::I16(i16::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::I32 => Value::I32(i32::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::I64 => Value::I64(i64::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::I128 => Value::I128(i128::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::F32 => Value::F32(f32::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::F64 => Value::F64(f64::from_le_bytes(bytes.try_into().ok()?)),
+            FieldType::Bool => Value::Bool(bytes[0] != 0),
+            FieldType::Pubkey => Value::Pubkey(Pubkey::new_from_array(bytes.try_into().ok()?)),
+            FieldType::Bytes(_) => Value::Bytes(bytes),
+        })
+    }
+}
+
+/// Primitive types a payload field can have.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldType {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    Bool,
+    Pubkey,
+    /// A fixed-size byte array.
+    Bytes(usize),
+}
+
+impl FieldType {
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        match self {
+            Self::U8 | Self::I8 | Self::Bool => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+            Self::U128 | Self::I128 => 16,
+            Self::Pubkey => 32,
+            Self::Bytes(len) => *len,
+        }
+    }
+}
+
+/// A decoded field value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'a> {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Pubkey(Pubkey),
+    Bytes(&'a [u8]),
+}
+
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::U8(value) => value.fmt(f),
+            Self::U16(value) => value.fmt(f),
+            Self::U32(value) => value.fmt(f),
+            Self::U64(value) => value.fmt(f),
+            Self::U128(value) => value.fmt(f),
+            Self::I8(value) => value.fmt(f),
+            Self::I16(value) => value.fmt(f),
+            Self::I32(value) => value.fmt(f),
+            Self::I64(value) => value.fmt(f),
+            Self::I128(value) => value.fmt(f),
+            Self::F32(value) => value.fmt(f),
+            Self::F64(value) => value.fmt(f),
+            Self::Bool(value) => value.fmt(f),
+            Self::Pubkey(value) => value.fmt(f),
+            Self::Bytes(bytes) => {
+                f.write_str("0x")?;
+                bytes.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+        }
+    }
+}
+
+/// Implements [`PayloadSchema`] for a `#[repr(C)]` payload struct, taking
+/// field offsets from the compiler:
+///
+/// ```
+/// #[repr(C)]
+/// #[derive(Clone, Copy)]
+/// struct PropAMM {
+///     bid: u64,
+///     ask: u64,
+///     pool: [u8; 32],
+/// }
+///
+/// doppler_sdk::payload_schema!(PropAMM {
+///     bid: U64,
+///     ask: U64,
+///     pool: Bytes(32),
+/// });
+/// ```
+#[macro_export]
+macro_rules! payload_schema {
+    ($payload:ident { $($field:ident: $ty:ident $(($len:expr))?),* $(,)? }) => {
+        impl $crate::schema::PayloadSchema for $payload {
+            const SCHEMA: $crate::schema::Schema = $crate::schema::Schema {
+                name: stringify!($payload),
+                size: ::core::mem::size_of::<$payload>(),
+                fields: &[$(
+                    $crate::schema::Field {
+                        name: stringify!($field),
+                        offset: ::core::mem::offset_of!($payload, $field),
+                        ty: $crate::schema::FieldType::$ty $(($len))?,
+                    },
+                )*],
+            };
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct Quote {
+        flags: u8,
+        price: u64,
+        mint: [u8; 32],
+    }
+
+    crate::payload_schema!(Quote {
+        flags: U8,
+        price: U64,
+        mint: Pubkey,
+    });
+
+    #[test]
+    fn test_schema_reads_fields() {
+        let schema = Quote::SCHEMA;
+        assert_eq!(schema.name, "Quote");
+        assert_eq!(schema.size, 48);
+        assert_eq!(schema.fields[1].offset, 8);
+
+        let mut payload = [0u8; 48];
+        payload[0] = 1;
+        payload[8..16].copy_from_slice(&1_100_000u64.to_le_bytes());
+
+        let fields: Vec<_> = schema.read(&payload).collect();
+        assert_eq!(fields[0], ("flags", Some(Value::U8(1))));
+        assert_eq!(fields[1], ("price", Some(Value::U64(1_100_000))));
+        assert_eq!(fields[2], ("mint", Some(Value::Pubkey(Pubkey::default()))));
+
+        assert_eq!(schema.read(&payload[..16]).last(), Some(("mint", None)));
+    }
+}
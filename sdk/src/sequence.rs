@@ -0,0 +1,72 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static LAST_MILLIS: AtomicU64 = AtomicU64::new(0);
+
+/// Sequence numbers for oracles that use wall-clock time as their sequence.
+pub struct Sequence;
+
+impl Sequence {
+    /// Returns the current Unix time in milliseconds, bumped if needed so it is
+    /// strictly greater than any value previously returned in this process.
+    ///
+    /// Backwards clock steps (NTP corrections, VM migrations) or several calls
+    /// within the same millisecond would otherwise produce a sequence the
+    /// program rejects as stale.
+    pub fn now_millis() -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+
+        next(&LAST_MILLIS, now)
+    }
+}
+
+/// Returns `now`, or one past the value in `last` if that is not earlier,
+/// and stores the result in `last`.
+fn next(last: &AtomicU64, now: u64) -> u64 {
+    let prev = last
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+            Some(now.max(last + 1))
+        })
+        .unwrap_or_else(|last| last);
+
+    now.max(prev + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_now_millis_is_strictly_increasing() {
+        let mut last = Sequence::now_millis();
+        for _ in 0..10_000 {
+            let next = Sequence::now_millis();
+            assert!(next > last);
+            last = next;
+        }
+    }
+
+    #[test]
+    fn test_next_bumps_within_the_same_millisecond() {
+        let last = AtomicU64::new(0);
+
+        assert_eq!(next(&last, 1_000), 1_000);
+        assert_eq!(next(&last, 1_000), 1_001);
+        assert_eq!(next(&last, 1_000), 1_002);
+        assert_eq!(next(&last, 1_005), 1_005);
+        assert_eq!(last.load(Ordering::Acquire), 1_005);
+    }
+
+    #[test]
+    fn test_next_survives_clock_step_back() {
+        let last = AtomicU64::new(0);
+
+        assert_eq!(next(&last, 61_000), 61_000);
+        assert_eq!(next(&last, 1_000), 61_001);
+        assert_eq!(next(&last, 61_500), 61_500);
+    }
+}
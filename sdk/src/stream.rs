@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use futures::{future, Stream, StreamExt};
+use solana_pubkey::Pubkey;
+
+use crate::accounts::Oracle;
+
+/// An oracle observation that carries the account it was read from and its
+/// sequence.
+pub trait Sequenced {
+    /// The oracle and sequence to deduplicate on, or `None` for items that
+    /// should always be passed through, such as errors.
+    fn sequence_key(&self) -> Option<(Pubkey, u64)>;
+}
+
+impl<T: Sized + Copy> Sequenced for (Pubkey, Oracle<T>) {
+    fn sequence_key(&self) -> Option<(Pubkey, u64)> {
+        Some((self.0, self.1.sequence))
+    }
+}
+
+#[cfg(feature = "geyser")]
+impl<T: Sized + Copy> Sequenced for crate::geyser::OracleUpdate<T> {
+    fn sequence_key(&self) -> Option<(Pubkey, u64)> {
+        Some((self.pubkey, self.oracle.sequence))
+    }
+}
+
+impl<S: Sequenced, E> Sequenced for Result<S, E> {
+    fn sequence_key(&self) -> Option<(Pubkey, u64)> {
+        self.as_ref().ok().and_then(Sequenced::sequence_key)
+    }
+}
+
+/// Drops updates whose sequence has not advanced past the last one seen for
+/// the same oracle.
+///
+/// RPC and Geyser both re-notify on writes that did not change the account,
+/// and reconnects replay the current value, so downstream consumers would
+/// otherwise see the same observation several times.
+pub fn dedup_by_sequence<S>(updates: S) -> impl Stream<Item = S::Item>
+where
+    S: Stream,
+    S::Item: Sequenced,
+{
+    let mut latest = LatestSequences::default();
+
+    updates.filter(move |update| {
+        future::ready(match update.sequence_key() {
+            Some((pubkey, sequence)) => latest.advance(pubkey, sequence),
+            None => true,
+        })
+    })
+}
+
+/// The highest sequence seen per oracle.
+#[derive(Default)]
+pub(crate) struct LatestSequences(HashMap<Pubkey, u64>);
+
+impl LatestSequences {
+    /// Records `sequence` for `pubkey`, returning whether it is newer than
+    /// anything seen before.
+    pub(crate) fn advance(&mut self, pubkey: Pubkey, sequence: u64) -> bool {
+        match self.0.get_mut(&pubkey) {
+            Some(latest) if *latest >= sequence => false,
+            Some(latest) => {
+                *latest = sequence;
+                true
+            }
+            None => {
+                self.0.insert(pubkey, sequence);
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, stream};
+
+    use super::*;
+
+    fn update(pubkey: Pubkey, sequence: u64) -> Result<(Pubkey, Oracle<u64>), &'static str> {
+        Ok((
+            pubkey,
+            Oracle {
+                sequence,
+                payload: 0,
+            },
+        ))
+    }
+
+    #[test]
+    fn test_latest_sequences_drops_replays() {
+        let mut latest = LatestSequences::default();
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        assert!(latest.advance(a, 5));
+        assert!(!latest.advance(a, 5));
+        assert!(!latest.advance(a, 4));
+        assert!(latest.advance(a, 6));
+        assert!(latest.advance(b, 1));
+    }
+
+    #[test]
+    fn test_dedup_by_sequence() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+
+        let updates = stream::iter([
+            update(a, 1),
+            update(a, 1),
+            update(b, 1),
+            Err("disconnected"),
+            update(a, 2),
+            update(b, 1),
+        ]);
+
+        let seen: Vec<_> = block_on(dedup_by_sequence(updates).collect());
+
+        assert_eq!(
+            seen.iter()
+                .map(|update| update
+                    .as_ref()
+                    .map(|(pubkey, oracle)| (*pubkey, oracle.sequence)))
+                .collect::<Vec<_>>(),
+            vec![Ok((a, 1)), Ok((b, 1)), Err(&"disconnected"), Ok((a, 2))]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Decodes Switchboard On-Demand pull feed accounts, for tooling that reads
+//! a Switchboard price next to or ahead of migrating it to Doppler, and
+//! rescales fixed-point prices from any source to a feed's exponent.
+
+use solana_pubkey::Pubkey;
+
+/// Switchboard On-Demand program, owner of pull feed accounts.
+pub const ON_DEMAND_ID: Pubkey =
+    Pubkey::from_str_const("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+
+/// Size of a `PullFeedAccountData` account, discriminator included.
+pub const PULL_FEED_LEN: usize = 3208;
+/// Offset of `last_update_timestamp`, an `i64` of Unix seconds.
+pub const LAST_UPDATE_OFFSET: usize = 2216;
+/// Offset of `result.value`, an `i128` with [`RESULT_DECIMALS`] decimals.
+pub const RESULT_OFFSET: usize = 2264;
+/// Decimal places of pull feed results.
+pub const RESULT_DECIMALS: u32 = 18;
+
+/// The current result of a pull feed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PullFeedResult {
+    /// The value, with [`RESULT_DECIMALS`] decimal places.
+    pub value: i128,
+    /// When the feed was last updated, in Unix seconds.
+    pub last_update_timestamp: i64,
+}
+
+impl PullFeedResult {
+    /// Reads the current result from a pull feed account's data, or `None`
+    /// if the data is not the size of a pull feed. The account's owner is
+    /// not known here; check it against [`ON_DEMAND_ID`].
+    #[must_use]
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != PULL_FEED_LEN {
+            return None;
+        }
+
+        Some(Self {
+            value: i128::from_le_bytes(data[RESULT_OFFSET..RESULT_OFFSET + 16].try_into().ok()?),
+            last_update_timestamp: i64::from_le_bytes(
+                data[LAST_UPDATE_OFFSET..LAST_UPDATE_OFFSET + 8]
+                    .try_into()
+                    .ok()?,
+            ),
+        })
+    }
+
+    /// When the feed was last updated, in Unix milliseconds, or `None` if
+    /// the timestamp is negative.
+    #[must_use]
+    pub fn last_update_ms(&self) -> Option<u64> {
+        u64::try_from(self.last_update_timestamp)
+            .ok()?
+            .checked_mul(1000)
+    }
+}
+
+/// Converts a fixed-point `value * 10^expo` to a count of `10^exponent`
+/// units, truncating any finer precision. Negative values and values that
+/// overflow a `u64` don't fit a `PriceFeed` and return `None`.
+#[must_use]
+pub fn rescale(value: i128, expo: i32, exponent: i32) -> Option<u64> {
+    let value = u128::try_from(value).ok()?;
+    let shift = expo.checked_sub(exponent)?;
+
+    let scaled = if shift >= 0 {
+        value.checked_mul(10u128.checked_pow(shift.unsigned_abs())?)?
+    } else {
+        10u128
+            .checked_pow(shift.unsigned_abs())
+            .map_or(0, |divisor| value / divisor)
+    };
+
+    u64::try_from(scaled).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(
+            rescale(161_450_000_000_000_000_000, -18, -6),
+            Some(161_450_000)
+        );
+        assert_eq!(rescale(16_145, -2, -6), Some(161_450_000));
+        assert_eq!(rescale(1, -80, -6), Some(0));
+        assert_eq!(rescale(-1, -8, -6), None);
+        assert_eq!(rescale(i128::MAX, 0, -6), None);
+    }
+
+    #[test]
+    fn test_decode_pull_feed() {
+        let mut data = [0; PULL_FEED_LEN];
+        data[LAST_UPDATE_OFFSET..LAST_UPDATE_OFFSET + 8]
+            .copy_from_slice(&1_700_000_000i64.to_le_bytes());
+        data[RESULT_OFFSET..RESULT_OFFSET + 16]
+            .copy_from_slice(&161_450_000_000_000_000_000i128.to_le_bytes());
+
+        let result = PullFeedResult::decode(&data).unwrap();
+        assert_eq!(result.value, 161_450_000_000_000_000_000);
+        assert_eq!(result.last_update_ms(), Some(1_700_000_000_000));
+
+        assert_eq!(PullFeedResult::decode(&data[..PULL_FEED_LEN - 1]), None);
+
+        data[LAST_UPDATE_OFFSET..LAST_UPDATE_OFFSET + 8].copy_from_slice(&(-1i64).to_le_bytes());
+        assert_eq!(
+            PullFeedResult::decode(&data).unwrap().last_update_ms(),
+            None
+        );
+    }
+}
@@ -1,32 +1,214 @@
+#[cfg(feature = "blocking")]
+use std::collections::HashMap;
+
+#[cfg(feature = "nonblocking")]
+use futures::future::join_all;
+#[cfg(feature = "blocking")]
+use solana_account_decoder_client_types::UiAccountEncoding;
+#[cfg(any(feature = "blocking", feature = "nonblocking"))]
+use solana_client::client_error::Result as ClientResult;
+#[cfg(feature = "nonblocking")]
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+#[cfg(feature = "tpu")]
+use solana_client::tpu_client::TpuClient;
+#[cfg(feature = "blocking")]
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::{
+        RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
+    },
+    rpc_response::{Response, RpcSimulateTransactionResult},
+};
+#[cfg(feature = "blocking")]
+use solana_commitment_config::CommitmentConfig;
 use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_hash::Hash;
+#[cfg(feature = "blocking")]
+use solana_instruction::error::InstructionError;
 use solana_instruction::Instruction;
 use solana_keypair::Keypair;
+use solana_message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
 use solana_pubkey::Pubkey;
-use solana_signer::Signer as _;
-use solana_transaction::Transaction;
+#[cfg(feature = "tpu")]
+use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
+use solana_signature::Signature;
+use solana_signer::Signer;
+#[cfg(feature = "nonce")]
+use solana_system_interface::instruction::advance_nonce_account;
+#[cfg(feature = "jito")]
+use solana_system_interface::instruction::transfer;
+use solana_transaction::{versioned::VersionedTransaction, Transaction};
+#[cfg(feature = "blocking")]
+use solana_transaction_error::TransactionError;
+#[cfg(feature = "tpu")]
+use solana_transaction_error::{TransportError, TransportResult};
+#[cfg(feature = "blocking")]
+use solana_transaction_status_client_types::UiTransactionEncoding;
 
 use crate::accounts::{Oracle, UpdateInstruction};
+use crate::cluster::Cluster;
+#[cfg(any(feature = "jito", feature = "nonce", feature = "wormhole"))]
+use crate::constants::SYSTEM_PROGRAM_SIZE;
+#[cfg(any(feature = "jito", feature = "wormhole"))]
+use crate::constants::SYSTEM_TRANSFER_CU;
+#[cfg(feature = "blocking")]
+use crate::constants::{ADMIN_CHECK_EXIT_CODE, INVALID_SEQUENCE_EXIT_CODE};
 use crate::constants::{
     COMPUTE_BUDGET_DATA_LIMIT_SIZE, COMPUTE_BUDGET_IX_CU, COMPUTE_BUDGET_PROGRAM_SIZE,
-    COMPUTE_BUDGET_UNIT_LIMIT_SIZE, COMPUTE_BUDGET_UNIT_PRICE_SIZE, ORACLE_PROGRAM_SIZE,
+    COMPUTE_BUDGET_UNIT_LIMIT_SIZE, COMPUTE_BUDGET_UNIT_PRICE_SIZE, MAX_COMPUTE_UNIT_LIMIT,
+    ORACLE_PROGRAM_SIZE, PACKET_DATA_SIZE,
+};
+#[cfg(feature = "nonce")]
+use crate::constants::{
+    NONCE_ACCOUNT_SIZE, RECENT_BLOCKHASHES_SYSVAR_SIZE, SYSTEM_ADVANCE_NONCE_CU,
+};
+#[cfg(feature = "blocking")]
+use crate::constants::{PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+#[cfg(feature = "wormhole")]
+use crate::constants::{
+    WORMHOLE_ACCOUNTS_SIZE, WORMHOLE_MESSAGE_HEADER_SIZE, WORMHOLE_POST_MESSAGE_CU,
 };
+use crate::error::{BuildError, SigningError};
+#[cfg(feature = "blocking")]
+use crate::error::{DopplerProgramError, SimulationError};
+#[cfg(feature = "wormhole")]
+use crate::wormhole::{CoreBridge, Emission, ORACLE_OFFSET};
+
+/// Lamport cost of landing a built transaction.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// Signature fee charged regardless of priority.
+    pub base_fee: u64,
+    /// Compute unit price multiplied by the requested compute unit limit.
+    pub priority_fee: u64,
+    /// Lamports the updated oracle accounts must hold to stay rent exempt.
+    pub rent_exempt_reserve: u64,
+}
+
+#[cfg(feature = "blocking")]
+impl CostEstimate {
+    /// Lamports paid by the fee payer to land the transaction.
+    #[must_use]
+    pub const fn total_fee(&self) -> u64 {
+        self.base_fee + self.priority_fee
+    }
+}
+
+/// Leader-aware QUIC client used by [`Builder::send_via_tpu`], created with
+/// `TpuClient::new`.
+#[cfg(feature = "tpu")]
+pub type QuicTpuClient = TpuClient<QuicPool, QuicConnectionManager, QuicConfig>;
+
+/// A confirmed transaction along with what it cost to land.
+#[cfg(feature = "blocking")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SendOutcome {
+    pub signature: Signature,
+    pub slot: u64,
+    /// Compute units consumed, if reported by the RPC node.
+    pub compute_units_consumed: Option<u64>,
+    pub fee_lamports: u64,
+}
+
+/// The result of simulating a transaction with [`Builder::dry_run`].
+#[cfg(feature = "blocking")]
+#[derive(Clone, Debug)]
+pub struct DryRun {
+    /// Slot the simulation ran against.
+    pub slot: u64,
+    pub err: Option<TransactionError>,
+    pub logs: Vec<String>,
+    /// Compute units consumed, if reported by the RPC node.
+    pub compute_units_consumed: Option<u64>,
+    /// Simulated data of each updated oracle after the transaction, in
+    /// update order, or `None` if the node did not return the account.
+    pub oracles: Vec<(Pubkey, Option<Vec<u8>>)>,
+}
+
+#[cfg(feature = "blocking")]
+impl DryRun {
+    /// Decodes the simulated post-state of `oracle_pubkey`.
+    #[must_use]
+    pub fn oracle<T: Sized + Copy>(&self, oracle_pubkey: &Pubkey) -> Option<Oracle<T>> {
+        let (_, data) = self
+            .oracles
+            .iter()
+            .find(|(pubkey, _)| pubkey == oracle_pubkey)?;
+
+        Oracle::try_from_bytes(data.as_deref()?).map(|(oracle, _)| oracle)
+    }
+}
+
+/// What an oracle update adds to its transaction.
+#[derive(Clone, Copy)]
+struct UpdateCost {
+    compute_units: u32,
+    loaded_account_data_size: u32,
+    /// Size of the oracle account, which its rent-exempt reserve is based on.
+    #[cfg_attr(not(feature = "blocking"), allow(dead_code))]
+    oracle_len: usize,
+}
 
+/// Assembles oracle update transactions with matching compute budget
+/// instructions.
+///
+/// A keeper publishing the same feeds every tick can build a template once,
+/// then clone it and refresh each feed with [`Builder::set_oracle`] instead
+/// of re-adding updates.
+#[derive(Clone)]
 pub struct Builder<'a> {
     oracle_update_ixs: Vec<Instruction>,
-    admin: &'a Keypair,
+    update_costs: Vec<UpdateCost>,
+    admin: Pubkey,
+    fee_payer: Option<Pubkey>,
+    signer: Option<&'a Keypair>,
+    fee_payer_signer: Option<&'a Keypair>,
+    cluster: Cluster,
+    #[cfg(feature = "blocking")]
+    auto_sequenced: Vec<usize>,
+    #[cfg(feature = "nonce")]
+    durable_nonce: Option<(Pubkey, Pubkey)>,
+    #[cfg(feature = "wormhole")]
+    emissions: Vec<Emission<'a>>,
     unit_price: Option<u64>,
     compute_units: u32,
     loaded_account_data_size: u32,
+    max_compute_units: u32,
+    lookup_tables: Vec<AddressLookupTableAccount>,
 }
 
 impl<'a> Builder<'a> {
     #[must_use]
-    pub const fn new(admin: &'a Keypair) -> Self {
+    pub fn new(admin: &'a Keypair) -> Self {
+        Self {
+            signer: Some(admin),
+            ..Builder::new_offline(admin.pubkey())
+        }
+    }
+
+    /// Creates a builder that only knows the admin's public key, for
+    /// assembling messages that are signed on another machine.
+    #[must_use]
+    pub const fn new_offline(admin: Pubkey) -> Self {
         Self {
             admin,
+            fee_payer: None,
+            signer: None,
+            fee_payer_signer: None,
+            cluster: Cluster::Mainnet,
             oracle_update_ixs: vec![],
+            update_costs: vec![],
+            #[cfg(feature = "blocking")]
+            auto_sequenced: vec![],
+            #[cfg(feature = "nonce")]
+            durable_nonce: None,
+            #[cfg(feature = "wormhole")]
+            emissions: vec![],
             unit_price: None,
+            max_compute_units: MAX_COMPUTE_UNIT_LIMIT,
+            lookup_tables: vec![],
             compute_units: COMPUTE_BUDGET_IX_CU * 2, // default 2 compute budget ixs
             loaded_account_data_size: ORACLE_PROGRAM_SIZE
                 + COMPUTE_BUDGET_PROGRAM_SIZE
@@ -42,53 +224,1657 @@ impl<'a> Builder<'a> {
         oracle: Oracle<T>,
     ) -> Self {
         let update_ix = UpdateInstruction {
-            admin: self.admin.pubkey(),
+            admin: self.admin,
             oracle_pubkey,
             oracle,
         };
 
-        self.compute_units += update_ix.compute_units();
-        self.loaded_account_data_size += update_ix.loaded_accounts_data_size_limit() * 2;
+        let cost = UpdateCost {
+            compute_units: update_ix.compute_units(),
+            loaded_account_data_size: update_ix.loaded_accounts_data_size_limit() * 2,
+            oracle_len: Oracle::<T>::LEN,
+        };
+        self.push_update(update_ix.into_instruction(self.cluster), cost);
+
+        self
+    }
+
+    /// Adds an update followed by a Wormhole message carrying it, posted to
+    /// `bridge` into the `message` account with the admin as emitter. See
+    /// [`crate::wormhole`] for the message layout.
+    ///
+    /// The fee payer pays the bridge fee and, the first time `message` is
+    /// used, its rent. `message` signs alongside the admin.
+    #[cfg(feature = "wormhole")]
+    pub fn add_oracle_update_with_wormhole<T: Sized + Copy>(
+        mut self,
+        oracle_pubkey: Pubkey,
+        oracle: Oracle<T>,
+        bridge: CoreBridge,
+        message: &'a Keypair,
+    ) -> Self {
+        let update_ix = UpdateInstruction {
+            admin: self.admin,
+            oracle_pubkey,
+            oracle,
+        };
 
-        self.oracle_update_ixs.push(update_ix.into());
+        let cost = UpdateCost {
+            compute_units: update_ix.compute_units()
+                + SYSTEM_TRANSFER_CU
+                + WORMHOLE_POST_MESSAGE_CU,
+            loaded_account_data_size: update_ix.loaded_accounts_data_size_limit() * 2
+                + SYSTEM_PROGRAM_SIZE
+                + WORMHOLE_ACCOUNTS_SIZE
+                + WORMHOLE_MESSAGE_HEADER_SIZE
+                + (ORACLE_OFFSET + Oracle::<T>::ENCODED_LEN) as u32,
+            oracle_len: Oracle::<T>::LEN,
+        };
+        self.push_update(update_ix.into_instruction(self.cluster), cost);
+        self.emissions.push(Emission {
+            oracle_pubkey,
+            bridge,
+            message,
+        });
 
         self
     }
 
+    /// Replaces the sequence and payload of the update queued for
+    /// `oracle_pubkey`, keeping its compute budget accounting.
+    ///
+    /// # Panics
+    /// Panics if no update is queued for `oracle_pubkey` or `T` encodes to a
+    /// different size than the queued payload.
+    pub fn set_oracle<T: Sized + Copy>(&mut self, oracle_pubkey: &Pubkey, oracle: Oracle<T>) {
+        let oracle_ix = self
+            .oracle_update_ixs
+            .iter_mut()
+            .find(|ix| ix.accounts[1].pubkey == *oracle_pubkey)
+            .expect("no update queued for oracle");
+        assert_eq!(
+            oracle_ix.data.len(),
+            Oracle::<T>::ENCODED_LEN,
+            "payload size differs from the queued update"
+        );
+
+        oracle.encode_into(&mut oracle_ix.data);
+    }
+
+    /// Adds an update whose sequence is one past the oracle's current
+    /// on-chain sequence. If [`Builder::send_and_confirm`] is rejected for a
+    /// stale sequence, these updates are refetched and resent once.
+    #[cfg(feature = "blocking")]
+    pub fn add_oracle_update_auto_seq<T: Sized + Copy>(
+        mut self,
+        client: &RpcClient,
+        oracle_pubkey: Pubkey,
+        payload: T,
+    ) -> ClientResult<Self> {
+        let sequence = crate::fetch::sequence_with_config(
+            client,
+            &oracle_pubkey,
+            self.cluster,
+            client.commitment(),
+            None,
+        )?;
+        self.auto_sequenced.push(self.oracle_update_ixs.len());
+
+        Ok(self.add_oracle_update(
+            oracle_pubkey,
+            Oracle {
+                sequence: sequence + 1,
+                payload,
+            },
+        ))
+    }
+
     #[must_use]
     pub const fn with_unit_price(mut self, micro_lamports: u64) -> Self {
         self.unit_price = Some(micro_lamports);
         self
     }
 
+    /// Uses the durable nonce in `nonce_pubkey` instead of a recent
+    /// blockhash, so the transaction can be signed offline and sent later.
+    /// Pass the nonce's stored blockhash (see [`crate::nonce::fetch`]) as
+    /// the blockhash when building.
+    ///
+    /// `nonce_authority` must also sign; [`Builder::build`] only signs with
+    /// the admin, so use [`Builder::build_unsigned`] for other authorities.
+    ///
+    /// Only the first transaction advancing a nonce lands, so
+    /// [`Builder::pack`] and [`Builder::send_updates`] fail rather than
+    /// spread the updates over several transactions sharing the nonce.
+    #[cfg(feature = "nonce")]
+    #[must_use]
+    pub const fn with_durable_nonce(
+        mut self,
+        nonce_pubkey: Pubkey,
+        nonce_authority: Pubkey,
+    ) -> Self {
+        if self.durable_nonce.is_none() {
+            self.compute_units += SYSTEM_ADVANCE_NONCE_CU;
+            self.loaded_account_data_size +=
+                SYSTEM_PROGRAM_SIZE + NONCE_ACCOUNT_SIZE + RECENT_BLOCKHASHES_SYSVAR_SIZE;
+        }
+        self.durable_nonce = Some((nonce_pubkey, nonce_authority));
+        self
+    }
+
+    /// Caps the compute unit limit of each transaction produced by
+    /// [`Builder::pack`] and [`Builder::build_all`]. Defaults to the
+    /// per-transaction maximum.
+    #[must_use]
+    pub const fn with_max_compute_units(mut self, max_compute_units: u32) -> Self {
+        self.max_compute_units = max_compute_units;
+        self
+    }
+
+    /// Loads accounts from `lookup_tables` in the v0 transactions built by
+    /// [`Builder::build_versioned`] and [`Builder::build_all_versioned`], and
+    /// sizes the transactions of [`Builder::pack`] as such. The other build
+    /// methods still produce legacy transactions.
+    #[must_use]
+    pub fn with_lookup_tables(mut self, lookup_tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = lookup_tables;
+        self
+    }
+
+    /// Targets the Doppler deployment on `cluster` instead of mainnet,
+    /// including for updates that were already added.
+    #[must_use]
+    pub fn with_cluster(mut self, cluster: Cluster) -> Self {
+        self.cluster = cluster;
+        for ix in &mut self.oracle_update_ixs {
+            ix.program_id = cluster.program_id();
+        }
+        self
+    }
+
+    /// Pays transaction fees from `fee_payer` instead of the admin.
+    #[must_use]
+    pub const fn with_fee_payer(mut self, fee_payer: Pubkey) -> Self {
+        self.fee_payer = Some(fee_payer);
+        self.fee_payer_signer = None;
+        self
+    }
+
+    /// Pays transaction fees from `fee_payer` instead of the admin, signing
+    /// with it alongside the admin.
+    #[must_use]
+    pub fn with_fee_payer_keypair(mut self, fee_payer: &'a Keypair) -> Self {
+        self.fee_payer = Some(fee_payer.pubkey());
+        self.fee_payer_signer = Some(fee_payer);
+        self
+    }
+
+    /// Signs the transaction, failing if it would not fit in a single
+    /// packet.
+    ///
+    /// # Panics
+    /// Panics if the builder was created with [`Builder::new_offline`] or
+    /// has a fee payer given without its keypair; use
+    /// [`Builder::build_unsigned`] for those.
+    pub fn build(self, recent_blockhash: Hash) -> Result<Transaction, BuildError> {
+        let signers = self.signers();
+        let fee_payer = self.fee_payer();
+        let ixs = self.into_instructions();
+
+        check_size(Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&fee_payer),
+            &signers,
+            recent_blockhash,
+        ))
+    }
+
+    /// Partitions the oracle updates into builders that each stay within
+    /// the packet size and the compute unit cap set by
+    /// [`Builder::with_max_compute_units`], keeping the signers and fee
+    /// settings.
+    ///
+    /// Each update goes into the first builder it fits in, so a large
+    /// payload that spills into a new transaction does not strand the
+    /// space left by the one before it. An update that does not fit on its
+    /// own gets a builder to itself.
+    ///
+    /// Sizes are those of legacy messages, where every account is listed in
+    /// full, unless lookup tables were given with
+    /// [`Builder::with_lookup_tables`]. Then they are those of v0 messages
+    /// loading oracles from the tables, and updates are grouped by the
+    /// first table holding their oracle, so each transaction loads as few
+    /// tables as possible. Updates keep their order within a table.
+    ///
+    /// Fails with a durable nonce if the updates need more than one
+    /// transaction, since only the first to advance the nonce would land.
+    pub fn pack(self) -> Result<Vec<Self>, BuildError> {
+        let mut chunks: Vec<Self> = vec![];
+
+        let mut updates: Vec<_> = self
+            .oracle_update_ixs
+            .iter()
+            .zip(&self.update_costs)
+            .collect();
+        updates.sort_by_key(|(ix, _)| self.lookup_table_index(&ix.accounts[1].pubkey));
+
+        for (ix, cost) in updates {
+            let chunk = chunks.iter_mut().find(|chunk| {
+                let mut candidate = Self::clone(chunk);
+                candidate.push_packed(&self, ix.clone(), *cost);
+                candidate.fits()
+            });
+
+            match chunk {
+                Some(chunk) => chunk.push_packed(&self, ix.clone(), *cost),
+                None => {
+                    let mut chunk = self.without_updates();
+                    chunk.push_packed(&self, ix.clone(), *cost);
+                    chunks.push(chunk);
+                }
+            }
+        }
+
+        #[cfg(feature = "nonce")]
+        if self.durable_nonce.is_some() && chunks.len() > 1 {
+            return Err(BuildError::NonceReused {
+                transactions: chunks.len(),
+            });
+        }
+
+        Ok(chunks)
+    }
+
+    /// Packs the oracle updates into as few signed transactions as possible
+    /// with [`Builder::pack`].
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    pub fn build_all(self, recent_blockhash: Hash) -> Result<Vec<Transaction>, BuildError> {
+        self.pack()?
+            .into_iter()
+            .map(|chunk| chunk.build(recent_blockhash))
+            .collect()
+    }
+
+    /// Signs a v0 transaction loading accounts from the lookup tables set
+    /// with [`Builder::with_lookup_tables`], failing if it would not fit in
+    /// a single packet.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    pub fn build_versioned(
+        self,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction, BuildError> {
+        let signers = self.signers();
+        let message = self.compile_v0(recent_blockhash)?;
+
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &signers)
+            .expect("the builder's signers sign its message");
+        let size = versioned_size(&tx);
+
+        if size > PACKET_DATA_SIZE {
+            return Err(BuildError::TooLarge {
+                size,
+                max: PACKET_DATA_SIZE,
+            });
+        }
+
+        Ok(tx)
+    }
+
+    /// Packs the oracle updates into as few signed v0 transactions as
+    /// possible with [`Builder::pack`], loading accounts from the lookup
+    /// tables set with [`Builder::with_lookup_tables`].
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    pub fn build_all_versioned(
+        self,
+        recent_blockhash: Hash,
+    ) -> Result<Vec<VersionedTransaction>, BuildError> {
+        self.pack()?
+            .into_iter()
+            .map(|chunk| chunk.build_versioned(recent_blockhash))
+            .collect()
+    }
+
+    /// Builds the transaction without signatures, to be completed one signer
+    /// at a time with [`PartialTransaction::sign_with`].
+    #[must_use]
+    pub fn build_unsigned(self, recent_blockhash: Hash) -> PartialTransaction {
+        let fee_payer = self.fee_payer();
+        let ixs = self.into_instructions();
+
+        PartialTransaction {
+            transaction: Transaction::new_unsigned(Message::new_with_blockhash(
+                &ixs,
+                Some(&fee_payer),
+                &recent_blockhash,
+            )),
+        }
+    }
+
+    /// Builds the unsigned transaction message, to be signed offline and
+    /// completed with [`attach_signatures`].
+    #[must_use]
+    pub fn build_message(self, recent_blockhash: Hash) -> VersionedMessage {
+        let fee_payer = self.fee_payer();
+        let ixs = self.into_instructions();
+
+        VersionedMessage::Legacy(Message::new_with_blockhash(
+            &ixs,
+            Some(&fee_payer),
+            &recent_blockhash,
+        ))
+    }
+
+    /// Builds a Jito bundle whose transaction ends with a `tip_lamports`
+    /// transfer from the fee payer to `tip_account`, failing if it would
+    /// not fit in a single packet.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    #[cfg(feature = "jito")]
+    pub fn build_bundle(
+        mut self,
+        tip_lamports: u64,
+        tip_account: Pubkey,
+        recent_blockhash: Hash,
+    ) -> Result<Vec<Transaction>, BuildError> {
+        let signers = self.signers();
+        let fee_payer = self.fee_payer();
+        let tip_ix = transfer(&fee_payer, &tip_account, tip_lamports);
+
+        self.compute_units += SYSTEM_TRANSFER_CU;
+        self.loaded_account_data_size += SYSTEM_PROGRAM_SIZE;
+
+        let mut ixs = self.into_instructions();
+        ixs.push(tip_ix);
+
+        Ok(vec![check_size(Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&fee_payer),
+            &signers,
+            recent_blockhash,
+        ))?])
+    }
+
+    /// Signs with the latest blockhash, sends and confirms the transaction.
+    /// Fails before sending if the transaction would not fit in a packet.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    #[cfg(feature = "blocking")]
+    pub fn send_and_confirm(mut self, client: &RpcClient) -> ClientResult<SendOutcome> {
+        let result =
+            client.send_and_confirm_transaction(&self.sign(client.get_latest_blockhash()?)?);
+
+        let result = match result {
+            Err(err) if self.is_auto_sequence_error(err.get_transaction_error()) => {
+                for index in self.auto_sequenced.clone() {
+                    let oracle_ix = &mut self.oracle_update_ixs[index];
+                    let sequence = crate::fetch::sequence_with_config(
+                        client,
+                        &oracle_ix.accounts[1].pubkey,
+                        self.cluster,
+                        client.commitment(),
+                        None,
+                    )?;
+                    oracle_ix.data[SEQUENCE_OFFSET..PAYLOAD_OFFSET]
+                        .copy_from_slice(&(sequence + 1).to_le_bytes());
+                }
+
+                client.send_and_confirm_transaction(&self.sign(client.get_latest_blockhash()?)?)
+            }
+            result => result,
+        };
+
+        send_outcome(client, result?)
+    }
+
+    /// Sends each update in its own transaction, using this builder's signer
+    /// and fee settings, and confirms them concurrently so a lagging feed
+    /// does not hold up the rest. Updates already added to the builder are
+    /// not included.
+    ///
+    /// Returns each feed's result in the order given. Fails with a durable
+    /// nonce if there is more than one update, since each transaction would
+    /// advance the same nonce.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    #[cfg(feature = "nonblocking")]
+    pub async fn send_updates<T: Sized + Copy>(
+        &self,
+        client: &NonblockingRpcClient,
+        updates: Vec<(Pubkey, Oracle<T>)>,
+    ) -> ClientResult<Vec<(Pubkey, ClientResult<Signature>)>> {
+        #[cfg(feature = "nonce")]
+        if self.durable_nonce.is_some() && updates.len() > 1 {
+            return Err(BuildError::NonceReused {
+                transactions: updates.len(),
+            }
+            .into());
+        }
+
+        let recent_blockhash = client.get_latest_blockhash().await?;
+
+        let sends = updates
+            .into_iter()
+            .map(|(oracle_pubkey, oracle)| async move {
+                let result = match self
+                    .without_updates()
+                    .add_oracle_update(oracle_pubkey, oracle)
+                    .build(recent_blockhash)
+                {
+                    Ok(tx) => client.send_and_confirm_transaction(&tx).await,
+                    Err(err) => Err(err.into()),
+                };
+
+                (oracle_pubkey, result)
+            });
+
+        Ok(join_all(sends).await)
+    }
+
+    /// Signs the transaction and pushes it straight to the current and
+    /// upcoming leaders over QUIC instead of through an RPC node. Delivery is
+    /// not confirmed; poll the returned signature to track landing.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    #[cfg(feature = "tpu")]
+    pub fn send_via_tpu(
+        &self,
+        tpu_client: &QuicTpuClient,
+        recent_blockhash: Hash,
+    ) -> TransportResult<Signature> {
+        let tx = self
+            .sign(recent_blockhash)
+            .map_err(|err| TransportError::Custom(err.to_string()))?;
+
+        tpu_client.try_send_transaction(&tx)?;
+
+        Ok(tx.signatures[0])
+    }
+
+    /// Simulates the transaction and checks that every oracle account would
+    /// end up owned by Doppler and holding exactly the sequence and payload
+    /// being written before sending and confirming it.
+    ///
+    /// # Panics
+    /// Panics under the same conditions as [`Builder::build`].
+    #[cfg(feature = "blocking")]
+    pub fn simulate_and_send(&self, client: &RpcClient) -> Result<SendOutcome, SimulationError> {
+        let tx = self.sign(client.get_latest_blockhash()?)?;
+        let oracles = self.oracle_pubkeys();
+        let simulation = self.simulate(client, &tx, true)?.value;
+
+        if let Some(err) = simulation.err {
+            return Err(SimulationError::Failed(err));
+        }
+
+        let accounts = simulation.accounts.unwrap_or_default();
+        for (index, (oracle_ix, oracle)) in self.oracle_update_ixs.iter().zip(&oracles).enumerate()
+        {
+            let account = accounts
+                .get(index)
+                .and_then(Option::as_ref)
+                .ok_or(SimulationError::MissingAccount(*oracle))?;
+            let data = account
+                .data
+                .decode()
+                .ok_or(SimulationError::MissingAccount(*oracle))?;
+
+            if account.owner != self.cluster.program_id().to_string()
+                || !data.starts_with(&oracle_ix.data)
+            {
+                return Err(SimulationError::Mismatch(*oracle));
+            }
+        }
+
+        let signature = client.send_and_confirm_transaction(&tx)?;
+
+        Ok(send_outcome(client, signature)?)
+    }
+
+    /// Simulates the transaction against current cluster state without
+    /// broadcasting it, returning the logs, compute units consumed and
+    /// post-state of every updated oracle, whether or not it succeeded.
+    ///
+    /// Signatures are not verified and the node substitutes its own recent
+    /// blockhash, so offline builders can be dry-run too.
+    #[cfg(feature = "blocking")]
+    pub fn dry_run(&self, client: &RpcClient) -> Result<DryRun, SimulationError> {
+        let tx = check_size(Transaction::new_unsigned(Message::new(
+            &self.instructions(),
+            Some(&self.fee_payer()),
+        )))?;
+        let Response { context, value } = self.simulate(client, &tx, false)?;
+
+        let accounts = value.accounts.unwrap_or_default();
+        let oracles = self
+            .oracle_pubkeys()
+            .into_iter()
+            .enumerate()
+            .map(|(index, oracle)| {
+                let data = accounts
+                    .get(index)
+                    .and_then(Option::as_ref)
+                    .and_then(|account| account.data.decode());
+                (oracle, data)
+            })
+            .collect();
+
+        Ok(DryRun {
+            slot: context.slot,
+            err: value.err,
+            logs: value.logs.unwrap_or_default(),
+            compute_units_consumed: value.units_consumed,
+            oracles,
+        })
+    }
+
+    /// Simulates `tx`, returning the post-state of the updated oracles.
+    #[cfg(feature = "blocking")]
+    fn simulate(
+        &self,
+        client: &RpcClient,
+        tx: &Transaction,
+        sig_verify: bool,
+    ) -> ClientResult<Response<RpcSimulateTransactionResult>> {
+        client.simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify,
+                replace_recent_blockhash: !sig_verify,
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: self
+                        .oracle_pubkeys()
+                        .iter()
+                        .map(Pubkey::to_string)
+                        .collect(),
+                }),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[cfg(feature = "blocking")]
+    fn oracle_pubkeys(&self) -> Vec<Pubkey> {
+        self.oracle_update_ixs
+            .iter()
+            .map(|ix| ix.accounts[1].pubkey)
+            .collect()
+    }
+
+    #[cfg(feature = "blocking")]
+    fn sign(&self, recent_blockhash: Hash) -> Result<Transaction, BuildError> {
+        check_size(Transaction::new_signed_with_payer(
+            &self.instructions(),
+            Some(&self.fee_payer()),
+            &self.signers(),
+            recent_blockhash,
+        ))
+    }
+
+    /// Decodes a failed send of this transaction into the Doppler exit code
+    /// that caused it, fetching the oracle's current sequence for stale
+    /// sequence failures.
+    ///
+    /// Returns `None` if the failure did not come from one of the oracle
+    /// updates or the oracle account could not be fetched.
+    #[cfg(feature = "blocking")]
+    pub fn decode_error(
+        &self,
+        client: &RpcClient,
+        error: &ClientError,
+    ) -> Option<DopplerProgramError> {
+        let (index, code) = self.failed_oracle_update(error.get_transaction_error()?)?;
+        let oracle_ix = &self.oracle_update_ixs[index];
+
+        match code {
+            ADMIN_CHECK_EXIT_CODE => Some(DopplerProgramError::InvalidAdmin {
+                admin: oracle_ix.accounts[0].pubkey,
+                oracle: oracle_ix.accounts[1].pubkey,
+            }),
+            INVALID_SEQUENCE_EXIT_CODE => {
+                let mut sent = [0u8; 8];
+                sent.copy_from_slice(&oracle_ix.data[SEQUENCE_OFFSET..PAYLOAD_OFFSET]);
+
+                Some(DopplerProgramError::StaleSequence {
+                    oracle: oracle_ix.accounts[1].pubkey,
+                    current: crate::fetch::sequence_with_config(
+                        client,
+                        &oracle_ix.accounts[1].pubkey,
+                        self.cluster,
+                        client.commitment(),
+                        None,
+                    )
+                    .ok()?,
+                    sent: u64::from_le_bytes(sent),
+                })
+            }
+            code => Some(DopplerProgramError::Unknown(code)),
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    fn is_auto_sequence_error(&self, error: Option<TransactionError>) -> bool {
+        match error.and_then(|error| self.failed_oracle_update(error)) {
+            Some((index, INVALID_SEQUENCE_EXIT_CODE)) => self.auto_sequenced.contains(&index),
+            _ => false,
+        }
+    }
+
+    /// Maps a custom instruction error to the index of the oracle update that
+    /// raised it and its exit code.
+    #[cfg(feature = "blocking")]
+    fn failed_oracle_update(&self, error: TransactionError) -> Option<(usize, u32)> {
+        let offset = self.leading_instructions().len();
+
+        match error {
+            TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+                let index = usize::from(index).checked_sub(offset)?;
+                (index < self.oracle_update_ixs.len()).then_some((index, code))
+            }
+            _ => None,
+        }
+    }
+
+    /// Estimates the fees of the transaction `build` would produce and the
+    /// rent-exempt reserve held by the oracle accounts it updates.
+    #[cfg(feature = "blocking")]
+    pub fn estimate_cost(&self, client: &RpcClient) -> ClientResult<CostEstimate> {
+        let message = Message::new_with_blockhash(
+            &self.instructions(),
+            Some(&self.fee_payer()),
+            &client.get_latest_blockhash()?,
+        );
+        let fee = client.get_fee_for_message(&message)?;
+        let priority_fee = self.priority_fee();
+
+        let mut rent_by_size = HashMap::new();
+        let mut rent_exempt_reserve = 0;
+        for cost in &self.update_costs {
+            let size = cost.oracle_len;
+            let rent = match rent_by_size.get(&size) {
+                Some(rent) => *rent,
+                None => {
+                    let rent = client.get_minimum_balance_for_rent_exemption(size)?;
+                    rent_by_size.insert(size, rent);
+                    rent
+                }
+            };
+            rent_exempt_reserve += rent;
+        }
+
+        Ok(CostEstimate {
+            base_fee: fee.saturating_sub(priority_fee),
+            priority_fee,
+            rent_exempt_reserve,
+        })
+    }
+
+    /// Lamports of priority fee the transaction `build` would produce pays:
+    /// the compute unit price times the requested compute unit limit.
     #[must_use]
-    pub fn build(self, recent_blockhash: Hash) -> Transaction {
-        let mut ixs = Vec::with_capacity(self.oracle_update_ixs.len() + 3);
-        let mut loaded_account_data_size = self.loaded_account_data_size;
-        let mut compute_units = self.compute_units;
+    pub fn priority_fee(&self) -> u64 {
+        self.unit_price.map_or(0, |unit_price| {
+            (u128::from(unit_price) * u128::from(self.compute_unit_limit())).div_ceil(1_000_000)
+                as u64
+        })
+    }
+
+    fn fee_payer(&self) -> Pubkey {
+        self.fee_payer.unwrap_or(self.admin)
+    }
+
+    /// The admin, then the fee payer if it signs separately.
+    fn signers(&self) -> Vec<&'a Keypair> {
+        let signer = self
+            .signer
+            .expect("offline builders must use build_message");
+
+        let mut signers = vec![signer];
+        if let Some(fee_payer) = self
+            .fee_payer_signer
+            .filter(|fee_payer| fee_payer.pubkey() != self.admin)
+        {
+            signers.push(fee_payer);
+        }
+        #[cfg(feature = "wormhole")]
+        signers.extend(self.emissions.iter().map(|emission| emission.message));
+
+        signers
+    }
+
+    const fn compute_unit_limit(&self) -> u32 {
+        match self.unit_price {
+            Some(_) => self.compute_units + COMPUTE_BUDGET_IX_CU,
+            None => self.compute_units,
+        }
+    }
+
+    const fn loaded_accounts_data_size_limit(&self) -> u32 {
+        match self.unit_price {
+            Some(_) => self.loaded_account_data_size + COMPUTE_BUDGET_UNIT_PRICE_SIZE,
+            None => self.loaded_account_data_size,
+        }
+    }
+
+    fn compute_budget_instructions(&self) -> Vec<Instruction> {
+        let mut ixs = Vec::with_capacity(self.oracle_update_ixs.len() + 4);
 
         if let Some(unit_price) = self.unit_price {
             ixs.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
-            loaded_account_data_size += COMPUTE_BUDGET_UNIT_PRICE_SIZE;
-            compute_units += COMPUTE_BUDGET_IX_CU;
         }
 
         ixs.push(
-            ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(loaded_account_data_size),
+            ComputeBudgetInstruction::set_loaded_accounts_data_size_limit(
+                self.loaded_accounts_data_size_limit(),
+            ),
         );
         ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(
-            compute_units,
+            self.compute_unit_limit(),
         ));
 
-        for oracle_ix in self.oracle_update_ixs {
-            ixs.push(oracle_ix);
+        ixs
+    }
+
+    fn push_update(&mut self, ix: Instruction, cost: UpdateCost) {
+        self.compute_units += cost.compute_units;
+        self.loaded_account_data_size += cost.loaded_account_data_size;
+        self.oracle_update_ixs.push(ix);
+        self.update_costs.push(cost);
+    }
+
+    /// Pushes an update of `builder` into this chunk along with its
+    /// Wormhole message, if it has one.
+    #[cfg_attr(not(feature = "wormhole"), allow(unused_variables))]
+    fn push_packed(&mut self, builder: &Self, ix: Instruction, cost: UpdateCost) {
+        #[cfg(feature = "wormhole")]
+        self.emissions.extend(
+            builder
+                .emissions
+                .iter()
+                .filter(|emission| emission.oracle_pubkey == ix.accounts[1].pubkey)
+                .cloned(),
+        );
+
+        self.push_update(ix, cost);
+    }
+
+    /// The Wormhole messages, after every update so that each is only
+    /// posted if its update succeeded. Payloads are read from the queued
+    /// updates, so they follow [`Builder::set_oracle`] and refetched
+    /// sequences.
+    #[cfg(feature = "wormhole")]
+    fn emission_instructions(&self) -> Vec<Instruction> {
+        let payer = self.fee_payer();
+
+        self.emissions
+            .iter()
+            .flat_map(|emission| {
+                let update_ix = self
+                    .oracle_update_ixs
+                    .iter()
+                    .find(|ix| ix.accounts[1].pubkey == emission.oracle_pubkey)
+                    .expect("no update queued for emission");
+                let mut payload = emission.oracle_pubkey.to_bytes().to_vec();
+                payload.extend_from_slice(&update_ix.data);
+
+                emission.bridge.post_message(
+                    &self.admin,
+                    &payer,
+                    &emission.message.pubkey(),
+                    &payload,
+                )
+            })
+            .collect()
+    }
+
+    /// An empty builder with the same signers and fee settings.
+    fn without_updates(&self) -> Self {
+        let builder = Self {
+            fee_payer: self.fee_payer,
+            signer: self.signer,
+            fee_payer_signer: self.fee_payer_signer,
+            cluster: self.cluster,
+            unit_price: self.unit_price,
+            max_compute_units: self.max_compute_units,
+            lookup_tables: self.lookup_tables.clone(),
+            ..Builder::new_offline(self.admin)
+        };
+
+        #[cfg(feature = "nonce")]
+        if let Some((nonce_pubkey, nonce_authority)) = self.durable_nonce {
+            return builder.with_durable_nonce(nonce_pubkey, nonce_authority);
         }
 
-        Transaction::new_signed_with_payer(
-            &ixs,
-            Some(&self.admin.pubkey()),
-            &[&self.admin],
+        builder
+    }
+
+    /// Whether the built transaction stays within the packet size and
+    /// compute unit cap, as a v0 transaction if lookup tables are set.
+    fn fits(&self) -> bool {
+        let size = if self.lookup_tables.is_empty() {
+            let message = Message::new(&self.instructions(), Some(&self.fee_payer()));
+            serialized_size(&Transaction::new_unsigned(message))
+        } else {
+            match self.compile_v0(Hash::default()) {
+                Ok(message) => packet_size(
+                    usize::from(message.header.num_required_signatures),
+                    VersionedMessage::V0(message).serialize().len(),
+                ),
+                Err(_) => return false,
+            }
+        };
+
+        self.compute_unit_limit() <= self.max_compute_units && size <= PACKET_DATA_SIZE
+    }
+
+    /// Compiles the instructions into a v0 message loading accounts from
+    /// the lookup tables.
+    fn compile_v0(&self, recent_blockhash: Hash) -> Result<v0::Message, BuildError> {
+        v0::Message::try_compile(
+            &self.fee_payer(),
+            &self.instructions(),
+            &self.lookup_tables,
             recent_blockhash,
         )
+        .map_err(|_| BuildError::TooManyAccounts)
+    }
+
+    /// Index of the first lookup table holding `oracle_pubkey`, if any.
+    fn lookup_table_index(&self, oracle_pubkey: &Pubkey) -> Option<usize> {
+        self.lookup_tables
+            .iter()
+            .position(|table| table.addresses.contains(oracle_pubkey))
+    }
+
+    /// Instructions placed before the oracle updates: the nonce advance,
+    /// which must come first, then the compute budget.
+    fn leading_instructions(&self) -> Vec<Instruction> {
+        #[cfg(feature = "nonce")]
+        if let Some((nonce_pubkey, nonce_authority)) = self.durable_nonce {
+            let mut ixs = vec![advance_nonce_account(&nonce_pubkey, &nonce_authority)];
+            ixs.extend(self.compute_budget_instructions());
+            return ixs;
+        }
+
+        self.compute_budget_instructions()
+    }
+
+    fn instructions(&self) -> Vec<Instruction> {
+        let mut ixs = self.leading_instructions();
+        ixs.extend(self.oracle_update_ixs.iter().cloned());
+        #[cfg(feature = "wormhole")]
+        ixs.extend(self.emission_instructions());
+        ixs
+    }
+
+    fn into_instructions(self) -> Vec<Instruction> {
+        let mut ixs = self.leading_instructions();
+        #[cfg(feature = "wormhole")]
+        let emissions = self.emission_instructions();
+        ixs.extend(self.oracle_update_ixs);
+        #[cfg(feature = "wormhole")]
+        ixs.extend(emissions);
+        ixs
+    }
+}
+
+/// Fetches the status meta of a confirmed transaction.
+#[cfg(feature = "blocking")]
+fn send_outcome(client: &RpcClient, signature: Signature) -> ClientResult<SendOutcome> {
+    let tx = client.get_transaction_with_config(
+        &signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let meta = tx.transaction.meta.ok_or_else(|| {
+        ClientErrorKind::Custom(format!("transaction {signature} has no status meta"))
+    })?;
+
+    Ok(SendOutcome {
+        signature,
+        slot: tx.slot,
+        compute_units_consumed: meta.compute_units_consumed.into(),
+        fee_lamports: meta.fee,
+    })
+}
+
+/// Length of `tx` on the wire: the compact-u16 signature count, the
+/// signatures and the serialized message.
+fn serialized_size(tx: &Transaction) -> usize {
+    packet_size(tx.signatures.len(), tx.message_data().len())
+}
+
+fn versioned_size(tx: &VersionedTransaction) -> usize {
+    packet_size(tx.signatures.len(), tx.message.serialize().len())
+}
+
+/// Wire size of a transaction with `signatures` signatures and a
+/// serialized message of `message_len` bytes.
+fn packet_size(signatures: usize, message_len: usize) -> usize {
+    let signatures_len_prefix = match signatures {
+        0..0x80 => 1,
+        0x80..0x4000 => 2,
+        _ => 3,
+    };
+
+    signatures_len_prefix + signatures * 64 + message_len
+}
+
+fn check_size(tx: Transaction) -> Result<Transaction, BuildError> {
+    let size = serialized_size(&tx);
+
+    if size > PACKET_DATA_SIZE {
+        return Err(BuildError::TooLarge {
+            size,
+            max: PACKET_DATA_SIZE,
+        });
+    }
+
+    Ok(tx)
+}
+
+/// A transaction awaiting signatures from one or more parties.
+pub struct PartialTransaction {
+    transaction: Transaction,
+}
+
+impl PartialTransaction {
+    /// Message bytes each signer must sign.
+    #[must_use]
+    pub fn message_data(&self) -> Vec<u8> {
+        self.transaction.message_data()
+    }
+
+    /// Signs with `signer`, which must be one of the required signers.
+    pub fn sign_with<S: Signer + ?Sized>(mut self, signer: &S) -> Result<Self, SigningError> {
+        let pubkey = signer.try_pubkey()?;
+        let signature = signer.try_sign_message(&self.transaction.message_data())?;
+        self.add_signature(pubkey, signature)?;
+        Ok(self)
+    }
+
+    /// Adds a signature collected elsewhere, verifying it first.
+    pub fn add_signature(
+        &mut self,
+        pubkey: Pubkey,
+        signature: Signature,
+    ) -> Result<(), SigningError> {
+        let index = self
+            .signers()
+            .iter()
+            .position(|signer| *signer == pubkey)
+            .ok_or(SigningError::UnexpectedSigner(pubkey))?;
+
+        if !signature.verify(pubkey.as_ref(), &self.transaction.message_data()) {
+            return Err(SigningError::InvalidSignature(pubkey));
+        }
+
+        self.transaction.signatures[index] = signature;
+        Ok(())
+    }
+
+    /// Required signers that have not signed yet.
+    #[must_use]
+    pub fn missing_signers(&self) -> Vec<Pubkey> {
+        self.signers()
+            .iter()
+            .zip(&self.transaction.signatures)
+            .filter(|(_, signature)| **signature == Signature::default())
+            .map(|(signer, _)| *signer)
+            .collect()
+    }
+
+    /// Returns the fully signed transaction.
+    pub fn finish(self) -> Result<Transaction, SigningError> {
+        match self.missing_signers().first() {
+            Some(signer) => Err(SigningError::MissingSignature(*signer)),
+            None => Ok(self.transaction),
+        }
+    }
+
+    fn signers(&self) -> &[Pubkey] {
+        let message = &self.transaction.message;
+        &message.account_keys[..usize::from(message.header.num_required_signatures)]
+    }
+}
+
+/// Combines a message built with [`Builder::build_message`] with signatures
+/// produced offline, verifying each one and placing it in signer order.
+pub fn attach_signatures(
+    message: VersionedMessage,
+    signatures: &[(Pubkey, Signature)],
+) -> Result<VersionedTransaction, SigningError> {
+    let message_bytes = message.serialize();
+    let signers =
+        &message.static_account_keys()[..usize::from(message.header().num_required_signatures)];
+    let mut ordered = vec![Signature::default(); signers.len()];
+
+    for (pubkey, signature) in signatures {
+        let index = signers
+            .iter()
+            .position(|signer| signer == pubkey)
+            .ok_or(SigningError::UnexpectedSigner(*pubkey))?;
+
+        if !signature.verify(pubkey.as_ref(), &message_bytes) {
+            return Err(SigningError::InvalidSignature(*pubkey));
+        }
+
+        ordered[index] = *signature;
+    }
+
+    if let Some(index) = ordered.iter().position(|s| *s == Signature::default()) {
+        return Err(SigningError::MissingSignature(signers[index]));
+    }
+
+    Ok(VersionedTransaction {
+        signatures: ordered,
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_program::PriceFeed;
+
+    use super::*;
+    use crate::constants::ID;
+    #[cfg(feature = "jito")]
+    use crate::jito::TIP_ACCOUNTS;
+
+    fn offline_message(admin: Pubkey) -> VersionedMessage {
+        Builder::new_offline(admin)
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build_message(Hash::default())
+    }
+
+    #[test]
+    fn test_attach_offline_signature() {
+        let admin = Keypair::new();
+        let message = offline_message(admin.pubkey());
+        let signature = admin.sign_message(&message.serialize());
+
+        let tx = attach_signatures(message, &[(admin.pubkey(), signature)]).unwrap();
+
+        assert_eq!(tx.signatures, vec![signature]);
+    }
+
+    #[test]
+    fn test_attach_rejects_wrong_signer() {
+        let admin = Keypair::new();
+        let other = Keypair::new();
+        let message = offline_message(admin.pubkey());
+        let signature = other.sign_message(&message.serialize());
+
+        assert_eq!(
+            attach_signatures(message.clone(), &[(other.pubkey(), signature)]).unwrap_err(),
+            SigningError::UnexpectedSigner(other.pubkey())
+        );
+        assert_eq!(
+            attach_signatures(message.clone(), &[(admin.pubkey(), signature)]).unwrap_err(),
+            SigningError::InvalidSignature(admin.pubkey())
+        );
+        assert_eq!(
+            attach_signatures(message, &[]).unwrap_err(),
+            SigningError::MissingSignature(admin.pubkey())
+        );
+    }
+
+    #[test]
+    fn test_sign_with_separate_fee_payer() {
+        let admin = Keypair::new();
+        let fee_payer = Keypair::new();
+
+        let partial = Builder::new_offline(admin.pubkey())
+            .with_fee_payer(fee_payer.pubkey())
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build_unsigned(Hash::default())
+            .sign_with(&admin)
+            .unwrap();
+
+        assert_eq!(partial.missing_signers(), vec![fee_payer.pubkey()]);
+
+        let tx = partial.sign_with(&fee_payer).unwrap().finish().unwrap();
+
+        assert_eq!(tx.message.account_keys[0], fee_payer.pubkey());
+        assert!(tx.is_signed());
+    }
+
+    #[test]
+    fn test_build_with_fee_payer_keypair() {
+        let admin = Keypair::new();
+        let fee_payer = Keypair::new();
+
+        let tx = Builder::new(&admin)
+            .with_fee_payer_keypair(&fee_payer)
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build(Hash::default())
+            .unwrap();
+
+        assert_eq!(tx.message.account_keys[0], fee_payer.pubkey());
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.is_signed());
+    }
+
+    #[cfg(feature = "jito")]
+    #[test]
+    fn test_build_bundle_appends_tip() {
+        let admin = Keypair::new();
+        let bundle = Builder::new(&admin)
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build_bundle(10_000, TIP_ACCOUNTS[0], Hash::default())
+            .unwrap();
+
+        assert_eq!(bundle.len(), 1);
+
+        let message = &bundle[0].message;
+        let tip_ix = message.instructions.last().unwrap();
+        let program_id = message.account_keys[tip_ix.program_id_index as usize];
+        let tip_account = message.account_keys[tip_ix.accounts[1] as usize];
+
+        assert_eq!(program_id, solana_system_interface::program::ID);
+        assert_eq!(tip_account, TIP_ACCOUNTS[0]);
+
+        let oversized = (0..40).fold(Builder::new(&admin), |builder, _| {
+            builder.add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+        });
+        assert!(matches!(
+            oversized.build_bundle(10_000, TIP_ACCOUNTS[0], Hash::default()),
+            Err(BuildError::TooLarge { .. })
+        ));
+    }
+
+    #[cfg(feature = "wormhole")]
+    #[test]
+    fn test_wormhole_message_follows_updates() {
+        let admin = Keypair::new();
+        let message = Keypair::new();
+        let (emitted, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let bridge = crate::wormhole::CoreBridge::mainnet(100);
+
+        let mut builder = Builder::new(&admin)
+            .add_oracle_update_with_wormhole(
+                emitted,
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+                bridge,
+                &message,
+            )
+            .add_oracle_update(
+                other,
+                Oracle {
+                    sequence: 1,
+                    payload: 1_000_000u64,
+                },
+            );
+        let update = Oracle {
+            sequence: 2,
+            payload: 1_200_000u64,
+        };
+        builder.set_oracle(&emitted, update);
+
+        let tx = builder.build(Hash::default()).unwrap();
+        assert_eq!(tx.signatures.len(), 2);
+        assert!(tx.is_signed());
+
+        let programs: Vec<Pubkey> = tx
+            .message
+            .instructions
+            .iter()
+            .map(|ix| tx.message.account_keys[ix.program_id_index as usize])
+            .collect();
+        assert_eq!(
+            programs[programs.len() - 4..],
+            [
+                ID,
+                ID,
+                solana_system_interface::program::ID,
+                crate::wormhole::MAINNET_CORE_BRIDGE
+            ]
+        );
+
+        let post = tx.message.instructions.last().unwrap();
+        let payload = crate::wormhole::message_payload(&emitted, &update);
+        assert_eq!(&post.data[9..9 + payload.len()], payload);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_dry_run_decodes_oracle_post_state() {
+        let oracle = Pubkey::new_unique();
+        let dry_run = DryRun {
+            slot: 1,
+            err: None,
+            logs: vec![],
+            compute_units_consumed: Some(21),
+            oracles: vec![
+                (
+                    oracle,
+                    Some(
+                        Oracle {
+                            sequence: 3,
+                            payload: 1_100_000u64,
+                        }
+                        .to_bytes(),
+                    ),
+                ),
+                (Pubkey::new_unique(), None),
+            ],
+        };
+
+        let decoded = dry_run.oracle::<u64>(&oracle).unwrap();
+        assert_eq!((decoded.sequence, decoded.payload), (3, 1_100_000));
+        assert!(dry_run.oracle::<u64>(&dry_run.oracles[1].0).is_none());
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_failed_oracle_update_skips_compute_budget() {
+        let builder = Builder::new_offline(Pubkey::new_unique()).add_oracle_update(
+            Pubkey::new_unique(),
+            Oracle {
+                sequence: 1,
+                payload: 1_100_000u64,
+            },
+        );
+        let offset = builder.leading_instructions().len() as u8;
+        let failure =
+            |index, code| TransactionError::InstructionError(index, InstructionError::Custom(code));
+
+        assert_eq!(
+            builder.failed_oracle_update(failure(offset, INVALID_SEQUENCE_EXIT_CODE)),
+            Some((0, INVALID_SEQUENCE_EXIT_CODE))
+        );
+        assert_eq!(builder.failed_oracle_update(failure(0, 1)), None);
+        assert_eq!(builder.failed_oracle_update(failure(offset + 1, 1)), None);
+    }
+
+    #[test]
+    fn test_build_rejects_oversized_transaction() {
+        let admin = Keypair::new();
+        let single = Builder::new(&admin)
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build(Hash::default())
+            .unwrap();
+
+        assert_eq!(
+            serialized_size(&single) as u64,
+            bincode::serialized_size(&single).unwrap()
+        );
+
+        let builder = (0..40).fold(Builder::new(&admin), |builder, _| {
+            builder.add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+        });
+
+        assert!(matches!(
+            builder.build(Hash::default()),
+            Err(BuildError::TooLarge { size, max: PACKET_DATA_SIZE }) if size > PACKET_DATA_SIZE
+        ));
+    }
+
+    #[test]
+    fn test_build_all_packs_updates() {
+        let admin = Keypair::new();
+        let oracles: Vec<Pubkey> = (0..50).map(|_| Pubkey::new_unique()).collect();
+        let builder = oracles
+            .iter()
+            .fold(Builder::new(&admin), |builder, oracle| {
+                builder.add_oracle_update(
+                    *oracle,
+                    Oracle {
+                        sequence: 1,
+                        payload: 1_100_000u64,
+                    },
+                )
+            });
+
+        let txs = builder.clone().build_all(Hash::default()).unwrap();
+        assert!(txs.len() > 1);
+        assert!(txs.iter().all(|tx| serialized_size(tx) <= PACKET_DATA_SIZE));
+
+        let packed: Vec<Pubkey> = txs
+            .iter()
+            .flat_map(|tx| {
+                tx.message
+                    .instructions
+                    .iter()
+                    .filter(|ix| tx.message.account_keys[ix.program_id_index as usize] == ID)
+                    .map(|ix| tx.message.account_keys[ix.accounts[1] as usize])
+            })
+            .collect();
+        assert_eq!(packed, oracles);
+    }
+
+    /// Rent is charged on the account, which pads a `u32` payload to 16
+    /// bytes, not on the 12 bytes of instruction data.
+    #[test]
+    fn test_update_cost_uses_account_size() {
+        let builder = Builder::new_offline(Pubkey::new_unique()).add_oracle_update(
+            Pubkey::new_unique(),
+            Oracle {
+                sequence: 1,
+                payload: 7u32,
+            },
+        );
+
+        assert_eq!(builder.oracle_update_ixs[0].data.len(), 12);
+        assert_eq!(builder.update_costs[0].oracle_len, 16);
+    }
+
+    #[test]
+    fn test_pack_respects_compute_unit_cap() {
+        let admin = Keypair::new();
+        fn update(builder: Builder<'_>) -> Builder<'_> {
+            builder.add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+        }
+        let single = update(Builder::new(&admin));
+        let cap = single.compute_unit_limit() + single.update_costs[0].compute_units;
+
+        let chunks = (0..5)
+            .fold(Builder::new(&admin), |builder, _| update(builder))
+            .with_max_compute_units(cap)
+            .pack()
+            .unwrap();
+
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| chunk.oracle_update_ixs.len())
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+        assert!(chunks.iter().all(|chunk| chunk.compute_unit_limit() <= cap));
+    }
+
+    #[test]
+    fn test_pack_groups_updates_by_lookup_table() {
+        let admin = Keypair::new();
+        let tables: Vec<_> = (0..2)
+            .map(|_| AddressLookupTableAccount {
+                key: Pubkey::new_unique(),
+                addresses: (0..4).map(|_| Pubkey::new_unique()).collect(),
+            })
+            .collect();
+        fn update(builder: Builder<'_>, oracle_pubkey: Pubkey) -> Builder<'_> {
+            builder.add_oracle_update(
+                oracle_pubkey,
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+        }
+        let single = update(Builder::new(&admin), Pubkey::new_unique());
+        let cap = single.compute_unit_limit() + 3 * single.update_costs[0].compute_units;
+
+        let chunks = (0..4)
+            .flat_map(|i| tables.iter().map(move |table| table.addresses[i]))
+            .fold(Builder::new(&admin), update)
+            .with_lookup_tables(tables.clone())
+            .with_max_compute_units(cap)
+            .pack()
+            .unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        for (chunk, table) in chunks.into_iter().zip(&tables) {
+            assert!(chunk
+                .oracle_update_ixs
+                .iter()
+                .all(|ix| table.addresses.contains(&ix.accounts[1].pubkey)));
+
+            let tx = chunk.build_versioned(Hash::default()).unwrap();
+            let lookups = tx.message.address_table_lookups().unwrap();
+            assert_eq!(lookups.len(), 1);
+            assert_eq!(lookups[0].account_key, table.key);
+        }
+    }
+
+    #[test]
+    fn test_build_all_versioned_fits_more_updates() {
+        let admin = Keypair::new();
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: (0..50).map(|_| Pubkey::new_unique()).collect(),
+        };
+        let builder =
+            table
+                .addresses
+                .iter()
+                .fold(Builder::new(&admin), |builder, oracle_pubkey| {
+                    builder.add_oracle_update(
+                        *oracle_pubkey,
+                        Oracle {
+                            sequence: 1,
+                            payload: 1_100_000u64,
+                        },
+                    )
+                });
+
+        let legacy = builder.clone().build_all(Hash::default()).unwrap();
+        let versioned = builder
+            .with_lookup_tables(vec![table])
+            .build_all_versioned(Hash::default())
+            .unwrap();
+
+        assert!(versioned.len() < legacy.len());
+        assert!(versioned
+            .iter()
+            .all(|tx| versioned_size(tx) <= PACKET_DATA_SIZE));
+    }
+
+    /// Every transaction packed with a durable nonce would advance the same
+    /// nonce, so only the first could land.
+    #[cfg(feature = "nonce")]
+    #[test]
+    fn test_pack_rejects_shared_durable_nonce() {
+        let admin = Keypair::new();
+        let nonce_pubkey = Pubkey::new_unique();
+        fn update(builder: Builder<'_>) -> Builder<'_> {
+            builder.add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+        }
+
+        let single = update(Builder::new(&admin).with_durable_nonce(nonce_pubkey, admin.pubkey()));
+        assert_eq!(single.pack().unwrap().len(), 1);
+
+        let many = (0..50).fold(
+            Builder::new(&admin).with_durable_nonce(nonce_pubkey, admin.pubkey()),
+            |builder, _| update(builder),
+        );
+        assert!(matches!(
+            many.clone().pack(),
+            Err(BuildError::NonceReused { transactions }) if transactions > 1
+        ));
+        assert!(matches!(
+            many.build_all(Hash::new_unique()),
+            Err(BuildError::NonceReused { .. })
+        ));
+    }
+
+    #[cfg(feature = "nonce")]
+    #[test]
+    fn test_durable_nonce_advance_comes_first() {
+        let admin = Keypair::new();
+        let nonce_pubkey = Pubkey::new_unique();
+
+        let tx = Builder::new(&admin)
+            .with_durable_nonce(nonce_pubkey, admin.pubkey())
+            .add_oracle_update(
+                Pubkey::new_unique(),
+                Oracle {
+                    sequence: 1,
+                    payload: 1_100_000u64,
+                },
+            )
+            .build(Hash::new_unique())
+            .unwrap();
+
+        let first_ix = &tx.message.instructions[0];
+        assert_eq!(
+            tx.message.account_keys[first_ix.program_id_index as usize],
+            solana_system_interface::program::ID
+        );
+        assert_eq!(
+            tx.message.account_keys[first_ix.accounts[0] as usize],
+            nonce_pubkey
+        );
+    }
+
+    #[test]
+    fn test_template_refresh_matches_fresh_build() {
+        let admin = Keypair::new();
+        let oracle_pubkey = Pubkey::new_unique();
+        let builder_for = |sequence| {
+            Builder::new(&admin)
+                .with_unit_price(1_000)
+                .add_oracle_update(
+                    oracle_pubkey,
+                    Oracle {
+                        sequence,
+                        payload: PriceFeed { price: sequence },
+                    },
+                )
+        };
+
+        let template = builder_for(1);
+        let mut refreshed = template.clone();
+        refreshed.set_oracle(
+            &oracle_pubkey,
+            Oracle {
+                sequence: 2,
+                payload: PriceFeed { price: 2 },
+            },
+        );
+
+        assert_eq!(
+            refreshed.build(Hash::default()).unwrap(),
+            builder_for(2).build(Hash::default()).unwrap()
+        );
+        assert_eq!(
+            template.build(Hash::default()).unwrap(),
+            builder_for(1).build(Hash::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_batch_message_snapshot() {
+        let admin = Pubkey::from([0x11; 32]);
+        let message = Builder::new_offline(admin)
+            .with_unit_price(1_000)
+            .add_oracle_update(
+                Pubkey::from([0x21; 32]),
+                Oracle {
+                    sequence: 1,
+                    payload: PriceFeed { price: 1_100_000 },
+                },
+            )
+            .add_oracle_update(
+                Pubkey::from([0x22; 32]),
+                Oracle {
+                    sequence: 2,
+                    payload: [10_500_000u64, 10_550_000],
+                },
+            )
+            .add_oracle_update(
+                Pubkey::from([0x23; 32]),
+                Oracle {
+                    sequence: 3,
+                    payload: 42u32,
+                },
+            )
+            .build_message(Hash::default());
+
+        let keys = message.static_account_keys();
+        let mut snapshot: String = keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                format!(
+                    "key {index}: {key} signer={} writable={}\n",
+                    message.is_signer(index),
+                    message.is_maybe_writable(index, None)
+                )
+            })
+            .collect();
+        for ix in message.instructions() {
+            snapshot += &format!(
+                "ix: program={} accounts={:?} data={}\n",
+                ix.program_id_index,
+                ix.accounts,
+                ix.data
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<String>()
+            );
+        }
+
+        insta::assert_snapshot!(snapshot);
     }
 }
@@ -0,0 +1,122 @@
+use std::{marker::PhantomData, time::Duration};
+
+use futures::StreamExt;
+use solana_pubkey::Pubkey;
+use tokio::task::JoinHandle;
+use yellowstone_grpc_proto::prelude::CommitmentLevel;
+
+use crate::{
+    geyser::{self, GeyserError, OracleUpdate},
+    stream::LatestSequences,
+};
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A background task that streams writes of a set of oracles over Geyser and
+/// calls a handler with every new value.
+///
+/// The subscription is re-established with exponential backoff whenever the
+/// connection drops, and updates whose sequence has not advanced past the
+/// last one seen, such as replays after a reconnect, are dropped. The task
+/// stops when the watcher is dropped.
+pub struct OracleWatcher<T> {
+    task: JoinHandle<()>,
+    _payload: PhantomData<fn() -> T>,
+}
+
+impl<T: Sized + Copy + Send + 'static> OracleWatcher<T> {
+    /// Spawns the watcher onto the current tokio runtime.
+    ///
+    /// # Panics
+    /// Panics if called outside of a tokio runtime.
+    pub fn spawn<F>(
+        endpoint: impl Into<String>,
+        x_token: Option<String>,
+        oracles: Vec<Pubkey>,
+        commitment: CommitmentLevel,
+        handler: F,
+    ) -> Self
+    where
+        F: FnMut(OracleUpdate<T>) + Send + 'static,
+    {
+        let endpoint = endpoint.into();
+        let task = tokio::spawn(async move {
+            let mut handler = handler;
+            let mut latest = LatestSequences::default();
+            let mut backoff = MIN_BACKOFF;
+
+            loop {
+                let watched = watch(
+                    &endpoint,
+                    x_token.clone(),
+                    &oracles,
+                    commitment,
+                    &mut latest,
+                    &mut handler,
+                )
+                .await;
+
+                if watched.is_ok_and(|delivered| delivered) {
+                    backoff = MIN_BACKOFF;
+                }
+
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+        });
+
+        Self {
+            task,
+            _payload: PhantomData,
+        }
+    }
+
+    /// Stops the background task.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl<T> Drop for OracleWatcher<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Runs one subscription until the stream ends or fails, returning whether
+/// any update was received.
+async fn watch<T: Sized + Copy>(
+    endpoint: &str,
+    x_token: Option<String>,
+    oracles: &[Pubkey],
+    commitment: CommitmentLevel,
+    latest: &mut LatestSequences,
+    handler: &mut impl FnMut(OracleUpdate<T>),
+) -> Result<bool, GeyserError> {
+    let mut client = geyser::connect(endpoint, x_token).await?;
+    let updates = geyser::subscribe::<T>(&mut client, oracles, commitment).await?;
+    futures::pin_mut!(updates);
+
+    let mut delivered = false;
+    while let Some(update) = updates.next().await {
+        let update = match update {
+            Ok(update) => update,
+            // A single malformed account should not tear down the stream.
+            Err(GeyserError::InvalidLayout(_) | GeyserError::InvalidPubkey) => continue,
+            Err(err) => return Err(err),
+        };
+
+        delivered = true;
+        if latest.advance(update.pubkey, update.oracle.sequence) {
+            handler(update);
+        }
+    }
+
+    Ok(delivered)
+}
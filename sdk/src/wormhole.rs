@@ -0,0 +1,258 @@
+//! Posting Doppler updates to the Wormhole core bridge, so they can be
+//! verified on other chains through guardian attestations.
+//!
+//! [`Builder::add_oracle_update_with_wormhole`] follows an update with a
+//! core bridge `post_message_unreliable` in the same transaction, so a
+//! message is only posted for an update the program accepted. The admin
+//! signs the update and emits the message, so a consumer trusts VAAs from
+//! emitter chain [`SOLANA_CHAIN_ID`] whose emitter address is the admin.
+//!
+//! Each message carries [`message_payload`]: the oracle's address followed by
+//! its new account data, the little-endian sequence then the payload as
+//! stored on chain.
+//!
+//! [`Builder::add_oracle_update_with_wormhole`]: crate::transaction::Builder::add_oracle_update_with_wormhole
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "blocking")]
+use solana_client::{
+    client_error::{ClientErrorKind, Result as ClientResult},
+    rpc_client::RpcClient,
+};
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+use solana_system_interface::instruction::transfer;
+
+use crate::accounts::Oracle;
+
+pub const MAINNET_CORE_BRIDGE: Pubkey =
+    Pubkey::from_str_const("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+pub const DEVNET_CORE_BRIDGE: Pubkey =
+    Pubkey::from_str_const("3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5");
+
+/// Wormhole's chain ID for Solana, the emitter chain of every message.
+pub const SOLANA_CHAIN_ID: u16 = 1;
+
+/// Bytes of a message payload before the oracle's data.
+pub const ORACLE_OFFSET: usize = 32;
+
+// Core bridge instruction tags
+const POST_MESSAGE_UNRELIABLE: u8 = 8;
+
+// Offset of the message fee in the core bridge's `Bridge` account
+#[cfg(feature = "blocking")]
+const BRIDGE_FEE_OFFSET: usize = 16;
+
+/// How long guardians wait before attesting a message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Consistency {
+    /// Once the update's slot is confirmed.
+    Confirmed,
+    /// Once the update's slot is finalized, so the VAA cannot be rolled back.
+    #[default]
+    Finalized,
+}
+
+/// A Wormhole core bridge deployment to post messages to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CoreBridge {
+    pub program_id: Pubkey,
+    /// Lamports the bridge charges per message, see [`fetch_fee`].
+    pub fee_lamports: u64,
+    pub consistency: Consistency,
+}
+
+impl CoreBridge {
+    #[must_use]
+    pub const fn new(program_id: Pubkey, fee_lamports: u64) -> Self {
+        Self {
+            program_id,
+            fee_lamports,
+            consistency: Consistency::Finalized,
+        }
+    }
+
+    #[must_use]
+    pub const fn mainnet(fee_lamports: u64) -> Self {
+        Self::new(MAINNET_CORE_BRIDGE, fee_lamports)
+    }
+
+    #[must_use]
+    pub const fn devnet(fee_lamports: u64) -> Self {
+        Self::new(DEVNET_CORE_BRIDGE, fee_lamports)
+    }
+
+    #[must_use]
+    pub const fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = consistency;
+        self
+    }
+
+    /// The bridge's configuration account, holding the message fee.
+    #[must_use]
+    pub fn bridge(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"Bridge"], &self.program_id).0
+    }
+
+    #[must_use]
+    pub fn fee_collector(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"fee_collector"], &self.program_id).0
+    }
+
+    /// The account counting the messages `emitter` posted, the sequence of
+    /// its VAAs.
+    #[must_use]
+    pub fn sequence(&self, emitter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"Sequence", emitter.as_ref()], &self.program_id).0
+    }
+
+    /// Pays the message fee from `payer` and posts `payload` from `emitter`
+    /// into the `message` account.
+    ///
+    /// Unreliable messages overwrite their account, so reusing the same
+    /// `message` keypair for a feed pays its rent once. It can only be
+    /// reused for payloads of the same length.
+    #[must_use]
+    pub fn post_message(
+        &self,
+        emitter: &Pubkey,
+        payer: &Pubkey,
+        message: &Pubkey,
+        payload: &[u8],
+    ) -> [Instruction; 2] {
+        let fee_collector = self.fee_collector();
+
+        // Borsh encoded `(Instruction::PostMessageUnreliable, PostMessageData)`
+        let mut data = Vec::with_capacity(10 + payload.len());
+        data.push(POST_MESSAGE_UNRELIABLE);
+        data.extend_from_slice(&0u32.to_le_bytes()); // nonce
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(payload);
+        data.push(self.consistency as u8);
+
+        [
+            transfer(payer, &fee_collector, self.fee_lamports),
+            Instruction {
+                program_id: self.program_id,
+                accounts: vec![
+                    AccountMeta::new(self.bridge(), false),
+                    AccountMeta::new(*message, true),
+                    AccountMeta::new_readonly(*emitter, true),
+                    AccountMeta::new(self.sequence(emitter), false),
+                    AccountMeta::new(*payer, true),
+                    AccountMeta::new(fee_collector, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::sysvar::clock::ID, false),
+                    AccountMeta::new_readonly(solana_sdk_ids::sysvar::rent::ID, false),
+                    AccountMeta::new_readonly(solana_system_interface::program::ID, false),
+                ],
+                data,
+            },
+        ]
+    }
+}
+
+/// The payload of the message posted for an update of `oracle_pubkey`.
+#[must_use]
+pub fn message_payload<T: Sized + Copy>(oracle_pubkey: &Pubkey, oracle: &Oracle<T>) -> Vec<u8> {
+    let mut payload = vec![0; ORACLE_OFFSET + Oracle::<T>::ENCODED_LEN];
+    payload[..ORACLE_OFFSET].copy_from_slice(oracle_pubkey.as_ref());
+    oracle.encode_into(&mut payload[ORACLE_OFFSET..]);
+    payload
+}
+
+/// Decodes the payload of a verified VAA back into the oracle it updated,
+/// or `None` if it is not exactly one update with a `T` payload.
+#[must_use]
+pub fn decode_message_payload<T: Sized + Copy>(payload: &[u8]) -> Option<(Pubkey, Oracle<T>)> {
+    if payload.len() != ORACLE_OFFSET + Oracle::<T>::ENCODED_LEN {
+        return None;
+    }
+    let (oracle_pubkey, data) = payload.split_at(ORACLE_OFFSET);
+
+    // Pad the encoded update to the account's length for `try_from_bytes`
+    let mut account = vec![0; Oracle::<T>::LEN.max(data.len())];
+    account[..data.len()].copy_from_slice(data);
+    let (oracle, _) = Oracle::try_from_bytes(&account)?;
+
+    Some((Pubkey::try_from(oracle_pubkey).ok()?, oracle))
+}
+
+/// Reads the fee the core bridge at `program_id` currently charges per
+/// message.
+#[cfg(feature = "blocking")]
+pub fn fetch_fee(client: &RpcClient, program_id: &Pubkey) -> ClientResult<u64> {
+    let bridge = CoreBridge::new(*program_id, 0).bridge();
+    let data = client.get_account_data(&bridge)?;
+    let fee = data
+        .get(BRIDGE_FEE_OFFSET..BRIDGE_FEE_OFFSET + 8)
+        .ok_or_else(|| ClientErrorKind::Custom(format!("{bridge} is not a core bridge config")))?;
+
+    Ok(u64::from_le_bytes(fee.try_into().unwrap()))
+}
+
+/// An update queued with [`Builder::add_oracle_update_with_wormhole`],
+/// whose message is built from the update's instruction data when the
+/// transaction is.
+///
+/// [`Builder::add_oracle_update_with_wormhole`]: crate::transaction::Builder::add_oracle_update_with_wormhole
+#[derive(Clone)]
+pub(crate) struct Emission<'a> {
+    pub(crate) oracle_pubkey: Pubkey,
+    pub(crate) bridge: CoreBridge,
+    pub(crate) message: &'a solana_keypair::Keypair,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_payload_roundtrip() {
+        let oracle_pubkey = Pubkey::new_unique();
+        let oracle = Oracle {
+            sequence: 7,
+            payload: 1_100_000u64,
+        };
+
+        let payload = message_payload(&oracle_pubkey, &oracle);
+        assert_eq!(payload.len(), 48);
+        assert_eq!(&payload[..32], oracle_pubkey.as_ref());
+        assert_eq!(&payload[32..], oracle.to_bytes());
+
+        let (decoded_pubkey, decoded) = decode_message_payload::<u64>(&payload).unwrap();
+        assert_eq!(decoded_pubkey, oracle_pubkey);
+        assert_eq!((decoded.sequence, decoded.payload), (7, 1_100_000));
+
+        assert!(decode_message_payload::<[u64; 2]>(&payload).is_none());
+    }
+
+    #[test]
+    fn test_post_message_layout() {
+        let bridge = CoreBridge::mainnet(100).with_consistency(Consistency::Confirmed);
+        let (emitter, payer, message) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        let [fee, post] = bridge.post_message(&emitter, &payer, &message, &[0xaa; 3]);
+
+        assert_eq!(fee.program_id, solana_system_interface::program::ID);
+        assert_eq!(fee.accounts[1].pubkey, bridge.fee_collector());
+        assert_eq!(post.program_id, MAINNET_CORE_BRIDGE);
+        assert_eq!(
+            post.data,
+            [8, 0, 0, 0, 0, 3, 0, 0, 0, 0xaa, 0xaa, 0xaa, 0].to_vec()
+        );
+        assert_eq!(
+            post.accounts
+                .iter()
+                .filter(|meta| meta.is_signer)
+                .map(|meta| meta.pubkey)
+                .collect::<Vec<_>>(),
+            vec![message, emitter, payer]
+        );
+        assert_eq!(post.accounts[3].pubkey, bridge.sequence(&emitter));
+    }
+}
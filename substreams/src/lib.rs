@@ -0,0 +1,351 @@
+//! Substreams modules for Doppler, declared in `substreams.yaml`:
+//!
+//! - `map_updates` extracts every Doppler update that landed in a Solana
+//!   block as a typed [`Update`], taking the program ID of the deployment
+//!   to index as its parameter.
+//! - `map_oracle_changes` folds a block's updates into the oracle accounts
+//!   they wrote, as [`OracleChange`]s. An update's data is the oracle's new
+//!   account data, so no account stream is needed.
+
+use std::collections::BTreeMap;
+
+use substreams::errors::Error;
+use substreams_solana::pb::sf::solana::r#type::v1::{Block, ConfirmedTransaction};
+
+mod pb;
+pub use pb::doppler::v1::{OracleChange, OracleChanges, Update, Updates};
+
+#[substreams::handlers::map]
+fn map_updates(params: String, block: Block) -> Result<Updates, Error> {
+    let program_id = match params.trim() {
+        "" => doppler_sdk::ID.to_bytes(),
+        params => bs58::decode(params.as_bytes())
+            .into_array_const::<32>()
+            .map_err(|err| Error::msg(format!("invalid program ID {params}: {err}")))?,
+    };
+
+    Ok(updates(&block, &program_id))
+}
+
+#[substreams::handlers::map]
+fn map_oracle_changes(updates: Updates) -> Result<OracleChanges, Error> {
+    Ok(oracle_changes(updates))
+}
+
+/// Every update to `program_id` that landed in `block`, top-level or made
+/// through another program.
+pub fn updates(block: &Block, program_id: &[u8; 32]) -> Updates {
+    let block_time = block.block_time.as_ref().map_or(0, |time| time.timestamp);
+    let mut updates = Vec::new();
+
+    for transaction in &block.transactions {
+        let Some(instructions) = Instructions::of(transaction) else {
+            continue;
+        };
+
+        for (instruction_index, inner_index, program, accounts, data) in instructions.iter() {
+            if instructions.key(program) != Some(program_id.as_slice()) {
+                continue;
+            }
+            let (Some(admin), Some(oracle)) = (
+                accounts.first().and_then(|index| instructions.key(*index)),
+                accounts.get(1).and_then(|index| instructions.key(*index)),
+            ) else {
+                continue;
+            };
+            let Some((sequence, payload)) = data.split_first_chunk::<8>() else {
+                continue;
+            };
+
+            updates.push(Update {
+                signature: instructions.signature.clone(),
+                slot: block.slot,
+                block_time,
+                instruction_index,
+                inner_index,
+                admin: bs58::encode(admin).into_string(),
+                oracle: bs58::encode(oracle).into_string(),
+                sequence: u64::from_le_bytes(*sequence),
+                payload: payload.to_vec(),
+                // Only a `PriceFeed` payload is exactly one `u64`.
+                price: <[u8; 8]>::try_from(payload).ok().map(u64::from_le_bytes),
+            });
+        }
+    }
+
+    Updates { updates }
+}
+
+/// The state of each oracle `updates` wrote, after the last write.
+pub fn oracle_changes(updates: Updates) -> OracleChanges {
+    let mut changes: BTreeMap<String, OracleChange> = BTreeMap::new();
+
+    for update in updates.updates {
+        let writes = changes
+            .get(&update.oracle)
+            .map_or(0, |change| change.updates);
+        let mut data = update.sequence.to_le_bytes().to_vec();
+        data.extend_from_slice(&update.payload);
+
+        changes.insert(
+            update.oracle.clone(),
+            OracleChange {
+                oracle: update.oracle,
+                slot: update.slot,
+                block_time: update.block_time,
+                signature: update.signature,
+                sequence: update.sequence,
+                data,
+                updates: writes + 1,
+            },
+        );
+    }
+
+    OracleChanges {
+        changes: changes.into_values().collect(),
+    }
+}
+
+/// The instructions of a transaction that succeeded, with its account keys
+/// resolved through address lookup tables.
+struct Instructions<'a> {
+    transaction: &'a ConfirmedTransaction,
+    keys: Vec<&'a [u8]>,
+    signature: String,
+}
+
+impl<'a> Instructions<'a> {
+    fn of(transaction: &'a ConfirmedTransaction) -> Option<Self> {
+        let meta = transaction.meta.as_ref()?;
+        if meta.err.is_some() {
+            return None;
+        }
+        let inner = transaction.transaction.as_ref()?;
+        let message = inner.message.as_ref()?;
+        let keys = message
+            .account_keys
+            .iter()
+            .chain(&meta.loaded_writable_addresses)
+            .chain(&meta.loaded_readonly_addresses)
+            .map(Vec::as_slice)
+            .collect();
+
+        Some(Self {
+            transaction,
+            keys,
+            signature: bs58::encode(inner.signatures.first()?).into_string(),
+        })
+    }
+
+    fn key(&self, index: impl TryInto<usize>) -> Option<&'a [u8]> {
+        self.keys.get(index.try_into().ok()?).copied()
+    }
+
+    /// Each instruction as its top-level index, inner index, program and
+    /// account indexes and data, inner instructions right after the
+    /// instruction that invoked them.
+    fn iter(&self) -> impl Iterator<Item = (u32, Option<u32>, u32, &'a [u8], &'a [u8])> {
+        let transaction = self.transaction;
+        let instructions = transaction
+            .transaction
+            .iter()
+            .filter_map(|inner| inner.message.as_ref())
+            .flat_map(|message| &message.instructions);
+        let inner = transaction
+            .meta
+            .iter()
+            .flat_map(|meta| &meta.inner_instructions);
+
+        instructions
+            .zip(0u32..)
+            .flat_map(move |(instruction, index)| {
+                let invoked = inner
+                    .clone()
+                    .filter(move |inner| inner.index == index)
+                    .flat_map(|inner| inner.instructions.iter().zip(0u32..))
+                    .map(move |(instruction, inner_index)| {
+                        (
+                            index,
+                            Some(inner_index),
+                            instruction.program_id_index,
+                            instruction.accounts.as_slice(),
+                            instruction.data.as_slice(),
+                        )
+                    });
+
+                std::iter::once((
+                    index,
+                    None,
+                    instruction.program_id_index,
+                    instruction.accounts.as_slice(),
+                    instruction.data.as_slice(),
+                ))
+                .chain(invoked)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use substreams_solana::pb::sf::solana::r#type::v1::{
+        CompiledInstruction, InnerInstruction, InnerInstructions, Message, Transaction,
+        TransactionError, TransactionStatusMeta, UnixTimestamp,
+    };
+
+    use super::*;
+
+    const ADMIN: [u8; 32] = [1; 32];
+    const ORACLE: [u8; 32] = [2; 32];
+    const OTHER_PROGRAM: [u8; 32] = [3; 32];
+
+    fn update_data(sequence: u64, price: u64) -> Vec<u8> {
+        [sequence.to_le_bytes(), price.to_le_bytes()].concat()
+    }
+
+    /// A transaction whose keys are the admin, the oracle, Doppler and
+    /// another program.
+    fn transaction(
+        signature: u8,
+        instructions: Vec<CompiledInstruction>,
+        inner: Vec<InnerInstructions>,
+        failed: bool,
+    ) -> ConfirmedTransaction {
+        ConfirmedTransaction {
+            transaction: Some(Transaction {
+                signatures: vec![vec![signature; 64]],
+                message: Some(Message {
+                    account_keys: vec![
+                        ADMIN.to_vec(),
+                        ORACLE.to_vec(),
+                        doppler_sdk::ID.to_bytes().to_vec(),
+                        OTHER_PROGRAM.to_vec(),
+                    ],
+                    instructions,
+                    ..Default::default()
+                }),
+            }),
+            meta: Some(TransactionStatusMeta {
+                err: failed.then(TransactionError::default),
+                inner_instructions: inner,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn doppler(data: Vec<u8>) -> CompiledInstruction {
+        CompiledInstruction {
+            program_id_index: 2,
+            accounts: vec![0, 1],
+            data,
+        }
+    }
+
+    fn block(transactions: Vec<ConfirmedTransaction>) -> Block {
+        Block {
+            slot: 42,
+            block_time: Some(UnixTimestamp {
+                timestamp: 1_700_000_000,
+            }),
+            transactions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_updates_skip_failed_and_foreign_instructions() {
+        let block = block(vec![
+            transaction(
+                7,
+                vec![
+                    CompiledInstruction {
+                        program_id_index: 3,
+                        accounts: vec![0, 1],
+                        data: update_data(1, 1),
+                    },
+                    doppler(update_data(100, 1_100_000)),
+                ],
+                vec![],
+                false,
+            ),
+            transaction(8, vec![doppler(update_data(99, 1_000_000))], vec![], true),
+        ]);
+
+        let updates = updates(&block, &doppler_sdk::ID.to_bytes()).updates;
+        assert_eq!(
+            updates,
+            vec![Update {
+                signature: bs58::encode([7; 64]).into_string(),
+                slot: 42,
+                block_time: 1_700_000_000,
+                instruction_index: 1,
+                inner_index: None,
+                admin: bs58::encode(ADMIN).into_string(),
+                oracle: bs58::encode(ORACLE).into_string(),
+                sequence: 100,
+                payload: 1_100_000u64.to_le_bytes().to_vec(),
+                price: Some(1_100_000),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_updates_include_inner_instructions() {
+        let block = block(vec![transaction(
+            7,
+            vec![CompiledInstruction {
+                program_id_index: 3,
+                accounts: vec![0, 1, 2],
+                data: vec![],
+            }],
+            vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    program_id_index: 2,
+                    accounts: vec![0, 1],
+                    data: update_data(100, 1_100_000),
+                    stack_height: Some(2),
+                }],
+            }],
+            false,
+        )]);
+
+        let updates = updates(&block, &doppler_sdk::ID.to_bytes()).updates;
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].instruction_index, 0);
+        assert_eq!(updates[0].inner_index, Some(0));
+        assert_eq!(updates[0].sequence, 100);
+    }
+
+    #[test]
+    fn test_updates_only_decode_price_feeds() {
+        let mut data = update_data(100, 1_100_000);
+        data.extend_from_slice(&[0; 8]);
+        let block = block(vec![transaction(7, vec![doppler(data)], vec![], false)]);
+
+        let updates = updates(&block, &doppler_sdk::ID.to_bytes()).updates;
+        assert_eq!(updates[0].payload.len(), 16);
+        assert_eq!(updates[0].price, None);
+    }
+
+    #[test]
+    fn test_oracle_changes_keep_last_write() {
+        let block = block(vec![
+            transaction(7, vec![doppler(update_data(100, 1_100_000))], vec![], false),
+            transaction(8, vec![doppler(update_data(101, 1_200_000))], vec![], false),
+        ]);
+
+        let changes = oracle_changes(updates(&block, &doppler_sdk::ID.to_bytes())).changes;
+        assert_eq!(
+            changes,
+            vec![OracleChange {
+                oracle: bs58::encode(ORACLE).into_string(),
+                slot: 42,
+                block_time: 1_700_000_000,
+                signature: bs58::encode([8; 64]).into_string(),
+                sequence: 101,
+                data: update_data(101, 1_200_000),
+                updates: 2,
+            }]
+        );
+    }
+}
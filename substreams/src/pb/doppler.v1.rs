@@ -0,0 +1,73 @@
+// @generated
+// This file is @generated by prost-build.
+/// Every successful Doppler update in a block, in execution order.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Updates {
+    #[prost(message, repeated, tag="1")]
+    pub updates: ::prost::alloc::vec::Vec<Update>,
+}
+/// An update instruction that landed. Updates that failed, such as stale
+/// sequences or a wrong admin, are left out.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Update {
+    /// Base58 signature of the transaction.
+    #[prost(string, tag="1")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(uint64, tag="2")]
+    pub slot: u64,
+    /// Unix seconds, 0 if the block has no time.
+    #[prost(int64, tag="3")]
+    pub block_time: i64,
+    /// Index of the top-level instruction.
+    #[prost(uint32, tag="4")]
+    pub instruction_index: u32,
+    /// Index among the instruction's inner instructions, for updates made
+    /// through another program.
+    #[prost(uint32, optional, tag="5")]
+    pub inner_index: ::core::option::Option<u32>,
+    /// Base58 pubkeys.
+    #[prost(string, tag="6")]
+    pub admin: ::prost::alloc::string::String,
+    #[prost(string, tag="7")]
+    pub oracle: ::prost::alloc::string::String,
+    #[prost(uint64, tag="8")]
+    pub sequence: u64,
+    /// The payload as written, padding included.
+    #[prost(bytes="vec", tag="9")]
+    pub payload: ::prost::alloc::vec::Vec<u8>,
+    /// The price of `PriceFeed` oracles, the program's default payload.
+    #[prost(uint64, optional, tag="10")]
+    pub price: ::core::option::Option<u64>,
+}
+/// The oracles written in a block, each as it was after the block.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OracleChanges {
+    #[prost(message, repeated, tag="1")]
+    pub changes: ::prost::alloc::vec::Vec<OracleChange>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OracleChange {
+    /// Base58 pubkey.
+    #[prost(string, tag="1")]
+    pub oracle: ::prost::alloc::string::String,
+    #[prost(uint64, tag="2")]
+    pub slot: u64,
+    #[prost(int64, tag="3")]
+    pub block_time: i64,
+    /// Transaction of the last write in the block.
+    #[prost(string, tag="4")]
+    pub signature: ::prost::alloc::string::String,
+    #[prost(uint64, tag="5")]
+    pub sequence: u64,
+    /// The oracle account data: the sequence followed by the payload.
+    #[prost(bytes="vec", tag="6")]
+    pub data: ::prost::alloc::vec::Vec<u8>,
+    /// Writes to the oracle in the block.
+    #[prost(uint32, tag="7")]
+    pub updates: u32,
+}
+// @@protoc_insertion_point(module)
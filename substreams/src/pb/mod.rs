@@ -0,0 +1,6 @@
+// @generated
+pub mod doppler {
+    pub mod v1 {
+        include!("doppler.v1.rs");
+    }
+}
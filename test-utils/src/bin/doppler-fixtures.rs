@@ -0,0 +1,128 @@
+//! Captures fixtures of the typical Doppler flows against the program's ELF,
+//! one JSON file per flow, for integrators to replay in their own mollusk
+//! tests with [`Fixture::replay`] or start from with
+//! [`Fixture::post_accounts`].
+
+use std::{fs, path::PathBuf};
+
+use clap::Parser;
+use doppler_sdk::{Oracle, UpdateInstruction};
+use doppler_test_utils::{
+    create_oracle_instruction, fixtures::Fixture, keyed_account_for_admin,
+    keyed_account_for_oracle, oracle_address, update_chain,
+};
+use mollusk_svm::{program::keyed_account_for_system_program, Mollusk};
+use solana_account::Account;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+/// The admin key the program is built with.
+const ADMIN: Pubkey = Pubkey::from_str_const("admnz5UvRa93HM5nTrxXmsJ1rw2tvXMBFGauvCgzQhE");
+const SEED: &str = "SOL/USDC";
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct PriceFeed {
+    price: u64,
+}
+
+/// A flow's name, its instructions and the accounts they run against.
+type Flow = (&'static str, Vec<Instruction>, Vec<(Pubkey, Account)>);
+
+#[derive(Parser)]
+#[command(about = "Captures mollusk fixtures of Doppler flows")]
+struct Args {
+    /// Path to the program's ELF, without `.so`.
+    #[arg(long, default_value = "target/deploy/doppler_program")]
+    program: String,
+    /// Directory to write the fixtures to.
+    #[arg(long, default_value = "fixtures")]
+    out: PathBuf,
+}
+
+fn update(admin: Pubkey, oracle: Pubkey, sequence: u64, price: u64) -> Instruction {
+    UpdateInstruction {
+        admin,
+        oracle_pubkey: oracle,
+        oracle: Oracle {
+            sequence,
+            payload: PriceFeed { price },
+        },
+    }
+    .into()
+}
+
+fn main() {
+    let args = Args::parse();
+    let mollusk = Mollusk::new(&doppler_sdk::ID, &args.program);
+    let elf = format!(
+        "{}.so",
+        args.program.rsplit('/').next().unwrap_or(&args.program)
+    );
+    fs::create_dir_all(&args.out).unwrap();
+
+    let (oracle, oracle_account) =
+        keyed_account_for_oracle(&mollusk, ADMIN, SEED, PriceFeed { price: 100_000 });
+    let intruder = Pubkey::new_from_array([0x11; 32]);
+    let (batch_accounts, batch_instructions) = update_chain(
+        &mollusk,
+        ADMIN,
+        8,
+        PriceFeed { price: 100_000 },
+        Oracle {
+            sequence: 1,
+            payload: PriceFeed { price: 1_100_000 },
+        },
+    );
+
+    let flows: [Flow; 5] = [
+        (
+            "create",
+            vec![create_oracle_instruction::<PriceFeed>(
+                &mollusk, &ADMIN, SEED,
+            )],
+            vec![
+                keyed_account_for_admin(ADMIN),
+                (oracle_address(&ADMIN, SEED), Account::default()),
+                keyed_account_for_system_program(),
+            ],
+        ),
+        (
+            "update",
+            vec![update(ADMIN, oracle, 1, 1_100_000)],
+            vec![
+                keyed_account_for_admin(ADMIN),
+                (oracle, oracle_account.clone()),
+            ],
+        ),
+        (
+            "stale_update",
+            vec![update(ADMIN, oracle, 0, 1_100_000)],
+            vec![
+                keyed_account_for_admin(ADMIN),
+                (oracle, oracle_account.clone()),
+            ],
+        ),
+        (
+            "wrong_admin",
+            vec![update(intruder, oracle, 1, 1_100_000)],
+            vec![keyed_account_for_admin(intruder), (oracle, oracle_account)],
+        ),
+        ("batch_update", batch_instructions, batch_accounts),
+    ];
+
+    for (name, instructions, accounts) in flows {
+        let fixture = Fixture::capture(&mollusk, name, &elf, &instructions, &accounts);
+        let path = args.out.join(format!("{name}.json"));
+        fixture.save(&path).unwrap();
+        println!(
+            "{}: {} CUs{}",
+            path.display(),
+            fixture.compute_units,
+            fixture
+                .error
+                .map(|err| format!(", fails with {err}"))
+                .unwrap_or_default()
+        );
+    }
+}
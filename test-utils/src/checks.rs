@@ -0,0 +1,43 @@
+use mollusk_svm::result::Check;
+use solana_instruction::error::InstructionError;
+
+/// Exit code of a failed admin check.
+pub const INVALID_ADMIN: u32 = 1;
+/// Exit code of an update whose sequence does not increase.
+pub const STALE_SEQUENCE: u32 = 2;
+
+/// A way a Doppler update is rejected, either by the program's checks or by
+/// the runtime after it runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The first account is not the admin.
+    WrongAdmin,
+    /// The admin is the first account but did not sign.
+    MissingSignature,
+    /// The update's sequence is not above the oracle's.
+    StaleSequence,
+    /// The oracle is not owned by the program, so the runtime discards the
+    /// write.
+    WrongOwner,
+    /// The oracle is not writable, so the runtime discards the write.
+    ReadonlyOracle,
+}
+
+impl Failure {
+    /// The error the instruction fails with.
+    #[must_use]
+    pub const fn instruction_error(self) -> InstructionError {
+        match self {
+            Self::WrongAdmin | Self::MissingSignature => InstructionError::Custom(INVALID_ADMIN),
+            Self::StaleSequence => InstructionError::Custom(STALE_SEQUENCE),
+            Self::WrongOwner => InstructionError::ExternalAccountDataModified,
+            Self::ReadonlyOracle => InstructionError::ReadonlyDataModified,
+        }
+    }
+
+    /// A mollusk check that the instruction fails this way.
+    #[must_use]
+    pub fn check(self) -> Check<'static> {
+        Check::instruction_err(self.instruction_error())
+    }
+}
@@ -0,0 +1,242 @@
+//! Fixtures of Doppler flows for replaying under mollusk without deploying
+//! the program: the instructions, every account before them and every
+//! account after, as captured against the program's ELF.
+//!
+//! Accounts are stored in the shape `solana account --output json` prints,
+//! so fixtures can be compared with, or built from, accounts on a cluster.
+//!
+//! ```json
+//! {
+//!   "name": "update",
+//!   "program": { "program_id": "fastRQ...", "elf": "doppler_program.so" },
+//!   "instructions": [{ "program_id": "...", "data": "<base64>", "accounts": [...] }],
+//!   "pre": [{ "pubkey": "...", "account": { "lamports": 1, "data": ["<base64>", "base64"], ... } }],
+//!   "post": [...],
+//!   "compute_units": 21,
+//!   "error": null
+//! }
+//! ```
+
+use std::{fs, io, path::Path, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mollusk_svm::{result::InstructionResult, Mollusk};
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+/// A flow captured against the Doppler program.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Fixture {
+    pub name: String,
+    pub program: FixtureProgram,
+    /// Run in order as one chain.
+    pub instructions: Vec<FixtureInstruction>,
+    /// Every account the instructions read, before they run.
+    pub pre: Vec<FixtureAccount>,
+    /// The same accounts after the chain, unchanged if it failed.
+    pub post: Vec<FixtureAccount>,
+    pub compute_units: u64,
+    /// The error the chain failed with, if it did.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FixtureProgram {
+    pub program_id: String,
+    /// File name of the ELF the fixture was captured against.
+    pub elf: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FixtureInstruction {
+    pub program_id: String,
+    /// Base64 encoded.
+    pub data: String,
+    pub accounts: Vec<FixtureMeta>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FixtureMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// An account as printed by `solana account --output json`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FixtureAccount {
+    pub pubkey: String,
+    pub account: CliAccount,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccount {
+    pub lamports: u64,
+    /// Data and its encoding, which is always `base64`.
+    pub data: (String, String),
+    pub owner: String,
+    pub executable: bool,
+    pub rent_epoch: u64,
+}
+
+impl Fixture {
+    /// Runs `instructions` as one chain against `mollusk` and records the
+    /// accounts before and after.
+    pub fn capture(
+        mollusk: &Mollusk,
+        name: &str,
+        elf: &str,
+        instructions: &[Instruction],
+        accounts: &[(Pubkey, Account)],
+    ) -> Self {
+        let result = mollusk.process_instruction_chain(instructions, accounts);
+        let post = if result.raw_result.is_ok() {
+            &result.resulting_accounts[..]
+        } else {
+            accounts
+        };
+
+        Self {
+            name: name.to_string(),
+            program: FixtureProgram {
+                program_id: doppler_sdk::ID.to_string(),
+                elf: elf.to_string(),
+            },
+            instructions: instructions.iter().map(FixtureInstruction::from).collect(),
+            pre: accounts.iter().map(FixtureAccount::from).collect(),
+            post: post.iter().map(FixtureAccount::from).collect(),
+            compute_units: result.compute_units_consumed,
+            error: result.raw_result.err().map(|err| err.to_string()),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+    }
+
+    #[must_use]
+    pub fn instructions(&self) -> Vec<Instruction> {
+        self.instructions.iter().map(Instruction::from).collect()
+    }
+
+    /// The accounts before the chain, to run it against.
+    #[must_use]
+    pub fn pre_accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.pre.iter().map(<(Pubkey, Account)>::from).collect()
+    }
+
+    /// The accounts after the chain, to start a test from without running
+    /// the program.
+    #[must_use]
+    pub fn post_accounts(&self) -> Vec<(Pubkey, Account)> {
+        self.post.iter().map(<(Pubkey, Account)>::from).collect()
+    }
+
+    /// Runs the chain against `mollusk` and asserts it ends as captured:
+    /// the same error, or the same accounts.
+    ///
+    /// # Panics
+    /// Panics if the result differs from the fixture.
+    pub fn replay(&self, mollusk: &Mollusk) -> InstructionResult {
+        let result = mollusk.process_instruction_chain(&self.instructions(), &self.pre_accounts());
+
+        assert_eq!(
+            result.raw_result.clone().err().map(|err| err.to_string()),
+            self.error,
+            "{}: unexpected result",
+            self.name
+        );
+        if self.error.is_none() {
+            for (pubkey, account) in self.post_accounts() {
+                assert_eq!(
+                    result.get_account(&pubkey),
+                    Some(&account),
+                    "{}: account {pubkey} differs",
+                    self.name
+                );
+            }
+        }
+
+        result
+    }
+}
+
+fn pubkey(key: &str) -> Pubkey {
+    Pubkey::from_str(key).unwrap()
+}
+
+impl From<&Instruction> for FixtureInstruction {
+    fn from(ix: &Instruction) -> Self {
+        Self {
+            program_id: ix.program_id.to_string(),
+            data: STANDARD.encode(&ix.data),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| FixtureMeta {
+                    pubkey: meta.pubkey.to_string(),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&FixtureInstruction> for Instruction {
+    fn from(ix: &FixtureInstruction) -> Self {
+        Self {
+            program_id: pubkey(&ix.program_id),
+            accounts: ix
+                .accounts
+                .iter()
+                .map(|meta| AccountMeta {
+                    pubkey: pubkey(&meta.pubkey),
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: STANDARD.decode(&ix.data).unwrap(),
+        }
+    }
+}
+
+impl From<&(Pubkey, Account)> for FixtureAccount {
+    fn from((pubkey, account): &(Pubkey, Account)) -> Self {
+        Self {
+            pubkey: pubkey.to_string(),
+            account: CliAccount {
+                lamports: account.lamports,
+                data: (STANDARD.encode(&account.data), "base64".to_string()),
+                owner: account.owner.to_string(),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+            },
+        }
+    }
+}
+
+impl From<&FixtureAccount> for (Pubkey, Account) {
+    fn from(fixture: &FixtureAccount) -> Self {
+        let account = &fixture.account;
+        assert_eq!(account.data.1, "base64", "account data must be base64");
+
+        (
+            pubkey(&fixture.pubkey),
+            Account {
+                lamports: account.lamports,
+                data: STANDARD.decode(&account.data.0).unwrap(),
+                owner: pubkey(&account.owner),
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+            },
+        )
+    }
+}
@@ -0,0 +1,125 @@
+//! Builders for testing Doppler oracles under Mollusk, and under LiteSVM
+//! with the `litesvm` feature: funded admin accounts, oracle accounts at
+//! any state, the instructions that create and update them, and the ways
+//! those updates fail.
+
+pub mod checks;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+#[cfg(feature = "litesvm")]
+pub mod litesvm;
+
+use doppler_sdk::{Oracle, UpdateInstruction};
+use mollusk_svm::Mollusk;
+use solana_account::Account;
+use solana_clock::Epoch;
+use solana_instruction::Instruction;
+use solana_pubkey::Pubkey;
+
+/// Lamports the admin account is funded with.
+pub const ADMIN_LAMPORTS: u64 = 10_000_000_000;
+
+/// A funded system account for `key`, to sign and pay as the admin.
+#[must_use]
+pub fn keyed_account_for_admin(key: Pubkey) -> (Pubkey, Account) {
+    (
+        key,
+        Account::new(ADMIN_LAMPORTS, 0, &solana_sdk_ids::system_program::ID),
+    )
+}
+
+/// The rent-exempt oracle seeded with `seed` under `admin`, holding
+/// `payload` at sequence 0.
+pub fn keyed_account_for_oracle<T: Sized + Copy>(
+    mollusk: &Mollusk,
+    admin: Pubkey,
+    seed: &str,
+    payload: T,
+) -> (Pubkey, Account) {
+    keyed_account_for_oracle_state(
+        mollusk,
+        admin,
+        seed,
+        Oracle {
+            sequence: 0,
+            payload,
+        },
+    )
+}
+
+/// The rent-exempt oracle seeded with `seed` under `admin`, holding
+/// `oracle`, sized for `Oracle<T>` as created on chain.
+pub fn keyed_account_for_oracle_state<T: Sized + Copy>(
+    mollusk: &Mollusk,
+    admin: Pubkey,
+    seed: &str,
+    oracle: Oracle<T>,
+) -> (Pubkey, Account) {
+    let key = oracle_address(&admin, seed);
+    let mut data = vec![0; Oracle::<T>::LEN];
+    oracle.encode_into(&mut data);
+    let account = Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(data.len()),
+        data,
+        owner: doppler_sdk::ID,
+        executable: false,
+        rent_epoch: Epoch::default(),
+    };
+
+    (key, account)
+}
+
+/// The address of the oracle seeded with `seed` under `admin`.
+#[must_use]
+pub fn oracle_address(admin: &Pubkey, seed: &str) -> Pubkey {
+    Pubkey::create_with_seed(admin, seed, &doppler_sdk::ID).unwrap()
+}
+
+/// Creates the oracle seeded with `seed`, rent-exempt and sized for
+/// `Oracle<T>`, with `admin` paying.
+pub fn create_oracle_instruction<T: Sized + Copy>(
+    mollusk: &Mollusk,
+    admin: &Pubkey,
+    seed: &str,
+) -> Instruction {
+    solana_system_interface::instruction::create_account_with_seed(
+        admin,
+        &oracle_address(admin, seed),
+        admin,
+        seed,
+        mollusk.sysvars.rent.minimum_balance(Oracle::<T>::LEN),
+        Oracle::<T>::LEN as u64,
+        &doppler_sdk::ID,
+    )
+}
+
+/// A batch of `count` oracles seeded `feed-0`, `feed-1`, ..., each holding
+/// `payload` at sequence 0, and one instruction updating each to `update`.
+/// The accounts start with the admin's, ready for
+/// [`Mollusk::process_instruction_chain`].
+pub fn update_chain<T: Sized + Copy>(
+    mollusk: &Mollusk,
+    admin: Pubkey,
+    count: usize,
+    payload: T,
+    update: Oracle<T>,
+) -> (Vec<(Pubkey, Account)>, Vec<Instruction>) {
+    let mut accounts = vec![keyed_account_for_admin(admin)];
+    let mut instructions = vec![];
+
+    for index in 0..count {
+        let (oracle, oracle_account) =
+            keyed_account_for_oracle(mollusk, admin, &format!("feed-{index}"), payload);
+        accounts.push((oracle, oracle_account));
+        instructions.push(
+            UpdateInstruction {
+                admin,
+                oracle_pubkey: oracle,
+                oracle: update,
+            }
+            .into(),
+        );
+    }
+
+    (accounts, instructions)
+}
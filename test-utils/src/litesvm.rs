@@ -0,0 +1,82 @@
+use doppler_sdk::Oracle;
+use litesvm::LiteSVM;
+use solana_instruction::Instruction;
+use solana_keypair::Keypair;
+use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_system_interface::instruction::create_account_with_seed;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+
+use crate::{checks::Failure, oracle_address};
+
+/// Signs `instructions` with `payer` and sends them, returning why the
+/// transaction failed if it did.
+pub fn send(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    instructions: &[Instruction],
+) -> Result<(), TransactionError> {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+        .map(|_| ())
+        .map_err(|failed| failed.err)
+}
+
+/// Creates the rent-exempt oracle seeded with `seed` under `admin`, `extra`
+/// bytes larger than `Oracle<T>`.
+///
+/// # Panics
+/// Panics if the transaction fails.
+pub fn create_oracle<T: Sized + Copy>(
+    svm: &mut LiteSVM,
+    admin: &Keypair,
+    seed: &str,
+    extra: usize,
+) -> Pubkey {
+    let oracle = oracle_address(&admin.pubkey(), seed);
+    let space = Oracle::<T>::LEN + extra;
+    let lamports = svm.minimum_balance_for_rent_exemption(space);
+
+    send(
+        svm,
+        admin,
+        &[create_account_with_seed(
+            &admin.pubkey(),
+            &oracle,
+            &admin.pubkey(),
+            seed,
+            lamports,
+            space as u64,
+            &doppler_sdk::ID,
+        )],
+    )
+    .unwrap();
+    oracle
+}
+
+/// Decodes the oracle stored at `oracle`.
+///
+/// # Panics
+/// Panics if the account doesn't exist.
+pub fn read_oracle<T: Sized + Copy>(svm: &LiteSVM, oracle: &Pubkey) -> Oracle<T> {
+    Oracle::from_bytes(&svm.get_account(oracle).unwrap().data)
+}
+
+/// Asserts that `result` is a transaction rejected with `failure`.
+///
+/// # Panics
+/// Panics if the transaction succeeded or failed another way.
+pub fn assert_failure(result: Result<(), TransactionError>, failure: Failure) {
+    match result {
+        Err(TransactionError::InstructionError(_, error)) => {
+            assert_eq!(error, failure.instruction_error(), "expected {failure:?}");
+        }
+        other => panic!("expected {failure:?}, got {other:?}"),
+    }
+}
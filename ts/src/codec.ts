@@ -0,0 +1,28 @@
+/**
+ * Encodes and decodes an oracle payload with the layout of its
+ * `#[repr(C)]` Rust struct.
+ */
+export interface PayloadCodec<T> {
+  /** Size of the payload in bytes, `size_of::<T>()`. */
+  readonly size: number;
+  encode(value: T): Uint8Array;
+  decode(data: Uint8Array): T;
+}
+
+export function readU128(view: DataView, offset: number): bigint {
+  return view.getBigUint64(offset, true) | (view.getBigUint64(offset + 8, true) << 64n);
+}
+
+export function readI128(view: DataView, offset: number): bigint {
+  return BigInt.asIntN(128, readU128(view, offset));
+}
+
+export function writeU128(view: DataView, offset: number, value: bigint): void {
+  const bits = BigInt.asUintN(128, value);
+  view.setBigUint64(offset, BigInt.asUintN(64, bits), true);
+  view.setBigUint64(offset + 8, bits >> 64n, true);
+}
+
+export function writeI128(view: DataView, offset: number, value: bigint): void {
+  writeU128(view, offset, value);
+}
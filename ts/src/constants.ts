@@ -0,0 +1,29 @@
+import { PublicKey } from "@solana/web3.js";
+
+/** Program ID of the Doppler oracle program. */
+export const PROGRAM_ID = new PublicKey("fastRQJt3nLdY3QA7n8eZ8ETEVefy56ryfUGVkfZokm");
+
+// Oracle account data offsets
+export const SEQUENCE_OFFSET = 0x00; // (sequence: u64)
+export const PAYLOAD_OFFSET = 0x08; // (payload: T)
+
+/**
+ * Anchor's discriminator for an account named `Oracle`, which prefixes the
+ * oracles of a program built with the `anchor` feature.
+ */
+export const ANCHOR_DISCRIMINATOR = Uint8Array.from([0x8b, 0xc2, 0x83, 0xb3, 0x8c, 0xb3, 0xe5, 0xf4]);
+
+// Program exit codes
+export const ADMIN_CHECK_EXIT_CODE = 1;
+export const INVALID_SEQUENCE_EXIT_CODE = 2;
+
+export const SEQUENCE_CHECK_CU = 5;
+export const ADMIN_VERIFICATION_CU = 6;
+export const PAYLOAD_WRITE_CU = 6;
+
+export const COMPUTE_BUDGET_IX_CU = 150;
+export const COMPUTE_BUDGET_UNIT_PRICE_SIZE = 9;
+export const COMPUTE_BUDGET_UNIT_LIMIT_SIZE = 5;
+export const COMPUTE_BUDGET_DATA_LIMIT_SIZE = 5;
+export const COMPUTE_BUDGET_PROGRAM_SIZE = 22;
+export const ORACLE_PROGRAM_SIZE = 36;
@@ -0,0 +1,22 @@
+import { ADMIN_CHECK_EXIT_CODE, INVALID_SEQUENCE_EXIT_CODE } from "./constants.js";
+
+/** Why the program rejected an update, from its exit code. */
+export type DopplerProgramError =
+  | { kind: "InvalidAdmin" }
+  | { kind: "StaleSequence" }
+  | { kind: "Unknown"; code: number };
+
+/**
+ * Decodes the custom error code of a failed Doppler instruction, e.g. the
+ * `Custom` value of an `InstructionError`.
+ */
+export function decodeProgramError(code: number): DopplerProgramError {
+  switch (code) {
+    case ADMIN_CHECK_EXIT_CODE:
+      return { kind: "InvalidAdmin" };
+    case INVALID_SEQUENCE_EXIT_CODE:
+      return { kind: "StaleSequence" };
+    default:
+      return { kind: "Unknown", code };
+  }
+}
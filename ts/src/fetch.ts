@@ -0,0 +1,70 @@
+import { Commitment, Connection, PublicKey } from "@solana/web3.js";
+
+import { PayloadCodec } from "./codec.js";
+import { PROGRAM_ID } from "./constants.js";
+import { decodeOracle, Oracle } from "./oracle.js";
+
+/** An oracle read along with the slot the RPC node served it at. */
+export interface OracleSnapshot<T> {
+  slot: number;
+  oracle: Oracle<T>;
+}
+
+export interface FetchOptions {
+  commitment?: Commitment;
+  /** A Doppler deployment other than the default one. */
+  programId?: PublicKey;
+}
+
+/**
+ * Reads an oracle, refusing accounts that do not exist or are not owned by
+ * the program.
+ */
+export async function fetchOracle<T>(
+  connection: Connection,
+  address: PublicKey,
+  codec: PayloadCodec<T>,
+  { commitment, programId = PROGRAM_ID }: FetchOptions = {},
+): Promise<OracleSnapshot<T>> {
+  const { context, value } = await connection.getAccountInfoAndContext(address, commitment);
+  if (value === null) {
+    throw new Error(`oracle ${address.toBase58()} does not exist`);
+  }
+  if (!value.owner.equals(programId)) {
+    throw new Error(`oracle ${address.toBase58()} is owned by ${value.owner.toBase58()}, not the doppler program`);
+  }
+
+  return { slot: context.slot, oracle: decodeOracle(codec, value.data) };
+}
+
+/**
+ * Calls `callback` with every write to the oracle whose sequence is newer
+ * than the last one seen, over the RPC node's websocket. Resolves to a
+ * function that unsubscribes.
+ */
+export function subscribeOracle<T>(
+  connection: Connection,
+  address: PublicKey,
+  codec: PayloadCodec<T>,
+  callback: (snapshot: OracleSnapshot<T>) => void,
+  { commitment, programId = PROGRAM_ID }: FetchOptions = {},
+): () => Promise<void> {
+  let lastSequence = -1n;
+
+  const id = connection.onAccountChange(
+    address,
+    (account, context) => {
+      if (!account.owner.equals(programId)) {
+        return;
+      }
+      const oracle = decodeOracle(codec, account.data);
+      if (oracle.sequence > lastSequence) {
+        lastSequence = oracle.sequence;
+        callback({ slot: context.slot, oracle });
+      }
+    },
+    commitment,
+  );
+
+  return () => connection.removeAccountChangeListener(id);
+}
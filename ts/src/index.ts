@@ -0,0 +1,7 @@
+export * from "./codec.js";
+export * from "./constants.js";
+export * from "./errors.js";
+export * from "./fetch.js";
+export * from "./instructions.js";
+export * from "./oracle.js";
+export * from "./payloads.js";
@@ -0,0 +1,149 @@
+import {
+  ComputeBudgetProgram,
+  PublicKey,
+  SystemProgram,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import { Buffer } from "buffer";
+
+import { PayloadCodec } from "./codec.js";
+import {
+  ADMIN_VERIFICATION_CU,
+  COMPUTE_BUDGET_DATA_LIMIT_SIZE,
+  COMPUTE_BUDGET_IX_CU,
+  COMPUTE_BUDGET_PROGRAM_SIZE,
+  COMPUTE_BUDGET_UNIT_LIMIT_SIZE,
+  COMPUTE_BUDGET_UNIT_PRICE_SIZE,
+  ORACLE_PROGRAM_SIZE,
+  PAYLOAD_WRITE_CU,
+  PROGRAM_ID,
+  SEQUENCE_CHECK_CU,
+} from "./constants.js";
+import { encodeOracle, Oracle, oracleLen } from "./oracle.js";
+
+/** Maximum compute units a single transaction can request. */
+const MAX_COMPUTE_UNIT_LIMIT = 1_400_000;
+
+export interface OracleUpdate<T> {
+  /** The oracle account to write. */
+  oracle: PublicKey;
+  update: Oracle<T>;
+}
+
+export interface BudgetOptions {
+  /** Compute unit price in micro-lamports, to add a priority fee. */
+  unitPrice?: number | bigint;
+  /** A Doppler deployment other than the default one. */
+  programId?: PublicKey;
+}
+
+/** Derives the address of an oracle created with `createAccountWithSeed`. */
+export function deriveOracleAddress(
+  admin: PublicKey,
+  seed: string,
+  programId: PublicKey = PROGRAM_ID,
+): Promise<PublicKey> {
+  return PublicKey.createWithSeed(admin, seed, programId);
+}
+
+/**
+ * Creates the oracle account at `oracle`, derived from `admin` and `seed`,
+ * sized for the payload. Fund it with the rent-exempt minimum for
+ * `oracleLen(codec)` bytes.
+ */
+export function createOracleInstruction<T>(
+  codec: PayloadCodec<T>,
+  params: {
+    admin: PublicKey;
+    oracle: PublicKey;
+    seed: string;
+    lamports: number;
+    programId?: PublicKey;
+  },
+): TransactionInstruction {
+  return SystemProgram.createAccountWithSeed({
+    fromPubkey: params.admin,
+    newAccountPubkey: params.oracle,
+    basePubkey: params.admin,
+    seed: params.seed,
+    lamports: params.lamports,
+    space: oracleLen(codec),
+    programId: params.programId ?? PROGRAM_ID,
+  });
+}
+
+/** Builds an oracle update instruction, matching `UpdateInstruction<T>`. */
+export function updateInstruction<T>(
+  codec: PayloadCodec<T>,
+  admin: PublicKey,
+  { oracle, update }: OracleUpdate<T>,
+  programId: PublicKey = PROGRAM_ID,
+): TransactionInstruction {
+  return new TransactionInstruction({
+    programId,
+    keys: [
+      { pubkey: admin, isSigner: true, isWritable: false },
+      { pubkey: oracle, isSigner: false, isWritable: true },
+    ],
+    data: Buffer.from(encodeOracle(codec, update)),
+  });
+}
+
+/** Compute units an update consumes, matching `UpdateInstruction::compute_units`. */
+export function updateComputeUnits<T>(codec: PayloadCodec<T>): number {
+  return (
+    SEQUENCE_CHECK_CU + ADMIN_VERIFICATION_CU + PAYLOAD_WRITE_CU + Math.floor(oracleLen(codec) / 4)
+  );
+}
+
+/**
+ * Builds the instructions of one transaction updating every oracle in
+ * `updates`, preceded by the compute budget instructions the Rust SDK's
+ * `Builder` requests: an optional unit price, then the loaded accounts data
+ * size and compute unit limits sized for the updates.
+ */
+export function updateTransactionInstructions<T>(
+  codec: PayloadCodec<T>,
+  admin: PublicKey,
+  updates: OracleUpdate<T>[],
+  { unitPrice, programId = PROGRAM_ID }: BudgetOptions = {},
+): TransactionInstruction[] {
+  let computeUnits = COMPUTE_BUDGET_IX_CU * 2 + updates.length * updateComputeUnits(codec);
+  let loadedAccountsDataSize =
+    ORACLE_PROGRAM_SIZE +
+    COMPUTE_BUDGET_PROGRAM_SIZE +
+    COMPUTE_BUDGET_UNIT_LIMIT_SIZE +
+    COMPUTE_BUDGET_DATA_LIMIT_SIZE +
+    2 +
+    updates.length * oracleLen(codec) * 2;
+
+  const instructions: TransactionInstruction[] = [];
+  if (unitPrice !== undefined) {
+    computeUnits += COMPUTE_BUDGET_IX_CU;
+    loadedAccountsDataSize += COMPUTE_BUDGET_UNIT_PRICE_SIZE;
+    instructions.push(ComputeBudgetProgram.setComputeUnitPrice({ microLamports: unitPrice }));
+  }
+  if (computeUnits > MAX_COMPUTE_UNIT_LIMIT) {
+    throw new RangeError(`${updates.length} updates need ${computeUnits} compute units, over the limit`);
+  }
+
+  instructions.push(
+    setLoadedAccountsDataSizeLimit(loadedAccountsDataSize),
+    ComputeBudgetProgram.setComputeUnitLimit({ units: computeUnits }),
+    ...updates.map((update) => updateInstruction(codec, admin, update, programId)),
+  );
+  return instructions;
+}
+
+/** `ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit`, which web3.js lacks. */
+function setLoadedAccountsDataSizeLimit(bytes: number): TransactionInstruction {
+  const data = Buffer.alloc(5);
+  data.writeUInt8(4, 0);
+  data.writeUInt32LE(bytes, 1);
+
+  return new TransactionInstruction({
+    programId: ComputeBudgetProgram.programId,
+    keys: [],
+    data,
+  });
+}
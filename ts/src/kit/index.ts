@@ -0,0 +1,2 @@
+export * from "./oracle.js";
+export * from "./payloads.js";
@@ -0,0 +1,59 @@
+import {
+  type Address,
+  type FixedSizeCodec,
+  address,
+  getConstantCodec,
+  getHiddenPrefixCodec,
+  getStructCodec,
+  getU64Codec,
+  padRightCodec,
+} from "@solana/kit";
+
+/** Program address of the Doppler oracle program. */
+export const DOPPLER_PROGRAM_ADDRESS: Address = address("fastRQJt3nLdY3QA7n8eZ8ETEVefy56ryfUGVkfZokm");
+
+/**
+ * Anchor's discriminator for an account named `Oracle`, which prefixes the
+ * oracles of a program built with the `anchor` feature.
+ */
+export const ANCHOR_DISCRIMINATOR = Uint8Array.from([0x8b, 0xc2, 0x83, 0xb3, 0x8c, 0xb3, 0xe5, 0xf4]);
+
+export type Oracle<T> = {
+  sequence: bigint;
+  payload: T;
+};
+
+export type OracleArgs<T> = {
+  sequence: number | bigint;
+  payload: T;
+};
+
+/**
+ * Codec of an oracle account, `Oracle<T>`: the sequence and payload,
+ * padded to the sequence's 8-byte alignment so its size is
+ * `Oracle::<T>::LEN`.
+ */
+export function getOracleCodec<TFrom, TTo>(
+  payload: FixedSizeCodec<TFrom, TTo>,
+): FixedSizeCodec<OracleArgs<TFrom>, Oracle<TTo>> {
+  const size = 8 + payload.fixedSize;
+
+  return padRightCodec(
+    getStructCodec([
+      ["sequence", getU64Codec()],
+      ["payload", payload],
+    ]),
+    Math.ceil(size / 8) * 8 - size,
+  );
+}
+
+/**
+ * Codec of an oracle account of a program built with the `anchor` feature,
+ * prefixed with `ANCHOR_DISCRIMINATOR`. Decoding fails on any other
+ * prefix, including the zeroes of an oracle that was never updated.
+ */
+export function getAnchorOracleCodec<TFrom, TTo>(
+  payload: FixedSizeCodec<TFrom, TTo>,
+): FixedSizeCodec<OracleArgs<TFrom>, Oracle<TTo>> {
+  return getHiddenPrefixCodec(getOracleCodec(payload), [getConstantCodec(ANCHOR_DISCRIMINATOR)]);
+}
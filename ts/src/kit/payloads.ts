@@ -0,0 +1,122 @@
+// Generated by `doppler codegen` from the Rust payload layouts. Do not edit.
+
+import {
+  type Address,
+  type FixedSizeCodec,
+  type ReadonlyUint8Array,
+  fixCodecSize,
+  getAddressCodec,
+  getBytesCodec,
+  getI128Codec,
+  getI32Codec,
+  getI64Codec,
+  getStructCodec,
+  getU64Codec,
+  getU8Codec,
+  padLeftCodec,
+} from "@solana/kit";
+
+export type PriceFeed = {
+  price: bigint;
+};
+
+export type PriceFeedArgs = {
+  price: number | bigint;
+};
+
+/** `PriceFeed`, 8 bytes. */
+export function getPriceFeedCodec(): FixedSizeCodec<PriceFeedArgs, PriceFeed> {
+  return getStructCodec([
+    ["price", getU64Codec()],
+  ]);
+}
+
+export type PythPriceUpdate = {
+  writeAuthority: Address;
+  verificationLevel: number;
+  feedId: ReadonlyUint8Array;
+  price: bigint;
+  conf: bigint;
+  exponent: number;
+  publishTime: bigint;
+  prevPublishTime: bigint;
+  emaPrice: bigint;
+  emaConf: bigint;
+  postedSlot: bigint;
+};
+
+export type PythPriceUpdateArgs = {
+  writeAuthority: Address;
+  verificationLevel: number;
+  feedId: ReadonlyUint8Array;
+  price: number | bigint;
+  conf: number | bigint;
+  exponent: number;
+  publishTime: number | bigint;
+  prevPublishTime: number | bigint;
+  emaPrice: number | bigint;
+  emaConf: number | bigint;
+  postedSlot: number | bigint;
+};
+
+/** `PythPriceUpdate`, 125 bytes. */
+export function getPythPriceUpdateCodec(): FixedSizeCodec<PythPriceUpdateArgs, PythPriceUpdate> {
+  return getStructCodec([
+    ["writeAuthority", getAddressCodec()],
+    ["verificationLevel", getU8Codec()],
+    ["feedId", fixCodecSize(getBytesCodec(), 32)],
+    ["price", getI64Codec()],
+    ["conf", getU64Codec()],
+    ["exponent", getI32Codec()],
+    ["publishTime", getI64Codec()],
+    ["prevPublishTime", getI64Codec()],
+    ["emaPrice", getI64Codec()],
+    ["emaConf", getU64Codec()],
+    ["postedSlot", getU64Codec()],
+  ]);
+}
+
+export type SwitchboardResult = {
+  value: bigint;
+  stdDev: bigint;
+  mean: bigint;
+  range: bigint;
+  minValue: bigint;
+  maxValue: bigint;
+  numSamples: number;
+  submissionIdx: number;
+  slot: bigint;
+  minSlot: bigint;
+  maxSlot: bigint;
+};
+
+export type SwitchboardResultArgs = {
+  value: number | bigint;
+  stdDev: number | bigint;
+  mean: number | bigint;
+  range: number | bigint;
+  minValue: number | bigint;
+  maxValue: number | bigint;
+  numSamples: number;
+  submissionIdx: number;
+  slot: number | bigint;
+  minSlot: number | bigint;
+  maxSlot: number | bigint;
+};
+
+/** `SwitchboardResult`, 128 bytes. */
+export function getSwitchboardResultCodec(): FixedSizeCodec<SwitchboardResultArgs, SwitchboardResult> {
+  return getStructCodec([
+    ["value", getI128Codec()],
+    ["stdDev", getI128Codec()],
+    ["mean", getI128Codec()],
+    ["range", getI128Codec()],
+    ["minValue", getI128Codec()],
+    ["maxValue", getI128Codec()],
+    ["numSamples", getU8Codec()],
+    ["submissionIdx", getU8Codec()],
+    ["slot", padLeftCodec(getU64Codec(), 6)],
+    ["minSlot", getU64Codec()],
+    ["maxSlot", getU64Codec()],
+  ]);
+}
@@ -0,0 +1,41 @@
+import { PayloadCodec } from "./codec.js";
+import { PAYLOAD_OFFSET, SEQUENCE_OFFSET } from "./constants.js";
+
+export interface Oracle<T> {
+  sequence: bigint;
+  payload: T;
+}
+
+/**
+ * Size of the oracle account data, `Oracle::<T>::LEN`: the sequence and
+ * payload, padded to the sequence's 8-byte alignment.
+ */
+export function oracleLen<T>(codec: PayloadCodec<T>): number {
+  return Math.ceil((PAYLOAD_OFFSET + codec.size) / 8) * 8;
+}
+
+/** Serializes an oracle, matching `Oracle::<T>::to_bytes`. */
+export function encodeOracle<T>(codec: PayloadCodec<T>, oracle: Oracle<T>): Uint8Array {
+  const data = new Uint8Array(PAYLOAD_OFFSET + codec.size);
+  new DataView(data.buffer).setBigUint64(SEQUENCE_OFFSET, oracle.sequence, true);
+  data.set(codec.encode(oracle.payload), PAYLOAD_OFFSET);
+  return data;
+}
+
+/**
+ * Deserializes oracle account data, matching `Oracle::<T>::from_bytes`.
+ * Trailing bytes past the payload are ignored.
+ */
+export function decodeOracle<T>(codec: PayloadCodec<T>, data: Uint8Array): Oracle<T> {
+  if (data.length < PAYLOAD_OFFSET + codec.size) {
+    throw new RangeError(
+      `expected at least ${PAYLOAD_OFFSET + codec.size} bytes of oracle data, got ${data.length}`,
+    );
+  }
+  const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+
+  return {
+    sequence: view.getBigUint64(SEQUENCE_OFFSET, true),
+    payload: codec.decode(data.subarray(PAYLOAD_OFFSET)),
+  };
+}
@@ -0,0 +1,133 @@
+// Generated by `doppler codegen` from the Rust payload layouts. Do not edit.
+
+import { PublicKey } from "@solana/web3.js";
+import { PayloadCodec, readI128, writeI128 } from "./codec.js";
+
+export interface PriceFeed {
+  price: bigint;
+}
+
+export const PriceFeedCodec: PayloadCodec<PriceFeed> = {
+  size: 8,
+  encode(value) {
+    const data = new Uint8Array(8);
+    const view = new DataView(data.buffer);
+    view.setBigUint64(0, value.price, true);
+    return data;
+  },
+  decode(data) {
+    if (data.length < 8) {
+      throw new RangeError(`PriceFeed needs 8 bytes, got ${data.length}`);
+    }
+    const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+    return {
+      price: view.getBigUint64(0, true),
+    };
+  },
+};
+
+export interface PythPriceUpdate {
+  writeAuthority: PublicKey;
+  verificationLevel: number;
+  feedId: Uint8Array;
+  price: bigint;
+  conf: bigint;
+  exponent: number;
+  publishTime: bigint;
+  prevPublishTime: bigint;
+  emaPrice: bigint;
+  emaConf: bigint;
+  postedSlot: bigint;
+}
+
+export const PythPriceUpdateCodec: PayloadCodec<PythPriceUpdate> = {
+  size: 125,
+  encode(value) {
+    const data = new Uint8Array(125);
+    const view = new DataView(data.buffer);
+    data.set(value.writeAuthority.toBytes(), 0);
+    view.setUint8(32, value.verificationLevel);
+    data.set(value.feedId.subarray(0, 32), 33);
+    view.setBigInt64(65, value.price, true);
+    view.setBigUint64(73, value.conf, true);
+    view.setInt32(81, value.exponent, true);
+    view.setBigInt64(85, value.publishTime, true);
+    view.setBigInt64(93, value.prevPublishTime, true);
+    view.setBigInt64(101, value.emaPrice, true);
+    view.setBigUint64(109, value.emaConf, true);
+    view.setBigUint64(117, value.postedSlot, true);
+    return data;
+  },
+  decode(data) {
+    if (data.length < 125) {
+      throw new RangeError(`PythPriceUpdate needs 125 bytes, got ${data.length}`);
+    }
+    const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+    return {
+      writeAuthority: new PublicKey(data.slice(0, 32)),
+      verificationLevel: view.getUint8(32),
+      feedId: data.slice(33, 65),
+      price: view.getBigInt64(65, true),
+      conf: view.getBigUint64(73, true),
+      exponent: view.getInt32(81, true),
+      publishTime: view.getBigInt64(85, true),
+      prevPublishTime: view.getBigInt64(93, true),
+      emaPrice: view.getBigInt64(101, true),
+      emaConf: view.getBigUint64(109, true),
+      postedSlot: view.getBigUint64(117, true),
+    };
+  },
+};
+
+export interface SwitchboardResult {
+  value: bigint;
+  stdDev: bigint;
+  mean: bigint;
+  range: bigint;
+  minValue: bigint;
+  maxValue: bigint;
+  numSamples: number;
+  submissionIdx: number;
+  slot: bigint;
+  minSlot: bigint;
+  maxSlot: bigint;
+}
+
+export const SwitchboardResultCodec: PayloadCodec<SwitchboardResult> = {
+  size: 128,
+  encode(value) {
+    const data = new Uint8Array(128);
+    const view = new DataView(data.buffer);
+    writeI128(view, 0, value.value);
+    writeI128(view, 16, value.stdDev);
+    writeI128(view, 32, value.mean);
+    writeI128(view, 48, value.range);
+    writeI128(view, 64, value.minValue);
+    writeI128(view, 80, value.maxValue);
+    view.setUint8(96, value.numSamples);
+    view.setUint8(97, value.submissionIdx);
+    view.setBigUint64(104, value.slot, true);
+    view.setBigUint64(112, value.minSlot, true);
+    view.setBigUint64(120, value.maxSlot, true);
+    return data;
+  },
+  decode(data) {
+    if (data.length < 128) {
+      throw new RangeError(`SwitchboardResult needs 128 bytes, got ${data.length}`);
+    }
+    const view = new DataView(data.buffer, data.byteOffset, data.byteLength);
+    return {
+      value: readI128(view, 0),
+      stdDev: readI128(view, 16),
+      mean: readI128(view, 32),
+      range: readI128(view, 48),
+      minValue: readI128(view, 64),
+      maxValue: readI128(view, 80),
+      numSamples: view.getUint8(96),
+      submissionIdx: view.getUint8(97),
+      slot: view.getBigUint64(104, true),
+      minSlot: view.getBigUint64(112, true),
+      maxSlot: view.getBigUint64(120, true),
+    };
+  },
+};
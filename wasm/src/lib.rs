@@ -0,0 +1,160 @@
+use core::str::FromStr;
+
+use doppler_sdk::{ID, PAYLOAD_OFFSET, SEQUENCE_OFFSET};
+use solana_pubkey::Pubkey;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone)]
+pub struct AccountMeta {
+    pub pubkey: String,
+    #[wasm_bindgen(js_name = isSigner)]
+    pub is_signer: bool,
+    #[wasm_bindgen(js_name = isWritable)]
+    pub is_writable: bool,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+pub struct Instruction {
+    #[wasm_bindgen(js_name = programId)]
+    pub program_id: String,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+#[wasm_bindgen(getter_with_clone)]
+pub struct Oracle {
+    pub sequence: u64,
+    pub payload: Vec<u8>,
+}
+
+fn pubkey(value: &str) -> Result<Pubkey, JsError> {
+    Pubkey::from_str(value).map_err(|e| JsError::new(&format!("invalid pubkey {value}: {e}")))
+}
+
+/// Program ID of the Doppler oracle program.
+#[wasm_bindgen(js_name = programId)]
+pub fn program_id() -> String {
+    ID.to_string()
+}
+
+/// Serializes an oracle account, matching `Oracle::<T>::to_bytes`.
+#[wasm_bindgen(js_name = encodeOracle)]
+pub fn encode_oracle(sequence: u64, payload: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(PAYLOAD_OFFSET + payload.len());
+    data.extend_from_slice(&sequence.to_le_bytes());
+    data.extend_from_slice(payload);
+    data
+}
+
+/// Deserializes oracle account data, matching `Oracle::<T>::from_bytes`.
+#[wasm_bindgen(js_name = decodeOracle)]
+pub fn decode_oracle(data: &[u8], payload_len: usize) -> Result<Oracle, JsError> {
+    if data.len() < PAYLOAD_OFFSET + payload_len {
+        return Err(JsError::new(&format!(
+            "expected at least {} bytes of oracle data, got {}",
+            PAYLOAD_OFFSET + payload_len,
+            data.len()
+        )));
+    }
+
+    let mut sequence = [0u8; 8];
+    sequence.copy_from_slice(&data[SEQUENCE_OFFSET..PAYLOAD_OFFSET]);
+
+    Ok(Oracle {
+        sequence: u64::from_le_bytes(sequence),
+        payload: data[PAYLOAD_OFFSET..PAYLOAD_OFFSET + payload_len].to_vec(),
+    })
+}
+
+/// Builds an oracle update instruction, matching `UpdateInstruction<T>`.
+#[wasm_bindgen(js_name = updateInstruction)]
+pub fn update_instruction(
+    admin: &str,
+    oracle: &str,
+    sequence: u64,
+    payload: &[u8],
+) -> Result<Instruction, JsError> {
+    Ok(Instruction {
+        program_id: ID.to_string(),
+        accounts: vec![
+            AccountMeta {
+                pubkey: pubkey(admin)?.to_string(),
+                is_signer: true,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: pubkey(oracle)?.to_string(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+        data: encode_oracle(sequence, payload),
+    })
+}
+
+/// Derives an oracle address created with `create_account_with_seed`.
+#[wasm_bindgen(js_name = deriveOracleAddress)]
+pub fn derive_oracle_address(base: &str, seed: &str) -> Result<String, JsError> {
+    Pubkey::create_with_seed(&pubkey(base)?, seed, &ID)
+        .map(|address| address.to_string())
+        .map_err(|e| JsError::new(&format!("invalid seed {seed}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use doppler_sdk::UpdateInstruction;
+
+    use super::*;
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    struct PropAMM {
+        bid: u64,
+        ask: u64,
+    }
+
+    #[test]
+    fn test_update_instruction_matches_sdk() {
+        let admin = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let payload = PropAMM {
+            bid: 10_500_000,
+            ask: 10_550_000,
+        };
+
+        let expected: solana_instruction::Instruction = UpdateInstruction {
+            admin,
+            oracle_pubkey: oracle,
+            oracle: doppler_sdk::Oracle {
+                sequence: 7,
+                payload,
+            },
+        }
+        .into();
+
+        let mut raw_payload = payload.bid.to_le_bytes().to_vec();
+        raw_payload.extend_from_slice(&payload.ask.to_le_bytes());
+
+        let instruction =
+            update_instruction(&admin.to_string(), &oracle.to_string(), 7, &raw_payload)
+                .unwrap_or_else(|_| panic!("failed to build instruction"));
+
+        assert_eq!(instruction.program_id, expected.program_id.to_string());
+        assert_eq!(instruction.data, expected.data);
+        for (meta, expected) in instruction.accounts.iter().zip(expected.accounts.iter()) {
+            assert_eq!(meta.pubkey, expected.pubkey.to_string());
+            assert_eq!(meta.is_signer, expected.is_signer);
+            assert_eq!(meta.is_writable, expected.is_writable);
+        }
+    }
+
+    #[test]
+    fn test_decode_oracle_roundtrip() {
+        let data = encode_oracle(42, &123u32.to_le_bytes());
+        let oracle = decode_oracle(&data, 4).unwrap_or_else(|_| panic!("failed to decode"));
+
+        assert_eq!(oracle.sequence, 42);
+        assert_eq!(oracle.payload, 123u32.to_le_bytes());
+    }
+}